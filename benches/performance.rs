@@ -513,6 +513,36 @@ fn bench_critical_operations(c: &mut Criterion) {
         });
     });
 
+    // Benchmark: Expanding a journal abbreviation file shared by many entries
+    group.bench_function("macro_heavy_expansion", |b| {
+        use bibtex_parser::Parser;
+        use std::fmt::Write as _;
+
+        let mut input = String::new();
+        for journal in 0..200 {
+            writeln!(
+                input,
+                "@string{{j{journal}base = {{Journal of Topic {journal}}}}}"
+            )
+            .unwrap();
+            writeln!(input, "@string{{j{journal} = \"Proc. \" # j{journal}base}}").unwrap();
+        }
+        for entry in 0..5_000 {
+            let journal = entry % 200;
+            writeln!(
+                input,
+                "@article{{e{entry}, journal = j{journal}, month = jan}}"
+            )
+            .unwrap();
+        }
+        let parser = Parser::new().expand_values();
+
+        b.iter(|| {
+            let document = parser.parse_document(black_box(&input)).unwrap();
+            black_box(document.entries().len());
+        });
+    });
+
     group.finish();
 }
 
@@ -591,6 +621,122 @@ fn bench_corpus_parsing(c: &mut Criterion) {
     group.finish();
 }
 
+/// Cost of tolerant parsing and source capture on clean and malformed input
+fn bench_diagnostics(c: &mut Criterion) {
+    use bibtex_parser::Library;
+
+    let clean = extract_entries(TUGBOAT_BIB, 1000);
+    let malformed = corrupt_entries(&clean, 10);
+
+    let mut group = c.benchmark_group("diagnostics");
+    group.measurement_time(Duration::from_secs(12));
+    group.warm_up_time(Duration::from_secs(5));
+    group.sample_size(120);
+    group.throughput(Throughput::Bytes(clean.len() as u64));
+
+    stabilize_system();
+
+    let strict = Library::parser();
+    let tolerant = Library::parser().tolerant();
+    let located = Library::parser().tolerant().capture_source();
+    assert!(strict.parse(&malformed).is_err());
+    let recovered = located.parse_document(&malformed).unwrap();
+    assert!(!recovered.diagnostics().is_empty());
+
+    group.bench_function("strict_clean", |b| {
+        b.iter(|| {
+            let library = strict.parse(black_box(&clean)).unwrap();
+            black_box(&library);
+        });
+    });
+
+    group.bench_function("tolerant_clean", |b| {
+        b.iter(|| {
+            let library = tolerant.parse(black_box(&clean)).unwrap();
+            black_box(&library);
+        });
+    });
+
+    group.bench_function("tolerant_malformed", |b| {
+        b.iter(|| {
+            let library = tolerant.parse(black_box(&malformed)).unwrap();
+            black_box(&library);
+        });
+    });
+
+    group.bench_function("document_spans_clean", |b| {
+        b.iter(|| {
+            let document = located.parse_document(black_box(&clean)).unwrap();
+            black_box(&document);
+        });
+    });
+
+    group.bench_function("document_spans_malformed", |b| {
+        b.iter(|| {
+            let document = located.parse_document(black_box(&malformed)).unwrap();
+            black_box(document.diagnostics().len());
+        });
+    });
+
+    group.finish();
+}
+
+/// Field lookup cost on entries with 5, 15, and 30 fields
+fn bench_field_lookup(c: &mut Criterion) {
+    use bibtex_parser::Library;
+    use std::fmt::Write as _;
+
+    let mut group = c.benchmark_group("lookup");
+    group.measurement_time(Duration::from_secs(8));
+    group.warm_up_time(Duration::from_secs(3));
+    group.sample_size(150);
+
+    stabilize_system();
+
+    for fields in [5, 15, 30] {
+        let mut input = String::from("@misc{key");
+        for field in 0..fields {
+            write!(input, ",\n  field{field} = {{Value {field}}}").unwrap();
+        }
+        input.push_str("\n}");
+        let library = Library::parse(&input).unwrap();
+        let entry = &library.entries()[0];
+        let last = format!("field{}", fields - 1);
+        let last_upper = last.to_ascii_uppercase();
+        assert!(entry.get(&last).is_some());
+
+        group.bench_with_input(BenchmarkId::new("get_first", fields), entry, |b, entry| {
+            b.iter(|| black_box(entry.get(black_box("field0"))));
+        });
+        group.bench_with_input(BenchmarkId::new("get_last", fields), entry, |b, entry| {
+            b.iter(|| black_box(entry.get(black_box(&last))));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("get_missing", fields),
+            entry,
+            |b, entry| {
+                b.iter(|| black_box(entry.get(black_box("missing"))));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_ignore_case_last", fields),
+            entry,
+            |b, entry| {
+                b.iter(|| black_box(entry.get_ignore_case(black_box(&last_upper))));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_as_string_last", fields),
+            entry,
+            |b, entry| {
+                b.iter(|| black_box(entry.get_as_string(black_box(&last))));
+            },
+        );
+    }
+
+    group.finish();
+}
+
 /// Extract first N entries from BibTeX string
 fn extract_entries(input: &str, max_entries: usize) -> String {
     let mut result = String::with_capacity(input.len() / 10);
@@ -651,6 +797,30 @@ fn extract_entries(input: &str, max_entries: usize) -> String {
     result
 }
 
+/// Drop the comma after the key of every `every`th entry so it fails to parse.
+fn corrupt_entries(input: &str, every: usize) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut entries = 0;
+    for line in input.lines() {
+        let trimmed = line.trim_start();
+        let is_entry = trimmed.starts_with('@')
+            && !["@comment", "@preamble", "@string"]
+                .iter()
+                .any(|name| trimmed.to_ascii_lowercase().starts_with(name));
+        if is_entry {
+            entries += 1;
+            if entries % every == 0 {
+                result.push_str(line.trim_end().trim_end_matches(','));
+                result.push('\n');
+                continue;
+            }
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default()
@@ -660,7 +830,7 @@ criterion_group! {
         .significance_level(0.02)
         .confidence_level(0.98)
         .noise_threshold(0.03);
-    targets = bench_parser_comparison, bench_critical_operations, bench_memory_efficiency, bench_corpus_parsing, bench_writing
+    targets = bench_parser_comparison, bench_critical_operations, bench_memory_efficiency, bench_corpus_parsing, bench_writing, bench_diagnostics, bench_field_lookup
 }
 
 criterion_main!(benches);
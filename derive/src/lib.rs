@@ -0,0 +1,254 @@
+//! Derive macros for `bibtex-parser`.
+//!
+//! Use these through the `derive` feature of `bibtex-parser`, which
+//! re-exports them next to the `FromEntry` and `ToEntry` traits.
+//!
+//! Field attributes, written `#[bibtex(...)]`:
+//!
+//! - `rename = "name"`: BibTeX field name; defaults to the Rust field name.
+//! - `key`: the citation key, converted with `From<&str>` and `ToString`.
+//!   `ToEntry` requires exactly one key field.
+//! - `skip`: ignored when writing; filled with `Default` when reading.
+//! - `parse_with = "path"`: a `fn(Option<&str>) -> Result<T, FieldErrorKind>`
+//!   used instead of `FromField`.
+//! - `write_with = "path"`: a `fn(&T) -> Option<String>` used instead of
+//!   `ToField`.
+//!
+//! The container attribute `#[bibtex(entry_type = "article")]` sets the type
+//! written by `ToEntry`; it defaults to `misc`.
+
+#![forbid(unsafe_code)]
+#![warn(clippy::all, clippy::pedantic, missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Path, Type};
+
+/// Derive `bibtex_parser::FromEntry`.
+#[proc_macro_derive(FromEntry, attributes(bibtex))]
+pub fn derive_from_entry(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_entry(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `bibtex_parser::ToEntry`.
+#[proc_macro_derive(ToEntry, attributes(bibtex))]
+pub fn derive_to_entry(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_entry(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FieldSpec {
+    ident: Ident,
+    ty: Type,
+    name: String,
+    key: bool,
+    skip: bool,
+    parse_with: Option<Path>,
+    write_with: Option<Path>,
+}
+
+fn fields(input: &DeriveInput) -> syn::Result<Vec<FieldSpec>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "only structs can be mapped to BibTeX entries",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "only structs with named fields can be mapped to BibTeX entries",
+        ));
+    };
+
+    named
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field");
+            let mut spec = FieldSpec {
+                name: ident.to_string().trim_start_matches("r#").to_string(),
+                ident,
+                ty: field.ty.clone(),
+                key: false,
+                skip: false,
+                parse_with: None,
+                write_with: None,
+            };
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("bibtex"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        spec.name = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("key") {
+                        spec.key = true;
+                    } else if meta.path.is_ident("skip") {
+                        spec.skip = true;
+                    } else if meta.path.is_ident("parse_with") {
+                        spec.parse_with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    } else if meta.path.is_ident("write_with") {
+                        spec.write_with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    } else {
+                        return Err(meta.error("unknown bibtex field attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(spec)
+        })
+        .collect()
+}
+
+fn entry_type(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut entry_type = LitStr::new("misc", Span::call_site());
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("bibtex"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("entry_type") {
+                entry_type = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown bibtex container attribute"))
+            }
+        })?;
+    }
+    Ok(entry_type)
+}
+
+fn expand_from_entry(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input)?;
+    entry_type(input)?;
+    let reader = format_ident!("__bibtex_fields");
+    let entry = format_ident!("__bibtex_entry");
+
+    let reads = fields.iter().enumerate().map(|(index, field)| {
+        let local = format_ident!("__bibtex_field_{index}");
+        let ty = &field.ty;
+        let name = &field.name;
+        let read = if field.skip {
+            quote!(::core::option::Option::Some(<#ty as ::core::default::Default>::default()))
+        } else if field.key {
+            quote!(::core::option::Option::Some(
+                <#ty as ::core::convert::From<&str>>::from(#entry.key())
+            ))
+        } else if let Some(path) = &field.parse_with {
+            quote!(#reader.read_with::<#ty>(#name, #path))
+        } else {
+            quote!(#reader.read::<#ty>(#name))
+        };
+        quote!(let #local = #read;)
+    });
+    let assignments = fields.iter().enumerate().map(|(index, field)| {
+        let local = format_ident!("__bibtex_field_{index}");
+        let ident = &field.ident;
+        quote!(#ident: #local?)
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bibtex_parser::FromEntry for #ident #ty_generics #where_clause {
+            fn from_entry(
+                #entry: &::bibtex_parser::Entry<'_>,
+            ) -> ::core::result::Result<Self, ::bibtex_parser::ExtractError> {
+                let mut #reader = ::bibtex_parser::FieldReader::new(#entry);
+                #(#reads)*
+                #reader.finish(|| ::core::option::Option::Some(Self { #(#assignments,)* }))
+            }
+        }
+    })
+}
+
+fn expand_to_entry(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input)?;
+    let entry_type = entry_type(input)?;
+    let entry = format_ident!("__bibtex_entry");
+
+    let mut keys = fields.iter().filter(|field| field.key);
+    let Some(key) = keys.next() else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ToEntry needs a `#[bibtex(key)]` field for the citation key",
+        ));
+    };
+    if let Some(extra) = keys.next() {
+        return Err(syn::Error::new_spanned(
+            &extra.ident,
+            "only one field can be the `#[bibtex(key)]`",
+        ));
+    }
+    let key_ident = &key.ident;
+    let key = quote!(::std::string::ToString::to_string(&self.#key_ident));
+    let writes = fields
+        .iter()
+        .filter(|field| !field.key && !field.skip)
+        .map(|field| {
+            let ident = &field.ident;
+            let name = &field.name;
+            let value = field.write_with.as_ref().map_or_else(
+                || quote!(::bibtex_parser::ToField::to_field(&self.#ident)),
+                |path| quote!(#path(&self.#ident)),
+            );
+            quote!(#entry.push_field(#name, #value);)
+        });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bibtex_parser::ToEntry for #ident #ty_generics #where_clause {
+            fn to_entry(&self) -> ::bibtex_parser::Entry<'static> {
+                let mut #entry = ::bibtex_parser::Entry::owned(#entry_type, #key);
+                #(#writes)*
+                #entry
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_entry_error(input: &DeriveInput) -> String {
+        expand_to_entry(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn to_entry_requires_exactly_one_key_field() {
+        let keyless: DeriveInput = syn::parse_quote! {
+            struct Paper {
+                title: String,
+            }
+        };
+        assert_eq!(
+            to_entry_error(&keyless),
+            "ToEntry needs a `#[bibtex(key)]` field for the citation key"
+        );
+        assert!(expand_from_entry(&keyless).is_ok());
+
+        let two_keys: DeriveInput = syn::parse_quote! {
+            struct Paper {
+                #[bibtex(key)]
+                key: String,
+                #[bibtex(key)]
+                id: String,
+            }
+        };
+        assert_eq!(
+            to_entry_error(&two_keys),
+            "only one field can be the `#[bibtex(key)]`"
+        );
+    }
+}
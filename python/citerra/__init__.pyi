@@ -183,6 +183,8 @@ class PersonName:
     prefix: list[str]
     suffix: list[str]
     literal: str | None
+    nameaddon: str
+    is_corporate: bool
     def display_name(self) -> str: ...
 
 class DateParts:
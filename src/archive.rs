@@ -0,0 +1,252 @@
+//! Bibliography bundles packed in zip and tar archives.
+//!
+//! Enabled by the `archive` feature. Some reference managers export a
+//! collection as an archive of `.bib` files, one per group or folder.
+//! [`Parser::parse_archive`] parses every `.bib` member and merges the
+//! entries into one library with a [`MergePolicy`], remembering which
+//! member each entry came from.
+//!
+//! ```no_run
+//! use bibtex_parser::MergePolicy;
+//! use bibtex_parser::Parser;
+//!
+//! let import = Parser::new().parse_archive("export.zip", MergePolicy::KeepExisting)?;
+//! for (index, entry) in import.library.entries().iter().enumerate() {
+//!     println!("{} from {}", entry.key(), import.member_of(index).unwrap_or("?"));
+//! }
+//! # Ok::<(), bibtex_parser::Error>(())
+//! ```
+
+use crate::{AddOutcome, Error, Library, MergePolicy, Parser, Result};
+use std::io::Read;
+use std::path::Path;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Limit on the decompressed size of one archive member: 1 GiB.
+pub const MAX_MEMBER_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Entries merged from the `.bib` members of an archive.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveImport {
+    /// Merged library.
+    pub library: Library<'static>,
+    /// Paths of the parsed members, in archive order.
+    pub members: Vec<String>,
+    /// Member index that each entry of `library` was first read from.
+    pub provenance: Vec<usize>,
+    /// Entries left unmerged because they disagreed with an earlier member.
+    pub conflicts: Vec<ArchiveConflict>,
+}
+
+/// An entry the merge policy did not apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveConflict {
+    /// Index of the member holding the rejected entry.
+    pub member: usize,
+    /// Citation key of the rejected entry.
+    pub key: String,
+    /// Index of the existing entry in the merged library.
+    pub existing: usize,
+    /// Shared fields whose values differ.
+    pub fields: Vec<String>,
+}
+
+impl ArchiveImport {
+    /// Return the path of the member entry `index` of the library came from.
+    #[must_use]
+    pub fn member_of(&self, index: usize) -> Option<&str> {
+        let member = *self.provenance.get(index)?;
+        self.members.get(member).map(String::as_str)
+    }
+
+    fn add_member(&mut self, path: String, library: &Library<'_>, policy: MergePolicy) {
+        let member = self.members.len();
+        self.members.push(path);
+        for entry in library.entries() {
+            let key = entry.key().to_string();
+            match self
+                .library
+                .add_or_update_entry(entry.clone().into_owned(), policy)
+            {
+                AddOutcome::Inserted(_) => self.provenance.push(member),
+                AddOutcome::Conflicted { index, fields } => {
+                    self.conflicts.push(ArchiveConflict {
+                        member,
+                        key,
+                        existing: index,
+                        fields,
+                    });
+                }
+                AddOutcome::Updated(_) | AddOutcome::Unchanged(_) => {}
+            }
+        }
+    }
+}
+
+/// Parse the archive at `path` with a default [`Parser`], rejecting
+/// conflicting duplicates.
+///
+/// # Errors
+///
+/// See [`Parser::parse_archive`].
+pub fn parse_archive(path: impl AsRef<Path>) -> Result<ArchiveImport> {
+    Parser::new().parse_archive(path, MergePolicy::default())
+}
+
+impl Parser {
+    /// Parse every `.bib` member of the zip or tar archive at `path`.
+    ///
+    /// The format is recognized from the file contents; gzipped tarballs
+    /// are decompressed. Members are parsed in archive order and their
+    /// entries merged with [`Library::add_or_update_entry`] under `policy`,
+    /// so duplicates across members match on key or DOI. Other members,
+    /// and `@string`, `@preamble`, and comment blocks, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Archive`] when the archive cannot be read or a
+    /// member fails to parse or decompresses to more than
+    /// [`MAX_MEMBER_BYTES`], naming the member, and an I/O error when
+    /// `path` cannot be opened.
+    pub fn parse_archive(
+        &self,
+        path: impl AsRef<Path>,
+        policy: MergePolicy,
+    ) -> Result<ArchiveImport> {
+        let bytes = std::fs::read(path)?;
+        let mut import = ArchiveImport::default();
+        let mut add = |name: String, content: Vec<u8>| -> Result<()> {
+            let content = String::from_utf8(content)
+                .map_err(|error| Error::Archive(format!("{name}: {error}")))?;
+            let library = self
+                .parse(&content)
+                .map_err(|error| Error::Archive(format!("{name}: {error}")))?;
+            import.add_member(name, &library, policy);
+            Ok(())
+        };
+
+        if bytes.starts_with(ZIP_MAGIC) {
+            read_zip(&bytes, MAX_MEMBER_BYTES, &mut add)?;
+        } else if bytes.starts_with(GZIP_MAGIC) {
+            read_tar(
+                flate2::read::MultiGzDecoder::new(bytes.as_slice()),
+                MAX_MEMBER_BYTES,
+                &mut add,
+            )?;
+        } else {
+            read_tar(bytes.as_slice(), MAX_MEMBER_BYTES, &mut add)?;
+        }
+        Ok(import)
+    }
+}
+
+/// Whether an archive member is a bibliography file.
+fn is_bib_member(name: &str) -> bool {
+    let file = name.rsplit('/').next().unwrap_or(name);
+    Path::new(file)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("bib"))
+        && !file.starts_with("._")
+        && !name.starts_with("__MACOSX/")
+}
+
+fn archive_error(error: impl std::fmt::Display) -> Error {
+    Error::Archive(error.to_string())
+}
+
+/// Read a member's content, failing when it is larger than `max_bytes`.
+fn read_member(file: impl Read, name: &str, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut content)
+        .map_err(|error| Error::Archive(format!("{name}: {error}")))?;
+    if content.len() as u64 > max_bytes {
+        return Err(Error::Archive(format!(
+            "{name}: member is larger than the {max_bytes}-byte limit"
+        )));
+    }
+    Ok(content)
+}
+
+fn read_zip(
+    bytes: &[u8],
+    max_bytes: u64,
+    add: &mut impl FnMut(String, Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(archive_error)?;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(archive_error)?;
+        let name = file.name().to_string();
+        if !file.is_file() || !is_bib_member(&name) {
+            continue;
+        }
+        let content = read_member(&mut file, &name, max_bytes)?;
+        add(name, content)?;
+    }
+    Ok(())
+}
+
+fn read_tar(
+    reader: impl Read,
+    max_bytes: u64,
+    add: &mut impl FnMut(String, Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for file in archive.entries().map_err(archive_error)? {
+        let mut file = file.map_err(archive_error)?;
+        if !file.header().entry_type().is_file() {
+            continue;
+        }
+        let name = file
+            .path()
+            .map_err(archive_error)?
+            .to_string_lossy()
+            .into_owned();
+        if !is_bib_member(&name) {
+            continue;
+        }
+        let content = read_member(&mut file, &name, max_bytes)?;
+        add(name, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn members_larger_than_the_limit_are_rejected() {
+        let text = "@misc{a}\n".repeat(100);
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("refs.bib", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(text.as_bytes()).unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(text.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "refs.bib", text.as_bytes())
+            .unwrap();
+        let tar = builder.into_inner().unwrap();
+
+        let mut sizes = Vec::new();
+        let mut add = |_: String, content: Vec<u8>| -> Result<()> {
+            sizes.push(content.len());
+            Ok(())
+        };
+        read_zip(&zip, 900, &mut add).unwrap();
+        read_tar(tar.as_slice(), 900, &mut add).unwrap();
+
+        let error = read_zip(&zip, 899, &mut add).unwrap_err();
+        assert!(error.to_string().contains("refs.bib: member is larger"));
+        assert!(read_tar(tar.as_slice(), 899, &mut add).is_err());
+        assert_eq!(sizes, [900, 900]);
+    }
+}
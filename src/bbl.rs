@@ -0,0 +1,133 @@
+//! Consistency checks between a library and a generated `.bbl` file.
+//!
+//! A `.bbl` file lists the entries BibTeX or Biber resolved for a document.
+//! Comparing it with the `.bib` library and the document's citations catches
+//! stale builds and references that never made it into the bibliography.
+
+use crate::keys::{cited_keys, skip_optional_argument};
+use crate::parser::simd::find_balanced_braces;
+use crate::Library;
+use ahash::AHashSet;
+use memchr::memchr;
+
+/// Return the keys listed in a `.bbl` file, in order of appearance.
+///
+/// Both BibTeX `\bibitem[label]{key}` items and Biber `\entry{key}{type}{}`
+/// records are recognized.
+///
+/// ```
+/// use bibtex_parser::bbl_keys;
+///
+/// let bbl = r"\begin{thebibliography}{1}
+/// \bibitem[Doe(2024)]{doe} J.~Doe. \newblock A title.
+/// \bibitem{roe} R.~Roe.
+/// \end{thebibliography}";
+/// assert_eq!(bbl_keys(bbl), ["doe", "roe"]);
+/// ```
+#[must_use]
+pub fn bbl_keys(bbl: &str) -> Vec<&str> {
+    let bytes = bbl.as_bytes();
+    let mut keys: Vec<&str> = Vec::new();
+    let mut seen = AHashSet::new();
+    let mut pos = 0;
+    while let Some(offset) = memchr(b'\\', &bytes[pos..]) {
+        let start = pos + offset;
+        let name_end = start
+            + 1
+            + bytes[start + 1..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_alphabetic())
+                .count();
+        pos = name_end.max(start + 2).min(bytes.len());
+
+        let optional = match &bbl[start + 1..name_end] {
+            "bibitem" => true,
+            "entry" => false,
+            _ => continue,
+        };
+        let mut cursor = skip_spaces(bytes, name_end);
+        if optional && bytes.get(cursor) == Some(&b'[') {
+            let Some(end) = skip_optional_argument(bytes, cursor, b']') else {
+                continue;
+            };
+            cursor = skip_spaces(bytes, end);
+        }
+        if bytes.get(cursor) != Some(&b'{') {
+            continue;
+        }
+        let Some(length) = find_balanced_braces(&bytes[cursor..]) else {
+            continue;
+        };
+        let key = bbl[cursor + 1..cursor + length - 1].trim();
+        if !key.is_empty() && seen.insert(key) {
+            keys.push(key);
+        }
+        pos = cursor + length;
+    }
+    keys
+}
+
+fn skip_spaces(bytes: &[u8], pos: usize) -> usize {
+    pos + bytes[pos..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_whitespace())
+        .count()
+}
+
+/// Problems found by [`Library::check_bbl`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BblCheck {
+    /// Keys in the `.bbl` file that the library does not define.
+    pub missing_from_library: Vec<String>,
+    /// Cited keys that the `.bbl` file does not list.
+    pub missing_from_bbl: Vec<String>,
+}
+
+impl BblCheck {
+    /// Return `true` when the library, `.bbl` file, and citations agree.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.missing_from_library.is_empty() && self.missing_from_bbl.is_empty()
+    }
+}
+
+impl Library<'_> {
+    /// Compare a generated `.bbl` file with this library and with the
+    /// citations in `latex`.
+    ///
+    /// Every `.bbl` key must exist in the library, and every key cited by a
+    /// `\cite`-family command in `latex` must appear in the `.bbl` file. Pass
+    /// an empty `latex` to check only the first direction. Keys are compared
+    /// exactly, so a key that differs only in case is reported.
+    ///
+    /// ```
+    /// use bibtex_parser::Library;
+    ///
+    /// let library = Library::parse("@misc{doe, year = 2024}")?;
+    /// let bbl = r"\bibitem{doe} Doe. \bibitem{old} Removed entry.";
+    /// let check = library.check_bbl(bbl, r"\cite{doe} and \cite{new}");
+    ///
+    /// assert_eq!(check.missing_from_library, ["old"]);
+    /// assert_eq!(check.missing_from_bbl, ["new"]);
+    /// assert!(!check.is_clean());
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn check_bbl(&self, bbl: &str, latex: &str) -> BblCheck {
+        let listed = bbl_keys(bbl);
+        let defined: AHashSet<&str> = self.entries().iter().map(crate::Entry::key).collect();
+        let listed_set: AHashSet<&str> = listed.iter().copied().collect();
+        BblCheck {
+            missing_from_library: listed
+                .iter()
+                .filter(|key| !defined.contains(*key))
+                .map(|key| (*key).to_string())
+                .collect(),
+            missing_from_bbl: cited_keys(latex)
+                .into_iter()
+                .filter(|key| !listed_set.contains(key))
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
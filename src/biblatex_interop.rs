@@ -0,0 +1,118 @@
+//! Conversions to and from the `biblatex` crate.
+//!
+//! Enabled by the `biblatex` feature. Going to `biblatex`, entries are written
+//! as BibTeX and read back by `biblatex`, so its own rules for escapes,
+//! commands, and month macros apply. Coming back, chunks become field text:
+//! verbatim chunks are braced, math chunks wrapped in `$`, and special
+//! characters escaped outside verbatim fields such as `url` and `doi`.
+//!
+//! ```
+//! use bibtex_parser::Library;
+//!
+//! let library = Library::parse("@article{a, title = {On {NASA}}, year = 2024}")?;
+//! let bibliography = biblatex::Bibliography::try_from(&library)?;
+//! assert_eq!(bibliography.get("a").unwrap().fields.len(), 2);
+//!
+//! let back = Library::from(&bibliography);
+//! assert_eq!(back.entries()[0].get("title"), Some("On {NASA}"));
+//! # Ok::<(), bibtex_parser::Error>(())
+//! ```
+
+use crate::{to_string, to_string_entry, Entry, Error, Field, Library, Value};
+use biblatex::{Bibliography, Chunk, Spanned};
+use std::borrow::Cow;
+
+impl TryFrom<&Entry<'_>> for biblatex::Entry {
+    type Error = Error;
+
+    /// Unresolved string variables other than month names are rejected.
+    fn try_from(entry: &Entry<'_>) -> Result<Self, Error> {
+        let bibliography = parse_biblatex(&to_string_entry(entry)?)?;
+        bibliography
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Conversion(format!("biblatex dropped entry `{}`", entry.key)))
+    }
+}
+
+impl TryFrom<&Library<'_>> for Bibliography {
+    type Error = Error;
+
+    /// `@string` definitions are resolved by `biblatex`; later duplicates of
+    /// a key replace earlier ones.
+    fn try_from(library: &Library<'_>) -> Result<Self, Error> {
+        parse_biblatex(&to_string(library)?)
+    }
+}
+
+impl From<&biblatex::Entry> for Entry<'static> {
+    fn from(entry: &biblatex::Entry) -> Self {
+        let mut converted = Self::owned(&entry.entry_type.to_string(), entry.key.clone());
+        converted.fields = entry
+            .fields
+            .iter()
+            .map(|(name, chunks)| Field {
+                name: Cow::Owned(name.clone()),
+                value: Value::Literal(Cow::Owned(chunks_to_text(chunks, is_verbatim_field(name)))),
+            })
+            .collect();
+        converted
+    }
+}
+
+impl From<&Bibliography> for Library<'static> {
+    fn from(bibliography: &Bibliography) -> Self {
+        let mut library = Self::new();
+        library.extend(bibliography.iter().map(Entry::from));
+        library
+    }
+}
+
+fn parse_biblatex(source: &str) -> Result<Bibliography, Error> {
+    Bibliography::parse(source).map_err(|error| Error::Conversion(format!("biblatex: {error}")))
+}
+
+/// Fields `biblatex` reads with escapes turned off.
+fn is_verbatim_field(name: &str) -> bool {
+    matches!(
+        name,
+        "file" | "doi" | "uri" | "eprint" | "verba" | "verbb" | "verbc" | "pdf" | "url" | "urlraw"
+    )
+}
+
+fn chunks_to_text(chunks: &[Spanned<Chunk>], verbatim: bool) -> String {
+    let mut text = String::new();
+    for chunk in chunks {
+        match &chunk.v {
+            Chunk::Normal(value) => push_escaped(&mut text, value, verbatim),
+            Chunk::Verbatim(value) => {
+                text.push('{');
+                push_escaped(&mut text, value, verbatim);
+                text.push('}');
+            }
+            Chunk::Math(value) => {
+                text.push('$');
+                text.push_str(value);
+                text.push('$');
+            }
+        }
+    }
+    text
+}
+
+fn push_escaped(text: &mut String, value: &str, verbatim: bool) {
+    if verbatim {
+        text.push_str(value);
+        return;
+    }
+    for ch in value.chars() {
+        match ch {
+            '\\' => text.push_str("\\textbackslash{}"),
+            '{' | '}' | '&' | '%' | '#' | '_' | '$' => {
+                text.push('\\');
+                text.push(ch);
+            }
+            _ => text.push(ch),
+        }
+    }
+}
@@ -0,0 +1,493 @@
+//! Heuristic clean-up passes for field values.
+//!
+//! Passes rewrite literal text only; variables are left alone. Each change
+//! is reported as a [`ValueRepair`] so callers can review what was touched.
+
+use crate::document::{ParsedDocument, ParsedField};
+use crate::{classify_resource_field, SourceSpan, Value};
+use std::borrow::Cow;
+
+/// Windows-1252 characters for bytes `0x80..=0x9F`, where defined.
+const CP1252_HIGH: [Option<char>; 32] = [
+    Some('\u{20AC}'),
+    None,
+    Some('\u{201A}'),
+    Some('\u{0192}'),
+    Some('\u{201E}'),
+    Some('\u{2026}'),
+    Some('\u{2020}'),
+    Some('\u{2021}'),
+    Some('\u{02C6}'),
+    Some('\u{2030}'),
+    Some('\u{0160}'),
+    Some('\u{2039}'),
+    Some('\u{0152}'),
+    None,
+    Some('\u{017D}'),
+    None,
+    None,
+    Some('\u{2018}'),
+    Some('\u{2019}'),
+    Some('\u{201C}'),
+    Some('\u{201D}'),
+    Some('\u{2022}'),
+    Some('\u{2013}'),
+    Some('\u{2014}'),
+    Some('\u{02DC}'),
+    Some('\u{2122}'),
+    Some('\u{0161}'),
+    Some('\u{203A}'),
+    Some('\u{0153}'),
+    None,
+    Some('\u{017E}'),
+    Some('\u{0178}'),
+];
+
+/// One field value changed by a clean-up pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueRepair {
+    /// Index of the entry in the library or document.
+    pub entry: usize,
+    /// Field name as written.
+    pub field: String,
+    /// Literal text before the repair.
+    pub original: String,
+    /// Literal text after the repair.
+    pub repaired: String,
+    /// Source location of the field value, when known.
+    pub span: Option<SourceSpan>,
+}
+
+/// Repair UTF-8 text that was decoded as Latin-1 or Windows-1252.
+///
+/// Only character runs that re-encode to valid multi-byte UTF-8 are
+/// replaced, so `"KÃ¶rper"` becomes `"Körper"` while correct text is left
+/// untouched. Returns `None` when nothing changed.
+#[must_use]
+pub fn repair_mojibake(text: &str) -> Option<String> {
+    if text.is_ascii() {
+        return None;
+    }
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut index = 0;
+    while index < chars.len() {
+        if let Some((decoded, consumed)) = decode_sequence(&chars[index..]) {
+            output.push(decoded);
+            index += consumed;
+            changed = true;
+        } else {
+            output.push(chars[index]);
+            index += 1;
+        }
+    }
+    changed.then_some(output)
+}
+
+fn decode_sequence(chars: &[char]) -> Option<(char, usize)> {
+    let lead = single_byte(chars[0])?;
+    let len = match lead {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let mut bytes = [lead, 0, 0, 0];
+    for (slot, &ch) in bytes[1..len].iter_mut().zip(chars.get(1..len)?) {
+        *slot = single_byte(ch).filter(|byte| (0x80..=0xBF).contains(byte))?;
+    }
+    let decoded = std::str::from_utf8(&bytes[..len]).ok()?.chars().next()?;
+    Some((decoded, len))
+}
+
+/// Byte a character had under Latin-1 or Windows-1252 decoding.
+fn single_byte(ch: char) -> Option<u8> {
+    if let Ok(byte) = u8::try_from(u32::from(ch)) {
+        return (byte >= 0x80).then_some(byte);
+    }
+    CP1252_HIGH
+        .iter()
+        .position(|mapped| *mapped == Some(ch))
+        .and_then(|offset| u8::try_from(0x80 + offset).ok())
+}
+
+/// Decode HTML named and numeric character references.
+///
+/// `&amp;` becomes `\&` and `&nbsp;` becomes `~` so the result stays valid
+/// BibTeX, and numeric references to TeX specials are escaped the same way
+/// (`&#123;` becomes `\{`, `&#36;` becomes `\$`); other references become
+/// the Unicode characters they name.
+/// Unknown or malformed references are kept. Returns `None` when nothing
+/// changed.
+#[must_use]
+pub fn decode_html_entities(text: &str) -> Option<String> {
+    if !text.contains('&') {
+        return None;
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .get(1..)
+            .and_then(|tail| tail.find(';').filter(|&end| end <= 32))
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+        if let Some((replacement, consumed)) = decoded {
+            output.push_str(replacement.as_ref());
+            rest = &rest[consumed..];
+            changed = true;
+        } else {
+            output.push('&');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    changed.then_some(output)
+}
+
+fn decode_entity(name: &str) -> Option<Cow<'static, str>> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return match char::from_u32(code)? {
+            '\u{A0}' => Some(Cow::Borrowed("~")),
+            ch if ch.is_control() => None,
+            ch => Some(tex_escape(ch).map_or_else(|| Cow::Owned(ch.to_string()), Cow::Borrowed)),
+        };
+    }
+    match name {
+        "amp" => Some(Cow::Borrowed("\\&")),
+        "nbsp" => Some(Cow::Borrowed("~")),
+        _ => HTML_ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == name)
+            .map(|(_, ch)| Cow::Owned(ch.to_string())),
+    }
+}
+
+/// TeX spelling of a character that is special in BibTeX values.
+const fn tex_escape(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        '&' => "\\&",
+        '{' => "\\{",
+        '}' => "\\}",
+        '$' => "\\$",
+        '#' => "\\#",
+        '%' => "\\%",
+        '_' => "\\_",
+        '\\' => "\\textbackslash{}",
+        '^' => "\\textasciicircum{}",
+        '~' => "\\textasciitilde{}",
+        _ => return None,
+    })
+}
+
+/// Named HTML entities other than `amp` and `nbsp`.
+const HTML_ENTITIES: &[(&str, char)] = &[
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("ndash", '\u{2013}'),
+    ("mdash", '\u{2014}'),
+    ("minus", '\u{2212}'),
+    ("hellip", '\u{2026}'),
+    ("lsquo", '\u{2018}'),
+    ("rsquo", '\u{2019}'),
+    ("ldquo", '\u{201C}'),
+    ("rdquo", '\u{201D}'),
+    ("laquo", '\u{AB}'),
+    ("raquo", '\u{BB}'),
+    ("copy", '\u{A9}'),
+    ("reg", '\u{AE}'),
+    ("trade", '\u{2122}'),
+    ("deg", '\u{B0}'),
+    ("plusmn", '\u{B1}'),
+    ("times", '\u{D7}'),
+    ("divide", '\u{F7}'),
+    ("micro", '\u{B5}'),
+    ("middot", '\u{B7}'),
+    ("sect", '\u{A7}'),
+    ("euro", '\u{20AC}'),
+    ("pound", '\u{A3}'),
+    ("iexcl", '\u{A1}'),
+    ("iquest", '\u{BF}'),
+    ("szlig", '\u{DF}'),
+    ("Agrave", '\u{C0}'),
+    ("Aacute", '\u{C1}'),
+    ("Acirc", '\u{C2}'),
+    ("Atilde", '\u{C3}'),
+    ("Auml", '\u{C4}'),
+    ("Aring", '\u{C5}'),
+    ("AElig", '\u{C6}'),
+    ("Ccedil", '\u{C7}'),
+    ("Egrave", '\u{C8}'),
+    ("Eacute", '\u{C9}'),
+    ("Ecirc", '\u{CA}'),
+    ("Euml", '\u{CB}'),
+    ("Igrave", '\u{CC}'),
+    ("Iacute", '\u{CD}'),
+    ("Icirc", '\u{CE}'),
+    ("Iuml", '\u{CF}'),
+    ("ETH", '\u{D0}'),
+    ("Ntilde", '\u{D1}'),
+    ("Ograve", '\u{D2}'),
+    ("Oacute", '\u{D3}'),
+    ("Ocirc", '\u{D4}'),
+    ("Otilde", '\u{D5}'),
+    ("Ouml", '\u{D6}'),
+    ("Oslash", '\u{D8}'),
+    ("Ugrave", '\u{D9}'),
+    ("Uacute", '\u{DA}'),
+    ("Ucirc", '\u{DB}'),
+    ("Uuml", '\u{DC}'),
+    ("Yacute", '\u{DD}'),
+    ("THORN", '\u{DE}'),
+    ("agrave", '\u{E0}'),
+    ("aacute", '\u{E1}'),
+    ("acirc", '\u{E2}'),
+    ("atilde", '\u{E3}'),
+    ("auml", '\u{E4}'),
+    ("aring", '\u{E5}'),
+    ("aelig", '\u{E6}'),
+    ("ccedil", '\u{E7}'),
+    ("egrave", '\u{E8}'),
+    ("eacute", '\u{E9}'),
+    ("ecirc", '\u{EA}'),
+    ("euml", '\u{EB}'),
+    ("igrave", '\u{EC}'),
+    ("iacute", '\u{ED}'),
+    ("icirc", '\u{EE}'),
+    ("iuml", '\u{EF}'),
+    ("eth", '\u{F0}'),
+    ("ntilde", '\u{F1}'),
+    ("ograve", '\u{F2}'),
+    ("oacute", '\u{F3}'),
+    ("ocirc", '\u{F4}'),
+    ("otilde", '\u{F5}'),
+    ("ouml", '\u{F6}'),
+    ("oslash", '\u{F8}'),
+    ("ugrave", '\u{F9}'),
+    ("uacute", '\u{FA}'),
+    ("ucirc", '\u{FB}'),
+    ("uuml", '\u{FC}'),
+    ("yacute", '\u{FD}'),
+    ("thorn", '\u{FE}'),
+    ("yuml", '\u{FF}'),
+    ("OElig", '\u{152}'),
+    ("oelig", '\u{153}'),
+    ("Scaron", '\u{160}'),
+    ("scaron", '\u{161}'),
+    ("alpha", '\u{3B1}'),
+    ("beta", '\u{3B2}'),
+    ("gamma", '\u{3B3}'),
+    ("delta", '\u{3B4}'),
+    ("epsilon", '\u{3B5}'),
+    ("lambda", '\u{3BB}'),
+    ("mu", '\u{3BC}'),
+    ("pi", '\u{3C0}'),
+    ("sigma", '\u{3C3}'),
+];
+
+/// Target form for [`normalize_punctuation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunctuationStyle {
+    /// Write ``` ``quotes'' ```, `--`, `---`, and `~`.
+    Latex,
+    /// Write curly quotes, en and em dashes, and non-breaking spaces.
+    Unicode,
+}
+
+/// Convert quotes, dashes, and non-breaking spaces to `style`.
+///
+/// Toward Unicode, backslash-escaped characters such as `\~`, math between
+/// `$` signs, and the braced argument of `\url`, `\href`, and `\path` are
+/// kept, and straight `'` is left alone because it cannot be told apart from
+/// an apostrophe. Returns `None` when nothing
+/// changed.
+#[must_use]
+pub fn normalize_punctuation(text: &str, style: PunctuationStyle) -> Option<String> {
+    let output = match style {
+        PunctuationStyle::Latex => punctuation_to_latex(text),
+        PunctuationStyle::Unicode => punctuation_to_unicode(text),
+    };
+    (output != text).then_some(output)
+}
+
+fn punctuation_to_latex(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{2018}' => output.push('`'),
+            '\u{2019}' => output.push('\''),
+            '\u{201C}' => output.push_str("``"),
+            '\u{201D}' => output.push_str("''"),
+            '\u{2013}' => output.push_str("--"),
+            '\u{2014}' => output.push_str("---"),
+            '\u{A0}' => output.push('~'),
+            _ => output.push(ch),
+        }
+    }
+    output
+}
+
+fn punctuation_to_unicode(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut math = false;
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        let (replacement, consumed) = match ch {
+            '\\' => {
+                let escaped = url_command_len(rest)
+                    .unwrap_or_else(|| 1 + rest[1..].chars().next().map_or(0, char::len_utf8));
+                output.push_str(&rest[..escaped]);
+                rest = &rest[escaped..];
+                continue;
+            }
+            '$' => {
+                math = !math;
+                (None, 1)
+            }
+            _ if math => (None, ch.len_utf8()),
+            '`' if rest.starts_with("``") => (Some('\u{201C}'), 2),
+            '`' => (Some('\u{2018}'), 1),
+            '\'' if rest.starts_with("''") => (Some('\u{201D}'), 2),
+            '-' if rest.starts_with("---") => (Some('\u{2014}'), 3),
+            '-' if rest.starts_with("--") => (Some('\u{2013}'), 2),
+            '~' => (Some('\u{A0}'), 1),
+            _ => (None, ch.len_utf8()),
+        };
+        match replacement {
+            Some(replacement) => output.push(replacement),
+            None => output.push_str(&rest[..consumed]),
+        }
+        rest = &rest[consumed..];
+    }
+    output
+}
+
+/// Length of a `\url`, `\href`, or `\path` command at the start of `text`,
+/// through the end of its braced argument.
+fn url_command_len(text: &str) -> Option<usize> {
+    let name_len = text[1..]
+        .bytes()
+        .take_while(u8::is_ascii_alphabetic)
+        .count();
+    if !matches!(&text[1..=name_len], "url" | "href" | "path") {
+        return None;
+    }
+    let after_name = &text[1 + name_len..];
+    let argument = after_name.trim_start();
+    if !argument.starts_with('{') {
+        return None;
+    }
+    let start = text.len() - argument.len();
+    let mut depth = 0_usize;
+    for (offset, byte) in argument.bytes().enumerate() {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(text.len())
+}
+
+/// Whether a field holds prose rather than an identifier or path.
+pub(crate) fn is_prose_field(name: &str) -> bool {
+    classify_resource_field(name).is_none()
+}
+
+/// Apply `repair` to every literal in `value`, returning `(original, repaired)` pairs.
+pub(crate) fn repair_literals(
+    value: &mut Value<'_>,
+    repair: &impl Fn(&str) -> Option<String>,
+) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+    match value {
+        Value::Literal(text) => {
+            if let Some(repaired) = repair(text) {
+                let original = std::mem::replace(text, Cow::Owned(repaired.clone()));
+                changes.push((original.into_owned(), repaired));
+            }
+        }
+        Value::Concat(parts) => {
+            for part in parts.iter_mut() {
+                changes.extend(repair_literals(part, repair));
+            }
+        }
+        Value::Number(_) | Value::Variable(_) => {}
+    }
+    changes
+}
+
+fn repair_field(
+    entry: usize,
+    field: &mut ParsedField<'_>,
+    repair: &impl Fn(&str) -> Option<String>,
+) -> Vec<ValueRepair> {
+    let changes = repair_literals(&mut field.value.value, repair);
+    if !changes.is_empty() {
+        field.value.raw = None;
+        field.raw = None;
+        field.value.expanded = None;
+    }
+    changes
+        .into_iter()
+        .map(|(original, repaired)| ValueRepair {
+            entry,
+            field: field.name.to_string(),
+            original,
+            repaired,
+            span: field.value_source,
+        })
+        .collect()
+}
+
+impl ParsedDocument<'_> {
+    /// Repair mojibake in literal field values.
+    ///
+    /// See [`repair_mojibake`]; reported spans point at the original values.
+    pub fn repair_mojibake(&mut self) -> Vec<ValueRepair> {
+        self.repair_values(|_| true, &repair_mojibake)
+    }
+
+    /// Decode HTML entities in literal field values.
+    ///
+    /// See [`decode_html_entities`]; reported spans point at the original values.
+    pub fn decode_html_entities(&mut self) -> Vec<ValueRepair> {
+        self.repair_values(|_| true, &decode_html_entities)
+    }
+
+    /// Convert quotes, dashes, and non-breaking spaces in literal field values.
+    ///
+    /// See [`normalize_punctuation`]; identifier fields such as `url` and
+    /// `doi` are left alone.
+    pub fn normalize_punctuation(&mut self, style: PunctuationStyle) -> Vec<ValueRepair> {
+        self.repair_values(is_prose_field, &|text| normalize_punctuation(text, style))
+    }
+
+    fn repair_values(
+        &mut self,
+        fields: impl Fn(&str) -> bool,
+        repair: &impl Fn(&str) -> Option<String>,
+    ) -> Vec<ValueRepair> {
+        let mut repairs = Vec::new();
+        for (index, entry) in self.entries_mut().iter_mut().enumerate() {
+            for field in entry.fields.iter_mut().filter(|field| fields(&field.name)) {
+                repairs.extend(repair_field(index, field, repair));
+            }
+        }
+        repairs
+    }
+}
@@ -0,0 +1,81 @@
+//! Reading bibliography files that may be compressed.
+
+use crate::Result;
+use std::path::Path;
+
+/// Limit on the decompressed size of a file: 1 GiB.
+#[cfg(feature = "compress")]
+const MAX_DECOMPRESSED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Read `path` as text.
+///
+/// With the `compress` feature, gzip and zstd data is recognized by its
+/// magic bytes and decompressed, whatever the file is called. Data that
+/// decompresses to more than 1 GiB is rejected as invalid.
+pub fn read_to_string(path: &Path) -> Result<String> {
+    #[cfg(feature = "compress")]
+    {
+        let bytes = decompress(std::fs::read(path)?, MAX_DECOMPRESSED_BYTES)?;
+        String::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error).into())
+    }
+    #[cfg(not(feature = "compress"))]
+    {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(feature = "compress")]
+fn decompress(bytes: Vec<u8>, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+    const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+    let decoder: Box<dyn Read + '_> = if bytes.starts_with(GZIP_MAGIC) {
+        Box::new(flate2::read::MultiGzDecoder::new(bytes.as_slice()))
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+        Box::new(zstd::stream::read::Decoder::new(bytes.as_slice())?)
+    } else {
+        return Ok(bytes);
+    };
+    let mut text = Vec::new();
+    decoder
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut text)?;
+    if text.len() as u64 > max_bytes {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("decompressed data is larger than the {max_bytes}-byte limit"),
+        ));
+    }
+    Ok(text)
+}
+
+#[cfg(all(test, feature = "compress"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn decompressed_size_is_limited() {
+        let text = "@misc{a}\n".repeat(100);
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(text.as_bytes()).unwrap();
+        let gzip = encoder.finish().unwrap();
+        let zstd = zstd::stream::encode_all(text.as_bytes(), 0).unwrap();
+
+        for compressed in [gzip, zstd] {
+            assert_eq!(
+                decompress(compressed.clone(), 900).unwrap(),
+                text.as_bytes()
+            );
+            let error = decompress(compressed, 899).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+        assert_eq!(
+            decompress(text.clone().into_bytes(), 10).unwrap(),
+            text.as_bytes()
+        );
+    }
+}
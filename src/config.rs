@@ -0,0 +1,553 @@
+//! Shared formatter, linter, and parser configuration files.
+//!
+//! A project keeps its settings in a `.bibfmt.toml` or `bibtex.toml` file.
+//! [`Config::discover`] looks for one in a directory and then in each parent
+//! directory, like `.editorconfig`, and the nearest file wins, so every tool
+//! run in a project formats and lints the same way.
+//!
+//! ```toml
+//! [parser]
+//! profile = "zotero"          # strict, tolerant, zotero, jabref, or legacy
+//!
+//! [format]
+//! indent = "    "
+//! align_values = true         # or tabular_column = 16
+//! trailing_comma = true
+//! sort_entries = false
+//! value_delimiter = "braces"  # braces, quotes, or preserve
+//! blank_lines = 1
+//! field_order = "standard"
+//!
+//! [field_order]               # per-type templates, over `standard` if set
+//! article = ["author", "title", "journal", "year"]
+//! default = ["author", "title", "year"]
+//!
+//! [lint]
+//! level = "strict"            # minimal, standard, or strict
+//! baseline = "lint-baseline.txt"
+//!
+//! [lint.rules]                # off, info, warning, or error per code
+//! empty-field = "off"
+//! invalid-doi = "error"
+//! ```
+//!
+//! The file is read with a small TOML subset: tables, bare or quoted keys,
+//! strings, integers, booleans, arrays, and `#` comments. Unknown tables
+//! and keys are errors, so typos do not go unnoticed.
+
+use crate::{
+    DelimiterStyle, DiagnosticSeverity, Error, FieldOrder, LintOptions, Parser, ParserProfile,
+    Result, TabularStyle, TrailingComma, ValidationLevel, WriterConfig,
+};
+use std::path::{Path, PathBuf};
+
+/// Configuration file names, in lookup order within a directory.
+pub const CONFIG_FILE_NAMES: [&str; 2] = [".bibfmt.toml", "bibtex.toml"];
+
+/// Settings loaded from a configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// File the settings were read from, if any.
+    pub path: Option<PathBuf>,
+    /// Parser profile from `[parser] profile`.
+    pub profile: Option<ParserProfile>,
+    /// Writer settings from `[format]` and `[field_order]`.
+    pub writer: WriterConfig,
+    /// Lint level and rule severities from `[lint]` and `[lint.rules]`.
+    pub lint: LintOptions,
+    /// Lint baseline file from `[lint] baseline`, relative to the
+    /// configuration file when loaded from one.
+    pub baseline: Option<PathBuf>,
+}
+
+impl Config {
+    /// Find and load the nearest configuration file in `dir` or a parent
+    /// directory.
+    ///
+    /// A relative `dir` is taken from the current working directory. Returns
+    /// `Ok(None)` when no directory up to the filesystem root has one.
+    pub fn discover(dir: impl AsRef<Path>) -> Result<Option<Self>> {
+        let dir = std::env::current_dir()?.join(dir);
+        for dir in dir.ancestors() {
+            for name in CONFIG_FILE_NAMES {
+                let path = dir.join(name);
+                if path.is_file() {
+                    return Self::load(path).map(Some);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load a configuration file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let mut config = Self::parse(&text).map_err(|error| match error {
+            Error::Config(message) => Error::Config(format!("{}: {message}", path.display())),
+            error => error,
+        })?;
+        if let (Some(baseline), Some(dir)) = (&mut config.baseline, path.parent()) {
+            *baseline = dir.join(&*baseline);
+        }
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Parse configuration text.
+    #[allow(clippy::too_many_lines)]
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config = Self::default();
+        let mut standard_order = false;
+        let mut templates = Vec::new();
+        let mut tabular_column = None;
+        let mut tabular_across_file = false;
+        let mut level = ValidationLevel::default();
+        let mut rules = Vec::new();
+
+        for setting in parse_settings(text)? {
+            let Setting {
+                line,
+                table,
+                key,
+                value,
+            } = setting;
+            let error = |message: &str| Error::Config(format!("line {line}: `{key}` {message}"));
+            let flag = || {
+                value
+                    .as_bool()
+                    .ok_or_else(|| error("must be true or false"))
+            };
+            let count = || value.as_count().ok_or_else(|| error("must be a count"));
+            match (table.as_str(), key.as_str()) {
+                ("parser", "profile") => {
+                    config.profile = Some(match value.as_str() {
+                        Some("strict") => ParserProfile::Strict,
+                        Some("tolerant") => ParserProfile::Tolerant,
+                        Some("zotero") => ParserProfile::Zotero,
+                        Some("jabref") => ParserProfile::JabRef,
+                        Some("legacy") => ParserProfile::Legacy,
+                        _ => return Err(error("must be a parser profile name")),
+                    });
+                }
+                ("format", "indent") => {
+                    config.writer.indent = value
+                        .as_str()
+                        .ok_or_else(|| error("must be a string"))?
+                        .to_string();
+                }
+                ("format", "align_values") => {
+                    config.writer.align_values = flag()?;
+                }
+                ("format", "sort_entries") => {
+                    config.writer.sort_entries = flag()?;
+                }
+                ("format", "sort_fields") => {
+                    config.writer.sort_fields = flag()?;
+                }
+                ("format", "trailing_comma") => {
+                    config.writer.trailing_comma = if flag()? {
+                        TrailingComma::Always
+                    } else {
+                        TrailingComma::Omit
+                    };
+                }
+                ("format", "blank_lines") => {
+                    config.writer.blank_lines_between_entries = Some(count()?);
+                }
+                ("format", "value_delimiter") => {
+                    config.writer.value_delimiter = match value.as_str() {
+                        Some("braces") => DelimiterStyle::Braces,
+                        Some("quotes") => DelimiterStyle::Quotes,
+                        Some("preserve") => DelimiterStyle::PreserveOriginal,
+                        _ => return Err(error("must be braces, quotes, or preserve")),
+                    };
+                }
+                ("format", "tabular_column") => {
+                    tabular_column = Some(count()?);
+                }
+                ("format", "tabular_across_file") => {
+                    tabular_across_file = flag()?;
+                }
+                ("format", "field_order") => {
+                    if value.as_str() != Some("standard") {
+                        return Err(error("must be \"standard\""));
+                    }
+                    standard_order = true;
+                }
+                ("field_order", _) => {
+                    let fields = value
+                        .as_string_array()
+                        .ok_or_else(|| error("must be an array of field names"))?;
+                    templates.push((key.clone(), fields));
+                }
+                ("lint", "level") => {
+                    level = match value.as_str() {
+                        Some("minimal") => ValidationLevel::Minimal,
+                        Some("standard") => ValidationLevel::Standard,
+                        Some("strict") => ValidationLevel::Strict,
+                        _ => return Err(error("must be minimal, standard, or strict")),
+                    };
+                }
+                ("lint", "baseline") => {
+                    config.baseline = Some(PathBuf::from(
+                        value.as_str().ok_or_else(|| error("must be a string"))?,
+                    ));
+                }
+                ("lint.rules", _) => {
+                    let severity = match value.as_str() {
+                        Some("off") => None,
+                        Some("info") => Some(DiagnosticSeverity::Info),
+                        Some("warning") => Some(DiagnosticSeverity::Warning),
+                        Some("error") => Some(DiagnosticSeverity::Error),
+                        _ => return Err(error("must be off, info, warning, or error")),
+                    };
+                    rules.push((key.clone(), severity));
+                }
+                ("parser" | "format" | "lint", _) => {
+                    return Err(Error::Config(format!(
+                        "line {line}: unknown key `{key}` in [{table}]"
+                    )))
+                }
+                _ => {
+                    return Err(Error::Config(format!(
+                        "line {line}: unknown table [{table}]"
+                    )))
+                }
+            }
+        }
+
+        if standard_order || !templates.is_empty() {
+            let base = if standard_order {
+                FieldOrder::standard()
+            } else {
+                FieldOrder::new()
+            };
+            config.writer.field_order = Some(templates.into_iter().fold(
+                base,
+                |order, (entry_type, fields)| {
+                    if entry_type == "default" {
+                        order.default_order(fields)
+                    } else {
+                        order.entry_type(&entry_type, fields)
+                    }
+                },
+            ));
+        }
+        config.writer.tabular = tabular_column.map(|column| {
+            let style = TabularStyle::new(column);
+            if tabular_across_file {
+                style.across_file()
+            } else {
+                style
+            }
+        });
+        config.lint =
+            rules
+                .into_iter()
+                .fold(
+                    LintOptions::new(level),
+                    |options, (code, severity)| match severity {
+                        Some(severity) => options.severity(&code, severity),
+                        None => options.suppress(&code),
+                    },
+                );
+        Ok(config)
+    }
+
+    /// Return a parser with the configured profile applied.
+    #[must_use]
+    pub fn parser(&self) -> Parser {
+        self.profile
+            .map_or_else(Parser::new, |profile| Parser::new().profile(profile))
+    }
+}
+
+/// A value in the supported TOML subset.
+#[derive(Debug, Clone, PartialEq)]
+enum TomlValue {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Self>),
+}
+
+impl TomlValue {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_count(&self) -> Option<usize> {
+        match self {
+            Self::Integer(value) => usize::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_string_array(&self) -> Option<Vec<String>> {
+        match self {
+            Self::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// One `key = value` line with the table it belongs to.
+#[derive(Debug)]
+struct Setting {
+    line: usize,
+    table: String,
+    key: String,
+    value: TomlValue,
+}
+
+/// Read `key = value` settings from TOML text.
+fn parse_settings(text: &str) -> Result<Vec<Setting>> {
+    let mut settings = Vec::new();
+    let mut table = String::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let error = |message: &str| Error::Config(format!("line {line_number}: {message}"));
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let (name, rest) = header
+                .split_once(']')
+                .ok_or_else(|| error("unclosed table header"))?;
+            if !is_blank(rest) || name.starts_with('[') {
+                return Err(error("unsupported table header"));
+            }
+            table = name.trim().to_string();
+            continue;
+        }
+
+        let (key, rest) = parse_key(line).ok_or_else(|| error("expected `key = value`"))?;
+        // Arrays may continue over following lines until their brackets close.
+        let mut source = rest.to_string();
+        while source.trim_start().starts_with('[') && !brackets_closed(&source) {
+            let (_, next) = lines.next().ok_or_else(|| error("unclosed array"))?;
+            source.push('\n');
+            source.push_str(next);
+        }
+        let mut cursor = Cursor::new(&source);
+        let value = cursor.value().ok_or_else(|| error("invalid value"))?;
+        if !is_blank(cursor.rest()) {
+            return Err(error("unexpected text after value"));
+        }
+        settings.push(Setting {
+            line: line_number,
+            table: table.clone(),
+            key,
+            value,
+        });
+    }
+    Ok(settings)
+}
+
+/// Split a bare or quoted key from the text after its `=`.
+fn parse_key(line: &str) -> Option<(String, &str)> {
+    let (key, rest) = if let Some(quoted) = line.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else {
+        let end = line.find(|ch: char| !(ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-')))?;
+        (line[..end].to_string(), &line[end..])
+    };
+    let rest = rest.trim_start().strip_prefix('=')?;
+    (!key.is_empty()).then_some((key, rest))
+}
+
+/// Return `true` when `text` holds only whitespace and a comment.
+fn is_blank(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.starts_with('#')
+}
+
+/// Return `true` when every `[` outside strings and comments is closed.
+fn brackets_closed(source: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut comment = false;
+    for ch in source.chars() {
+        match (quote, ch) {
+            _ if comment => comment = ch != '\n',
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(open), ch) if ch == open && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '#') => comment = true,
+            (None, '[') => depth += 1,
+            (None, ']') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        escaped = false;
+    }
+    depth == 0
+}
+
+/// Reader for a single TOML value.
+struct Cursor<'a> {
+    text: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    const fn new(text: &'a str) -> Self {
+        Self { text }
+    }
+
+    const fn rest(&self) -> &'a str {
+        self.text
+    }
+
+    /// Skip whitespace, newlines, and comments.
+    fn skip_space(&mut self) {
+        loop {
+            self.text = self.text.trim_start();
+            match self.text.strip_prefix('#') {
+                Some(comment) => {
+                    self.text = comment.find('\n').map_or("", |end| &comment[end..]);
+                }
+                None => return,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Option<TomlValue> {
+        self.skip_space();
+        if let Some(rest) = self.text.strip_prefix('"') {
+            self.text = rest;
+            return self.basic_string().map(TomlValue::String);
+        }
+        if let Some(rest) = self.text.strip_prefix('\'') {
+            let end = rest.find('\'')?;
+            self.text = &rest[end + 1..];
+            return Some(TomlValue::String(rest[..end].to_string()));
+        }
+        if let Some(rest) = self.text.strip_prefix('[') {
+            self.text = rest;
+            return self.array();
+        }
+        let end = self
+            .text
+            .find(|ch: char| ch.is_whitespace() || matches!(ch, ',' | ']' | '#'))
+            .unwrap_or(self.text.len());
+        let (token, rest) = self.text.split_at(end);
+        self.text = rest;
+        match token {
+            "true" => Some(TomlValue::Boolean(true)),
+            "false" => Some(TomlValue::Boolean(false)),
+            _ => token.replace('_', "").parse().ok().map(TomlValue::Integer),
+        }
+    }
+
+    /// Read the rest of a `"..."` string after its opening quote.
+    fn basic_string(&mut self) -> Option<String> {
+        let mut value = String::new();
+        let mut chars = self.text.char_indices();
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                '"' => {
+                    self.text = &self.text[index + 1..];
+                    return Some(value);
+                }
+                '\\' => {
+                    let (_, escape) = chars.next()?;
+                    value.push(match escape {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        '"' => '"',
+                        '\\' => '\\',
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, ch)| ch).collect();
+                            char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                        }
+                        _ => return None,
+                    });
+                }
+                '\n' => return None,
+                ch => value.push(ch),
+            }
+        }
+        None
+    }
+
+    /// Read the rest of an array after its opening bracket.
+    fn array(&mut self) -> Option<TomlValue> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_space();
+            if let Some(rest) = self.text.strip_prefix(']') {
+                self.text = rest;
+                return Some(TomlValue::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_space();
+            if let Some(rest) = self.text.strip_prefix(',') {
+                self.text = rest;
+            } else if !self.text.starts_with(']') {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_subset_reads_strings_arrays_and_comments() {
+        let settings = parse_settings(
+            "# top\n[format]\nindent = \"\\t\" # tab\n\"quoted-key\" = 'C:\\path'\n\n[field_order]\narticle = [\n  \"author\", # first\n  \"title\",\n]\ncount = 1_000\n",
+        )
+        .unwrap();
+        let values: Vec<_> = settings
+            .iter()
+            .map(|setting| (setting.table.as_str(), setting.key.as_str(), &setting.value))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("format", "indent", &TomlValue::String("\t".into())),
+                (
+                    "format",
+                    "quoted-key",
+                    &TomlValue::String("C:\\path".into())
+                ),
+                (
+                    "field_order",
+                    "article",
+                    &TomlValue::Array(vec![
+                        TomlValue::String("author".into()),
+                        TomlValue::String("title".into())
+                    ])
+                ),
+                ("field_order", "count", &TomlValue::Integer(1000)),
+            ]
+        );
+        assert_eq!(settings[2].line, 7);
+        assert!(parse_settings("key = \"open").is_err());
+        assert!(parse_settings("key = [1, 2").is_err());
+        assert!(parse_settings("[table\n").is_err());
+    }
+}
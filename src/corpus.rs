@@ -204,7 +204,12 @@ fn corpus_status(documents: &[ParsedDocument<'_>]) -> ParseStatus {
         .iter()
         .any(|document| document.status() != ParseStatus::Ok);
 
-    if !has_problem {
+    if documents
+        .iter()
+        .any(|document| document.status() == ParseStatus::Cancelled)
+    {
+        ParseStatus::Cancelled
+    } else if !has_problem {
         ParseStatus::Ok
     } else if has_content {
         ParseStatus::Partial
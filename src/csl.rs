@@ -0,0 +1,255 @@
+//! CSL JSON export for Pandoc citeproc.
+//!
+//! Entries map to CSL items with the conventions Pandoc's own BibTeX reader
+//! uses: the key becomes `id`, the entry type a CSL `type`, name fields
+//! structured name objects, and the entry date `issued` date parts. Text is
+//! unbraced and simple escapes such as `\&` are resolved; with the
+//! `latex_to_unicode` feature LaTeX accents are converted too.
+
+use crate::json::Json;
+use crate::{Entry, EntryType, Library, PersonName};
+
+/// `(BibTeX field, CSL variable)` pairs copied as text.
+const TEXT_FIELDS: &[(&str, &str)] = &[
+    ("series", "collection-title"),
+    ("edition", "edition"),
+    ("volume", "volume"),
+    ("chapter", "chapter-number"),
+    ("isbn", "ISBN"),
+    ("issn", "ISSN"),
+    ("url", "URL"),
+    ("note", "note"),
+    ("abstract", "abstract"),
+    ("keywords", "keyword"),
+    ("langid", "language"),
+    ("language", "language"),
+];
+
+impl Library<'_> {
+    /// Render entries as a CSL JSON array, usable as a Pandoc
+    /// `--bibliography` file.
+    ///
+    /// Data-only `@xdata` and `@set` entries are skipped. Call
+    /// [`Library::inherit_crossref_fields`] first to include fields from
+    /// cross-referenced parents.
+    ///
+    /// ```
+    /// use bibtex_parser::Library;
+    ///
+    /// let library = Library::parse(
+    ///     "@article{doe, author = {Doe, Jane}, title = {On {NASA}}, journal = {J}, year = 2024}",
+    /// )?;
+    /// let json = library.to_csl_json();
+    /// assert!(json.contains(r#""type": "article-journal""#));
+    /// assert!(json.contains(r#""title": "On NASA""#));
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_csl_json(&self) -> String {
+        self.csl_items().pretty()
+    }
+
+    /// Render entries as a Pandoc metadata object with a `references` list.
+    ///
+    /// The output is valid for `--metadata-file` and, being JSON, for a YAML
+    /// metadata block. Items are the same as [`Library::to_csl_json`].
+    #[must_use]
+    pub fn to_pandoc_references(&self) -> String {
+        let mut metadata = Json::object();
+        metadata.insert("references", self.csl_items());
+        metadata.pretty()
+    }
+
+    fn csl_items(&self) -> Json {
+        Json::Array(
+            self.entries()
+                .iter()
+                .filter(|entry| !entry.ty.is_data_only() && entry.ty != EntryType::Set)
+                .map(csl_item)
+                .collect(),
+        )
+    }
+}
+
+fn csl_item(entry: &Entry<'_>) -> Json {
+    let text = |name: &str| {
+        entry
+            .get_as_string_ignore_case(name)
+            .map(|value| csl_text(&value))
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut item = Json::object();
+    item.insert("id", entry.key());
+    item.insert("type", csl_type(entry));
+
+    for (field, variable) in [
+        ("author", "author"),
+        ("editor", "editor"),
+        ("translator", "translator"),
+    ] {
+        if let Some(names) = entry.get_as_string_ignore_case(field) {
+            let names = crate::parse_name_list(&names);
+            if !names.is_empty() {
+                let mut json = names.iter().map(csl_name).collect::<Vec<_>>();
+                if names.truncated {
+                    // CSL has no et al. flag; keep BibTeX's marker as a literal name.
+                    let mut others = Json::object();
+                    others.insert("literal", "others");
+                    json.push(others);
+                }
+                item.insert(variable, json);
+            }
+        }
+    }
+
+    if let Some(title) = text("title") {
+        match text("subtitle") {
+            Some(subtitle) => item.insert("title", format!("{title}: {subtitle}")),
+            None => item.insert("title", title),
+        }
+    }
+    if let Some(container) = text("journaltitle")
+        .or_else(|| text("journal"))
+        .or_else(|| text("booktitle"))
+    {
+        item.insert("container-title", container);
+    }
+    if let Some(publisher) = text("publisher")
+        .or_else(|| text("school"))
+        .or_else(|| text("institution"))
+        .or_else(|| text("organization"))
+    {
+        item.insert("publisher", publisher);
+    }
+    if let Some(place) = text("location").or_else(|| text("address")) {
+        item.insert("publisher-place", place);
+    }
+    if let Some(number) = text("number").or_else(|| text("issue")) {
+        let variable = if entry.ty == EntryType::Article {
+            "issue"
+        } else {
+            "number"
+        };
+        item.insert(variable, number);
+    }
+    if let Some(pages) = text("pages") {
+        item.insert("page", pages.replace("--", "-"));
+    }
+    if let Some(genre) = text("type").or_else(|| thesis_genre(&entry.ty).map(str::to_string)) {
+        item.insert("genre", genre);
+    }
+    if let Some(doi) = entry.doi() {
+        item.insert("DOI", doi);
+    }
+
+    let mut seen = Vec::new();
+    for &(field, variable) in TEXT_FIELDS {
+        if seen.contains(&variable) {
+            continue;
+        }
+        if let Some(value) = text(field) {
+            item.insert(variable, value);
+            seen.push(variable);
+        }
+    }
+
+    if let Some(Ok(parts)) = entry.date_parts() {
+        item.insert("issued", date_parts(parts));
+    }
+    if let Some(Ok(parts)) = entry.date_parts_for("urldate") {
+        item.insert("accessed", date_parts(parts));
+    }
+    item
+}
+
+/// CSL type names, following Pandoc's BibTeX reader.
+const fn csl_type(entry: &Entry<'_>) -> &'static str {
+    match &entry.ty {
+        EntryType::Article => "article-journal",
+        EntryType::Book
+        | EntryType::MvBook
+        | EntryType::Collection
+        | EntryType::MvCollection
+        | EntryType::Proceedings
+        | EntryType::MvProceedings
+        | EntryType::Reference
+        | EntryType::Manual => "book",
+        EntryType::Booklet => "pamphlet",
+        EntryType::InBook
+        | EntryType::BookInBook
+        | EntryType::SuppBook
+        | EntryType::InCollection
+        | EntryType::SuppCollection => "chapter",
+        EntryType::InReference => "entry-encyclopedia",
+        EntryType::InProceedings => "paper-conference",
+        EntryType::MastersThesis | EntryType::PhdThesis | EntryType::Thesis => "thesis",
+        EntryType::TechReport | EntryType::Report => "report",
+        EntryType::Patent => "patent",
+        EntryType::Periodical => "periodical",
+        EntryType::Online => "webpage",
+        EntryType::Software => "software",
+        EntryType::Dataset => "dataset",
+        EntryType::Unpublished => "manuscript",
+        EntryType::Misc | EntryType::Set | EntryType::XData | EntryType::Custom(_) => "document",
+    }
+}
+
+const fn thesis_genre(ty: &EntryType<'_>) -> Option<&'static str> {
+    match ty {
+        EntryType::MastersThesis => Some("Master's thesis"),
+        EntryType::PhdThesis => Some("PhD thesis"),
+        _ => None,
+    }
+}
+
+fn csl_name(name: &PersonName) -> Json {
+    let mut json = Json::object();
+    if let Some(literal) = &name.literal {
+        json.insert("literal", csl_text(literal));
+        return json;
+    }
+    for (part, key) in [
+        (&name.last, "family"),
+        (&name.first, "given"),
+        (&name.von, "non-dropping-particle"),
+        (&name.jr, "suffix"),
+    ] {
+        if !part.is_empty() {
+            json.insert(key, csl_text(part));
+        }
+    }
+    json
+}
+
+fn date_parts(parts: crate::DateParts) -> Json {
+    let mut date = vec![Json::Number(i64::from(parts.year))];
+    date.extend(parts.month.map(|month| Json::Number(i64::from(month))));
+    date.extend(parts.day.map(|day| Json::Number(i64::from(day))));
+    let mut json = Json::object();
+    json.insert("date-parts", vec![Json::Array(date)]);
+    json
+}
+
+/// Strip grouping braces and resolve escaped specials.
+fn csl_text(value: &str) -> String {
+    #[cfg(feature = "latex_to_unicode")]
+    let value = &crate::latex_unicode::latex_to_unicode(value);
+
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars
+                .peek()
+                .is_some_and(|next| matches!(next, '&' | '%' | '$' | '#' | '_' | '{' | '}')) =>
+            {
+                text.extend(chars.next());
+            }
+            '{' | '}' => {}
+            '~' => text.push('\u{a0}'),
+            _ => text.push(ch),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
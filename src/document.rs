@@ -4,15 +4,19 @@
 //! contains source-order blocks, per-item metadata, retained raw text,
 //! diagnostics, and partial parse results.
 
-use crate::library::BlockKind;
+#[cfg(feature = "key_canonicalization")]
+use crate::keys::KeyCanonicalization;
+use crate::keys::{rename_key_list, KeyRenameMap, KEY_REFERENCE_FIELDS};
 use crate::library::RawBuildItem;
-use crate::model::normalize_text_projection;
-use crate::source::SourceCursor;
+use crate::library::{BlockKind, MacroResolvers, ParseContinuation};
+use crate::model::{collect_variable_names, normalize_text_projection, reachable_variable_names};
+use crate::source::{SourceCursor, SourceOffset};
 use crate::{
     normalize_doi, Comment, DateParseError, DateParts, Entry, EntryType, FailedBlock, Field,
-    Library, PersonName, Preamble, ResourceField, SourceId, SourceMap, SourceSpan,
+    FileAttachment, Library, PersonName, Preamble, ResourceField, SourceId, SourceMap, SourceSpan,
     StringDefinition, Value,
 };
+use ahash::AHashMap;
 use std::borrow::Cow;
 use std::fmt;
 
@@ -25,6 +29,8 @@ pub enum ParseStatus {
     Partial,
     /// The document could not produce meaningful bibliography data.
     Failed,
+    /// Parsing was cancelled; the document holds the blocks parsed before.
+    Cancelled,
 }
 
 /// Diagnostic severity.
@@ -44,7 +50,8 @@ pub enum DiagnosticSeverity {
 /// `missing-entry-key`, `missing-field-separator`, `expected-field-name`,
 /// `empty-field-value`, `expected-value-atom`, `bad-field-boundary`,
 /// `bad-value-boundary`, `unclosed-entry`, `unclosed-braced-value`, and
-/// `unclosed-quoted-value`.
+/// `unclosed-quoted-value`. Validation findings carry codes such as
+/// `missing-required-field` and `invalid-doi`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DiagnosticCode(Cow<'static, str>);
 
@@ -71,6 +78,41 @@ impl DiagnosticCode {
     pub const UNCLOSED_BRACED_VALUE: Self = Self(Cow::Borrowed("unclosed-braced-value"));
     /// Quoted field value ended before its closing quote was found.
     pub const UNCLOSED_QUOTED_VALUE: Self = Self(Cow::Borrowed("unclosed-quoted-value"));
+    /// Bare digit-led value was kept as literal text instead of an integer.
+    pub const NUMERIC_LITERAL_FALLBACK: Self = Self(Cow::Borrowed("numeric-literal-fallback"));
+    /// Parsing stopped at a cancellation request.
+    pub const PARSE_CANCELLED: Self = Self(Cow::Borrowed("parse-cancelled"));
+    /// Parsing stopped because its budget ran out.
+    pub const PARSE_BUDGET_EXHAUSTED: Self = Self(Cow::Borrowed("parse-budget-exhausted"));
+
+    /// Validation finding without a more specific code.
+    pub const VALIDATION: Self = Self(Cow::Borrowed("validation"));
+    /// A field the entry type requires is missing.
+    pub const MISSING_REQUIRED_FIELD: Self = Self(Cow::Borrowed("missing-required-field"));
+    /// Neither `author` nor `editor` is present.
+    pub const MISSING_AUTHOR_OR_EDITOR: Self = Self(Cow::Borrowed("missing-author-or-editor"));
+    /// The year is a number outside the plausible range.
+    pub const IMPLAUSIBLE_YEAR: Self = Self(Cow::Borrowed("implausible-year"));
+    /// The year or date is not a number.
+    pub const INVALID_YEAR: Self = Self(Cow::Borrowed("invalid-year"));
+    /// The `pages` field is not a page or page range.
+    pub const BAD_PAGE_RANGE: Self = Self(Cow::Borrowed("bad-page-range"));
+    /// A field value is empty.
+    pub const EMPTY_FIELD: Self = Self(Cow::Borrowed("empty-field"));
+    /// The `doi` field is not a DOI.
+    pub const INVALID_DOI: Self = Self(Cow::Borrowed("invalid-doi"));
+    /// The `url` field is not an HTTP URL.
+    pub const INVALID_URL: Self = Self(Cow::Borrowed("invalid-url"));
+    /// The `isbn` field does not have 10 or 13 digits.
+    pub const INVALID_ISBN: Self = Self(Cow::Borrowed("invalid-isbn"));
+    /// The `month` field is not a month name or abbreviation.
+    pub const UNKNOWN_MONTH: Self = Self(Cow::Borrowed("unknown-month"));
+    /// A language field names no known language.
+    pub const UNKNOWN_LANGUAGE: Self = Self(Cow::Borrowed("unknown-language"));
+    /// `volume` or `number` is not numeric.
+    pub const NON_NUMERIC_FIELD: Self = Self(Cow::Borrowed("non-numeric-field"));
+    /// The `crossref` field is empty.
+    pub const EMPTY_CROSSREF: Self = Self(Cow::Borrowed("empty-crossref"));
 
     /// Create a borrowed static diagnostic code.
     #[must_use]
@@ -160,6 +202,20 @@ impl Diagnostic {
         }
     }
 
+    /// Create a warning diagnostic.
+    #[must_use]
+    pub fn warning(
+        code: DiagnosticCode,
+        message: impl Into<String>,
+        target: DiagnosticTarget,
+        source: Option<SourceSpan>,
+    ) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            ..Self::error(code, message, target, source)
+        }
+    }
+
     /// Attach source context to this diagnostic.
     #[must_use]
     pub fn with_snippet(mut self, snippet: impl Into<String>) -> Self {
@@ -375,12 +431,19 @@ pub enum ValueDelimiter {
 }
 
 /// Policy for variables that cannot be resolved during value expansion.
+///
+/// Used by [`ExpansionOptions`] for expanded text and by
+/// [`Parser::undefined_variables`](crate::Parser::undefined_variables) for
+/// library values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnresolvedVariablePolicy {
-    /// Keep the variable name as ordinary text.
+    /// Keep the variable: its name as expanded text, or the variable itself
+    /// in library values.
     Preserve,
     /// Render unresolved variables as `{undefined:name}`.
     Placeholder,
+    /// Replace unresolved variables with empty text.
+    Empty,
     /// Return an error for the first unresolved variable.
     Error,
 }
@@ -394,6 +457,10 @@ pub struct ExpansionOptions {
     pub expand_months: bool,
     /// Behavior when a variable cannot be resolved.
     pub unresolved_variables: UnresolvedVariablePolicy,
+    /// Limit on nested `@string` references followed for one value.
+    pub max_depth: usize,
+    /// Limit on the length in bytes of one expanded value.
+    pub max_length: usize,
 }
 
 impl Default for ExpansionOptions {
@@ -402,12 +469,14 @@ impl Default for ExpansionOptions {
             expand_strings: true,
             expand_months: true,
             unresolved_variables: UnresolvedVariablePolicy::Error,
+            max_depth: crate::library::DEFAULT_MAX_EXPANSION_DEPTH,
+            max_length: crate::library::DEFAULT_MAX_EXPANSION_LENGTH,
         }
     }
 }
 
 /// Parsed BibTeX value plus optional source-preserving metadata.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedValue<'a> {
     /// Structured value.
     pub value: Value<'a>,
@@ -507,7 +576,7 @@ impl<'a> ParsedValue<'a> {
 }
 
 /// Parsed field plus optional source-preserving metadata.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedField<'a> {
     /// Field name as it appeared after parsing.
     pub name: Cow<'a, str>,
@@ -521,6 +590,14 @@ pub struct ParsedField<'a> {
     pub name_source: Option<SourceSpan>,
     /// Source location for the field value, when available.
     pub value_source: Option<SourceSpan>,
+    /// Trailing `%` comment on the field's line, `%` included.
+    ///
+    /// A comment counts as trailing when it starts on the line where the
+    /// value ends, before or after the separating comma. Comments on their
+    /// own line inside an entry are skipped and not attached to any field.
+    pub comment: Option<Cow<'a, str>>,
+    /// Source location for the trailing comment, when available.
+    pub comment_source: Option<SourceSpan>,
 }
 
 impl<'a> ParsedField<'a> {
@@ -534,6 +611,8 @@ impl<'a> ParsedField<'a> {
             source: None,
             name_source: None,
             value_source: None,
+            comment: None,
+            comment_source: None,
         }
     }
 
@@ -556,6 +635,8 @@ impl<'a> ParsedField<'a> {
             source: self.source,
             name_source: self.name_source,
             value_source: self.value_source,
+            comment: self.comment.map(|comment| Cow::Owned(comment.into_owned())),
+            comment_source: self.comment_source,
         }
     }
 
@@ -567,6 +648,8 @@ impl<'a> ParsedField<'a> {
             source: None,
             name_source: None,
             value_source: None,
+            comment: None,
+            comment_source: None,
         }
     }
 }
@@ -578,6 +661,8 @@ pub struct ParsedEntry<'a> {
     pub ty: EntryType<'a>,
     /// Citation key.
     pub key: Cow<'a, str>,
+    /// Source spelling of the citation key when key canonicalization changed it.
+    pub original_key: Option<Cow<'a, str>>,
     /// Parsed fields in source order.
     pub fields: Vec<ParsedField<'a>>,
     /// Whether the entry is complete or recovered.
@@ -586,6 +671,8 @@ pub struct ParsedEntry<'a> {
     pub source: Option<SourceSpan>,
     /// Source location for the entry type token, when available.
     pub entry_type_source: Option<SourceSpan>,
+    /// Source spelling of the entry type when it differs from the canonical name.
+    pub entry_type_spelling: Option<Cow<'a, str>>,
     /// Source location for the citation key token, when available.
     pub key_source: Option<SourceSpan>,
     /// Entry body delimiter, when retained.
@@ -604,6 +691,7 @@ impl<'a> ParsedEntry<'a> {
         Self {
             ty: entry.ty,
             key: entry.key,
+            original_key: None,
             fields: entry
                 .fields
                 .into_iter()
@@ -612,6 +700,7 @@ impl<'a> ParsedEntry<'a> {
             status: ParsedEntryStatus::Complete,
             source,
             entry_type_source: None,
+            entry_type_spelling: None,
             key_source: None,
             delimiter: None,
             raw: None,
@@ -627,6 +716,7 @@ impl<'a> ParsedEntry<'a> {
         ParsedEntry {
             ty: entry.ty.into_owned(),
             key: Cow::Owned(entry.key.into_owned()),
+            original_key: None,
             fields: entry
                 .fields
                 .into_iter()
@@ -635,6 +725,7 @@ impl<'a> ParsedEntry<'a> {
             status: ParsedEntryStatus::Complete,
             source,
             entry_type_source: None,
+            entry_type_spelling: None,
             key_source: None,
             delimiter: None,
             raw: None,
@@ -662,6 +753,11 @@ impl<'a> ParsedEntry<'a> {
     ) -> ParsedEntry<'static> {
         let entry = located.entry;
         let entry_type_source = span_cursor.span(located.entry_type.0, located.entry_type.1);
+        let spelling = entry_type_spelling(
+            &entry.ty,
+            span_cursor.slice(located.entry_type.0, located.entry_type.1),
+        )
+        .map(|spelling| Cow::Owned(spelling.into_owned()));
         let key_source = span_cursor.span(located.key.0, located.key.1);
         let fields = entry
             .fields
@@ -671,6 +767,11 @@ impl<'a> ParsedEntry<'a> {
                 let field_source = span_cursor.span(location.whole.0, location.whole.1);
                 let name_source = span_cursor.span(location.name.0, location.name.1);
                 let value_source = span_cursor.span(location.value.0, location.value.1);
+                let comment = location.comment.and_then(|(start, end)| {
+                    let text = span_cursor.slice(start, end)?.to_string();
+                    Some((Cow::Owned(text), span_cursor.span(start, end)))
+                });
+                let (comment, comment_source) = comment.unzip();
                 ParsedField {
                     name: owned_field_name(field.name),
                     value: ParsedValue::from_owned_value(
@@ -682,6 +783,8 @@ impl<'a> ParsedEntry<'a> {
                     source: Some(field_source),
                     name_source: Some(name_source),
                     value_source: Some(value_source),
+                    comment,
+                    comment_source,
                 }
             })
             .collect();
@@ -689,10 +792,12 @@ impl<'a> ParsedEntry<'a> {
         ParsedEntry {
             ty: entry.ty.into_owned(),
             key: Cow::Owned(entry.key.into_owned()),
+            original_key: None,
             fields,
             status: ParsedEntryStatus::Complete,
             source: Some(source),
             entry_type_source: Some(entry_type_source),
+            entry_type_spelling: spelling,
             key_source: Some(key_source),
             delimiter: Some(located.delimiter),
             raw: None,
@@ -709,8 +814,10 @@ impl<'a> ParsedEntry<'a> {
             return;
         };
 
-        self.entry_type_source =
-            Some(source_map.span(locations.entry_type.0, locations.entry_type.1));
+        let entry_type_source = source_map.span(locations.entry_type.0, locations.entry_type.1);
+        self.entry_type_spelling =
+            entry_type_spelling(&self.ty, source_map.slice(entry_type_source));
+        self.entry_type_source = Some(entry_type_source);
         self.key_source = Some(source_map.span(locations.key.0, locations.key.1));
         self.delimiter = Some(locations.delimiter);
         if preserve_raw {
@@ -723,6 +830,13 @@ impl<'a> ParsedEntry<'a> {
             field.value.source = Some(source_map.span(location.value.0, location.value.1));
             field.value_source = field.value.source;
             field.value.delimiter = Some(location.value_delimiter);
+            field.comment_source = location
+                .comment
+                .map(|(start, end)| source_map.span(start, end));
+            field.comment = field
+                .comment_source
+                .and_then(|span| source_map.slice(span))
+                .map(Cow::Borrowed);
 
             if preserve_raw {
                 if let Some(source) = field.source.and_then(|span| source_map.slice(span)) {
@@ -758,6 +872,96 @@ impl<'a> ParsedEntry<'a> {
     /// Rename the citation key.
     pub fn rename_key(&mut self, key: impl Into<Cow<'a, str>>) {
         self.key = key.into();
+        self.original_key = None;
+    }
+
+    #[cfg(feature = "key_canonicalization")]
+    pub(crate) fn canonicalize_key(&mut self, canonicalization: KeyCanonicalization) {
+        if let Cow::Owned(key) = canonicalization.canonicalize(&self.key) {
+            if key != self.key {
+                let original = std::mem::replace(&mut self.key, Cow::Owned(key));
+                self.original_key.get_or_insert(original);
+            }
+        }
+    }
+
+    /// Canonicalize the keys named in this entry's reference fields.
+    ///
+    /// Used where the other entries are not at hand, such as while streaming.
+    #[cfg(feature = "key_canonicalization")]
+    pub(crate) fn canonicalize_key_references(&mut self, canonicalization: KeyCanonicalization) {
+        let renames: KeyRenameMap = self
+            .fields
+            .iter()
+            .filter(|field| is_key_reference_field(&field.name))
+            .flat_map(|field| {
+                field
+                    .value
+                    .value
+                    .to_plain_string()
+                    .split(',')
+                    .filter_map(|key| {
+                        let key = key.trim();
+                        match canonicalization.canonicalize(key) {
+                            Cow::Owned(new) if new != key => Some((key.to_string(), new)),
+                            _ => None,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        self.rename_key_references(&renames);
+    }
+
+    /// Rewrite `crossref`, `xdata`, `related`, `ids`, and `entryset` fields using `renames`.
+    /// Warn about bare numbers that were kept as literal text, returning
+    /// whether any warning was added.
+    ///
+    /// The value parser keeps leading zeros (`07`), trailing punctuation
+    /// (`2020.`), and integers outside the `i64` range as literals so they
+    /// round-trip; digit-led words such as `2020a` are literals by design and
+    /// are not reported. Signed tokens are skipped when the parser keeps them
+    /// as literals on purpose.
+    pub(crate) fn add_numeric_literal_diagnostics(
+        &mut self,
+        entry_index: usize,
+        signed_as_literals: bool,
+    ) -> bool {
+        let mut added = false;
+        for (field_index, field) in self.fields.iter().enumerate() {
+            if field.value.delimiter != Some(ValueDelimiter::Bare) {
+                continue;
+            }
+            let Some(text) = lossy_number_text(&field.value.value, signed_as_literals) else {
+                continue;
+            };
+            self.diagnostics.push(Diagnostic::warning(
+                DiagnosticCode::NUMERIC_LITERAL_FALLBACK,
+                format!("Bare value `{text}` is not a plain integer and was kept as text"),
+                DiagnosticTarget::Value {
+                    entry: entry_index,
+                    field: field_index,
+                },
+                field.value_source,
+            ));
+            added = true;
+        }
+        added
+    }
+
+    pub(crate) fn rename_key_references(&mut self, renames: &KeyRenameMap) {
+        if renames.is_empty() {
+            return;
+        }
+        for field in &mut self.fields {
+            if !is_key_reference_field(&field.name) {
+                continue;
+            }
+            if let Some(renamed) = rename_key_list(&field.value.value.to_plain_string(), renames) {
+                field.value = ParsedValue::new(Value::Literal(Cow::Owned(renamed)));
+                field.raw = None;
+            }
+        }
     }
 
     /// Replace the entry type.
@@ -774,6 +978,8 @@ impl<'a> ParsedEntry<'a> {
             source: None,
             name_source: None,
             value_source: None,
+            comment: None,
+            comment_source: None,
         });
     }
 
@@ -875,10 +1081,13 @@ impl<'a> ParsedEntry<'a> {
     fn remove_field_index(&mut self, index: usize) {
         let field = self.fields.remove(index);
         if let Some(source) = field.source {
-            match &mut self.removed_field_sources {
-                Some(sources) => sources.push(source),
-                None => {
-                    self.removed_field_sources = Some(Box::new(RemovedFieldSources::One(source)));
+            for source in std::iter::once(source).chain(field.comment_source) {
+                match &mut self.removed_field_sources {
+                    Some(sources) => sources.push(source),
+                    None => {
+                        self.removed_field_sources =
+                            Some(Box::new(RemovedFieldSources::One(source)));
+                    }
                 }
             }
         } else {
@@ -957,12 +1166,20 @@ impl<'a> ParsedEntry<'a> {
         self.clone().into_entry().resource_fields()
     }
 
+    /// Parse the annotated `file` field into structured attachments.
+    #[must_use]
+    pub fn attachments(&self) -> Vec<FileAttachment> {
+        self.get_as_string_ignore_case("file")
+            .map_or_else(Vec::new, |file| crate::parse_file_attachments(&file))
+    }
+
     /// Convert this parsed entry into an owned value.
     #[must_use]
     pub fn into_owned(self) -> ParsedEntry<'static> {
         ParsedEntry {
             ty: self.ty.into_owned(),
             key: Cow::Owned(self.key.into_owned()),
+            original_key: self.original_key.map(|key| Cow::Owned(key.into_owned())),
             fields: self
                 .fields
                 .into_iter()
@@ -971,6 +1188,9 @@ impl<'a> ParsedEntry<'a> {
             status: self.status,
             source: self.source,
             entry_type_source: self.entry_type_source,
+            entry_type_spelling: self
+                .entry_type_spelling
+                .map(|spelling| Cow::Owned(spelling.into_owned())),
             key_source: self.key_source,
             delimiter: self.delimiter,
             raw: self.raw.map(|raw| Cow::Owned(raw.into_owned())),
@@ -980,6 +1200,12 @@ impl<'a> ParsedEntry<'a> {
     }
 }
 
+fn entry_type_spelling<'a>(ty: &EntryType<'_>, spelling: Option<&'a str>) -> Option<Cow<'a, str>> {
+    spelling
+        .filter(|spelling| *spelling != ty.canonical_name())
+        .map(Cow::Borrowed)
+}
+
 fn owned_field_name(name: Cow<'_, str>) -> Cow<'static, str> {
     static_field_name(&name).map_or_else(|| Cow::Owned(name.into_owned()), Cow::Borrowed)
 }
@@ -1041,7 +1267,7 @@ fn nth_field_index(fields: &[ParsedField<'_>], name: &str, occurrence: usize) ->
 }
 
 /// Parsed string definition plus optional source-preserving metadata.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedString<'a> {
     /// String variable name.
     pub name: Cow<'a, str>,
@@ -1119,7 +1345,7 @@ impl<'a> ParsedString<'a> {
 }
 
 /// Parsed preamble plus optional source-preserving metadata.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedPreamble<'a> {
     /// Parsed preamble value.
     pub value: ParsedValue<'a>,
@@ -1301,6 +1527,7 @@ pub struct ParsedDocument<'a> {
     blocks: Vec<ParsedBlock>,
     diagnostics: Vec<Diagnostic>,
     status: ParseStatus,
+    continuation: Option<ParseContinuation>,
 }
 
 impl<'a> ParsedDocument<'a> {
@@ -1333,7 +1560,14 @@ impl<'a> ParsedDocument<'a> {
             .iter()
             .cloned()
             .enumerate()
-            .map(|(index, entry)| ParsedEntry::from_entry(entry, library.entry_source(index)))
+            .map(|(index, entry)| {
+                let mut parsed = ParsedEntry::from_entry(entry, library.entry_source(index));
+                parsed.original_key = library
+                    .entry_id(index)
+                    .and_then(|id| library.original_key(id))
+                    .map(|key| Cow::Owned(key.to_string()));
+                parsed
+            })
             .collect();
         let strings: Vec<ParsedString<'a>> = library
             .strings()
@@ -1394,6 +1628,7 @@ impl<'a> ParsedDocument<'a> {
             blocks,
             diagnostics,
             status,
+            continuation: None,
         }
     }
 
@@ -1417,6 +1652,7 @@ impl<'a> ParsedDocument<'a> {
             blocks,
             diagnostics: Vec::new(),
             status: ParseStatus::Ok,
+            continuation: None,
         }
     }
 
@@ -1437,8 +1673,10 @@ impl<'a> ParsedDocument<'a> {
             return;
         };
 
-        entry.entry_type_source =
-            Some(source_map.span(locations.entry_type.0, locations.entry_type.1));
+        let entry_type_source = source_map.span(locations.entry_type.0, locations.entry_type.1);
+        entry.entry_type_spelling =
+            entry_type_spelling(&entry.ty, source_map.slice(entry_type_source));
+        entry.entry_type_source = Some(entry_type_source);
         entry.key_source = Some(source_map.span(locations.key.0, locations.key.1));
         entry.delimiter = Some(locations.delimiter);
         if preserve_raw {
@@ -1451,6 +1689,13 @@ impl<'a> ParsedDocument<'a> {
             field.value.source = Some(source_map.span(location.value.0, location.value.1));
             field.value_source = field.value.source;
             field.value.delimiter = Some(location.value_delimiter);
+            field.comment_source = location
+                .comment
+                .map(|(start, end)| source_map.span(start, end));
+            field.comment = field
+                .comment_source
+                .and_then(|span| source_map.slice(span))
+                .map(Cow::Borrowed);
 
             if preserve_raw {
                 if let Some(source) = field.source.and_then(|span| source_map.slice(span)) {
@@ -1541,25 +1786,29 @@ impl<'a> ParsedDocument<'a> {
     pub(crate) fn populate_expanded_values(
         &mut self,
         options: ExpansionOptions,
+        predefined: &[StringDefinition<'static>],
+        resolvers: &MacroResolvers,
     ) -> crate::Result<()> {
-        let strings = &self.strings;
+        let combined;
+        let strings = if predefined.is_empty() {
+            &self.strings
+        } else {
+            combined = predefined
+                .iter()
+                .cloned()
+                .map(ParsedString::from_definition)
+                .chain(self.strings.iter().cloned())
+                .collect::<Vec<_>>();
+            &combined
+        };
+        let mut expander = StringExpander::new(strings, options, resolvers);
         for entry in &mut self.entries {
             for field in &mut entry.fields {
-                field.value.expanded = Some(Cow::Owned(expand_value_with_options(
-                    &field.value.value,
-                    strings,
-                    options,
-                    &mut Vec::new(),
-                )?));
+                field.value.expanded = Some(Cow::Owned(expander.expand(&field.value.value)?));
             }
         }
         for preamble in &mut self.preambles {
-            preamble.value.expanded = Some(Cow::Owned(expand_value_with_options(
-                &preamble.value.value,
-                strings,
-                options,
-                &mut Vec::new(),
-            )?));
+            preamble.value.expanded = Some(Cow::Owned(expander.expand(&preamble.value.value)?));
         }
         Ok(())
     }
@@ -1613,6 +1862,43 @@ impl<'a> ParsedDocument<'a> {
         self.rebuild_diagnostics_and_status();
     }
 
+    /// Warn about bare numbers that were kept as literal text.
+    ///
+    /// See [`ParsedEntry::add_numeric_literal_diagnostics`].
+    pub(crate) fn add_numeric_literal_diagnostics(&mut self, signed_as_literals: bool) {
+        let mut added = false;
+        for (entry_index, entry) in self.entries.iter_mut().enumerate() {
+            added |= entry.add_numeric_literal_diagnostics(entry_index, signed_as_literals);
+        }
+        if added {
+            self.rebuild_diagnostics_and_status();
+        }
+    }
+
+    /// Whether a field needs its value delimiter to tell if it is a lossy number.
+    pub(crate) fn needs_value_delimiters(&self, signed_as_literals: bool) -> bool {
+        self.entries
+            .iter()
+            .flat_map(|entry| &entry.fields)
+            .any(|field| {
+                field.value.delimiter.is_none()
+                    && lossy_number_text(&field.value.value, signed_as_literals).is_some()
+            })
+    }
+
+    /// Record value delimiters for the fields of the entry at `index`.
+    pub(crate) fn set_value_delimiters(
+        &mut self,
+        index: usize,
+        delimiters: impl IntoIterator<Item = ValueDelimiter>,
+    ) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            for (field, delimiter) in entry.fields.iter_mut().zip(delimiters) {
+                field.value.delimiter = Some(delimiter);
+            }
+        }
+    }
+
     fn rebuild_diagnostics_and_status(&mut self) {
         self.diagnostics.clear();
         self.diagnostics.extend(
@@ -1626,7 +1912,11 @@ impl<'a> ParsedDocument<'a> {
                 .flat_map(|failed| failed.diagnostics.iter().cloned()),
         );
 
-        self.status = if self.diagnostics.is_empty() {
+        self.status = if !self
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        {
             ParseStatus::Ok
         } else if self.entries.is_empty() && self.strings.is_empty() && self.preambles.is_empty() {
             ParseStatus::Failed
@@ -1635,6 +1925,36 @@ impl<'a> ParsedDocument<'a> {
         };
     }
 
+    /// Record that parsing stopped at `source` because it was cancelled.
+    pub(crate) fn mark_cancelled(&mut self, source: SourceSpan) {
+        self.diagnostics.push(Diagnostic {
+            severity: DiagnosticSeverity::Warning,
+            code: DiagnosticCode::PARSE_CANCELLED,
+            message: "parsing was cancelled; later blocks were not parsed".to_string(),
+            target: DiagnosticTarget::File,
+            source: Some(source),
+            snippet: None,
+        });
+        self.status = ParseStatus::Cancelled;
+    }
+
+    /// Record that a parse budget ran out before the block at `source`.
+    pub(crate) fn mark_budget_exhausted(
+        &mut self,
+        source: SourceSpan,
+        continuation: ParseContinuation,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity: DiagnosticSeverity::Info,
+            code: DiagnosticCode::PARSE_BUDGET_EXHAUSTED,
+            message: "parse budget exhausted; resume to parse later blocks".to_string(),
+            target: DiagnosticTarget::File,
+            source: Some(source),
+            snippet: None,
+        });
+        self.continuation = Some(continuation);
+    }
+
     pub(crate) fn failed_from_error(
         sources: Vec<ParsedSource<'a>>,
         source_map: &SourceMap<'a>,
@@ -1687,6 +2007,7 @@ impl<'a> ParsedDocument<'a> {
             blocks: vec![ParsedBlock::Failed(0)],
             diagnostics: vec![diagnostic],
             status: ParseStatus::Failed,
+            continuation: None,
         }
     }
 
@@ -1782,6 +2103,83 @@ impl<'a> ParsedDocument<'a> {
         &self.blocks
     }
 
+    /// Return where to resume when a [`ParseBudget`](crate::ParseBudget)
+    /// ran out before the end of the input.
+    #[must_use]
+    pub const fn continuation(&self) -> Option<&ParseContinuation> {
+        self.continuation.as_ref()
+    }
+
+    /// Carry `earlier` definitions into the continuation of a resumed parse.
+    pub(crate) fn inherit_continuation_strings(&mut self, earlier: &[StringDefinition<'static>]) {
+        if let Some(continuation) = &mut self.continuation {
+            continuation.inherit_strings(earlier);
+        }
+    }
+
+    /// Move every source span from a slice to the whole input.
+    pub(crate) fn offset_sources(&mut self, offset: SourceOffset) {
+        self.library.offset_sources(offset);
+        if let Some(continuation) = &mut self.continuation {
+            continuation.shift(offset.bytes());
+        }
+        for entry in &mut self.entries {
+            offset.apply(&mut entry.source);
+            offset.apply(&mut entry.entry_type_source);
+            offset.apply(&mut entry.key_source);
+            for field in &mut entry.fields {
+                offset.apply(&mut field.source);
+                offset.apply(&mut field.name_source);
+                offset.apply(&mut field.value_source);
+                offset.apply(&mut field.comment_source);
+                offset.apply(&mut field.value.source);
+            }
+            if let Some(removed) = entry.removed_field_sources.as_deref_mut() {
+                let sources = match removed {
+                    RemovedFieldSources::One(source) => std::slice::from_mut(source),
+                    RemovedFieldSources::Many(sources) => sources.as_mut_slice(),
+                };
+                for source in sources {
+                    offset.shift(source);
+                }
+            }
+            for diagnostic in &mut entry.diagnostics {
+                offset.apply(&mut diagnostic.source);
+            }
+        }
+        for definition in &mut self.strings {
+            offset.apply(&mut definition.source);
+            offset.apply(&mut definition.value.source);
+        }
+        for preamble in &mut self.preambles {
+            offset.apply(&mut preamble.source);
+            offset.apply(&mut preamble.value.source);
+        }
+        for comment in &mut self.comments {
+            offset.apply(&mut comment.source);
+        }
+        for failed in &mut self.failed_blocks {
+            offset.apply(&mut failed.source);
+            for diagnostic in &mut failed.diagnostics {
+                offset.apply(&mut diagnostic.source);
+            }
+        }
+        for diagnostic in &mut self.diagnostics {
+            offset.apply(&mut diagnostic.source);
+        }
+    }
+
+    /// Return the source location of a block, when available.
+    pub(crate) fn block_source(&self, block: ParsedBlock) -> Option<SourceSpan> {
+        match block {
+            ParsedBlock::Entry(index) => self.entries.get(index)?.source,
+            ParsedBlock::String(index) => self.strings.get(index)?.source,
+            ParsedBlock::Preamble(index) => self.preambles.get(index)?.source,
+            ParsedBlock::Comment(index) => self.comments.get(index)?.source,
+            ParsedBlock::Failed(index) => self.failed_blocks.get(index)?.source,
+        }
+    }
+
     /// Return document diagnostics.
     #[must_use]
     pub fn diagnostics(&self) -> &[Diagnostic] {
@@ -1824,6 +2222,63 @@ impl<'a> ParsedDocument<'a> {
         }
     }
 
+    /// Return string definitions that no entry, preamble, or used string refers to.
+    ///
+    /// Parsed-document values keep their unexpanded structure, so references
+    /// are read directly from the values without expanding them.
+    #[must_use]
+    pub fn unused_strings(&self) -> Vec<&ParsedString<'a>> {
+        let mut roots = Vec::new();
+        for entry in &self.entries {
+            for field in &entry.fields {
+                collect_variable_names(&field.value.value, &mut roots);
+            }
+        }
+        for preamble in &self.preambles {
+            collect_variable_names(&preamble.value.value, &mut roots);
+        }
+
+        let referenced = reachable_variable_names(roots, |name| {
+            self.string_definition(name)
+                .map(|definition| &definition.value.value)
+        });
+        self.strings
+            .iter()
+            .filter(|definition| !referenced.contains(definition.name.as_ref()))
+            .collect()
+    }
+
+    /// Return sorted variable names that are referenced but neither defined nor month constants.
+    #[must_use]
+    pub fn undefined_variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for entry in &self.entries {
+            for field in &entry.fields {
+                collect_variable_names(&field.value.value, &mut names);
+            }
+        }
+        for preamble in &self.preambles {
+            collect_variable_names(&preamble.value.value, &mut names);
+        }
+        for definition in &self.strings {
+            collect_variable_names(&definition.value.value, &mut names);
+        }
+
+        names.retain(|name| {
+            self.string_definition(name).is_none() && month_expansion(name).is_none()
+        });
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    fn string_definition(&self, name: &str) -> Option<&ParsedString<'a>> {
+        self.strings
+            .iter()
+            .rev()
+            .find(|definition| definition.name.as_ref() == name)
+    }
+
     /// Expand a parsed value using this document's string definitions.
     ///
     /// This allocates the expanded text. The structured value itself remains
@@ -1833,7 +2288,7 @@ impl<'a> ParsedDocument<'a> {
         value: &Value<'a>,
         options: ExpansionOptions,
     ) -> crate::Result<String> {
-        expand_value_with_options(value, &self.strings, options, &mut Vec::new())
+        StringExpander::new(&self.strings, options, &MacroResolvers::default()).expand(value)
     }
 
     /// Convert this parsed document into an owned value.
@@ -1874,6 +2329,7 @@ impl<'a> ParsedDocument<'a> {
             blocks: self.blocks,
             diagnostics: self.diagnostics,
             status: self.status,
+            continuation: self.continuation,
         }
     }
 }
@@ -1919,59 +2375,109 @@ fn owned_source_slice(source: &str, span: Option<SourceSpan>) -> Option<Cow<'sta
         .map(|raw| Cow::Owned(raw.to_string()))
 }
 
-fn expand_value_with_options(
-    value: &Value<'_>,
-    strings: &[ParsedString<'_>],
+/// Expands parsed values against one set of string definitions.
+///
+/// Each `@string` is expanded at most once; later references reuse the
+/// memoized text.
+struct StringExpander<'s, 'a> {
+    definitions: AHashMap<&'s str, &'s Value<'a>>,
     options: ExpansionOptions,
-    stack: &mut Vec<String>,
-) -> crate::Result<String> {
-    match value {
-        Value::Literal(text) => Ok(normalize_text_projection(text)),
-        Value::Number(number) => Ok(number.to_string()),
-        Value::Concat(parts) => {
-            let mut expanded = String::new();
-            for part in parts.iter() {
-                expanded.push_str(&expand_value_with_options(part, strings, options, stack)?);
+    resolvers: &'s MacroResolvers,
+    expanded: AHashMap<&'s str, String>,
+    stack: Vec<&'s str>,
+}
+
+impl<'s, 'a> StringExpander<'s, 'a> {
+    fn new(
+        strings: &'s [ParsedString<'a>],
+        options: ExpansionOptions,
+        resolvers: &'s MacroResolvers,
+    ) -> Self {
+        let mut definitions = AHashMap::with_capacity(strings.len());
+        if options.expand_strings {
+            // Later definitions shadow earlier ones of the same name.
+            for definition in strings {
+                definitions.insert(definition.name.as_ref(), &definition.value.value);
             }
-            Ok(expanded)
         }
-        Value::Variable(name) => expand_variable(name, strings, options, stack),
+        Self {
+            definitions,
+            options,
+            resolvers,
+            expanded: AHashMap::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    fn expand(&mut self, value: &Value<'_>) -> crate::Result<String> {
+        let expanded = self.expand_value(value);
+        if expanded.is_err() {
+            self.stack.clear();
+        }
+        expanded
+    }
+
+    fn expand_value(&mut self, value: &Value<'_>) -> crate::Result<String> {
+        match value {
+            Value::Literal(text) => Ok(normalize_text_projection(text)),
+            Value::Number(number) => Ok(number.to_string()),
+            Value::Concat(parts) => {
+                let mut expanded = String::new();
+                for part in parts.iter() {
+                    expanded.push_str(&self.expand_value(part)?);
+                    if expanded.len() > self.options.max_length {
+                        return Err(crate::Error::ExpansionLengthExceeded {
+                            limit: self.options.max_length,
+                        });
+                    }
+                }
+                Ok(expanded)
+            }
+            Value::Variable(name) => self.expand_variable(name),
+        }
     }
-}
 
-fn expand_variable(
-    name: &str,
-    strings: &[ParsedString<'_>],
-    options: ExpansionOptions,
-    stack: &mut Vec<String>,
-) -> crate::Result<String> {
-    if options.expand_strings {
-        if let Some(definition) = strings
-            .iter()
-            .rev()
-            .find(|definition| definition.name.as_ref() == name)
-        {
-            if stack.iter().any(|active| active == name) {
+    fn expand_variable(&mut self, name: &str) -> crate::Result<String> {
+        if let Some((&name, &definition)) = self.definitions.get_key_value(name) {
+            if let Some(expanded) = self.expanded.get(name) {
+                return Ok(expanded.clone());
+            }
+            if self.stack.contains(&name) {
                 return Err(crate::Error::CircularReference(name.to_string()));
             }
-            stack.push(name.to_string());
-            let expanded =
-                expand_value_with_options(&definition.value.value, strings, options, stack);
-            stack.pop();
-            return expanded;
+            if self.stack.len() >= self.options.max_depth {
+                self.stack.push(name);
+                return Err(crate::Error::ExpansionDepthExceeded {
+                    chain: self.stack.join(" -> "),
+                    limit: self.options.max_depth,
+                });
+            }
+            self.stack.push(name);
+            let expanded = self.expand_value(definition)?;
+            self.stack.pop();
+            self.expanded.insert(name, expanded.clone());
+            return Ok(expanded);
         }
-    }
 
-    if options.expand_months {
-        if let Some(month) = month_expansion(name) {
-            return Ok(month.to_string());
+        if self.options.expand_months {
+            if let Some(month) = month_expansion(name) {
+                return Ok(month.to_string());
+            }
+        }
+        if self.options.expand_strings {
+            if let Some(resolved) = self.resolvers.resolve(name) {
+                return Ok(resolved);
+            }
         }
-    }
 
-    match options.unresolved_variables {
-        UnresolvedVariablePolicy::Preserve => Ok(name.to_string()),
-        UnresolvedVariablePolicy::Placeholder => Ok(format!("{{undefined:{name}}}")),
-        UnresolvedVariablePolicy::Error => Err(crate::Error::UndefinedVariable(name.to_string())),
+        match self.options.unresolved_variables {
+            UnresolvedVariablePolicy::Preserve => Ok(name.to_string()),
+            UnresolvedVariablePolicy::Placeholder => Ok(format!("{{undefined:{name}}}")),
+            UnresolvedVariablePolicy::Empty => Ok(String::new()),
+            UnresolvedVariablePolicy::Error => {
+                Err(crate::Error::UndefinedVariable(name.to_string()))
+            }
+        }
     }
 }
 
@@ -2011,6 +2517,7 @@ struct FieldLocations {
     name: (usize, usize),
     value: (usize, usize),
     value_delimiter: ValueDelimiter,
+    comment: Option<(usize, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -2075,6 +2582,15 @@ fn recover_partial_entry<'a>(
     entry_index: usize,
     preserve_raw: bool,
 ) -> Option<ParsedEntry<'a>> {
+    // Blocks without a classified syntax problem, such as entries rejected
+    // for a repeated key, are well-formed and stay failed.
+    if failed
+        .diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.code == DiagnosticCode::PARSE_ERROR)
+    {
+        return None;
+    }
     let raw: &'a str = match &failed.raw {
         Cow::Borrowed(raw) => raw,
         Cow::Owned(_) => return None,
@@ -2095,13 +2611,16 @@ fn recover_partial_entry<'a>(
 
     let diagnostic = diagnostic_for_partial_entry(entry_index, failed, source_map);
 
+    let entry_type_spelling = entry_type_spelling(&header.ty, header.entry_type_text);
     Some(ParsedEntry {
         ty: header.ty,
         key: header.key,
+        original_key: None,
         fields,
         status: ParsedEntryStatus::Partial,
         source: failed.source,
         entry_type_source: header.entry_type_source,
+        entry_type_spelling,
         key_source: header.key_source,
         delimiter: Some(header.delimiter),
         raw: preserve_raw.then(|| failed.raw.clone()),
@@ -2123,6 +2642,7 @@ struct PartialHeader<'a> {
     ty: EntryType<'a>,
     key: Cow<'a, str>,
     entry_type_source: Option<SourceSpan>,
+    entry_type_text: Option<&'a str>,
     key_source: Option<SourceSpan>,
     delimiter: EntryDelimiter,
     field_start: usize,
@@ -2142,6 +2662,7 @@ fn parse_partial_header<'a>(
     if pos == entry_type_start {
         return None;
     }
+    let entry_type_end = pos;
     let ty = EntryType::parse(&raw[entry_type_start..pos]);
     let entry_type_source =
         Some(source_map.span(absolute_start + entry_type_start, absolute_start + pos));
@@ -2172,6 +2693,7 @@ fn parse_partial_header<'a>(
         ty,
         key,
         entry_type_source,
+        entry_type_text: Some(&raw[entry_type_start..entry_type_end]),
         key_source,
         delimiter,
         field_start: pos + 1,
@@ -2191,7 +2713,7 @@ fn recover_partial_fields<'a>(
     let mut fields = Vec::new();
 
     loop {
-        pos = skip_ascii_whitespace(bytes, pos);
+        pos = skip_whitespace_and_comments(bytes, pos);
         let Some(&byte) = bytes.get(pos) else {
             break;
         };
@@ -2218,12 +2740,22 @@ fn recover_partial_fields<'a>(
         let value_start = pos;
         let tail = &raw[value_start..];
         let mut value_input = tail;
-        let Ok(value) = crate::parser::value::parse_value_field(&mut value_input) else {
+        let Ok(value) = crate::parser::value::parse_value_field(
+            &mut value_input,
+            crate::parser::value::SignedNumbers::Numbers,
+        ) else {
             break;
         };
         let consumed = tail.len() - value_input.len();
         let value_end = trim_ascii_whitespace_end(bytes, value_start, value_start + consumed);
-        let boundary = value_start + consumed;
+        let mut comment = line_comment(bytes, value_end);
+        let boundary = skip_whitespace_and_comments(
+            bytes,
+            comment.map_or(value_start + consumed, |(_, end)| end),
+        );
+        if bytes.get(boundary) == Some(&b',') && comment.is_none() {
+            comment = line_comment(bytes, boundary + 1);
+        }
         let field_end = match bytes.get(boundary) {
             Some(b',') => boundary + 1,
             Some(byte) if *byte == closing => boundary,
@@ -2249,6 +2781,9 @@ fn recover_partial_fields<'a>(
                 source_map.span(absolute_start + name_start, absolute_start + name_end),
             ),
             value_source: Some(value_source),
+            comment: comment.map(|(start, end)| Cow::Borrowed(&raw[start..end])),
+            comment_source: comment
+                .map(|(start, end)| source_map.span(absolute_start + start, absolute_start + end)),
         });
 
         match bytes.get(boundary) {
@@ -2584,7 +3119,10 @@ fn locate_entry(raw: &str, absolute_start: usize, field_count: usize) -> Option<
     pos = skip_ascii_whitespace(bytes, pos);
 
     let key_start = pos;
-    pos += scan_identifier(&bytes[pos..]);
+    pos += bytes[pos..]
+        .iter()
+        .position(|byte| !crate::parser::lexer::is_bibtex_key_byte(*byte))
+        .unwrap_or(bytes.len() - pos);
     if pos == key_start {
         return None;
     }
@@ -2603,7 +3141,7 @@ fn locate_entry(raw: &str, absolute_start: usize, field_count: usize) -> Option<
 
     let mut fields = Vec::with_capacity(field_count);
     while fields.len() < field_count {
-        pos = skip_ascii_whitespace(bytes, pos);
+        pos = skip_whitespace_and_comments(bytes, pos);
         if bytes.get(pos) == Some(&closing) || pos >= bytes.len() {
             break;
         }
@@ -2626,11 +3164,15 @@ fn locate_entry(raw: &str, absolute_start: usize, field_count: usize) -> Option<
         let value_start = pos;
         let boundary = find_value_boundary(bytes, pos, closing);
         let value_end = trim_ascii_whitespace_end(bytes, value_start, boundary);
+        let mut comment = line_comment(bytes, value_end);
         let mut whole_end = value_end;
-        pos = boundary;
+        pos = skip_whitespace_and_comments(bytes, comment.map_or(boundary, |(_, end)| end));
         if bytes.get(pos) == Some(&b',') {
             whole_end = pos + 1;
             pos += 1;
+            if comment.is_none() {
+                comment = line_comment(bytes, pos);
+            }
         }
 
         fields.push(FieldLocations {
@@ -2638,6 +3180,7 @@ fn locate_entry(raw: &str, absolute_start: usize, field_count: usize) -> Option<
             name: (absolute_start + name_start, absolute_start + name_end),
             value: (absolute_start + value_start, absolute_start + value_end),
             value_delimiter: value_delimiter(&raw[value_start..value_end]),
+            comment: comment.map(|(start, end)| (absolute_start + start, absolute_start + end)),
         });
     }
 
@@ -2715,6 +3258,31 @@ fn skip_ascii_whitespace(bytes: &[u8], mut pos: usize) -> usize {
     pos
 }
 
+/// Skip whitespace and `%` line comments inside an entry body.
+fn skip_whitespace_and_comments(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        pos = skip_ascii_whitespace(bytes, pos);
+        if bytes.get(pos) != Some(&b'%') {
+            return pos;
+        }
+        pos = memchr::memchr(b'\n', &bytes[pos..]).map_or(bytes.len(), |end| pos + end);
+    }
+}
+
+/// Locate a `%` comment that starts on the same line as `pos`, returning it
+/// without the line ending or trailing whitespace.
+fn line_comment(bytes: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let mut start = pos;
+    while matches!(bytes.get(start), Some(b' ' | b'\t')) {
+        start += 1;
+    }
+    if bytes.get(start) != Some(&b'%') {
+        return None;
+    }
+    let end = memchr::memchr2(b'\n', b'\r', &bytes[start..]).map_or(bytes.len(), |end| start + end);
+    Some((start, trim_ascii_whitespace_end(bytes, start, end)))
+}
+
 fn trim_ascii_whitespace_end(bytes: &[u8], start: usize, mut end: usize) -> usize {
     while end > start && matches!(bytes.get(end - 1), Some(b' ' | b'\t' | b'\n' | b'\r')) {
         end -= 1;
@@ -2741,7 +3309,7 @@ fn find_value_boundary(bytes: &[u8], mut pos: usize, closing: u8) -> usize {
         match byte {
             b'{' => pos = skip_braced(bytes, pos + 1),
             b'"' => pos = skip_quoted(bytes, pos + 1),
-            b',' => break,
+            b',' | b'%' => break,
             b if b == closing => break,
             _ => pos += 1,
         }
@@ -2789,24 +3357,32 @@ fn skip_braced_checked(bytes: &[u8], mut pos: usize) -> Option<usize> {
     None
 }
 
-fn skip_quoted(bytes: &[u8], mut pos: usize) -> usize {
-    while let Some(&byte) = bytes.get(pos) {
-        match byte {
-            b'\\' => pos = (pos + 2).min(bytes.len()),
-            b'"' => return pos + 1,
-            _ => pos += 1,
-        }
-    }
-    pos
+fn skip_quoted(bytes: &[u8], pos: usize) -> usize {
+    skip_quoted_checked(bytes, pos).unwrap_or(bytes.len())
 }
 
-fn skip_quoted_checked(bytes: &[u8], mut pos: usize) -> Option<usize> {
-    while let Some(&byte) = bytes.get(pos) {
-        match byte {
-            b'\\' => pos = (pos + 2).min(bytes.len()),
-            b'"' => return Some(pos + 1),
-            _ => pos += 1,
-        }
-    }
-    None
+/// Skip past the quote closing a value whose opening quote ends at `pos`.
+fn skip_quoted_checked(bytes: &[u8], pos: usize) -> Option<usize> {
+    let start = pos.checked_sub(1)?;
+    crate::parser::simd::find_balanced_quotes(&bytes[start..]).map(|end| start + end)
+}
+
+fn is_key_reference_field(name: &str) -> bool {
+    KEY_REFERENCE_FIELDS
+        .iter()
+        .any(|reference| name.eq_ignore_ascii_case(reference))
+}
+
+/// Return the text of a literal that the value parser kept from a number.
+fn lossy_number_text<'v>(value: &'v Value<'_>, signed_as_literals: bool) -> Option<&'v str> {
+    let Value::Literal(text) = value else {
+        return None;
+    };
+    let digits = match text.strip_prefix(['+', '-']) {
+        Some(_) if signed_as_literals => return None,
+        Some(digits) => digits,
+        None => text,
+    };
+    let digits = digits.trim_end_matches(|ch: char| ch.is_ascii_punctuation());
+    (!digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit())).then_some(text)
 }
@@ -1,13 +1,18 @@
 //! Error types for the bibtex-parser crate
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type for bibtex-parser operations
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// The main error type for bibtex-parser
+///
+/// New variants may be added without a major release, so matches need a
+/// wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Parse error with location information
     #[error("Parse error at line {line}, column {column}: {message}")]
@@ -30,6 +35,22 @@ pub enum Error {
     #[error("Circular reference detected in string variables: {0}")]
     CircularReference(String),
 
+    /// String variable references nested deeper than the expansion limit
+    #[error("String variable expansion exceeded depth {limit}: {chain}")]
+    ExpansionDepthExceeded {
+        /// The chain of variables being expanded
+        chain: String,
+        /// The configured depth limit
+        limit: usize,
+    },
+
+    /// A string expansion produced more text than the expansion length limit
+    #[error("String variable expansion exceeded {limit} bytes")]
+    ExpansionLengthExceeded {
+        /// The configured length limit in bytes
+        limit: usize,
+    },
+
     /// Invalid entry type
     #[error("Invalid entry type '{0}'")]
     InvalidEntryType(String),
@@ -58,6 +79,48 @@ pub enum Error {
     /// Generic parse error from winnow
     #[error("Parse error: {0}")]
     WinnowError(String),
+
+    /// A value could not be converted to another crate's representation
+    #[error("Conversion error: {0}")]
+    Conversion(String),
+
+    /// A configuration file could not be read
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// An archive of bibliography files could not be read
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    /// A file watch could not be set up
+    #[error("File watch error: {0}")]
+    Watch(String),
+
+    /// A bibliography could not be downloaded
+    #[error("Fetch error: {0}")]
+    Fetch(String),
+
+    /// A metadata provider could not complete a lookup
+    #[error("Metadata lookup error: {0}")]
+    MetadataLookup(String),
+
+    /// A metadata provider timed out, failed with a 5xx status, or asked the
+    /// caller to slow down; the same lookup may succeed later
+    #[error("Metadata service unavailable: {message}")]
+    MetadataUnavailable {
+        /// Error message
+        message: String,
+        /// Wait requested by the service's `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+
+    /// Parsing stopped because its [`CancelToken`](crate::CancelToken) was cancelled
+    #[error("Parse cancelled")]
+    Cancelled,
+
+    /// A panic inside the parser, caught by [`parse_catch`](crate::parse_catch)
+    #[error("Internal parser panic: {0}")]
+    Panic(String),
 }
 
 /// Parse context for better error messages
@@ -85,10 +148,12 @@ impl ParseContext {
     /// Get a snippet of the input around the current position
     #[must_use]
     pub fn snippet(&self, pos: usize, context_size: usize) -> String {
-        let start = pos.saturating_sub(context_size);
-        let end = (pos + context_size).min(self.input.len());
+        let pos = crate::source::floor_char_boundary(&self.input, pos);
+        let start =
+            crate::source::floor_char_boundary(&self.input, pos.saturating_sub(context_size));
+        let end = crate::source::ceil_char_boundary(&self.input, pos + context_size);
         let snippet = &self.input[start..end];
-        let relative_pos = pos - start;
+        let relative_pos = self.input[start..pos].chars().count();
         format!("{}\n{}^", snippet, " ".repeat(relative_pos))
     }
 
@@ -0,0 +1,376 @@
+//! Mapping entries onto application types.
+//!
+//! Implement [`FromEntry`] for a struct and call [`Entry::extract`] to fill
+//! it. [`FieldReader`] converts each field with [`FromField`] and collects
+//! every failure, so one [`ExtractError`] reports all missing or invalid
+//! fields at once. [`ToEntry`] and [`ToField`] go the other way. With the
+//! `derive` feature, `#[derive(FromEntry, ToEntry)]` writes both impls.
+//!
+//! ```
+//! use bibtex_parser::{ExtractError, FieldReader, FromEntry};
+//! use bibtex_parser::{Entry, Library, PersonName};
+//!
+//! struct Paper {
+//!     title: String,
+//!     year: i32,
+//!     authors: Vec<PersonName>,
+//!     doi: Option<String>,
+//! }
+//!
+//! impl FromEntry for Paper {
+//!     fn from_entry(entry: &Entry<'_>) -> Result<Self, ExtractError> {
+//!         let mut fields = FieldReader::new(entry);
+//!         let title = fields.read("title");
+//!         let year = fields.read("year");
+//!         let authors = fields.read("author");
+//!         let doi = fields.read("doi");
+//!         fields.finish(|| {
+//!             Some(Self {
+//!                 title: title?,
+//!                 year: year?,
+//!                 authors: authors?,
+//!                 doi: doi?,
+//!             })
+//!         })
+//!     }
+//! }
+//!
+//! let library = Library::parse("@article{a, title = {T}, year = 2024, author = {Doe, Jane}}")?;
+//! let paper = library.entries()[0].extract::<Paper>().unwrap();
+//! assert_eq!(paper.year, 2024);
+//! assert_eq!(paper.authors[0].last, "Doe");
+//! assert!(paper.doi.is_none());
+//! # Ok::<(), bibtex_parser::Error>(())
+//! ```
+
+use crate::{parse_names, Entry, EntryType, Field, PersonName, Value};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Types that can be built from an [`Entry`].
+pub trait FromEntry: Sized {
+    /// Build a value from `entry`, reporting every field that failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExtractError`] when required fields are missing or invalid.
+    fn from_entry(entry: &Entry<'_>) -> Result<Self, ExtractError>;
+}
+
+/// Types that can be converted from a field value.
+///
+/// The value is the field's expanded string form, or `None` when the entry
+/// has no such field. Only `Option<T>` accepts a missing field.
+pub trait FromField: Sized {
+    /// Convert a field value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the value was rejected.
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind>;
+}
+
+/// Types that can be written as an [`Entry`].
+pub trait ToEntry {
+    /// Build an entry from `self`.
+    fn to_entry(&self) -> Entry<'static>;
+}
+
+/// Types that can be written as a field value.
+pub trait ToField {
+    /// Return the field text, or `None` to leave the field out.
+    fn to_field(&self) -> Option<String>;
+}
+
+/// Why one field could not be extracted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    /// The field is required but absent.
+    Missing,
+    /// The field is present but could not be converted.
+    Invalid {
+        /// Field value as found in the entry.
+        value: String,
+        /// Description of the expected form.
+        expected: String,
+    },
+}
+
+/// A field that could not be extracted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Field name as requested.
+    pub field: String,
+    /// What went wrong.
+    pub kind: FieldErrorKind,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FieldErrorKind::Missing => write!(f, "missing field `{}`", self.field),
+            FieldErrorKind::Invalid { value, expected } => {
+                write!(
+                    f,
+                    "field `{}`: expected {expected}, found {value:?}",
+                    self.field
+                )
+            }
+        }
+    }
+}
+
+/// Every field that failed while extracting one entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractError {
+    /// Citation key of the entry.
+    pub key: String,
+    /// Failed fields in the order they were read.
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot extract entry `{}`: ", self.key)?;
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ExtractError {}
+
+/// Reads typed fields from an entry and collects failures.
+#[derive(Debug)]
+pub struct FieldReader<'e, 'a> {
+    entry: &'e Entry<'a>,
+    errors: Vec<FieldError>,
+}
+
+impl<'e, 'a> FieldReader<'e, 'a> {
+    /// Start reading `entry`.
+    #[must_use]
+    pub const fn new(entry: &'e Entry<'a>) -> Self {
+        Self {
+            entry,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Return the entry being read.
+    #[must_use]
+    pub const fn entry(&self) -> &'e Entry<'a> {
+        self.entry
+    }
+
+    /// Read field `name`, matched case-insensitively.
+    ///
+    /// Returns `None` and records the failure when conversion fails.
+    pub fn read<T: FromField>(&mut self, name: &str) -> Option<T> {
+        self.read_with(name, T::from_field)
+    }
+
+    /// Read field `name` with a custom conversion.
+    pub fn read_with<T>(
+        &mut self,
+        name: &str,
+        convert: impl FnOnce(Option<&str>) -> Result<T, FieldErrorKind>,
+    ) -> Option<T> {
+        let value = self.entry.get_as_string_ignore_case(name);
+        match convert(value.as_deref()) {
+            Ok(value) => Some(value),
+            Err(kind) => {
+                self.errors.push(FieldError {
+                    field: name.to_string(),
+                    kind,
+                });
+                None
+            }
+        }
+    }
+
+    /// Finish reading and build the value.
+    ///
+    /// `build` runs only when every read succeeded, so it can unwrap the
+    /// read results with `?`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExtractError`] listing every failed read.
+    pub fn finish<T>(self, build: impl FnOnce() -> Option<T>) -> Result<T, ExtractError> {
+        if self.errors.is_empty() {
+            if let Some(value) = build() {
+                return Ok(value);
+            }
+        }
+        Err(ExtractError {
+            key: self.entry.key.to_string(),
+            errors: self.errors,
+        })
+    }
+}
+
+impl Entry<'_> {
+    /// Map this entry onto `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExtractError`] listing every missing or invalid field.
+    pub fn extract<T: FromEntry>(&self) -> Result<T, ExtractError> {
+        T::from_entry(self)
+    }
+}
+
+fn required(value: Option<&str>) -> Result<&str, FieldErrorKind> {
+    value.ok_or(FieldErrorKind::Missing)
+}
+
+fn invalid(value: &str, expected: &str) -> FieldErrorKind {
+    FieldErrorKind::Invalid {
+        value: value.to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+impl<T: FromField> FromField for Option<T> {
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+        value.map(|value| T::from_field(Some(value))).transpose()
+    }
+}
+
+impl FromField for String {
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+        required(value).map(str::to_string)
+    }
+}
+
+impl FromField for bool {
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+        let value = required(value)?;
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(true),
+            "false" | "no" | "0" => Ok(false),
+            _ => Err(invalid(value, "a boolean")),
+        }
+    }
+}
+
+macro_rules! from_field_parse {
+    ($($ty:ty => $expected:literal),* $(,)?) => {$(
+        impl FromField for $ty {
+            fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+                let value = required(value)?;
+                value.trim().parse().map_err(|_| invalid(value, $expected))
+            }
+        }
+    )*};
+}
+
+from_field_parse! {
+    i16 => "an integer",
+    i32 => "an integer",
+    i64 => "an integer",
+    u16 => "a non-negative integer",
+    u32 => "a non-negative integer",
+    u64 => "a non-negative integer",
+    usize => "a non-negative integer",
+    f64 => "a number",
+}
+
+/// Name lists such as `author` and `editor`, split on `and`.
+impl FromField for Vec<PersonName> {
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+        Ok(parse_names(required(value)?))
+    }
+}
+
+/// Comma- or semicolon-separated lists such as `keywords`.
+impl FromField for Vec<String> {
+    fn from_field(value: Option<&str>) -> Result<Self, FieldErrorKind> {
+        Ok(required(value)?
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+impl Entry<'static> {
+    /// Create an empty entry with an owned type and key.
+    #[must_use]
+    pub fn owned(ty: &str, key: impl Into<String>) -> Self {
+        Self {
+            ty: EntryType::parse(ty).into_owned(),
+            key: Cow::Owned(key.into()),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Append a literal field when `value` is `Some`.
+    pub fn push_field(&mut self, name: &'static str, value: Option<String>) {
+        if let Some(value) = value {
+            self.fields
+                .push(Field::new(name, Value::Literal(Cow::Owned(value))));
+        }
+    }
+}
+
+impl<T: ToField> ToField for Option<T> {
+    fn to_field(&self) -> Option<String> {
+        self.as_ref().and_then(T::to_field)
+    }
+}
+
+impl<T: ToField + ?Sized> ToField for &T {
+    fn to_field(&self) -> Option<String> {
+        (**self).to_field()
+    }
+}
+
+impl ToField for str {
+    fn to_field(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+macro_rules! to_field_display {
+    ($($ty:ty),* $(,)?) => {$(
+        impl ToField for $ty {
+            fn to_field(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+        }
+    )*};
+}
+
+to_field_display!(String, bool, i16, i32, i64, u16, u32, u64, usize, f64);
+
+/// Joined with ` and `, using each name's source text when it has one.
+impl ToField for Vec<PersonName> {
+    fn to_field(&self) -> Option<String> {
+        (!self.is_empty()).then(|| {
+            self.iter()
+                .map(|name| {
+                    if name.raw.is_empty() {
+                        name.display_name()
+                    } else {
+                        name.raw.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" and ")
+        })
+    }
+}
+
+/// Joined with `, `.
+impl ToField for Vec<String> {
+    fn to_field(&self) -> Option<String> {
+        (!self.is_empty()).then(|| self.join(", "))
+    }
+}
@@ -0,0 +1,240 @@
+//! Quick-fix code actions derived from entry validation.
+//!
+//! [`code_actions`] validates each parsed entry and turns the findings that
+//! have a mechanical repair into [`CodeAction`]s whose edits carry source
+//! spans, so editor integrations can offer them without re-parsing.
+
+use crate::document::{ParsedDocument, ParsedEntry, ParsedField, ValueDelimiter};
+use crate::model::parse_month_number;
+use crate::{DiagnosticCode, SourceSpan, ValidationError, ValidationLevel};
+
+const MONTH_MACROS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// A replacement of one source span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Source range to replace; an empty span inserts at its start.
+    pub span: SourceSpan,
+    /// Replacement text.
+    pub new_text: String,
+}
+
+/// A quick fix for one validation finding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeAction {
+    /// Short user-facing title.
+    pub title: String,
+    /// Parsed-entry index the fix applies to.
+    pub entry: usize,
+    /// Validation finding the fix addresses.
+    pub diagnostic: ValidationError,
+    /// Edits to apply together, in source order.
+    pub edits: Vec<TextEdit>,
+    /// `true` when the fix is safe to apply without review.
+    pub is_preferred: bool,
+}
+
+impl CodeAction {
+    /// Apply this action's edits to the text the document was parsed from.
+    ///
+    /// Edits whose spans fall outside `input` are skipped.
+    #[must_use]
+    pub fn apply(&self, input: &str) -> String {
+        let mut output = input.to_string();
+        for edit in self.edits.iter().rev() {
+            let range = edit.span.byte_start..edit.span.byte_end;
+            if output.get(range.clone()).is_some() {
+                output.replace_range(range, &edit.new_text);
+            }
+        }
+        output
+    }
+}
+
+/// Build quick fixes for the validation findings of every parsed entry.
+///
+/// Entries parsed without source locations produce no actions.
+#[must_use]
+pub fn code_actions(document: &ParsedDocument<'_>, level: ValidationLevel) -> Vec<CodeAction> {
+    let mut actions = Vec::new();
+    for (index, parsed) in document.entries().iter().enumerate() {
+        let Err(findings) = parsed.clone().into_entry().validate(level) else {
+            continue;
+        };
+        for finding in findings {
+            actions.extend(action_for(index, parsed, finding));
+        }
+    }
+    actions
+}
+
+fn action_for(
+    index: usize,
+    parsed: &ParsedEntry<'_>,
+    finding: ValidationError,
+) -> Option<CodeAction> {
+    let name = finding.field.clone()?;
+    let position = parsed
+        .fields
+        .iter()
+        .position(|field| field.name.eq_ignore_ascii_case(&name));
+    let Some(position) = position else {
+        return add_missing_field(index, parsed, finding, &name);
+    };
+    let field = &parsed.fields[position];
+    let text = field.value.value.as_str()?.trim();
+
+    let (title, edit, is_preferred) = if text.is_empty() {
+        (
+            format!("Remove empty field `{name}`"),
+            remove_field(parsed, position)?,
+            true,
+        )
+    } else {
+        match name.to_ascii_lowercase().as_str() {
+            "pages" => {
+                let pages = suggest_page_range(text)?;
+                (
+                    format!("Write pages as `{pages}`"),
+                    replace_value(field, &pages)?,
+                    true,
+                )
+            }
+            "month" => {
+                let month = parse_month_number(text.trim_end_matches('.'))?;
+                let abbrev = MONTH_MACROS[usize::from(month) - 1];
+                let edit = TextEdit {
+                    span: field.value_source?,
+                    new_text: abbrev.to_string(),
+                };
+                (format!("Use month macro `{abbrev}`"), edit, true)
+            }
+            "url" if !text.contains("://") => {
+                let url = format!("https://{text}");
+                (
+                    "Prefix URL with `https://`".to_string(),
+                    replace_value(field, &url)?,
+                    false,
+                )
+            }
+            _ => return None,
+        }
+    };
+
+    Some(CodeAction {
+        title,
+        entry: index,
+        diagnostic: finding,
+        edits: vec![edit],
+        is_preferred,
+    })
+}
+
+fn add_missing_field(
+    index: usize,
+    parsed: &ParsedEntry<'_>,
+    finding: ValidationError,
+    name: &str,
+) -> Option<CodeAction> {
+    if finding.code != DiagnosticCode::MISSING_REQUIRED_FIELD {
+        return None;
+    }
+    let anchor = parsed
+        .fields
+        .last()
+        .and_then(|field| field.source)
+        .or(parsed.key_source)?;
+    Some(CodeAction {
+        title: format!("Add missing field `{name}`"),
+        entry: index,
+        diagnostic: finding,
+        edits: vec![TextEdit {
+            span: empty_span_at_end(anchor),
+            new_text: format!(",\n  {name} = {{}}"),
+        }],
+        is_preferred: false,
+    })
+}
+
+fn remove_field(parsed: &ParsedEntry<'_>, position: usize) -> Option<TextEdit> {
+    let field = parsed.fields[position].source?;
+    let next = parsed.fields.get(position + 1).and_then(|next| next.source);
+    let previous = position
+        .checked_sub(1)
+        .and_then(|previous| parsed.fields[previous].source);
+    let span = match (next, previous) {
+        (Some(next), _) => join_spans(field, next, false),
+        (None, Some(previous)) => join_spans(empty_span_at_end(previous), field, true),
+        (None, None) => field,
+    };
+    Some(TextEdit {
+        span,
+        new_text: String::new(),
+    })
+}
+
+fn replace_value(field: &ParsedField<'_>, text: &str) -> Option<TextEdit> {
+    let new_text = match field.value.delimiter {
+        Some(ValueDelimiter::Quotes) => format!("\"{text}\""),
+        _ => format!("{{{text}}}"),
+    };
+    Some(TextEdit {
+        span: field.value_source?,
+        new_text,
+    })
+}
+
+pub(crate) fn suggest_page_range(pages: &str) -> Option<String> {
+    let parts = pages
+        .split([
+            '-', '\u{2010}', '\u{2011}', '\u{2012}', '\u{2013}', '\u{2014}', '\u{2212}',
+        ])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    match parts.as_slice() {
+        [first, last] if first.chars().chain(last.chars()).all(char::is_alphanumeric) => {
+            Some(format!("{first}--{last}"))
+        }
+        _ => None,
+    }
+}
+
+/// Span from the start of `start` to the start of `end`, or its end when `through_end`.
+const fn join_spans(start: SourceSpan, end: SourceSpan, through_end: bool) -> SourceSpan {
+    let (byte_end, end_line, end_column) = if through_end {
+        (end.byte_end, end.end_line, end.end_column)
+    } else {
+        (end.byte_start, end.line, end.column)
+    };
+    SourceSpan {
+        byte_end,
+        end_line,
+        end_column,
+        ..start
+    }
+}
+
+const fn empty_span_at_end(span: SourceSpan) -> SourceSpan {
+    SourceSpan {
+        source: span.source,
+        byte_start: span.byte_end,
+        byte_end: span.byte_end,
+        line: span.end_line,
+        column: span.end_column,
+        end_line: span.end_line,
+        end_column: span.end_column,
+    }
+}
+
+impl ParsedDocument<'_> {
+    /// Build quick fixes for this document's validation findings.
+    ///
+    /// See [`code_actions`].
+    #[must_use]
+    pub fn code_actions(&self, level: ValidationLevel) -> Vec<CodeAction> {
+        code_actions(self, level)
+    }
+}
@@ -0,0 +1,572 @@
+//! Best-effort parsing of plain-text reference strings.
+//!
+//! Enabled by the experimental `freeform` feature. [`parse_reference`] turns
+//! a formatted reference, such as one copied from a paper's bibliography,
+//! into an [`Entry`] using punctuation and keyword heuristics. Each field
+//! carries a confidence score between `0.0` and `1.0` so quick-entry tools
+//! can ask the user to check the uncertain ones. The heuristics are tuned
+//! for common author-title-venue layouts and may change between releases.
+
+use crate::{normalize_doi, parse_names, Entry, EntryType, Field, Value};
+use std::borrow::Cow;
+
+/// Words whose trailing period does not end a reference segment.
+const ABBREVIATIONS: &[&str] = &["vol", "no", "pp", "p", "ed", "eds", "jr", "vs", "dr", "st"];
+
+/// Venue words that suggest a conference paper.
+const PROCEEDINGS_WORDS: &[&str] = &[
+    "proceedings",
+    "proc.",
+    "conference",
+    "workshop",
+    "symposium",
+];
+
+/// Venue words that suggest a journal article.
+const JOURNAL_WORDS: &[&str] = &[
+    "journal",
+    "transactions",
+    "letters",
+    "review",
+    "annals",
+    "annalen",
+    "magazine",
+    "quarterly",
+];
+
+/// An entry guessed from a plain-text reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeformEntry {
+    /// The guessed entry, with a key built from the first author and year.
+    pub entry: Entry<'static>,
+    /// Confidence in the guessed entry type.
+    pub type_confidence: f32,
+    /// Confidence in each field of `entry`, in field order.
+    pub fields: Vec<FieldConfidence>,
+}
+
+impl FreeformEntry {
+    /// Return the confidence score for `field`, if it was found.
+    #[must_use]
+    pub fn confidence(&self, field: &str) -> Option<f32> {
+        self.fields
+            .iter()
+            .find(|confidence| confidence.field.eq_ignore_ascii_case(field))
+            .map(|confidence| confidence.score)
+    }
+}
+
+/// The confidence score of one guessed field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldConfidence {
+    /// Field name.
+    pub field: &'static str,
+    /// Score between `0.0` and `1.0`.
+    pub score: f32,
+}
+
+/// Guess an entry from a plain-text reference string.
+///
+/// Recognizes DOIs, URLs, arXiv identifiers, years, page ranges, volume and
+/// issue numbers, quoted titles, and author lists in both `A. Einstein` and
+/// `Einstein, A.` forms. The segment after the authors becomes the title and
+/// the rest the venue, whose wording decides between `@article`,
+/// `@inproceedings`, thesis, and `@book`.
+///
+/// ```
+/// use bibtex_parser::freeform::parse_reference;
+///
+/// let guess = parse_reference(
+///     "A. Einstein. Zur Elektrodynamik bewegter Körper. Annalen der Physik, 17:891–921, 1905.",
+/// );
+/// let entry = &guess.entry;
+/// assert_eq!(entry.key(), "einstein1905");
+/// assert_eq!(entry.get("author"), Some("A. Einstein"));
+/// assert_eq!(entry.get("title"), Some("Zur Elektrodynamik bewegter Körper"));
+/// assert_eq!(entry.get("journal"), Some("Annalen der Physik"));
+/// assert_eq!(entry.get("pages"), Some("891--921"));
+/// assert!(guess.confidence("year").unwrap() > 0.5);
+/// ```
+#[must_use]
+pub fn parse_reference(text: &str) -> FreeformEntry {
+    let mut fields = Guesses::default();
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    strip_label(&mut tokens);
+    take_identifiers(&mut tokens, &mut fields);
+
+    let mut rest = tokens.join(" ");
+    let quoted_title = take_quoted(&mut rest);
+    let year = take_year(&mut rest);
+    let initials_first = rest
+        .split_whitespace()
+        .next()
+        .is_some_and(|token| token.ends_with('.') && is_initials(token));
+    let segments = split_segments(&rest, initials_first);
+
+    let mut segments = segments.iter().map(String::as_str);
+    let first = segments.next();
+    let second = if quoted_title.is_none() {
+        segments.next()
+    } else {
+        None
+    };
+    let title = match (quoted_title, first, second) {
+        (Some(title), authors, _) => {
+            if let Some(authors) = authors {
+                fields.push_authors(authors, initials_first);
+            }
+            Some((title, 0.9))
+        }
+        (None, Some(authors), Some(title)) => {
+            fields.push_authors(authors, initials_first);
+            Some((title.to_string(), 0.7))
+        }
+        (None, title, _) => title.map(|title| (title.to_string(), 0.3)),
+    };
+    if let Some((title, score)) = title {
+        fields.push("title", title, score);
+    }
+    if let Some((year, score)) = year {
+        fields.push("year", year, score);
+    }
+
+    let venue = segments.collect::<Vec<_>>().join(". ");
+    let type_confidence = fields.push_venue(&venue);
+    fields.finish(type_confidence)
+}
+
+#[derive(Default)]
+struct Guesses {
+    ty: Option<EntryType<'static>>,
+    fields: Vec<(&'static str, String, f32)>,
+}
+
+impl Guesses {
+    fn push(&mut self, field: &'static str, value: String, score: f32) {
+        if !value.is_empty() && !self.has(field) {
+            self.fields.push((field, value, score));
+        }
+    }
+
+    fn has(&self, field: &str) -> bool {
+        self.fields.iter().any(|(name, ..)| *name == field)
+    }
+
+    fn push_authors(&mut self, segment: &str, initials_first: bool) {
+        let mut segment = segment.trim().trim_end_matches([',', ';', ':']).trim_end();
+        let mut others = false;
+        for suffix in ["et al.", "et al"] {
+            if let Some(stripped) = segment.strip_suffix(suffix) {
+                segment = stripped.trim_end().trim_end_matches(',');
+                others = true;
+            }
+        }
+
+        let listed = segment.contains(" & ") || segment.contains(" and ");
+        let segment = segment.replace(" & ", ", ").replace(" and ", ", ");
+        let parts: Vec<&str> = segment
+            .split([',', ';'])
+            .map(|part| part.trim().trim_start_matches("and ").trim())
+            .filter(|part| !part.is_empty())
+            .collect();
+        let mut names = Vec::new();
+        if !listed
+            && !initials_first
+            && parts.len() == 2
+            && !parts.iter().any(|part| part.contains(' '))
+        {
+            // `Doe, Jane`: one author with a spelled-out given name.
+            names.push(format!("{}, {}", parts[0], parts[1]));
+        } else if initials_first {
+            names.extend(parts.iter().map(|part| (*part).to_string()));
+        } else {
+            let mut pending: Option<&str> = None;
+            for part in parts {
+                if part.split_whitespace().all(is_initials) {
+                    match pending.take() {
+                        Some(last) => names.push(format!("{last}, {part}")),
+                        None => names.push(part.to_string()),
+                    }
+                } else if let Some(last) = pending.replace(part) {
+                    names.push(last.to_string());
+                }
+            }
+            names.extend(pending.map(str::to_string));
+        }
+        if names.is_empty() {
+            return;
+        }
+
+        let score = if names
+            .iter()
+            .all(|name| name.contains(',') || name.split_whitespace().count() > 1)
+        {
+            0.8
+        } else {
+            0.5
+        };
+        if others {
+            names.push("others".to_string());
+        }
+        self.push("author", names.join(" and "), score);
+    }
+
+    /// Record venue fields and the entry type; returns the type confidence.
+    fn push_venue(&mut self, venue: &str) -> f32 {
+        let mut tokens: Vec<&str> = venue.split_whitespace().collect();
+        let numbered = self.take_numbers(&mut tokens);
+
+        let text = tokens.join(" ");
+        let mut parts = text
+            .split([',', ';'])
+            .map(|part| part.trim().trim_matches(['.', ':', '(', ')']).trim())
+            .filter(|part| !part.is_empty());
+        let lower = text.to_lowercase();
+
+        if lower.contains("thesis") || lower.contains("dissertation") {
+            self.ty = Some(if lower.contains("master") {
+                EntryType::MastersThesis
+            } else {
+                EntryType::PhdThesis
+            });
+            if let Some(school) = parts.find(|part| {
+                let part = part.to_lowercase();
+                !part.contains("thesis") && !part.contains("dissertation")
+            }) {
+                self.push("school", school.to_string(), 0.5);
+            }
+            return 0.7;
+        }
+
+        let Some(name) = parts.next() else {
+            if numbered {
+                self.ty = Some(EntryType::Article);
+                return 0.4;
+            }
+            return 0.3;
+        };
+        let collection = name.get(..3).is_some_and(|prefix| {
+            prefix.eq_ignore_ascii_case("in ") || prefix.eq_ignore_ascii_case("in:")
+        });
+        let name = if collection {
+            name[3..].trim_start()
+        } else {
+            name
+        }
+        .to_string();
+        let lower_name = name.to_lowercase();
+
+        if collection
+            || PROCEEDINGS_WORDS
+                .iter()
+                .any(|word| lower_name.contains(word))
+        {
+            self.ty = Some(EntryType::InProceedings);
+            self.push("booktitle", name, 0.7);
+            0.8
+        } else if numbered || JOURNAL_WORDS.iter().any(|word| lower_name.contains(word)) {
+            self.ty = Some(EntryType::Article);
+            self.push("journal", name, if numbered { 0.7 } else { 0.6 });
+            if numbered {
+                0.8
+            } else {
+                0.6
+            }
+        } else {
+            self.ty = Some(EntryType::Book);
+            self.push("publisher", name, 0.4);
+            0.4
+        }
+    }
+
+    /// Remove page, volume, and issue tokens; returns `true` when any were found.
+    fn take_numbers(&mut self, tokens: &mut Vec<&str>) -> bool {
+        let mut keep = vec![true; tokens.len()];
+        for index in 0..tokens.len() {
+            let token = trim_number(tokens[index]);
+            let previous = index
+                .checked_sub(1)
+                .filter(|&previous| keep[previous])
+                .map(|previous| tokens[previous].to_ascii_lowercase());
+            let label = previous.as_deref().map(|label| label.trim_end_matches('.'));
+
+            let (volume, pages) = token
+                .split_once(':')
+                .map_or((None, Some(token)), |(volume, pages)| {
+                    (Some(volume), Some(pages))
+                });
+            if let Some(range) = pages.and_then(page_range) {
+                if let Some((volume, number)) = volume.and_then(volume_issue) {
+                    self.push("volume", volume.to_string(), 0.75);
+                    if let Some(number) = number {
+                        self.push("number", number.to_string(), 0.75);
+                    }
+                }
+                let labelled = matches!(label, Some("pp" | "p" | "pages"));
+                self.push("pages", range, if labelled { 0.85 } else { 0.7 });
+                keep[index] = false;
+                if labelled {
+                    keep[index - 1] = false;
+                }
+                continue;
+            }
+
+            let field = match label {
+                Some("vol" | "volume") => Some("volume"),
+                Some("no" | "number" | "issue") => Some("number"),
+                Some("pp" | "p" | "pages") => Some("pages"),
+                _ => None,
+            };
+            if let Some(field) = field.filter(|_| is_digits(token)) {
+                self.push(field, token.to_string(), 0.85);
+                keep[index - 1] = false;
+                keep[index] = false;
+            } else if let Some((volume, number)) = volume_issue(token) {
+                if number.is_some() || !self.has("volume") {
+                    let score = if number.is_some() { 0.75 } else { 0.55 };
+                    self.push("volume", volume.to_string(), score);
+                    if let Some(number) = number {
+                        self.push("number", number.to_string(), score);
+                    }
+                    keep[index] = false;
+                } else if !self.has("pages") {
+                    self.push("pages", volume.to_string(), 0.5);
+                    keep[index] = false;
+                }
+            }
+        }
+
+        let found = keep.contains(&false);
+        let mut keep = keep.into_iter();
+        tokens.retain(|_| keep.next().unwrap_or(true));
+        found
+    }
+
+    fn finish(self, type_confidence: f32) -> FreeformEntry {
+        let year = self
+            .fields
+            .iter()
+            .find(|(name, ..)| *name == "year")
+            .map(|(_, year, _)| year.as_str());
+        let author = self
+            .fields
+            .iter()
+            .find(|(name, ..)| *name == "author")
+            .map(|(_, author, _)| author.as_str());
+        let mut key: String = author
+            .and_then(|author| parse_names(author).into_iter().next())
+            .map(|name| {
+                name.last
+                    .chars()
+                    .filter(char::is_ascii_alphanumeric)
+                    .collect::<String>()
+                    .to_ascii_lowercase()
+            })
+            .filter(|last| !last.is_empty())
+            .unwrap_or_else(|| "ref".to_string());
+        key.push_str(year.unwrap_or_default());
+
+        let mut entry = Entry::new(self.ty.unwrap_or(EntryType::Misc), "");
+        entry.key = Cow::Owned(key);
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for (name, value, score) in self.fields {
+            entry.add_field(Field::new(name, Value::Literal(Cow::Owned(value))));
+            fields.push(FieldConfidence { field: name, score });
+        }
+        FreeformEntry {
+            entry,
+            type_confidence,
+            fields,
+        }
+    }
+}
+
+/// Drop a leading list label such as `[12]` or `3.`.
+fn strip_label(tokens: &mut Vec<&str>) {
+    let Some(first) = tokens.first() else {
+        return;
+    };
+    let bracketed = first.starts_with('[') && first.ends_with(']');
+    let numbered =
+        first.len() > 1 && first.ends_with(['.', ')']) && is_digits(&first[..first.len() - 1]);
+    if bracketed || numbered {
+        tokens.remove(0);
+    }
+}
+
+/// Move DOI, URL, and arXiv tokens into fields.
+fn take_identifiers(tokens: &mut Vec<&str>, fields: &mut Guesses) {
+    let mut keep = vec![true; tokens.len()];
+    for index in 0..tokens.len() {
+        let token = tokens[index].trim_end_matches([',', ';']);
+        let lower = token.to_ascii_lowercase();
+        if matches!(lower.as_str(), "doi" | "doi:") {
+            if let Some(doi) = tokens.get(index + 1).and_then(|next| normalize_doi(next)) {
+                fields.push("doi", doi, 0.95);
+                keep[index] = false;
+                keep[index + 1] = false;
+            }
+        } else if let Some(doi) = normalize_doi(token).or_else(|| {
+            lower
+                .starts_with("doi:")
+                .then(|| normalize_doi(&token[4..]))
+                .flatten()
+        }) {
+            fields.push("doi", doi, 0.95);
+            keep[index] = false;
+        } else if lower.starts_with("https://") || lower.starts_with("http://") {
+            fields.push("url", token.trim_end_matches('.').to_string(), 0.9);
+            keep[index] = false;
+        } else if lower.starts_with("arxiv:") && token.len() > 6 {
+            fields.push("eprint", token[6..].trim_end_matches('.').to_string(), 0.9);
+            fields.push("archiveprefix", "arXiv".to_string(), 0.9);
+            keep[index] = false;
+        }
+    }
+    let mut keep = keep.into_iter();
+    tokens.retain(|_| keep.next().unwrap_or(true));
+}
+
+/// Remove a title in straight or curly double quotes, leaving a segment break.
+fn take_quoted(text: &mut String) -> Option<String> {
+    let (start, open) = text
+        .char_indices()
+        .find(|(_, ch)| matches!(ch, '"' | '“'))?;
+    let close = if open == '“' { '”' } else { '"' };
+    let inner = start + open.len_utf8();
+    let end = inner + text[inner..].find(close)?;
+    let title = text[inner..end]
+        .trim()
+        .trim_end_matches([',', '.'])
+        .to_string();
+    text.replace_range(start..end + close.len_utf8(), ".");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Remove the publication year, preferring one in parentheses.
+fn take_year(text: &mut String) -> Option<(String, f32)> {
+    let bytes = text.as_bytes();
+    let mut candidates = Vec::new();
+    let mut start = 0;
+    while start + 4 <= bytes.len() {
+        let end = start + 4;
+        let bounded = (start == 0 || !bytes[start - 1].is_ascii_digit())
+            && bytes.get(end).map_or(true, |byte| !byte.is_ascii_digit());
+        if bounded && bytes[start..end].iter().all(u8::is_ascii_digit) {
+            let year: u16 = text[start..end].parse().unwrap_or_default();
+            if (1500..=2100).contains(&year) {
+                let parenthesized =
+                    start > 0 && bytes[start - 1] == b'(' && bytes.get(end) == Some(&b')');
+                candidates.push((start, parenthesized));
+            }
+        }
+        start += 1;
+    }
+
+    let &(start, parenthesized) = candidates
+        .iter()
+        .find(|(_, parenthesized)| *parenthesized)
+        .or_else(|| candidates.last())?;
+    let year = text[start..start + 4].to_string();
+    let score = if parenthesized || candidates.len() == 1 {
+        0.9
+    } else {
+        0.6
+    };
+    if parenthesized {
+        text.replace_range(start - 1..start + 5, "");
+    } else {
+        text.replace_range(start..start + 4, "");
+    }
+    Some((year, score))
+}
+
+/// Split at periods that end a sentence rather than an initial or abbreviation,
+/// and after question and exclamation marks.
+fn split_segments(text: &str, initials_first: bool) -> Vec<String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut segments = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for (index, &token) in tokens.iter().enumerate() {
+        if token.ends_with(['?', '!']) {
+            current.push(token);
+            push_segment(&mut segments, &current);
+            current.clear();
+            continue;
+        }
+        let Some(word) = token.strip_suffix('.') else {
+            current.push(token);
+            continue;
+        };
+        let initials = is_initials(word);
+        let boundary = if initials {
+            !initials_first
+                && tokens.get(index + 1).is_some_and(|&next| {
+                    next != "et" && !(next.ends_with('.') && is_initials(next))
+                })
+        } else {
+            !ABBREVIATIONS.contains(&word.to_ascii_lowercase().as_str())
+        };
+        if boundary {
+            current.push(if initials { token } else { word });
+            push_segment(&mut segments, &current);
+            current.clear();
+        } else {
+            current.push(token);
+        }
+    }
+    push_segment(&mut segments, &current);
+    segments
+}
+
+fn push_segment(segments: &mut Vec<String>, tokens: &[&str]) {
+    let segment = tokens.join(" ");
+    let segment = segment.trim_matches(|ch: char| matches!(ch, ',' | ';' | ':' | ' '));
+    if !segment.is_empty() {
+        segments.push(segment.to_string());
+    }
+}
+
+/// Return `true` for initials such as `A.`, `A.B.`, or `J.-P.`.
+fn is_initials(token: &str) -> bool {
+    let mut pieces = token
+        .split(['.', '-'])
+        .filter(|piece| !piece.is_empty())
+        .peekable();
+    pieces.peek().is_some()
+        && pieces.all(|piece| {
+            let mut chars = piece.chars();
+            chars.next().is_some_and(char::is_uppercase) && chars.next().is_none()
+        })
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn trim_number(token: &str) -> &str {
+    token
+        .trim_end_matches([',', '.', ';'])
+        .trim_start_matches('(')
+}
+
+/// Parse `891-921`, `891--921`, or `891–921` into BibTeX's `891--921`.
+fn page_range(text: &str) -> Option<String> {
+    let text = text.trim_end_matches(')');
+    let (first, last) = ["--", "-", "–", "—"]
+        .iter()
+        .find_map(|separator| text.split_once(separator))?;
+    (is_digits(first) && is_digits(last)).then(|| format!("{first}--{last}"))
+}
+
+/// Parse a volume with an optional issue, as in `17` or `17(10)`.
+fn volume_issue(text: &str) -> Option<(&str, Option<&str>)> {
+    let text = text.trim_end_matches(':');
+    match text.split_once('(') {
+        Some((volume, issue)) => {
+            let issue = issue.strip_suffix(')')?;
+            (is_digits(volume) && is_digits(issue)).then_some((volume, Some(issue)))
+        }
+        None => (is_digits(text) && text.len() <= 4).then_some((text, None)),
+    }
+}
@@ -0,0 +1,115 @@
+//! Coauthor graphs built from bibliography entries.
+
+use crate::{Entry, PersonName};
+use ahash::AHashMap;
+use std::collections::BTreeMap;
+
+/// One author node in a [`CoauthorGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphAuthor {
+    /// Normalized identity from [`PersonName::identity`].
+    pub identity: String,
+    /// Display name of the first occurrence.
+    pub name: String,
+    /// Number of entries listing this author.
+    pub entries: usize,
+}
+
+/// Undirected coauthor graph weighted by the number of shared entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoauthorGraph {
+    authors: Vec<GraphAuthor>,
+    adjacency: Vec<BTreeMap<usize, usize>>,
+}
+
+impl CoauthorGraph {
+    /// Build a graph from the `author` fields of `entries`.
+    ///
+    /// Authors are matched by [`PersonName::identity`]; a name repeated in
+    /// one entry counts once.
+    #[must_use]
+    pub fn from_entries<'e, 'a: 'e>(entries: impl IntoIterator<Item = &'e Entry<'a>>) -> Self {
+        let mut graph = Self::default();
+        let mut indices = AHashMap::new();
+        for entry in entries {
+            let mut nodes = entry
+                .author_list()
+                .iter()
+                .map(|name| graph.node(&mut indices, name))
+                .collect::<Vec<_>>();
+            nodes.sort_unstable();
+            nodes.dedup();
+            for (offset, &left) in nodes.iter().enumerate() {
+                graph.authors[left].entries += 1;
+                for &right in &nodes[offset + 1..] {
+                    *graph.adjacency[left].entry(right).or_insert(0) += 1;
+                    *graph.adjacency[right].entry(left).or_insert(0) += 1;
+                }
+            }
+        }
+        graph
+    }
+
+    fn node(&mut self, indices: &mut AHashMap<String, usize>, name: &PersonName) -> usize {
+        let identity = name.identity();
+        if let Some(&index) = indices.get(&identity) {
+            return index;
+        }
+        let index = self.authors.len();
+        self.authors.push(GraphAuthor {
+            identity: identity.clone(),
+            name: name.display_name(),
+            entries: 0,
+        });
+        self.adjacency.push(BTreeMap::new());
+        indices.insert(identity, index);
+        index
+    }
+
+    /// Return the author nodes in first-seen order.
+    #[must_use]
+    pub fn authors(&self) -> &[GraphAuthor] {
+        &self.authors
+    }
+
+    /// Return the node index for an author identity.
+    #[must_use]
+    pub fn index_of(&self, identity: &str) -> Option<usize> {
+        self.authors
+            .iter()
+            .position(|author| author.identity == identity)
+    }
+
+    /// Return `(neighbor, shared entries)` pairs for one author, by neighbor index.
+    pub fn neighbors(&self, author: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency
+            .get(author)
+            .into_iter()
+            .flat_map(|edges| edges.iter().map(|(&index, &weight)| (index, weight)))
+    }
+
+    /// Return the number of entries two authors share.
+    #[must_use]
+    pub fn weight(&self, left: usize, right: usize) -> usize {
+        self.adjacency
+            .get(left)
+            .and_then(|edges| edges.get(&right))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Iterate over each edge once as `(left, right, weight)` with `left < right`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.adjacency.iter().enumerate().flat_map(|(left, edges)| {
+            edges
+                .range(left + 1..)
+                .map(move |(&right, &weight)| (left, right, weight))
+        })
+    }
+
+    /// Return the number of distinct edges.
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+}
@@ -0,0 +1,375 @@
+//! Parsing input that arrives in pieces.
+//!
+//! [`ParserState`] parses chunks pushed by the caller into documents;
+//! [`Parser::parse_reader`] pulls from an [`std::io::BufRead`] into a
+//! [`Library`].
+
+use crate::source::SourceOffset;
+use crate::{Error, Library, ParseContinuation, ParsedDocument, Parser, Result, StringDefinition};
+use std::io::BufRead;
+
+/// Parser progress through input that arrives in chunks.
+///
+/// Each [`feed`](Self::feed) parses the blocks completed so far and keeps
+/// only the unfinished tail, so data read from a network stream never has
+/// to be buffered whole. A block counts as complete once a later block
+/// starts: an `@` at the beginning of a line, as for
+/// [`Parser::parse_range`], that is not inside an entry's braces or a
+/// quoted value. Brace depth and quoting carry over from one chunk to the
+/// next, so an entry whose braces never balance holds back the rest of the
+/// stream until [`finish`](Self::finish), which parses the rest.
+///
+/// Source spans are positions in the whole stream, and `@string`
+/// definitions from earlier chunks expand values in later ones. The state
+/// can be cloned to save it and carry on from the same point later.
+///
+/// ```
+/// use bibtex_parser::{Parser, ParserState};
+///
+/// let mut state = ParserState::new(Parser::new());
+/// let first = state.feed("@string{j = {Journal}}\n@article{a, jour")?;
+/// assert_eq!(first.strings().len(), 1);
+/// assert!(first.entries().is_empty());
+///
+/// let second = state.feed("nal = j}\n@article{b, journal = j}\n")?;
+/// assert_eq!(second.entries()[0].key(), "a");
+/// assert_eq!(second.entries()[0].source.unwrap().line, 2);
+///
+/// let last = state.finish()?;
+/// assert_eq!(last.library().entries()[0].get("journal"), Some("Journal"));
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserState {
+    parser: Parser,
+    pending: String,
+    consumed: usize,
+    lines: usize,
+    columns: usize,
+    scanner: BlockScanner,
+}
+
+impl ParserState {
+    /// Start parsing a stream with `parser`.
+    #[must_use]
+    pub fn new(parser: Parser) -> Self {
+        Self {
+            parser,
+            ..Self::default()
+        }
+    }
+
+    /// Append `chunk` and parse every block it completes.
+    ///
+    /// With a [`ParseBudget`](crate::ParseBudget), blocks past the budget
+    /// stay pending for the next call.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Parser::parse_document`]. The chunk is
+    /// kept, but the state is otherwise unchanged.
+    pub fn feed(&mut self, chunk: &str) -> Result<ParsedDocument<'static>> {
+        self.push(chunk);
+        self.parse_pending(self.scanner.last_block)
+    }
+
+    /// Parse everything still pending.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Parser::parse_document`].
+    pub fn finish(mut self) -> Result<ParsedDocument<'static>> {
+        let end = self.pending.len();
+        self.parse_pending(end)
+    }
+
+    /// Like [`Self::feed`], but parse with [`Parser::parse`] into a library.
+    pub(crate) fn feed_library(&mut self, chunk: &str) -> Result<Library<'static>> {
+        self.push(chunk);
+        self.parse_pending_library(self.scanner.last_block)
+    }
+
+    /// Like [`Self::finish`], but parse with [`Parser::parse`] into a library.
+    pub(crate) fn finish_library(mut self) -> Result<Library<'static>> {
+        let end = self.pending.len();
+        self.parse_pending_library(end)
+    }
+
+    /// Return the number of bytes parsed so far.
+    #[must_use]
+    pub const fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Return the input received but not parsed yet.
+    #[must_use]
+    pub fn pending(&self) -> &str {
+        &self.pending
+    }
+
+    fn push(&mut self, chunk: &str) {
+        self.pending.push_str(chunk);
+        self.scanner.advance(self.pending.as_bytes());
+    }
+
+    /// Parse `pending[..end]` and drop what was parsed from the buffer.
+    fn parse_pending(&mut self, end: usize) -> Result<ParsedDocument<'static>> {
+        let mut document = self
+            .parser
+            .parse_document(&self.pending[..end])
+            .map_err(|error| self.offset_error(error))?;
+        let parsed = document
+            .continuation()
+            .map_or(end, ParseContinuation::offset);
+        document.offset_sources(self.offset());
+        let document = document.into_owned();
+        self.drop_parsed(parsed, document.library().strings());
+        Ok(document)
+    }
+
+    /// Parse `pending[..end]` into a library and drop it from the buffer.
+    fn parse_pending_library(&mut self, end: usize) -> Result<Library<'static>> {
+        let mut library = self
+            .parser
+            .parse(&self.pending[..end])
+            .map_err(|error| self.offset_error(error))?;
+        library.offset_sources(self.offset());
+        let library = library.into_owned();
+        self.drop_parsed(end, library.strings());
+        Ok(library)
+    }
+
+    const fn offset(&self) -> SourceOffset {
+        SourceOffset::from_parts(self.consumed, self.lines, self.columns)
+    }
+
+    /// Drop the first `parsed` bytes, which defined `strings`, from the buffer.
+    fn drop_parsed(&mut self, parsed: usize, strings: &[StringDefinition<'static>]) {
+        if !strings.is_empty() {
+            self.parser = std::mem::take(&mut self.parser).preload_strings(strings.to_vec());
+        }
+        let done = &self.pending[..parsed];
+        self.consumed += parsed;
+        self.lines += memchr::memchr_iter(b'\n', done.as_bytes()).count();
+        self.columns = match done.rfind('\n') {
+            Some(line) => done[line + 1..].chars().count(),
+            None => self.columns + done.chars().count(),
+        };
+        self.pending.drain(..parsed);
+        self.scanner.shift(parsed);
+    }
+
+    /// Make the position of a parse error relative to the whole stream.
+    fn offset_error(&self, error: Error) -> Error {
+        match error {
+            Error::ParseError {
+                line,
+                column,
+                message,
+                snippet,
+            } => Error::ParseError {
+                column: if line == 1 {
+                    column + self.columns
+                } else {
+                    column
+                },
+                line: line + self.lines,
+                message,
+                snippet,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Finds block starts in text that arrives in pieces.
+///
+/// Bytes are scanned once; the position inside the current entry is kept
+/// between calls to [`advance`](Self::advance).
+#[derive(Debug, Clone, Copy, Default)]
+struct BlockScanner {
+    /// Bytes of the buffer scanned so far.
+    scanned: usize,
+    /// Start of the line holding the last top-level block start.
+    last_block: usize,
+    /// Start of the line being scanned.
+    line_start: usize,
+    /// Whether the line so far holds only spaces and tabs.
+    line_blank: bool,
+    /// Whether the previous byte inside an entry was an unescaped backslash.
+    escaped: bool,
+    place: ScanPlace,
+}
+
+/// Where a [`BlockScanner`] is relative to the entries around it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ScanPlace {
+    /// Between blocks, where text is a comment.
+    #[default]
+    Between,
+    /// After an `@`, reading the block type.
+    BlockType,
+    /// Inside a block that ends at `close`, `depth` groups deep.
+    Body {
+        close: u8,
+        depth: usize,
+        quoted: bool,
+    },
+}
+
+impl BlockScanner {
+    /// Scan the bytes of `buffer` not seen before.
+    fn advance(&mut self, buffer: &[u8]) {
+        if self.scanned == 0 {
+            self.line_blank = true;
+        }
+        for (index, &byte) in buffer.iter().enumerate().skip(self.scanned) {
+            let line_blank = self.line_blank;
+            if matches!(byte, b'\n' | b'\r') {
+                self.line_start = index + 1;
+                self.line_blank = true;
+            } else if !matches!(byte, b' ' | b'\t') {
+                self.line_blank = false;
+            }
+            self.place = match self.place {
+                ScanPlace::Between | ScanPlace::BlockType if byte == b'@' => {
+                    if line_blank {
+                        self.last_block = self.line_start;
+                    }
+                    ScanPlace::BlockType
+                }
+                ScanPlace::Between => ScanPlace::Between,
+                ScanPlace::BlockType => match byte {
+                    b'{' => ScanPlace::Body {
+                        close: b'}',
+                        depth: 1,
+                        quoted: false,
+                    },
+                    b'(' => ScanPlace::Body {
+                        close: b')',
+                        depth: 1,
+                        quoted: false,
+                    },
+                    byte if byte.is_ascii_alphanumeric()
+                        || byte.is_ascii_whitespace()
+                        || matches!(byte, b'_' | b'-' | b':' | b'.') =>
+                    {
+                        ScanPlace::BlockType
+                    }
+                    _ => ScanPlace::Between,
+                },
+                ScanPlace::Body { .. } if std::mem::take(&mut self.escaped) => self.place,
+                ScanPlace::Body {
+                    close,
+                    depth,
+                    quoted,
+                } => match byte {
+                    b'\\' => {
+                        self.escaped = true;
+                        self.place
+                    }
+                    b'{' => ScanPlace::Body {
+                        close,
+                        depth: depth + 1,
+                        quoted,
+                    },
+                    byte if byte == close && depth == 1 && !quoted => ScanPlace::Between,
+                    b'}' if depth > 1 => ScanPlace::Body {
+                        close,
+                        depth: depth - 1,
+                        quoted,
+                    },
+                    b'"' if depth == 1 => ScanPlace::Body {
+                        close,
+                        depth,
+                        quoted: !quoted,
+                    },
+                    _ => self.place,
+                },
+            };
+        }
+        self.scanned = buffer.len();
+    }
+
+    /// Account for the first `bytes` bytes leaving the buffer.
+    fn shift(&mut self, bytes: usize) {
+        self.scanned -= bytes;
+        self.last_block = self.last_block.saturating_sub(bytes);
+        self.line_start = self.line_start.saturating_sub(bytes);
+    }
+}
+
+impl Parser {
+    /// Read and parse `reader` into owned data, a block at a time.
+    ///
+    /// The input goes through a [`ParserState`], so blocks are parsed as
+    /// soon as a later block starts and input from a socket or stdin is
+    /// never held in memory whole. Error lines and captured source spans
+    /// are positions in the whole input.
+    ///
+    /// The result matches [`Self::parse`] when every `@string` is defined
+    /// once, before its first use. `parse` applies the last definition of a
+    /// name to the whole input, while here definitions only reach the blocks
+    /// parsed with or after them. So an entry that uses a name ahead of its
+    /// definition, or of a redefinition, may expand with an older value or
+    /// fail as undefined, depending on where the reader's buffer splits the
+    /// input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error when reading fails or the input is not UTF-8,
+    /// or the errors of [`Self::parse`].
+    pub fn parse_reader(&self, mut reader: impl BufRead) -> Result<Library<'static>> {
+        let mut state = ParserState::new(self.clone());
+        let mut library = Library::new();
+        let mut pending = Vec::new();
+        loop {
+            let chunk = reader.fill_buf()?;
+            let done = chunk.is_empty();
+            pending.extend_from_slice(chunk);
+            let read = chunk.len();
+            reader.consume(read);
+
+            let text = utf8_prefix(&pending, done)?;
+            let fed = text.len();
+            library.merge(state.feed_library(text)?);
+            pending.drain(..fed);
+            if done {
+                library.merge(state.finish_library()?);
+                return Ok(library);
+            }
+        }
+    }
+}
+
+impl Library<'static> {
+    /// Read and parse `reader` with a default [`Parser`].
+    ///
+    /// ```
+    /// use bibtex_parser::Library;
+    ///
+    /// let input = "@misc{a, title = {A}}\n@misc{b, title = {B}}\n";
+    /// let library = Library::from_reader(input.as_bytes())?;
+    /// assert_eq!(library.entries().len(), 2);
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Parser::parse_reader`].
+    pub fn from_reader(reader: impl BufRead) -> Result<Self> {
+        Parser::new().parse_reader(reader)
+    }
+}
+
+/// Return the longest UTF-8 prefix of `bytes`, allowing a character cut
+/// off at the end unless the input is `done`.
+fn utf8_prefix(bytes: &[u8], done: bool) -> Result<&str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(error) if error.error_len().is_none() && !done => {
+            Ok(std::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap_or_default())
+        }
+        Err(error) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error).into()),
+    }
+}
@@ -0,0 +1,111 @@
+//! Shared storage for repeated literal values.
+
+use crate::{Library, Value};
+use ahash::AHashSet;
+use std::borrow::Cow;
+
+/// Pool of literal values that owned libraries can share.
+///
+/// Collect literals from one or more libraries, then [`intern`](Self::intern)
+/// each library so identical literals (the same publisher, journal, or
+/// booktitle) borrow a single copy from the pool instead of owning their own.
+///
+/// ```
+/// use bibtex_parser::{Library, ValueInterner};
+///
+/// let library = Library::parse(
+///     "@inproceedings{a, booktitle = {Proc. VLDB}}\n@inproceedings{b, booktitle = {Proc. VLDB}}",
+/// )?;
+/// let mut interner = ValueInterner::new();
+/// interner.collect(&library);
+/// let library = interner.intern(library);
+///
+/// let a = library.entries()[0].get("booktitle").unwrap();
+/// let b = library.entries()[1].get("booktitle").unwrap();
+/// assert_eq!(a.as_ptr(), b.as_ptr());
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ValueInterner {
+    values: AHashSet<Box<str>>,
+}
+
+impl ValueInterner {
+    /// Create an empty interner.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one literal to the pool.
+    pub fn insert(&mut self, text: &str) {
+        if !self.values.contains(text) {
+            self.values.insert(text.into());
+        }
+    }
+
+    /// Add every literal in the fields, strings, and preambles of `library`.
+    pub fn collect(&mut self, library: &Library<'_>) {
+        for entry in library.entries() {
+            for field in &entry.fields {
+                self.insert_value(&field.value);
+            }
+        }
+        for definition in library.strings() {
+            self.insert_value(&definition.value);
+        }
+        for preamble in library.preambles() {
+            self.insert_value(&preamble.value);
+        }
+    }
+
+    /// Return the number of distinct literals in the pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return `true` when the pool is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Convert `library` to owned data whose pooled literals borrow from this interner.
+    ///
+    /// Literals that were never collected stay owned.
+    #[must_use]
+    pub fn intern<'i>(&'i self, library: Library<'_>) -> Library<'i> {
+        let mut library: Library<'i> = library.into_owned();
+        library.for_each_value_mut(|value| self.intern_value(value));
+        library
+    }
+
+    fn insert_value(&mut self, value: &Value<'_>) {
+        match value {
+            Value::Literal(text) => self.insert(text),
+            Value::Concat(parts) => {
+                for part in parts.iter() {
+                    self.insert_value(part);
+                }
+            }
+            Value::Number(_) | Value::Variable(_) => {}
+        }
+    }
+
+    fn intern_value<'i>(&'i self, value: &mut Value<'i>) {
+        match value {
+            Value::Literal(text) => {
+                if let Some(shared) = self.values.get(text.as_ref()) {
+                    *text = Cow::Borrowed(shared);
+                }
+            }
+            Value::Concat(parts) => {
+                for part in parts.iter_mut() {
+                    self.intern_value(part);
+                }
+            }
+            Value::Number(_) | Value::Variable(_) => {}
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Minimal JSON values for the crate's JSON exports and service responses.
+//!
+//! Responses are read with `serde_json` under the `metadata_providers`
+//! feature; writing needs no dependency.
+
+use std::fmt::{self, Write};
+
+/// A JSON value; object members keep insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+impl Json {
+    /// Create an empty object.
+    pub const fn object() -> Self {
+        Self::Object(Vec::new())
+    }
+
+    /// Append a member to an object; ignored for other values.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Self>) {
+        if let Self::Object(members) = self {
+            members.push((key.into(), value.into()));
+        }
+    }
+
+    /// Parse a JSON document, returning `None` when it is malformed.
+    #[cfg(feature = "metadata_providers")]
+    pub fn parse(input: &str) -> Option<Self> {
+        serde_json::from_str::<serde_json::Value>(input)
+            .ok()
+            .map(Self::from)
+    }
+
+    /// Return the member named `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Return the text of a string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Return the items of an array, or an empty slice for other values.
+    pub fn items(&self) -> &[Self] {
+        match self {
+            Self::Array(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// Render with two-space indentation.
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output, Some(0))
+            .expect("writing to a String cannot fail");
+        output
+    }
+
+    fn write(&self, out: &mut impl Write, indent: Option<usize>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(out, "{value}"),
+            Self::Number(value) => write!(out, "{value}"),
+            Self::Float(value) if value.is_finite() => write!(out, "{value}"),
+            Self::Null | Self::Float(_) => out.write_str("null"),
+            Self::String(value) => write_string(out, value),
+            Self::Array(items) => {
+                write_container(out, indent, ('[', ']'), items, |out, item, indent| {
+                    item.write(out, indent)
+                })
+            }
+            Self::Object(members) => write_container(
+                out,
+                indent,
+                ('{', '}'),
+                members,
+                |out, (key, value), indent| {
+                    write_string(out, key)?;
+                    out.write_str(if indent.is_some() { ": " } else { ":" })?;
+                    value.write(out, indent)
+                },
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    /// Render compactly on one line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, None)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Number(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<Vec<Self>> for Json {
+    fn from(value: Vec<Self>) -> Self {
+        Self::Array(value)
+    }
+}
+
+#[cfg(feature = "metadata_providers")]
+impl From<serde_json::Value> for Json {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Null,
+            serde_json::Value::Bool(value) => Self::Bool(value),
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(Self::Number)
+                .or_else(|| number.as_f64().map(Self::Float))
+                .unwrap_or(Self::Null),
+            serde_json::Value::String(value) => Self::String(value),
+            serde_json::Value::Array(items) => {
+                Self::Array(items.into_iter().map(Self::from).collect())
+            }
+            serde_json::Value::Object(members) => Self::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key, Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+fn write_container<W: Write, T>(
+    out: &mut W,
+    indent: Option<usize>,
+    (open, close): (char, char),
+    items: &[T],
+    mut write_item: impl FnMut(&mut W, &T, Option<usize>) -> fmt::Result,
+) -> fmt::Result {
+    out.write_char(open)?;
+    if items.is_empty() {
+        return out.write_char(close);
+    }
+    let inner = indent.map(|depth| depth + 1);
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            out.write_char(',')?;
+        }
+        newline(out, inner)?;
+        write_item(out, item, inner)?;
+    }
+    newline(out, indent)?;
+    out.write_char(close)
+}
+
+fn newline(out: &mut impl Write, indent: Option<usize>) -> fmt::Result {
+    if let Some(depth) = indent {
+        out.write_char('\n')?;
+        for _ in 0..depth {
+            out.write_str("  ")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut impl Write, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for ch in value.chars() {
+        match ch {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            ch if u32::from(ch) < 0x20 => write!(out, "\\u{:04x}", u32::from(ch))?,
+            ch => out.write_char(ch)?,
+        }
+    }
+    out.write_char('"')
+}
@@ -0,0 +1,279 @@
+//! Citation-key renaming and canonicalization helpers.
+
+use crate::parser::simd::find_balanced_braces;
+use ahash::AHashMap;
+use memchr::memchr;
+#[cfg(feature = "key_canonicalization")]
+use std::borrow::Cow;
+#[cfg(feature = "key_canonicalization")]
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Field names whose values list citation keys of other entries.
+pub(crate) const KEY_REFERENCE_FIELDS: &[&str] =
+    &["crossref", "xdata", "related", "ids", "entryset"];
+
+/// Ordered map from old citation keys to their replacements.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRenameMap {
+    renames: Vec<(String, String)>,
+    /// Position of each old key in `renames`.
+    index: AHashMap<String, usize>,
+}
+
+impl KeyRenameMap {
+    /// Create an empty rename map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `old` is now called `new`, replacing any earlier rename of `old`.
+    pub fn insert(&mut self, old: impl Into<String>, new: impl Into<String>) {
+        let (old, new) = (old.into(), new.into());
+        if let Some(&position) = self.index.get(&old) {
+            self.renames[position].1 = new;
+        } else {
+            self.index.insert(old.clone(), self.renames.len());
+            self.renames.push((old, new));
+        }
+    }
+
+    /// Return the new key for `old`.
+    #[must_use]
+    pub fn get(&self, old: &str) -> Option<&str> {
+        self.index
+            .get(old)
+            .map(|&position| self.renames[position].1.as_str())
+    }
+
+    /// Iterate over `(old, new)` pairs in rename order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.renames
+            .iter()
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+    }
+
+    /// Return the number of renamed keys.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.renames.len()
+    }
+
+    /// Return `true` when no keys were renamed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+}
+
+impl PartialEq for KeyRenameMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.renames == other.renames
+    }
+}
+
+impl Eq for KeyRenameMap {}
+
+impl<O: Into<String>, N: Into<String>> FromIterator<(O, N)> for KeyRenameMap {
+    fn from_iter<I: IntoIterator<Item = (O, N)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (old, new) in iter {
+            map.insert(old, new);
+        }
+        map
+    }
+}
+
+/// How citation keys are canonicalized while parsing.
+///
+/// Keys are trimmed and NFC-normalized, so a key typed as a precomposed
+/// `é` matches one saved by a tool that writes `e` plus a combining accent.
+#[cfg(feature = "key_canonicalization")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyCanonicalization {
+    lowercase: bool,
+}
+
+#[cfg(feature = "key_canonicalization")]
+impl KeyCanonicalization {
+    /// Trim and NFC-normalize keys.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { lowercase: false }
+    }
+
+    /// Also lowercase keys.
+    #[must_use]
+    #[inline]
+    pub const fn lowercase(mut self) -> Self {
+        self.lowercase = true;
+        self
+    }
+
+    /// Return the canonical form of `key`, borrowing when it is unchanged.
+    #[must_use]
+    pub fn canonicalize(self, key: &str) -> Cow<'_, str> {
+        let trimmed = key.trim();
+        let mut key = if is_nfc(trimmed) {
+            Cow::Borrowed(trimmed)
+        } else {
+            Cow::Owned(trimmed.nfc().collect())
+        };
+        if self.lowercase && key.chars().any(char::is_uppercase) {
+            key = Cow::Owned(key.to_lowercase());
+        }
+        key
+    }
+    /// Return `true` when `left` and `right` have the same canonical form.
+    #[must_use]
+    pub fn matches(self, left: &str, right: &str) -> bool {
+        left == right || self.canonicalize(left) == self.canonicalize(right)
+    }
+}
+
+/// Rewrite citation keys inside `\cite`-family commands in LaTeX source.
+///
+/// Any command whose name contains `cite` (such as `\citep`, `\textcite`, or
+/// `\nocite`) is rewritten, including the repeated key groups of multi-cite
+/// commands like `\cites`. Everything outside the key lists is kept
+/// byte-for-byte.
+#[must_use]
+pub fn rewrite_citations(latex: &str, renames: &KeyRenameMap) -> String {
+    if renames.is_empty() {
+        return latex.to_string();
+    }
+
+    let mut output = String::with_capacity(latex.len());
+    let mut copied = 0;
+    for_each_citation_list(latex, |start, end| {
+        if let Some(renamed) = rename_key_list(&latex[start..end], renames) {
+            output.push_str(&latex[copied..start]);
+            output.push_str(&renamed);
+            copied = end;
+        }
+    });
+    output.push_str(&latex[copied..]);
+    output
+}
+
+/// Return the keys cited by `\cite`-family commands in LaTeX source, in
+/// order of first appearance.
+///
+/// Commands are recognized as for [`rewrite_citations`]. The `*` wildcard of
+/// `\nocite{*}` is not a key and is skipped.
+///
+/// ```
+/// use bibtex_parser::cited_keys;
+///
+/// let keys = cited_keys(r"See \citep[p.~2]{doe, roe} and \textcite{doe}.");
+/// assert_eq!(keys, ["doe", "roe"]);
+/// ```
+#[must_use]
+pub fn cited_keys(latex: &str) -> Vec<&str> {
+    let mut keys: Vec<&str> = Vec::new();
+    for_each_citation_list(latex, |start, end| {
+        for key in latex[start..end].split(',').map(str::trim) {
+            if !key.is_empty() && key != "*" && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    });
+    keys
+}
+
+/// Call `visit` with the byte range of every key list inside a
+/// `\cite`-family command, excluding the surrounding braces.
+fn for_each_citation_list(latex: &str, mut visit: impl FnMut(usize, usize)) {
+    let bytes = latex.as_bytes();
+    let mut pos = 0;
+    while let Some(offset) = memchr(b'\\', &bytes[pos..]) {
+        let start = pos + offset;
+        let name_end = start
+            + 1
+            + bytes[start + 1..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_alphabetic())
+                .count();
+        if name_end == start + 1 {
+            pos = (start + 2).min(bytes.len());
+            continue;
+        }
+        pos = name_end;
+
+        let name = &latex[start + 1..name_end];
+        if !name.to_ascii_lowercase().contains("cite") {
+            continue;
+        }
+        let multi = name.ends_with("cites");
+        let mut cursor = name_end;
+        if bytes.get(cursor) == Some(&b'*') {
+            cursor += 1;
+        }
+        loop {
+            cursor += bytes[cursor..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+            match bytes.get(cursor) {
+                Some(b'[') => match skip_optional_argument(bytes, cursor, b']') {
+                    Some(end) => cursor = end,
+                    None => break,
+                },
+                Some(b'(') => match skip_optional_argument(bytes, cursor, b')') {
+                    Some(end) => cursor = end,
+                    None => break,
+                },
+                Some(b'{') => {
+                    let Some(length) = find_balanced_braces(&bytes[cursor..]) else {
+                        break;
+                    };
+                    let end = cursor + length;
+                    visit(cursor + 1, end - 1);
+                    cursor = end;
+                    if !multi {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        pos = cursor;
+    }
+}
+
+/// Return the position after the optional argument opened at `start`.
+pub(crate) fn skip_optional_argument(bytes: &[u8], start: usize, close: u8) -> Option<usize> {
+    let mut depth = 0usize;
+    for (offset, &byte) in bytes[start + 1..].iter().enumerate() {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ if byte == close && depth == 0 => return Some(start + offset + 2),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Rewrite the comma-separated key list in `value`, returning `None` when no key changes.
+pub(crate) fn rename_key_list(value: &str, renames: &KeyRenameMap) -> Option<String> {
+    let mut changed = false;
+    let mut output = String::with_capacity(value.len());
+    for (index, part) in value.split(',').enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+        let key = part.trim();
+        match renames.get(key) {
+            Some(new) if !key.is_empty() => {
+                let start = part.len() - part.trim_start().len();
+                output.push_str(&part[..start]);
+                output.push_str(new);
+                output.push_str(&part[start + key.len()..]);
+                changed = true;
+            }
+            _ => output.push_str(part),
+        }
+    }
+    changed.then_some(output)
+}
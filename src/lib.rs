@@ -17,6 +17,10 @@
 //! - Configurable writer for formatting and file output.
 //! - Optional `parallel` feature for parsing multiple files concurrently.
 //! - Optional `latex_to_unicode` feature for LaTeX accent conversion helpers.
+//! - Optional `tracing` feature for spans around parse phases.
+//! - Optional `testing` feature for semantic BibTeX assertions in tests.
+//! - Optional `biblatex` feature for conversions to and from `biblatex` crate types.
+//! - Optional `derive` feature for `#[derive(FromEntry, ToEntry)]`.
 //!
 //! ## Parse
 //!
@@ -73,10 +77,7 @@
 //!
 //! let library = Library::parse(r#"@article{paper, title = "Example Paper"}"#)?;
 //! let mut output = Vec::new();
-//! let config = WriterConfig {
-//!     align_values: true,
-//!     ..Default::default()
-//! };
+//! let config = WriterConfig::default().align_values(true);
 //!
 //! Writer::with_config(&mut output, config).write_library(&library)?;
 //! assert!(String::from_utf8(output).unwrap().contains("@article{paper"));
@@ -125,19 +126,51 @@
     clippy::multiple_crate_versions
 )]
 
+pub mod bbl;
+pub mod cleanup;
+pub mod config;
 pub mod corpus;
 pub mod document;
 pub mod error;
+pub mod extract;
+pub mod fixes;
+pub mod graph;
+pub mod incremental;
+pub mod intern;
+pub mod keys;
+pub mod lint;
+pub mod metadata;
 pub mod model;
 pub mod parser;
 #[cfg(feature = "python")]
 mod python;
+pub mod quirks;
+pub mod shared;
 pub mod source;
+pub mod syntax;
+pub mod venues;
 
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "freeform")]
+pub mod freeform;
 #[cfg(feature = "latex_to_unicode")]
 pub mod latex_unicode;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "watch")]
+pub mod watch;
 
+#[cfg(feature = "biblatex")]
+mod biblatex_interop;
+mod compress;
+mod csl;
+mod json;
 mod library;
+mod trace;
+mod typst;
 mod writer;
 
 #[cfg(all(
@@ -147,6 +180,13 @@ mod writer;
 #[global_allocator]
 static PYTHON_EXTENSION_ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+pub use bbl::{bbl_keys, BblCheck};
+#[cfg(feature = "derive")]
+pub use bibtex_parser_derive::{FromEntry, ToEntry};
+pub use cleanup::{
+    decode_html_entities, normalize_punctuation, repair_mojibake, PunctuationStyle, ValueRepair,
+};
+pub use config::{Config, CONFIG_FILE_NAMES};
 pub use corpus::{
     CorpusEvent, CorpusSource, DuplicateKeyGroup, DuplicateKeyOccurrence, ParsedCorpus,
 };
@@ -158,41 +198,89 @@ pub use document::{
     ValueDelimiter,
 };
 pub use error::{Error, Result, SourceId, SourceSpan};
+pub use extract::{
+    ExtractError, FieldError, FieldErrorKind, FieldReader, FromEntry, FromField, ToEntry, ToField,
+};
+pub use fixes::{code_actions, CodeAction, TextEdit};
+pub use graph::{CoauthorGraph, GraphAuthor};
+pub use incremental::ParserState;
+pub use intern::ValueInterner;
+#[cfg(feature = "key_canonicalization")]
+pub use keys::KeyCanonicalization;
+pub use keys::{cited_keys, rewrite_citations, KeyRenameMap};
 pub use library::{
-    Block, Comment, FailedBlock, FieldNameCase, FieldNormalizeOptions, IssueSummary, Library,
-    LibraryBuilder, LibraryStats, MonthStyle, Parser, Preamble, SortOptions, StringDefinition,
-    ValidationReport,
+    AddOutcome, Block, CancelToken, Comment, CommentPolicy, DuplicateKeyPolicy, EntryId, EntrySet,
+    ExpansionPolicy, FailedBlock, FieldNameCase, FieldNormalizeOptions, IssueSummary, KeyCharset,
+    Library, LibraryBuilder, LibraryReport, LibraryStats, LibraryView, MacroResolver, MergePolicy,
+    MonthStyle, ParseBudget, ParseContinuation, Parser, ParserProfile, Preamble, SortOptions,
+    StringDefinition, Timeline, ValidationReport, YearCounts, DEFAULT_MAX_EXPANSION_DEPTH,
+    DEFAULT_MAX_EXPANSION_LENGTH,
+};
+pub use lint::{lint, Baseline, Diagnostics, LintOptions, SarifReport, SUPPRESSION_DIRECTIVE};
+pub use metadata::{
+    EnrichmentPipeline, EnrichmentPlan, Identifier, MetadataCache, MetadataFailure,
+    MetadataProvider, MetadataResolution, ProposedChange, ResolvedEntry,
 };
 pub use model::{
-    canonical_biblatex_field_alias, classify_resource_field, normalize_biblatex_field_name,
-    normalize_doi, normalize_field_name_ascii, parse_date_parts, parse_names, DateParseError,
-    DateParts, Entry, EntryType, Field, PersonName, ResourceField, ResourceKind, ValidationError,
-    ValidationLevel, ValidationSeverity, Value,
+    canonical_biblatex_field_alias, classify_resource_field, format_author_list,
+    normalize_biblatex_field_name, normalize_corpus_id, normalize_doi, normalize_field_name_ascii,
+    normalize_openalex, normalize_s2id, parse_date_parts, parse_file_attachments, parse_name_list,
+    parse_names, DateParseError, DateParts, Entry, EntryType, EtAlStyle, Field, FieldAliases,
+    FieldOrigin, FileAttachment, LanguageTag, NameList, NameStyle, NormalizedValue, PersonName,
+    PublicationYear, ResourceField, ResourceKind, ValidationError, ValidationLevel,
+    ValidationSeverity, Value,
 };
-pub use parser::{parse_bibtex, ParsedItem};
+pub use parser::{
+    parse_bibtex, parse_fragments, stream, tokenize, ItemStream, ParsedItem, Token, TokenKind,
+    Tokens,
+};
+pub use quirks::{brace_acronyms, title_case_all_caps, ExportQuirks, QuirkFixes};
+pub use shared::SharedLibrary;
 pub use source::SourceMap;
+pub use syntax::{SyntaxKind, SyntaxNode, SyntaxTree};
+pub use venues::{cluster_venues, venue_key, VenueCluster};
 pub use writer::{
-    document_to_string, selected_entries_to_string, to_file, to_string, RawWriteMode,
-    TrailingComma, Writer, WriterConfig,
+    document_to_string, format_min_diff, selected_entries_to_string, to_file, to_string,
+    to_string_entry, to_string_preamble, to_string_string_def, BlockSpacing, DelimiterStyle,
+    DuplicateFieldPolicy, FieldOrder, FieldTruncation, FmtWriter, HeaderPlacement, RawWriteMode,
+    SectionGrouping, StreamWriter, TabularStyle, TrailingComma, Writer, WriterConfig,
 };
 
 /// Re-export of common parser functions
 pub mod prelude {
+    #[cfg(feature = "key_canonicalization")]
+    pub use crate::KeyCanonicalization;
     pub use crate::{
-        canonical_biblatex_field_alias, classify_resource_field, document_to_string,
-        normalize_biblatex_field_name, normalize_doi, normalize_field_name_ascii, parse_bibtex,
-        parse_date_parts, parse_names, selected_entries_to_string, Block, Comment, CorpusEvent,
-        CorpusSource, DateParseError, DateParts, Diagnostic, DiagnosticCode, DiagnosticSeverity,
-        DiagnosticTarget, DuplicateKeyGroup, DuplicateKeyOccurrence, Entry, EntryDelimiter,
-        EntryType, Error, ExpansionOptions, FailedBlock, Field, FieldNameCase,
-        FieldNormalizeOptions, IssueSummary, Library, LibraryBuilder, LibraryStats, MonthStyle,
-        ParseEvent, ParseFlow, ParseStatus, ParseSummary, ParsedBlock, ParsedComment, ParsedCorpus,
-        ParsedDocument, ParsedEntry, ParsedEntryStatus, ParsedFailedBlock, ParsedField, ParsedItem,
-        ParsedPreamble, ParsedSource, ParsedString, ParsedValue, Parser, PersonName, Preamble,
-        RawWriteMode, ResourceField, ResourceKind, Result, SortOptions, SourceId, SourceMap,
-        SourceSpan, StreamingSummary, StringDefinition, TrailingComma, UnresolvedVariablePolicy,
+        bbl_keys, brace_acronyms, canonical_biblatex_field_alias, cited_keys,
+        classify_resource_field, cluster_venues, decode_html_entities, document_to_string,
+        format_author_list, format_min_diff, normalize_biblatex_field_name, normalize_corpus_id,
+        normalize_doi, normalize_field_name_ascii, normalize_openalex, normalize_punctuation,
+        normalize_s2id, parse_bibtex, parse_date_parts, parse_file_attachments, parse_fragments,
+        parse_name_list, parse_names, repair_mojibake, rewrite_citations,
+        selected_entries_to_string, stream, title_case_all_caps, to_string_entry,
+        to_string_preamble, to_string_string_def, tokenize, venue_key, AddOutcome, Baseline,
+        BblCheck, Block, BlockSpacing, CancelToken, CoauthorGraph, CodeAction, Comment, Config,
+        CorpusEvent, CorpusSource, DateParseError, DateParts, DelimiterStyle, Diagnostic,
+        DiagnosticCode, DiagnosticSeverity, DiagnosticTarget, Diagnostics, DuplicateFieldPolicy,
+        DuplicateKeyGroup, DuplicateKeyOccurrence, EnrichmentPipeline, EnrichmentPlan, Entry,
+        EntryDelimiter, EntryId, EntrySet, EntryType, Error, EtAlStyle, ExpansionOptions,
+        ExpansionPolicy, ExportQuirks, FailedBlock, Field, FieldAliases, FieldNameCase,
+        FieldNormalizeOptions, FieldOrder, FieldOrigin, FieldReader, FieldTruncation,
+        FileAttachment, FmtWriter, FromEntry, FromField, GraphAuthor, HeaderPlacement, Identifier,
+        IssueSummary, ItemStream, KeyRenameMap, LanguageTag, Library, LibraryBuilder,
+        LibraryReport, LibraryStats, LibraryView, LintOptions, MacroResolver, MergePolicy,
+        MetadataCache, MetadataFailure, MetadataProvider, MetadataResolution, MonthStyle, NameList,
+        NameStyle, NormalizedValue, ParseBudget, ParseContinuation, ParseEvent, ParseFlow,
+        ParseStatus, ParseSummary, ParsedBlock, ParsedComment, ParsedCorpus, ParsedDocument,
+        ParsedEntry, ParsedEntryStatus, ParsedFailedBlock, ParsedField, ParsedItem, ParsedPreamble,
+        ParsedSource, ParsedString, ParsedValue, Parser, ParserProfile, ParserState, PersonName,
+        Preamble, ProposedChange, PublicationYear, PunctuationStyle, QuirkFixes, RawWriteMode,
+        ResolvedEntry, ResourceField, ResourceKind, Result, SarifReport, SectionGrouping,
+        SortOptions, SourceId, SourceMap, SourceSpan, StreamWriter, StreamingSummary,
+        StringDefinition, SyntaxKind, SyntaxNode, SyntaxTree, TabularStyle, TextEdit, Timeline,
+        ToEntry, ToField, Token, TokenKind, Tokens, TrailingComma, UnresolvedVariablePolicy,
         ValidationError, ValidationLevel, ValidationReport, ValidationSeverity, Value,
-        ValueDelimiter, Writer, WriterConfig,
+        ValueDelimiter, ValueInterner, ValueRepair, VenueCluster, Writer, WriterConfig, YearCounts,
     };
 }
 
@@ -201,9 +289,18 @@ pub fn parse(input: &str) -> Result<Library<'_>> {
     Library::parser().parse(input)
 }
 
+/// Parse a BibTeX library from a string, turning any panic into an error.
+///
+/// Meant for FFI layers, where unwinding across the boundary is undefined
+/// behavior. Parsing is not expected to panic; a caught panic is reported as
+/// [`Error::Panic`] and indicates a bug.
+pub fn parse_catch(input: &str) -> Result<Library<'_>> {
+    Parser::new().parse_catch(input)
+}
+
 /// Parse a BibTeX library from a file.
 pub fn parse_file(path: impl AsRef<std::path::Path>) -> Result<Library<'static>> {
-    let content = std::fs::read_to_string(path)?;
+    let content = compress::read_to_string(path.as_ref())?;
     parse(&content).map(Library::into_owned)
 }
 
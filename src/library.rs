@@ -1,16 +1,37 @@
 //! BibTeX library representation
 
+use crate::cleanup::{
+    decode_html_entities, is_prose_field, normalize_punctuation, repair_literals, repair_mojibake,
+    PunctuationStyle, ValueRepair,
+};
+use crate::graph::CoauthorGraph;
+#[cfg(feature = "key_canonicalization")]
+use crate::keys::KeyCanonicalization;
+use crate::keys::{rename_key_list, KeyRenameMap, KEY_REFERENCE_FIELDS};
+use crate::model::{collect_variable_names, is_plausible_year, reachable_variable_names};
+use crate::parser::value::SignedNumbers;
+use crate::parser::Syntax;
+use crate::source::SourceOffset;
+use crate::venues::{cluster_venues, VenueCluster, VENUE_FIELDS};
+use crate::writer::{FieldLimit, FieldTruncation};
 use crate::{
-    canonical_biblatex_field_alias, normalize_doi, CorpusEvent, CorpusSource, Entry, Error,
-    ParseEvent, ParseFlow, ParsedBlock, ParsedComment, ParsedCorpus, ParsedDocument, ParsedEntry,
-    ParsedFailedBlock, ParsedPreamble, ParsedSource, ParsedString, Result, SourceId, SourceMap,
-    SourceSpan, StreamingSummary, ValidationError, ValidationLevel, Value,
+    canonical_biblatex_field_alias, normalize_doi, CorpusEvent, CorpusSource, Entry, EntryType,
+    Error, Field, FieldOrigin, ParseEvent, ParseFlow, ParsedBlock, ParsedComment, ParsedCorpus,
+    ParsedDocument, ParsedEntry, ParsedFailedBlock, ParsedPreamble, ParsedSource, ParsedString,
+    PublicationYear, Result, SourceId, SourceMap, SourceSpan, StreamingSummary,
+    UnresolvedVariablePolicy, ValidationError, ValidationLevel, Value,
 };
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use memchr::memchr;
+use std::borrow::Borrow;
 use std::borrow::Cow;
-use std::ops::Deref;
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -19,6 +40,216 @@ const SMALL_EXPANSION_CACHE_LIMIT: usize = 16;
 const SMALL_STRING_LOOKUP_LIMIT: usize = 16;
 const CONCAT_CACHE_LIMIT: usize = 16;
 
+/// Default limit on nested `@string` references followed while expanding a value.
+pub const DEFAULT_MAX_EXPANSION_DEPTH: usize = 256;
+
+/// Default limit on the length in bytes of one expanded value: 1 MiB.
+pub const DEFAULT_MAX_EXPANSION_LENGTH: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct ExpansionDepth(usize);
+
+impl Default for ExpansionDepth {
+    fn default() -> Self {
+        Self(DEFAULT_MAX_EXPANSION_DEPTH)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ExpansionLength(usize);
+
+impl Default for ExpansionLength {
+    fn default() -> Self {
+        Self(DEFAULT_MAX_EXPANSION_LENGTH)
+    }
+}
+
+/// Expansion limits and fallbacks shared by a parser and the libraries it builds.
+#[derive(Debug, Clone, Default)]
+struct ExpansionSettings {
+    max_depth: ExpansionDepth,
+    max_length: ExpansionLength,
+    resolvers: MacroResolvers,
+    undefined: Option<UnresolvedVariablePolicy>,
+}
+
+impl ExpansionSettings {
+    /// Return `true` when every variable reference must go through expansion.
+    fn checks_variables(&self) -> bool {
+        !self.resolvers.is_empty() || self.undefined.is_some()
+    }
+
+    /// Apply the undefined-variable policy to a variable no source defines.
+    fn undefined_variable<'a>(&self, name: Cow<'a, str>) -> Result<Value<'a>> {
+        match self.undefined {
+            None | Some(UnresolvedVariablePolicy::Error) => {
+                Err(Error::UndefinedVariable(name.into_owned()))
+            }
+            Some(UnresolvedVariablePolicy::Preserve) => Ok(Value::Variable(name)),
+            Some(UnresolvedVariablePolicy::Placeholder) => {
+                Ok(Value::Literal(Cow::Owned(format!("{{undefined:{name}}}"))))
+            }
+            Some(UnresolvedVariablePolicy::Empty) => Ok(Value::Literal(Cow::Borrowed(""))),
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<Value<'static>> {
+        self.resolvers
+            .resolve(name)
+            .map(|text| Value::Literal(Cow::Owned(text)))
+    }
+}
+
+enum ExpansionFrame<'a> {
+    Variable(Cow<'a, str>),
+    Concat {
+        key: Box<[Value<'a>]>,
+        pending: std::vec::IntoIter<Value<'a>>,
+        expanded: Vec<Value<'a>>,
+    },
+}
+
+/// Work left while [`Library::expand_value_partial`] expands a nested value.
+enum PartialFrame<'l, 'a> {
+    /// A variable, its name, and the number of cuts when it was entered.
+    Variable(&'l Value<'a>, &'l str, usize),
+    Concat {
+        pending: std::slice::Iter<'l, Value<'a>>,
+        flattened: Vec<Value<'a>>,
+    },
+}
+
+/// Next step for a variable met during partial expansion.
+enum PartialStep<'l, 'a> {
+    Done(Value<'a>),
+    Expand(&'l Value<'a>),
+}
+
+/// State of [`Library::expand_value_partial`].
+struct PartialExpansion<'l, 'a> {
+    library: &'l Library<'a>,
+    /// Variables being expanded, kept in place when they recur.
+    expanding: AHashSet<&'l str>,
+    /// Expanded variables, and whether the length limit cut them short.
+    finished: AHashMap<&'l str, (Value<'a>, bool)>,
+    /// Variables kept unexpanded for the length limit so far.
+    cuts: usize,
+}
+
+impl<'l, 'a> PartialExpansion<'l, 'a> {
+    fn expand(&mut self, value: &'l Value<'a>) -> Value<'a> {
+        let mut frames = Vec::new();
+        let mut current = value;
+        'expand: loop {
+            let mut result = match current {
+                Value::Literal(_) | Value::Number(_) => current.clone(),
+                Value::Variable(name) => match self.enter(current, name) {
+                    PartialStep::Done(value) => value,
+                    PartialStep::Expand(user_value) => {
+                        frames.push(PartialFrame::Variable(current, name, self.cuts));
+                        current = user_value;
+                        continue 'expand;
+                    }
+                },
+                Value::Concat(parts) => {
+                    let mut pending = parts.iter();
+                    if let Some(first) = pending.next() {
+                        frames.push(PartialFrame::Concat {
+                            pending,
+                            flattened: Vec::with_capacity(parts.len()),
+                        });
+                        current = first;
+                        continue 'expand;
+                    }
+                    Value::Concat(Box::default())
+                }
+            };
+
+            // Hand the finished value back to the frames waiting on it.
+            loop {
+                match frames.pop() {
+                    None => return result,
+                    Some(PartialFrame::Variable(variable, name, cuts)) => {
+                        result = self.leave(variable, name, cuts, result);
+                    }
+                    Some(PartialFrame::Concat {
+                        mut pending,
+                        mut flattened,
+                    }) => {
+                        match result {
+                            Value::Concat(inner) => {
+                                for inner_part in inner.into_vec() {
+                                    push_merged_part(&mut flattened, inner_part);
+                                }
+                            }
+                            expanded => push_merged_part(&mut flattened, expanded),
+                        }
+                        if let Some(part) = pending.next() {
+                            frames.push(PartialFrame::Concat { pending, flattened });
+                            current = part;
+                            continue 'expand;
+                        }
+                        result = if flattened.len() == 1 {
+                            flattened.pop().unwrap_or_default()
+                        } else {
+                            Value::Concat(flattened.into_boxed_slice())
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolve `variable` or say which definition to expand for it.
+    fn enter(&mut self, variable: &'l Value<'a>, name: &'l str) -> PartialStep<'l, 'a> {
+        let library = self.library;
+        if let Some((value, cut)) = self.finished.get(name) {
+            self.cuts += usize::from(*cut);
+            return PartialStep::Done(value.clone());
+        }
+        if self.expanding.contains(name) {
+            return PartialStep::Done(variable.clone());
+        }
+        if let Some(user_value) = library.string_value(name) {
+            if self.expanding.len() >= library.expansion.max_depth.0 {
+                return PartialStep::Done(variable.clone());
+            }
+            self.expanding.insert(name);
+            return PartialStep::Expand(user_value);
+        }
+        PartialStep::Done(get_month_expansion(name).map_or_else(
+            || {
+                library
+                    .expansion
+                    .resolve(name)
+                    .unwrap_or_else(|| variable.clone())
+            },
+            |month| Value::Literal(Cow::Borrowed(month)),
+        ))
+    }
+
+    /// Finish `variable` with its expanded `result`, keeping the variable
+    /// when it or a variable inside it is too long.
+    fn leave(
+        &mut self,
+        variable: &'l Value<'a>,
+        name: &'l str,
+        cuts: usize,
+        mut result: Value<'a>,
+    ) -> Value<'a> {
+        self.expanding.remove(name);
+        if expanded_length(std::slice::from_ref(&result)) > self.library.expansion.max_length.0 {
+            self.cuts += 1;
+        }
+        let cut = self.cuts > cuts;
+        if cut {
+            result = variable.clone();
+        }
+        self.finished.insert(name, (result.clone(), cut));
+        result
+    }
+}
+
 enum ExpansionCache<'a> {
     Small(Vec<(Cow<'a, str>, Value<'a>)>),
     Large(AHashMap<Cow<'a, str>, Value<'a>>),
@@ -65,6 +296,13 @@ impl<'a> ExpansionCache<'a> {
             }
         }
     }
+
+    fn into_names(self) -> Vec<Cow<'a, str>> {
+        match self {
+            Self::Small(entries) => entries.into_iter().map(|(name, _)| name).collect(),
+            Self::Large(entries) => entries.into_keys().collect(),
+        }
+    }
 }
 
 struct ConcatCache<'a> {
@@ -306,6 +544,35 @@ fn next_recovery_boundary(input: &str, start: usize) -> usize {
     input.len()
 }
 
+/// Widen `range` to the block boundaries around it.
+fn block_range(input: &str, range: Range<usize>) -> (usize, usize) {
+    let bytes = input.as_bytes();
+    let range_start = range.start.min(input.len());
+    let range_end = range.end.clamp(range_start, input.len());
+    let start = memchr::memrchr_iter(b'@', &bytes[..(range_start + 1).min(bytes.len())])
+        .find(|&at| line_prefix_is_whitespace(bytes, at))
+        .unwrap_or(0);
+    let from = range_end.max(start + 1).min(bytes.len());
+    let end = memchr::memchr_iter(b'@', &bytes[from..])
+        .map(|offset| from + offset)
+        .find(|&at| line_prefix_is_whitespace(bytes, at))
+        .unwrap_or(bytes.len());
+    (start, end)
+}
+
+/// Collect the text of `@string` blocks that start before byte `end`.
+fn string_blocks_before(input: &str, end: usize) -> String {
+    let bytes = input.as_bytes();
+    let mut text = String::new();
+    for at in memchr::memchr_iter(b'@', &bytes[..end]) {
+        if starts_with_at_keyword(&bytes[at..], b"string") && line_prefix_is_whitespace(bytes, at) {
+            text.push_str(&input[at..next_recovery_boundary(input, at).min(end)]);
+            text.push('\n');
+        }
+    }
+    text
+}
+
 fn line_prefix_is_whitespace(bytes: &[u8], pos: usize) -> bool {
     let line_start = bytes[..pos]
         .iter()
@@ -317,6 +584,45 @@ fn line_prefix_is_whitespace(bytes: &[u8], pos: usize) -> bool {
         .all(|byte| matches!(byte, b' ' | b'\t'))
 }
 
+fn mark_stopped(
+    document: &mut ParsedDocument<'_>,
+    (at, stop): StoppedAt,
+    source_map: &SourceMap<'_>,
+) {
+    let source = source_map.span(at, at);
+    match stop {
+        ParseStop::Cancelled => document.mark_cancelled(source),
+        ParseStop::Budget => {
+            let continuation = ParseContinuation::new(at, document.library().strings());
+            document.mark_budget_exhausted(source, continuation);
+        }
+    }
+}
+
+/// Small deterministic generator for [`Library::sample`].
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Return a number below `bound`, which must not be zero.
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
 fn merge_streaming_summary(total: &mut StreamingSummary, source: StreamingSummary) {
     total.entries += source.entries;
     total.strings += source.strings;
@@ -330,12 +636,322 @@ fn merge_streaming_summary(total: &mut StreamingSummary, source: StreamingSummar
     total.stopped |= source.stopped;
 }
 
+/// Named bundles of parser settings for common kinds of input.
+///
+/// Apply one with [`Parser::profile`]; builder calls made afterwards
+/// override individual settings.
+///
+/// A profile covers error recovery, signed numbers, undefined variables,
+/// macro expansion, comments, duplicate keys, and the characters allowed in
+/// keys. Unless noted, a profile keeps comments, keeps every entry with a
+/// repeated key, and reads keys as [`KeyCharset::Identifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParserProfile {
+    /// Stop at the first malformed block and reject undefined variables and
+    /// repeated keys.
+    Strict,
+    /// Recover from malformed blocks and keep undefined variables as-is.
+    Tolerant,
+    /// Zotero and Better BibTeX exports: tolerant, and open page ranges
+    /// such as `pages = -5` stay literal text.
+    Zotero,
+    /// `JabRef` libraries: tolerant, keeping `@string` references in fields
+    /// other than `month` so a rewrite round-trips the abbreviations.
+    JabRef,
+    /// Classic BibTeX behavior for old files: tolerant, with undefined
+    /// variables expanding to empty text, comments dropped, only the first
+    /// entry with a key kept, and keys read as [`KeyCharset::BibTeX`], as
+    /// `bibtex` does.
+    Legacy,
+}
+
+/// What the parser does with comments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CommentPolicy {
+    /// Keep `@comment` blocks and text between entries.
+    #[default]
+    Keep,
+    /// Drop comments while parsing.
+    Discard,
+}
+
+/// What the parser does with an entry whose key an earlier entry already
+/// used.
+///
+/// Keys are compared case-insensitively, like BibTeX's repeated-entry check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateKeyPolicy {
+    /// Keep every entry; [`Library::find_duplicate_keys`] reports repeats.
+    #[default]
+    KeepAll,
+    /// Keep the first entry with a key and drop later ones.
+    KeepFirst,
+    /// Treat a repeated key as a malformed block.
+    Error,
+}
+
+/// Characters the parser accepts in citation keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KeyCharset {
+    /// ASCII letters, digits, `_`, `-`, `:`, and `.`.
+    #[default]
+    Identifier,
+    /// Any character `bibtex` accepts, such as `/`, `+`, or `ü`: everything
+    /// except whitespace, `,`, `{`, `}`, `(`, `)`, `"`, `#`, `%`, and `=`.
+    BibTeX,
+}
+
+/// Comment and duplicate-key rules for one parse.
+struct BlockRules {
+    comments: CommentPolicy,
+    duplicate_keys: DuplicateKeyPolicy,
+    seen_keys: AHashSet<String>,
+}
+
+/// Whether a parsed block passes [`BlockRules`].
+enum BlockVerdict {
+    Keep,
+    Skip,
+    Reject(String),
+}
+
+impl BlockRules {
+    fn check(&mut self, item: &crate::parser::ParsedItem<'_>) -> BlockVerdict {
+        match item {
+            crate::parser::ParsedItem::Comment(_) if self.comments == CommentPolicy::Discard => {
+                BlockVerdict::Skip
+            }
+            crate::parser::ParsedItem::Entry(entry) => {
+                if self.duplicate_keys == DuplicateKeyPolicy::KeepAll
+                    || self.seen_keys.insert(entry.key.to_lowercase())
+                {
+                    return BlockVerdict::Keep;
+                }
+                match self.duplicate_keys {
+                    DuplicateKeyPolicy::Error => {
+                        BlockVerdict::Reject(format!("Repeated citation key '{}'", entry.key))
+                    }
+                    _ => BlockVerdict::Skip,
+                }
+            }
+            _ => BlockVerdict::Keep,
+        }
+    }
+}
+
+/// Flag that asks a running parse to stop early.
+///
+/// Hand a clone to [`Parser::cancel_token`] and call [`cancel`](Self::cancel)
+/// from any thread, for example when the user navigates away from a huge
+/// file. The parser checks the flag between blocks, so a document parse
+/// returns the blocks read so far with [`ParseStatus::Cancelled`].
+///
+/// ```
+/// use bibtex_parser::{CancelToken, ParseStatus, Parser};
+///
+/// let token = CancelToken::new();
+/// token.cancel();
+/// let document = Parser::new()
+///     .cancel_token(token)
+///     .parse_document("@misc{a, title = {A}}")?;
+/// assert_eq!(document.status(), ParseStatus::Cancelled);
+/// assert!(document.entries().is_empty());
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+///
+/// [`ParseStatus::Cancelled`]: crate::ParseStatus::Cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every parse using this token or a clone.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Return `true` once [`cancel`](Self::cancel) has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Limits on how much input one document parse reads.
+///
+/// Set one with [`Parser::budget`] to preview enormous files: the parse
+/// stops at the first block boundary after the limit is reached, and
+/// [`ParsedDocument::continuation`] tells [`Parser::resume`] where to pick
+/// up. Each parse reads at least one entry, so resuming always progresses.
+///
+/// ```
+/// use bibtex_parser::{ParseBudget, Parser};
+///
+/// let input = "@misc{a, title = {A}}\n@misc{b, title = {B}}\n@misc{c, title = {C}}";
+/// let parser = Parser::new().budget(ParseBudget::new().entries(2));
+///
+/// let first = parser.parse_document(input)?;
+/// assert_eq!(first.entries().len(), 2);
+/// let rest = parser.resume(input, first.continuation().unwrap())?;
+/// assert_eq!(rest.entries()[0].key(), "c");
+/// assert!(rest.continuation().is_none());
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseBudget {
+    max_entries: Option<usize>,
+    max_time: Option<Duration>,
+}
+
+impl ParseBudget {
+    /// Create a budget without limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_entries: None,
+            max_time: None,
+        }
+    }
+
+    /// Stop after `count` entries.
+    #[must_use]
+    pub const fn entries(mut self, count: usize) -> Self {
+        self.max_entries = Some(count);
+        self
+    }
+
+    /// Stop once `limit` has passed since the parse started.
+    #[must_use]
+    pub const fn time(mut self, limit: Duration) -> Self {
+        self.max_time = Some(limit);
+        self
+    }
+
+    /// Return the entry limit, if any.
+    #[must_use]
+    pub const fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    /// Return the time limit, if any.
+    #[must_use]
+    pub const fn max_time(&self) -> Option<Duration> {
+        self.max_time
+    }
+}
+
+/// Position where a budgeted parse stopped.
+///
+/// Returned by [`ParsedDocument::continuation`] and passed back to
+/// [`Parser::resume`] with the same input. It carries the `@string`
+/// definitions read before the position, so resuming does not scan the
+/// input again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseContinuation {
+    offset: usize,
+    strings: Arc<[StringDefinition<'static>]>,
+}
+
+impl ParseContinuation {
+    pub(crate) fn new(offset: usize, strings: &[StringDefinition<'_>]) -> Self {
+        Self {
+            offset,
+            strings: owned_definitions(strings, &[]),
+        }
+    }
+
+    /// Return the byte offset of the first block that was not parsed.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub(crate) fn shift(&mut self, bytes: usize) {
+        self.offset += bytes;
+    }
+
+    /// Put the definitions read before this parse's input in front.
+    pub(crate) fn inherit_strings(&mut self, earlier: &[StringDefinition<'static>]) {
+        if !earlier.is_empty() {
+            self.strings = owned_definitions(earlier, &self.strings);
+        }
+    }
+}
+
+/// Owned copies of `first` followed by `then`, without source locations.
+fn owned_definitions(
+    first: &[StringDefinition<'_>],
+    then: &[StringDefinition<'_>],
+) -> Arc<[StringDefinition<'static>]> {
+    first
+        .iter()
+        .chain(then)
+        .map(|definition| StringDefinition {
+            source: None,
+            ..definition.clone().into_owned()
+        })
+        .collect()
+}
+
+/// Why a parse stopped before the end of its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseStop {
+    Cancelled,
+    Budget,
+}
+
+/// Byte offset of the first unparsed block and why parsing stopped there.
+type StoppedAt = (usize, ParseStop);
+
+/// Checks made between blocks that can end a parse early.
+#[derive(Debug, Clone, Copy, Default)]
+struct ParseLimits<'p> {
+    cancel: Option<&'p CancelToken>,
+    max_entries: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl ParseLimits<'_> {
+    /// Decide whether to stop before the next block, `entries` entries in.
+    fn check(&self, entries: usize) -> Option<ParseStop> {
+        if self.cancel.is_some_and(CancelToken::is_cancelled) {
+            return Some(ParseStop::Cancelled);
+        }
+        let exhausted = entries > 0
+            && (self.max_entries.is_some_and(|max| entries >= max)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline));
+        exhausted.then_some(ParseStop::Budget)
+    }
+}
+
 /// Parser configuration.
 #[derive(Debug, Default, Clone)]
 pub struct Parser {
     threads: Option<usize>,
     tolerant: bool,
+    signed_numbers_as_literals: bool,
+    track_field_origins: bool,
     document: DocumentOptions,
+    string_library: Option<Arc<[StringDefinition<'static>]>>,
+    expansion_policy: ExpansionPolicy,
+    max_expansion_depth: Option<usize>,
+    max_expansion_length: Option<usize>,
+    macro_resolvers: MacroResolvers,
+    undefined_variables: Option<UnresolvedVariablePolicy>,
+    comments: CommentPolicy,
+    duplicate_keys: DuplicateKeyPolicy,
+    key_charset: KeyCharset,
+    #[cfg(feature = "key_canonicalization")]
+    key_canonicalization: Option<KeyCanonicalization>,
+    cancel_token: Option<CancelToken>,
+    budget: Option<ParseBudget>,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -361,6 +977,31 @@ impl Parser {
         self
     }
 
+    /// Stop parsing at the next block boundary once `token` is cancelled.
+    ///
+    /// [`Self::parse_document`] then returns the blocks parsed so far with
+    /// [`ParseStatus::Cancelled`](crate::ParseStatus::Cancelled) and a
+    /// `parse-cancelled` diagnostic; [`Self::parse`] returns
+    /// [`Error::Cancelled`] and drops what it had read. Streaming parses stop
+    /// as if the callback had returned [`ParseFlow::Stop`].
+    #[must_use]
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Limit how much input each document parse reads.
+    ///
+    /// Applies to [`Self::parse_document`], [`Self::parse_range`], and
+    /// [`Self::resume`]; a document cut short has a `parse-budget-exhausted`
+    /// diagnostic and a [`ParsedDocument::continuation`]. Other parses read
+    /// the whole input.
+    #[must_use]
+    pub const fn budget(mut self, budget: ParseBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     /// Continue after malformed blocks and collect diagnostics.
     #[must_use]
     #[inline]
@@ -393,53 +1034,582 @@ impl Parser {
         self
     }
 
-    /// Parse a single input string.
-    #[inline]
-    pub fn parse<'a>(&self, input: &'a str) -> Result<Library<'a>> {
-        if self.tolerant {
-            Library::parse_tolerant(input, self.document.capture_source)
-        } else if self.document.capture_source {
-            Library::parse_with_spans(input)
-        } else {
-            Library::parse_sequential(input)
-        }
+    /// Pre-load `@string` definitions from a shared abbreviation file.
+    ///
+    /// The definitions are available while expanding values in every input
+    /// parsed afterwards, but they are not added to the resulting library's
+    /// strings or blocks. Entries, preambles, and comments in the abbreviation
+    /// file are ignored. Repeated calls append definitions, and strings defined
+    /// by a parsed input shadow pre-loaded ones of the same name.
+    pub fn with_string_library(self, input: &str) -> Result<Self> {
+        let abbreviations = Library::parse(input)?;
+        Ok(self.preload_strings(abbreviations.strings))
+    }
+
+    pub(crate) fn preload_strings(mut self, definitions: Vec<StringDefinition<'_>>) -> Self {
+        let mut strings = self
+            .string_library
+            .as_deref()
+            .map_or_else(Vec::new, <[StringDefinition<'static>]>::to_vec);
+        strings.extend(definitions.into_iter().map(|definition| StringDefinition {
+            source: None,
+            ..definition.into_owned()
+        }));
+        self.string_library = Some(strings.into());
+        self
     }
 
-    /// Parse a single input string into the parsed document model.
+    fn string_library(&self) -> &[StringDefinition<'static>] {
+        self.string_library.as_deref().unwrap_or_default()
+    }
+
+    /// Choose which fields have their macros expanded while building a [`Library`].
     ///
-    /// Use this when a caller needs source-order blocks, diagnostics, raw-text
-    /// slots, or partial parse results. Use [`Self::parse`] for the compact
-    /// [`Library`] API.
+    /// Fields the policy preserves keep their variables and concatenations,
+    /// so undefined macros in those fields are not an error.
+    #[must_use]
+    pub fn expansion_policy(mut self, policy: ExpansionPolicy) -> Self {
+        self.expansion_policy = policy;
+        self
+    }
+
+    /// Limit how many nested `@string` references are followed while expanding a value.
+    ///
+    /// Deeper chains fail with [`Error::ExpansionDepthExceeded`] instead of
+    /// exhausting the stack. Defaults to [`DEFAULT_MAX_EXPANSION_DEPTH`].
+    #[must_use]
     #[inline]
-    pub fn parse_document<'a>(&self, input: &'a str) -> Result<ParsedDocument<'a>> {
-        self.parse_document_with_source_id(SourceId::new(0), None, input)
+    pub const fn max_expansion_depth(mut self, depth: usize) -> Self {
+        self.max_expansion_depth = Some(depth);
+        self
     }
 
-    /// Parse a named source into the parsed document model.
+    /// Limit the length in bytes of the text one value expands to.
     ///
-    /// The parser does not read files itself; callers provide the source name
-    /// or path-like label together with the already-loaded input text.
+    /// Each `@string{b = a # a}` doubles the text, so a short input could
+    /// otherwise expand to gigabytes. Longer values fail with
+    /// [`Error::ExpansionLengthExceeded`]. Defaults to
+    /// [`DEFAULT_MAX_EXPANSION_LENGTH`].
+    #[must_use]
     #[inline]
-    pub fn parse_source<'a>(
-        &self,
-        source_name: impl Into<Cow<'a, str>>,
-        input: &'a str,
-    ) -> Result<ParsedDocument<'a>> {
-        self.parse_document_with_source_id(SourceId::new(0), Some(source_name.into()), input)
+    pub const fn max_expansion_length(mut self, length: usize) -> Self {
+        self.max_expansion_length = Some(length);
+        self
     }
 
-    /// Parse multiple named in-memory sources into a corpus result.
-    pub fn parse_sources<'a>(&self, sources: &[CorpusSource<'a>]) -> Result<ParsedCorpus<'a>> {
-        let mut documents = Vec::with_capacity(sources.len());
-        for (index, source) in sources.iter().enumerate() {
-            documents.push(self.parse_document_with_source_id(
-                SourceId::new(index),
-                Some(Cow::Borrowed(source.name)),
-                source.input,
-            )?);
+    /// Resolve variables the input does not define through `resolver`.
+    ///
+    /// Repeated calls add resolvers, which are tried in the order given.
+    #[must_use]
+    pub fn macro_resolver(mut self, resolver: impl MacroResolver + 'static) -> Self {
+        self.macro_resolvers.push(resolver);
+        self
+    }
+
+    fn expansion_settings(&self) -> ExpansionSettings {
+        ExpansionSettings {
+            max_depth: self
+                .max_expansion_depth
+                .map_or_else(ExpansionDepth::default, ExpansionDepth),
+            max_length: self
+                .max_expansion_length
+                .map_or_else(ExpansionLength::default, ExpansionLength),
+            resolvers: self.macro_resolvers.clone(),
+            undefined: self.undefined_variables,
         }
+    }
 
-        Ok(ParsedCorpus::from_documents(documents))
+    fn expansion_options(&self) -> crate::ExpansionOptions {
+        let defaults = crate::ExpansionOptions::default();
+        let settings = self.expansion_settings();
+        crate::ExpansionOptions {
+            max_depth: settings.max_depth.0,
+            max_length: settings.max_length.0,
+            unresolved_variables: self
+                .undefined_variables
+                .unwrap_or(defaults.unresolved_variables),
+            ..defaults
+        }
+    }
+
+    /// Choose what happens to variables that no `@string`, month constant, or
+    /// [`MacroResolver`] defines.
+    ///
+    /// Useful for files whose abbreviations live in a separate file that is
+    /// not available. Without this option, undefined variables are an error
+    /// when the input defines strings and are kept as variables otherwise.
+    #[must_use]
+    #[inline]
+    pub const fn undefined_variables(mut self, policy: UnresolvedVariablePolicy) -> Self {
+        self.undefined_variables = Some(policy);
+        self
+    }
+
+    /// Keep bare signed integers such as `pages = -5` as literal text.
+    ///
+    /// By default a leading `-` is read as part of an integer, which turns an
+    /// open page range like `pages = -5` into a negative number. With this
+    /// option signed tokens are read as numbers only in `year`, where
+    /// `year = -350` is a plausible year before the common era; everywhere
+    /// else only unsigned digit tokens become numbers. Signs are kept while
+    /// the entry is parsed, so the option adds no extra pass.
+    #[must_use]
+    #[inline]
+    pub const fn signed_numbers_as_literals(mut self) -> Self {
+        self.signed_numbers_as_literals = true;
+        self
+    }
+
+    /// Canonicalize citation keys while parsing.
+    ///
+    /// Each changed key's source spelling is kept: see
+    /// [`Library::original_key`] and [`ParsedEntry::original_key`].
+    #[cfg(feature = "key_canonicalization")]
+    #[must_use]
+    #[inline]
+    pub const fn canonicalize_keys(mut self, canonicalization: KeyCanonicalization) -> Self {
+        self.key_canonicalization = Some(canonicalization);
+        self
+    }
+
+    /// Apply a named bundle of settings.
+    ///
+    /// See [`ParserProfile`] for what each profile sets and which settings
+    /// it leaves alone.
+    #[must_use]
+    pub fn profile(mut self, profile: ParserProfile) -> Self {
+        self.tolerant = profile != ParserProfile::Strict;
+        self.signed_numbers_as_literals = profile == ParserProfile::Zotero;
+        self.undefined_variables = Some(match profile {
+            ParserProfile::Strict => UnresolvedVariablePolicy::Error,
+            ParserProfile::Legacy => UnresolvedVariablePolicy::Empty,
+            ParserProfile::Tolerant | ParserProfile::Zotero | ParserProfile::JabRef => {
+                UnresolvedVariablePolicy::Preserve
+            }
+        });
+        self.expansion_policy = if profile == ParserProfile::JabRef {
+            ExpansionPolicy::preserve_all().expand_field("month")
+        } else {
+            ExpansionPolicy::default()
+        };
+        self.comments = if profile == ParserProfile::Legacy {
+            CommentPolicy::Discard
+        } else {
+            CommentPolicy::Keep
+        };
+        self.duplicate_keys = match profile {
+            ParserProfile::Strict => DuplicateKeyPolicy::Error,
+            ParserProfile::Legacy => DuplicateKeyPolicy::KeepFirst,
+            ParserProfile::Tolerant | ParserProfile::Zotero | ParserProfile::JabRef => {
+                DuplicateKeyPolicy::KeepAll
+            }
+        };
+        self.key_charset = if profile == ParserProfile::Legacy {
+            KeyCharset::BibTeX
+        } else {
+            KeyCharset::Identifier
+        };
+        self
+    }
+
+    /// Choose whether comments are kept.
+    #[must_use]
+    #[inline]
+    pub const fn comments(mut self, policy: CommentPolicy) -> Self {
+        self.comments = policy;
+        self
+    }
+
+    /// Choose what happens to entries whose key an earlier entry used.
+    ///
+    /// A rejected entry fails the parse, or becomes a failed block when
+    /// [`Self::tolerant`] is set.
+    #[must_use]
+    #[inline]
+    pub const fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Choose which characters citation keys may use.
+    ///
+    /// Entries with other keys are malformed blocks.
+    #[must_use]
+    #[inline]
+    pub const fn key_charset(mut self, charset: KeyCharset) -> Self {
+        self.key_charset = charset;
+        self
+    }
+
+    fn block_rules(&self) -> BlockRules {
+        BlockRules {
+            comments: self.comments,
+            duplicate_keys: self.duplicate_keys,
+            seen_keys: AHashSet::new(),
+        }
+    }
+
+    fn uses_block_rules(&self) -> bool {
+        self.comments != CommentPolicy::Keep || self.duplicate_keys != DuplicateKeyPolicy::KeepAll
+    }
+
+    /// Apply [`Self::block_rules`] to parsed raw items.
+    fn apply_block_rules<'a>(
+        &self,
+        raw_items: Vec<RawBuildItem<'a>>,
+        capture_source: bool,
+    ) -> Result<Vec<RawBuildItem<'a>>> {
+        if !self.uses_block_rules() {
+            return Ok(raw_items);
+        }
+        let mut rules = self.block_rules();
+        let mut kept = Vec::with_capacity(raw_items.len());
+        for raw_item in raw_items {
+            let RawBuildItem::Parsed(item, source, raw) = &raw_item else {
+                kept.push(raw_item);
+                continue;
+            };
+            match rules.check(item) {
+                BlockVerdict::Keep => kept.push(raw_item),
+                BlockVerdict::Skip => {}
+                BlockVerdict::Reject(message) => {
+                    let failed = self.rejected_block(message, *source, raw)?;
+                    kept.push(RawBuildItem::Failed(FailedBlock {
+                        source: failed.source.filter(|_| capture_source),
+                        ..failed
+                    }));
+                }
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Turn a block the rules reject into a failed block, or into an error
+    /// unless parsing is tolerant.
+    const fn rejected_block<'a>(
+        &self,
+        message: String,
+        source: SourceSpan,
+        raw: &'a str,
+    ) -> Result<FailedBlock<'a>> {
+        if !self.tolerant {
+            return Err(Error::ParseError {
+                line: source.line,
+                column: source.column,
+                message,
+                snippet: None,
+            });
+        }
+        Ok(FailedBlock {
+            raw: Cow::Borrowed(raw),
+            error: message,
+            source: Some(source),
+        })
+    }
+
+    /// Record which macros each expanded field came from.
+    ///
+    /// Origins are available through [`Library::field_origin`].
+    #[must_use]
+    #[inline]
+    pub const fn track_field_origins(mut self) -> Self {
+        self.track_field_origins = true;
+        self
+    }
+
+    /// Limits for one parse, starting the time budget now if `budgeted`.
+    fn limits(&self, budgeted: bool) -> ParseLimits<'_> {
+        let budget = self.budget.filter(|_| budgeted).unwrap_or_default();
+        ParseLimits {
+            cancel: self.cancel_token.as_ref(),
+            max_entries: budget.max_entries,
+            deadline: budget
+                .max_time
+                .and_then(|limit| Instant::now().checked_add(limit)),
+        }
+    }
+
+    const fn syntax(&self) -> Syntax {
+        Syntax {
+            signed: if self.signed_numbers_as_literals {
+                SignedNumbers::Literals
+            } else {
+                SignedNumbers::Numbers
+            },
+            keys: self.key_charset,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel_token
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+    }
+
+    fn uses_value_settings(&self) -> bool {
+        self.cancel_token.is_some()
+            || self.string_library.is_some()
+            || self.signed_numbers_as_literals
+            || self.key_charset != KeyCharset::Identifier
+            || self.track_field_origins
+            || self.max_expansion_depth.is_some()
+            || self.max_expansion_length.is_some()
+            || !self.macro_resolvers.is_empty()
+            || self.undefined_variables.is_some()
+            || !self.expansion_policy.expands_everything()
+            || self.uses_block_rules()
+    }
+
+    /// Record macro origins per raw entry, by the entry's position among the
+    /// raw entries.
+    fn raw_field_origins(&self, raw_items: &[RawBuildItem<'_>]) -> RawFieldOrigins {
+        let mut origins = RawFieldOrigins::new();
+        if !self.track_field_origins {
+            return origins;
+        }
+        let entries = raw_items.iter().filter_map(|raw_item| match raw_item {
+            RawBuildItem::Parsed(crate::parser::ParsedItem::Entry(entry), _, _) => Some(entry),
+            _ => None,
+        });
+        for (index, entry) in entries.enumerate() {
+            for field in &entry.fields {
+                if !self.expansion_policy.expands_field(&field.name) {
+                    continue;
+                }
+                let mut names = Vec::new();
+                collect_variable_names(&field.value, &mut names);
+                let origin = match names.as_slice() {
+                    [] => continue,
+                    [name] => FieldOrigin::FromString((*name).to_string()),
+                    names => FieldOrigin::FromStrings(
+                        names.iter().map(|name| (*name).to_string()).collect(),
+                    ),
+                };
+                origins.push((index, field.name.to_ascii_lowercase(), origin));
+            }
+        }
+        origins
+    }
+
+    /// Parse a single input string.
+    ///
+    /// The result is all-or-nothing: use [`Self::parse_document`] to keep
+    /// the entries read before a cancellation or a malformed block.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first parse error unless [`Self::tolerant`] is set, and
+    /// [`Error::Cancelled`] once the [`cancel_token`](Self::cancel_token) is
+    /// cancelled; the entries read so far are discarded.
+    #[inline]
+    pub fn parse<'a>(&self, input: &'a str) -> Result<Library<'a>> {
+        let phase = crate::trace::phase!(info_span, "parse", input.len(), tolerant = self.tolerant);
+        #[cfg_attr(not(feature = "key_canonicalization"), allow(unused_mut))]
+        let mut library = self.parse_library(input)?;
+        #[cfg(feature = "key_canonicalization")]
+        if let Some(canonicalization) = self.key_canonicalization {
+            library.canonicalize_keys(canonicalization);
+        }
+        phase.entries(library.entries.len());
+        Ok(library)
+    }
+
+    /// Parse like [`Self::parse`], turning any panic into [`Error::Panic`].
+    pub fn parse_catch<'a>(&self, input: &'a str) -> Result<Library<'a>> {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.parse(input))).unwrap_or_else(
+            |payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| (*message).to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                Err(Error::Panic(message))
+            },
+        )
+    }
+
+    fn parse_library<'a>(&self, input: &'a str) -> Result<Library<'a>> {
+        if self.uses_value_settings() {
+            return self.parse_with_value_settings(input);
+        }
+
+        if self.tolerant {
+            Library::parse_tolerant(input, self.document.capture_source)
+        } else if self.document.capture_source {
+            Library::parse_with_spans(input)
+        } else {
+            Library::parse_sequential(input)
+        }
+    }
+
+    /// Parse `input` into raw items with this parser's recovery and number
+    /// settings.
+    fn parse_raw_items<'a>(
+        &self,
+        input: &'a str,
+        capture_source: bool,
+        source_map: &SourceMap<'_>,
+        limits: ParseLimits<'_>,
+    ) -> Result<(Vec<RawBuildItem<'a>>, Option<StoppedAt>)> {
+        let (raw_items, stopped) = if self.tolerant {
+            Library::parse_tolerant_raw_items(
+                input,
+                capture_source,
+                source_map,
+                limits,
+                self.syntax(),
+            )
+        } else {
+            Library::parse_raw_items_with_source(input, source_map, limits, self.syntax())?
+        };
+        Ok((self.apply_block_rules(raw_items, capture_source)?, stopped))
+    }
+
+    fn parse_with_value_settings<'a>(&self, input: &'a str) -> Result<Library<'a>> {
+        let source_map = SourceMap::anonymous(input);
+        let limits = self.limits(false);
+        let (raw_items, stopped) =
+            self.parse_raw_items(input, self.document.capture_source, &source_map, limits)?;
+        if stopped.is_some() {
+            return Err(Error::Cancelled);
+        }
+        let origins = self.raw_field_origins(&raw_items);
+        let phase = crate::trace::phase!(debug_span, "expand", input.len());
+        let mut library = Library::from_raw_items_with(
+            raw_items,
+            self.string_library(),
+            &self.expansion_policy,
+            self.expansion_settings(),
+        )?;
+        phase.entries(library.entries.len());
+        library.set_raw_field_origins(origins);
+        if !self.document.capture_source {
+            library.clear_sources();
+        }
+        Ok(library)
+    }
+
+    /// Parse a single input string into the parsed document model.
+    ///
+    /// Use this when a caller needs source-order blocks, diagnostics, raw-text
+    /// slots, or partial parse results. Use [`Self::parse`] for the compact
+    /// [`Library`] API.
+    #[inline]
+    pub fn parse_document<'a>(&self, input: &'a str) -> Result<ParsedDocument<'a>> {
+        self.parse_document_with_source_id(SourceId::new(0), None, input)
+    }
+
+    /// Parse a named source into the parsed document model.
+    ///
+    /// The parser does not read files itself; callers provide the source name
+    /// or path-like label together with the already-loaded input text.
+    #[inline]
+    pub fn parse_source<'a>(
+        &self,
+        source_name: impl Into<Cow<'a, str>>,
+        input: &'a str,
+    ) -> Result<ParsedDocument<'a>> {
+        self.parse_document_with_source_id(SourceId::new(0), Some(source_name.into()), input)
+    }
+
+    /// Parse only the blocks of `input` that overlap `range`.
+    ///
+    /// Editors showing part of a large file can parse just the visible
+    /// entries. The byte range is widened to block boundaries: it starts at
+    /// the last `@` at the beginning of a line at or before `range.start` and
+    /// ends before the first one at or after `range.end`, so an empty range
+    /// selects the block around its position. An `@` that starts a line
+    /// inside a braced value is taken as a boundary too.
+    ///
+    /// Source spans in the result are positions in the whole `input`.
+    /// `@string` definitions before the range are parsed as well, so values
+    /// in range can use them, but they are not part of the result.
+    ///
+    /// ```
+    /// use bibtex_parser::Parser;
+    ///
+    /// let input = "@string{j = {Journal}}\n@article{a, journal = j}\n@article{b, journal = j}\n";
+    /// let offset = input.find("@article{b").unwrap();
+    /// let document = Parser::new().parse_range(input, offset + 3..offset + 4)?;
+    ///
+    /// let entry = &document.entries()[0];
+    /// assert_eq!((entry.key.as_ref(), document.entries().len()), ("b", 1));
+    /// assert_eq!(entry.source.unwrap().line, 3);
+    /// assert_eq!(document.library().entries()[0].get("journal"), Some("Journal"));
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    pub fn parse_range<'a>(
+        &self,
+        input: &'a str,
+        range: Range<usize>,
+    ) -> Result<ParsedDocument<'a>> {
+        let (start, end) = block_range(input, range);
+        let strings = string_blocks_before(input, start);
+        let definitions = if strings.is_empty() {
+            Vec::new()
+        } else {
+            Self::new().tolerant().parse(&strings)?.strings
+        };
+        self.parse_slice(input, start, end, &owned_definitions(&definitions, &[]))
+    }
+
+    /// Continue a budgeted parse of `input` where `continuation` says it
+    /// stopped.
+    ///
+    /// `continuation` must come from a parse of the same `input`, or of a
+    /// range of it. The result is budgeted like the first parse, and source
+    /// spans are positions in the whole `input`. `@string` definitions
+    /// before the continuation come from `continuation`, so later values can
+    /// use them, but they are not part of the result. See [`ParseBudget`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::parse_document`].
+    pub fn resume<'a>(
+        &self,
+        input: &'a str,
+        continuation: &ParseContinuation,
+    ) -> Result<ParsedDocument<'a>> {
+        let mut start = continuation.offset().min(input.len());
+        while !input.is_char_boundary(start) {
+            start -= 1;
+        }
+        self.parse_slice(input, start, input.len(), &continuation.strings)
+    }
+
+    /// Parse `input[start..end]` as part of the whole `input`, with the
+    /// `earlier` definitions from before `start`.
+    fn parse_slice<'a>(
+        &self,
+        input: &'a str,
+        start: usize,
+        end: usize,
+        earlier: &[StringDefinition<'static>],
+    ) -> Result<ParsedDocument<'a>> {
+        let mut document = if earlier.is_empty() {
+            self.parse_document(&input[start..end])?
+        } else {
+            self.clone()
+                .preload_strings(earlier.to_vec())
+                .parse_document(&input[start..end])?
+        };
+        document.offset_sources(SourceOffset::new(input, start));
+        document.inherit_continuation_strings(earlier);
+        Ok(document)
+    }
+
+    /// Parse multiple named in-memory sources into a corpus result.
+    pub fn parse_sources<'a>(&self, sources: &[CorpusSource<'a>]) -> Result<ParsedCorpus<'a>> {
+        let mut documents = Vec::with_capacity(sources.len());
+        for (index, source) in sources.iter().enumerate() {
+            documents.push(self.parse_document_with_source_id(
+                SourceId::new(index),
+                Some(Cow::Borrowed(source.name)),
+                source.input,
+            )?);
+        }
+
+        Ok(ParsedCorpus::from_documents(documents))
     }
 
     /// Stream parsed source-order events to a callback.
@@ -520,6 +1690,9 @@ impl Parser {
         }
 
         summary.finalize_status();
+        if self.is_cancelled() {
+            summary.status = crate::ParseStatus::Cancelled;
+        }
         Ok(summary)
     }
 
@@ -535,17 +1708,49 @@ impl Parser {
     {
         let source_map = SourceMap::new(Some(source_id), source_name, input);
         let mut summary = StreamingSummary::default();
+        let mut rules = self.block_rules();
 
         if self.tolerant {
-            self.parse_tolerant_events(input, &source_map, &mut summary, &mut on_event)?;
+            self.parse_tolerant_events(
+                input,
+                &source_map,
+                &mut rules,
+                &mut summary,
+                &mut on_event,
+            )?;
         } else {
-            crate::parser::parse_bibtex_stream_with_spans(input, |item, span, raw| {
-                let source = source_map.span(span.byte_start, span.byte_end);
-                self.emit_parsed_event(item, source, raw, &source_map, &mut summary, &mut on_event)
-            })?;
+            crate::parser::parse_bibtex_stream_with_spans(
+                input,
+                self.syntax(),
+                |item, span, raw| {
+                    let source = source_map.span(span.byte_start, span.byte_end);
+                    match rules.check(&item) {
+                        BlockVerdict::Keep => self.emit_parsed_event(
+                            item,
+                            source,
+                            raw,
+                            &source_map,
+                            &mut summary,
+                            &mut on_event,
+                        ),
+                        BlockVerdict::Skip => Ok(()),
+                        BlockVerdict::Reject(message) => self.emit_rejected_block(
+                            message,
+                            source,
+                            raw,
+                            &source_map,
+                            &mut summary,
+                            &mut on_event,
+                        ),
+                    }
+                },
+            )?;
         }
 
         summary.finalize_status();
+        if self.is_cancelled() {
+            summary.status = crate::ParseStatus::Cancelled;
+        }
         Ok(summary)
     }
 
@@ -553,6 +1758,7 @@ impl Parser {
         &self,
         input: &'a str,
         source_map: &SourceMap<'a>,
+        rules: &mut BlockRules,
         summary: &mut StreamingSummary,
         on_event: &mut F,
     ) -> Result<()>
@@ -563,23 +1769,27 @@ impl Parser {
 
         loop {
             crate::parser::lexer::skip_whitespace(&mut remaining);
+            if self.is_cancelled() {
+                summary.stopped = true;
+            }
             if remaining.is_empty() || summary.stopped {
                 break;
             }
 
             let start = input.len() - remaining.len();
-            match crate::parser::parse_item(&mut remaining) {
+            match crate::parser::parse_item_with(&mut remaining, self.syntax()) {
                 Ok(item) => {
                     let end = input.len() - remaining.len();
                     let source = source_map.span(start, end);
-                    self.emit_parsed_event(
-                        item,
-                        source,
-                        &input[start..end],
-                        source_map,
-                        summary,
-                        on_event,
-                    )?;
+                    let raw = &input[start..end];
+                    match rules.check(&item) {
+                        BlockVerdict::Keep => self
+                            .emit_parsed_event(item, source, raw, source_map, summary, on_event)?,
+                        BlockVerdict::Skip => {}
+                        BlockVerdict::Reject(message) => self.emit_rejected_block(
+                            message, source, raw, source_map, summary, on_event,
+                        )?,
+                    }
                 }
                 Err(err) => {
                     let end = next_recovery_boundary(input, start);
@@ -600,6 +1810,8 @@ impl Parser {
                         summary.entries,
                         self.document.preserve_raw,
                     ) {
+                        #[cfg(feature = "key_canonicalization")]
+                        let partial = self.canonicalize_entry_key(partial);
                         Self::emit_event(ParseEvent::Entry(partial), summary, on_event)?;
                     } else {
                         Self::emit_event(ParseEvent::Failed(failed), summary, on_event)?;
@@ -624,19 +1836,29 @@ impl Parser {
     where
         F: FnMut(ParseEvent<'a>) -> Result<ParseFlow>,
     {
+        if self.is_cancelled() {
+            summary.stopped = true;
+        }
         if summary.stopped {
             return Ok(());
         }
 
         let event = match item {
             crate::parser::ParsedItem::Entry(entry) => {
-                ParseEvent::Entry(ParsedEntry::from_stream_entry(
+                let mut entry = ParsedEntry::from_stream_entry(
                     entry,
                     source,
                     raw,
                     source_map,
                     self.document.preserve_raw,
-                ))
+                );
+                entry.add_numeric_literal_diagnostics(
+                    summary.entries,
+                    self.signed_numbers_as_literals,
+                );
+                #[cfg(feature = "key_canonicalization")]
+                let entry = self.canonicalize_entry_key(entry);
+                ParseEvent::Entry(entry)
             }
             crate::parser::ParsedItem::String(name, value) => {
                 ParseEvent::String(ParsedString::from_stream_definition(
@@ -663,6 +1885,28 @@ impl Parser {
         Self::emit_event(event, summary, on_event)
     }
 
+    /// Emit a block that [`BlockRules`] rejected as a failed block.
+    fn emit_rejected_block<'a, F>(
+        &self,
+        message: String,
+        source: SourceSpan,
+        raw: &'a str,
+        source_map: &SourceMap<'a>,
+        summary: &mut StreamingSummary,
+        on_event: &mut F,
+    ) -> Result<()>
+    where
+        F: FnMut(ParseEvent<'a>) -> Result<ParseFlow>,
+    {
+        if summary.stopped {
+            return Ok(());
+        }
+        let failed = self.rejected_block(message, source, raw)?;
+        let failed =
+            ParsedFailedBlock::from_failed_block(summary.failed_blocks, failed, Some(source_map));
+        Self::emit_event(ParseEvent::Failed(failed), summary, on_event)
+    }
+
     fn emit_event<'a, F>(
         event: ParseEvent<'a>,
         summary: &mut StreamingSummary,
@@ -729,34 +1973,56 @@ impl Parser {
         source_name: Option<Cow<'a, str>>,
         input: &'a str,
     ) -> Result<ParsedDocument<'a>> {
+        let document_phase = crate::trace::phase!(
+            info_span,
+            "parse_document",
+            input.len(),
+            tolerant = self.tolerant
+        );
         let source_map = SourceMap::new(Some(source_id), source_name.clone(), input);
         let sources = vec![ParsedSource {
             id: source_id,
             name: source_name,
         }];
-        let raw_items = if self.tolerant {
-            Library::parse_tolerant_raw_items(input, true, &source_map)
-        } else {
-            match Library::parse_raw_items_with_source(input, &source_map) {
-                Ok(raw_items) => raw_items,
-                Err(error) => {
-                    return Ok(ParsedDocument::failed_from_error(
-                        sources,
-                        &source_map,
-                        &error,
-                    ));
-                }
+        let limits = self.limits(true);
+        let (raw_items, stopped) = match self.parse_raw_items(input, true, &source_map, limits) {
+            Ok(raw_items) => raw_items,
+            Err(error) => {
+                return Ok(ParsedDocument::failed_from_error(
+                    sources,
+                    &source_map,
+                    &error,
+                ));
             }
         };
-        let library = match Library::from_raw_items(raw_items.clone()) {
+        let phase = crate::trace::phase!(debug_span, "expand", input.len());
+        let mut library = match Library::from_raw_items_with(
+            raw_items.clone(),
+            self.string_library(),
+            &self.expansion_policy,
+            self.expansion_settings(),
+        ) {
             Ok(library) => library,
-            Err(Error::UndefinedVariable(_) | Error::CircularReference(_))
-                if !self.document.expand_values =>
-            {
-                Library::from_raw_items_unexpanded(raw_items.clone())
+            Err(
+                Error::UndefinedVariable(_)
+                | Error::CircularReference(_)
+                | Error::ExpansionDepthExceeded { .. }
+                | Error::ExpansionLengthExceeded { .. },
+            ) if !self.document.expand_values => {
+                let mut library = Library::from_raw_items_unexpanded(raw_items.clone());
+                library.expansion = self.expansion_settings();
+                library
             }
             Err(error) => return Err(error),
         };
+        library.set_raw_field_origins(self.raw_field_origins(&raw_items));
+        #[cfg(feature = "key_canonicalization")]
+        if let Some(canonicalization) = self.key_canonicalization {
+            library.canonicalize_keys(canonicalization);
+        }
+        phase.entries(library.entries.len());
+        phase.finish();
+        let phase = crate::trace::phase!(debug_span, "index", input.len());
         let mut document =
             ParsedDocument::from_library_with_source_map(library, sources, Some(&source_map));
         let mut entry_index = 0;
@@ -778,21 +2044,78 @@ impl Parser {
         if self.tolerant {
             document.recover_partial_entries(&source_map, self.document.preserve_raw);
         }
+        #[cfg(feature = "key_canonicalization")]
+        self.canonicalize_document_keys(&mut document);
+        document.add_numeric_literal_diagnostics(self.signed_numbers_as_literals);
         if self.document.expand_values {
-            document.populate_expanded_values(crate::ExpansionOptions::default())?;
+            document.populate_expanded_values(
+                self.expansion_options(),
+                self.string_library(),
+                &self.macro_resolvers,
+            )?;
+        }
+        phase.entries(document.entries().len());
+        document_phase.entries(document.entries().len());
+        if let Some(stopped) = stopped {
+            mark_stopped(&mut document, stopped, &source_map);
         }
         Ok(document)
     }
 
-    pub(crate) fn parse_compact_document_owned(
-        &self,
-        source_name: Option<String>,
-        input: &str,
-    ) -> Result<ParsedDocument<'static>> {
-        let source_name = source_name.map(Cow::Owned);
-        let sources = vec![ParsedSource {
-            id: SourceId::new(0),
-            name: source_name,
+    #[cfg(feature = "key_canonicalization")]
+    fn canonicalize_document_keys(&self, document: &mut ParsedDocument<'_>) {
+        let Some(canonicalization) = self.key_canonicalization else {
+            return;
+        };
+        let mut renames = KeyRenameMap::new();
+        for entry in document.entries_mut() {
+            entry.canonicalize_key(canonicalization);
+            if let Some(original) = &entry.original_key {
+                renames.insert(original.as_ref(), entry.key.as_ref());
+            }
+        }
+        for entry in document.entries_mut() {
+            entry.rename_key_references(&renames);
+        }
+    }
+
+    #[cfg(feature = "key_canonicalization")]
+    fn canonicalize_entry_key<'a>(&self, mut entry: ParsedEntry<'a>) -> ParsedEntry<'a> {
+        if let Some(canonicalization) = self.key_canonicalization {
+            entry.canonicalize_key(canonicalization);
+            entry.canonicalize_key_references(canonicalization);
+        }
+        entry
+    }
+
+    /// Check whether the owned document fast paths must defer to
+    /// [`Parser::parse_document`] to honor this parser's settings.
+    fn needs_full_document_parse(&self) -> bool {
+        self.tolerant || self.budget.is_some() || self.uses_value_settings()
+    }
+
+    /// Parse `input` through the full document path and take ownership.
+    fn parse_full_document_owned(
+        &self,
+        source_name: Option<String>,
+        input: &str,
+    ) -> Result<ParsedDocument<'static>> {
+        self.parse_document_with_source_id(SourceId::new(0), source_name.map(Cow::Owned), input)
+            .map(ParsedDocument::into_owned)
+    }
+
+    pub(crate) fn parse_compact_document_owned(
+        &self,
+        source_name: Option<String>,
+        input: &str,
+    ) -> Result<ParsedDocument<'static>> {
+        if self.needs_full_document_parse() {
+            return self.parse_full_document_owned(source_name, input);
+        }
+        let source_name = source_name.map(Cow::Owned);
+        let sources = vec![ParsedSource {
+            id: SourceId::new(0),
+            name: source_name,
         }];
         let input_scan = scan_input(input);
         let mut entries = Vec::with_capacity(input_scan.at_count);
@@ -845,8 +2168,26 @@ impl Parser {
             comments,
             blocks,
         );
+        #[cfg(feature = "key_canonicalization")]
+        self.canonicalize_document_keys(&mut document);
+        if document.needs_value_delimiters(self.signed_numbers_as_literals) {
+            let mut entry_index = 0;
+            crate::parser::parse_bibtex_stream_with_entry_locations(input, |item, _, _, _| {
+                if let crate::parser::LocatedParsedItem::Entry(located) = item {
+                    let delimiters = located.fields.iter().map(|field| field.value_delimiter);
+                    document.set_value_delimiters(entry_index, delimiters);
+                    entry_index += 1;
+                }
+                Ok(())
+            })?;
+        }
+        document.add_numeric_literal_diagnostics(self.signed_numbers_as_literals);
         if self.document.expand_values {
-            document.populate_expanded_values(crate::ExpansionOptions::default())?;
+            document.populate_expanded_values(
+                self.expansion_options(),
+                self.string_library(),
+                &self.macro_resolvers,
+            )?;
         }
         Ok(document)
     }
@@ -856,6 +2197,9 @@ impl Parser {
         source_name: Option<String>,
         input: &str,
     ) -> Result<ParsedDocument<'static>> {
+        if self.needs_full_document_parse() {
+            return self.parse_full_document_owned(source_name, input);
+        }
         let source_name = source_name.map(Cow::Owned);
         let source_id = SourceId::new(0);
         let source_map = SourceMap::new(Some(source_id), source_name.clone(), input);
@@ -915,19 +2259,29 @@ impl Parser {
             comments,
             blocks,
         );
+        #[cfg(feature = "key_canonicalization")]
+        self.canonicalize_document_keys(&mut document);
+        document.add_numeric_literal_diagnostics(self.signed_numbers_as_literals);
         if self.document.expand_values {
-            document.populate_expanded_values(crate::ExpansionOptions::default())?;
+            document.populate_expanded_values(
+                self.expansion_options(),
+                self.string_library(),
+                &self.macro_resolvers,
+            )?;
         }
         Ok(document)
     }
 
     /// Parse multiple files in parallel
+    ///
+    /// With the `compress` feature, gzip and zstd files are decompressed
+    /// first, up to 1 GiB each.
     pub fn parse_files<P: AsRef<Path> + Sync>(&self, paths: &[P]) -> Result<Library<'static>> {
         #[cfg(feature = "parallel")]
         {
             if let Some(threads) = self.threads {
                 if threads <= 1 {
-                    return Self::parse_files_sequential(paths);
+                    return self.parse_files_sequential(paths);
                 }
             }
 
@@ -937,8 +2291,8 @@ impl Parser {
                 paths
                     .par_iter()
                     .map(|path| {
-                        let content = std::fs::read_to_string(path)?;
-                        let library = Library::parse_sequential(&content)?;
+                        let content = crate::compress::read_to_string(path.as_ref())?;
+                        let library = self.parse_file_content(&content)?;
                         Ok(library.into_owned())
                     })
                     .collect()
@@ -950,21 +2304,29 @@ impl Parser {
 
         #[cfg(not(feature = "parallel"))]
         {
-            Self::parse_files_sequential(paths)
+            self.parse_files_sequential(paths)
         }
     }
 
     /// Sequential file parsing fallback
-    fn parse_files_sequential<P: AsRef<Path>>(paths: &[P]) -> Result<Library<'static>> {
+    fn parse_files_sequential<P: AsRef<Path>>(&self, paths: &[P]) -> Result<Library<'static>> {
         let mut result = Library::new();
         for path in paths {
-            let content = std::fs::read_to_string(path)?;
-            let library = Library::parse_sequential(&content)?;
+            let content = crate::compress::read_to_string(path.as_ref())?;
+            let library = self.parse_file_content(&content)?;
             result.merge(library.into_owned());
         }
         Ok(result)
     }
 
+    fn parse_file_content<'a>(&self, content: &'a str) -> Result<Library<'a>> {
+        if self.uses_value_settings() {
+            self.parse_with_value_settings(content)
+        } else {
+            Library::parse_sequential(content)
+        }
+    }
+
     #[cfg(feature = "parallel")]
     fn build_thread_pool(&self) -> Result<rayon::ThreadPool> {
         let mut builder = rayon::ThreadPoolBuilder::new();
@@ -1009,8 +2371,20 @@ pub enum RawBuildItem<'a> {
     Failed(FailedBlock<'a>),
 }
 
+fn raw_entry_count(raw_items: &[RawBuildItem<'_>]) -> usize {
+    raw_items
+        .iter()
+        .filter(|item| {
+            matches!(
+                item,
+                RawBuildItem::Parsed(crate::parser::ParsedItem::Entry(_), _, _)
+            )
+        })
+        .count()
+}
+
 /// A BibTeX string definition.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StringDefinition<'a> {
     /// String variable name.
     pub name: Cow<'a, str>,
@@ -1055,7 +2429,7 @@ impl<'a> StringDefinition<'a> {
 }
 
 /// A BibTeX preamble block.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Preamble<'a> {
     /// Expanded preamble value.
     pub value: Value<'a>,
@@ -1182,6 +2556,8 @@ pub struct SortOptions {
     pub entries_by_key: bool,
     /// Sort fields inside each entry by field name.
     pub fields_by_name: bool,
+    /// Sort regular entries by [`PublicationYear`], before the key when both are set.
+    pub entries_by_year: bool,
 }
 
 /// Field-name casing policy for field normalization.
@@ -1203,6 +2579,158 @@ pub struct FieldNormalizeOptions {
     pub biblatex_aliases: bool,
 }
 
+/// Field merge policy for [`Library::add_or_update_entry`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Add missing fields and leave the entry untouched when a shared field differs.
+    #[default]
+    RejectConflicts,
+    /// Add missing fields and keep existing values for shared fields.
+    KeepExisting,
+    /// Add missing fields and replace existing values for shared fields.
+    PreferIncoming,
+}
+
+/// Result of [`Library::add_or_update_entry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddOutcome {
+    /// The entry was appended at this index.
+    Inserted(usize),
+    /// The matching entry at this index gained or changed fields.
+    Updated(usize),
+    /// The matching entry at this index already had every incoming field.
+    Unchanged(usize),
+    /// The matching entry disagrees with the incoming entry and was left untouched.
+    Conflicted {
+        /// Index of the matching entry.
+        index: usize,
+        /// Shared fields whose values differ.
+        fields: Vec<String>,
+    },
+}
+
+/// Fallback source for `@string` variables the input does not define.
+///
+/// Resolvers are consulted in registration order after the input's own
+/// definitions and the standard month constants. Closures of type
+/// `Fn(&str) -> Option<String>` and `HashMap<String, String>` tables
+/// implement this trait.
+pub trait MacroResolver: Send + Sync {
+    /// Return the expansion of `name`, or `None` to try the next source.
+    fn resolve(&self, name: &str) -> Option<String>;
+}
+
+impl<F> MacroResolver for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn resolve(&self, name: &str) -> Option<String> {
+        self(name)
+    }
+}
+
+impl<S> MacroResolver for std::collections::HashMap<String, String, S>
+where
+    S: std::hash::BuildHasher + Send + Sync,
+{
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MacroResolvers(Vec<Arc<dyn MacroResolver>>);
+
+impl MacroResolvers {
+    fn push(&mut self, resolver: impl MacroResolver + 'static) {
+        self.0.push(Arc::new(resolver));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn resolve(&self, name: &str) -> Option<String> {
+        self.0.iter().find_map(|resolver| resolver.resolve(name))
+    }
+}
+
+impl std::fmt::Debug for MacroResolvers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MacroResolvers")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+/// Per-field macro expansion policy for [`Parser::expansion_policy`].
+///
+/// The default expands every field. Field names are matched ignoring ASCII
+/// case, and the last rule given for a field wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpansionPolicy {
+    expand_by_default: bool,
+    overrides: Vec<(String, bool)>,
+}
+
+impl Default for ExpansionPolicy {
+    fn default() -> Self {
+        Self::expand_all()
+    }
+}
+
+impl ExpansionPolicy {
+    /// Expand macros in every field unless a field is preserved.
+    #[must_use]
+    pub const fn expand_all() -> Self {
+        Self {
+            expand_by_default: true,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Preserve macros in every field unless a field is expanded.
+    #[must_use]
+    pub const fn preserve_all() -> Self {
+        Self {
+            expand_by_default: false,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Expand macros in `field`.
+    #[must_use]
+    pub fn expand_field(self, field: impl Into<String>) -> Self {
+        self.with_override(field.into(), true)
+    }
+
+    /// Keep macros in `field` intact.
+    #[must_use]
+    pub fn preserve_field(self, field: impl Into<String>) -> Self {
+        self.with_override(field.into(), false)
+    }
+
+    fn with_override(mut self, field: String, expand: bool) -> Self {
+        self.overrides
+            .retain(|(name, _)| !name.eq_ignore_ascii_case(&field));
+        self.overrides.push((field, expand));
+        self
+    }
+
+    /// Check whether macros in `field` are expanded.
+    #[must_use]
+    pub fn expands_field(&self, field: &str) -> bool {
+        self.overrides
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(field))
+            .map_or(self.expand_by_default, |&(_, expand)| expand)
+    }
+
+    fn expands_everything(&self) -> bool {
+        self.expand_by_default && self.overrides.iter().all(|&(_, expand)| expand)
+    }
+}
+
 /// A parsed BibTeX library.
 #[derive(Debug, Clone, Default)]
 pub struct Library<'a> {
@@ -1222,12 +2750,66 @@ pub struct Library<'a> {
     failed_blocks: Vec<FailedBlock<'a>>,
     /// Original block order
     block_order: Vec<BlockKind>,
+    /// Comment indices directly before each entry, by entry ID
+    leading_comments: AHashMap<EntryId, Vec<usize>>,
+    /// Comments pushed since the last non-comment block
+    pending_comments: Vec<usize>,
+    /// User strings resolved while expanding values during parsing
+    resolved_strings: Vec<Cow<'a, str>>,
+    /// Value origins by citation key and lowercase field name
+    field_origins: FieldOrigins,
+    /// Stable entry IDs, aligned with `entries`
+    entry_ids: Vec<EntryId>,
+    /// Current index of each entry ID
+    entry_positions: AHashMap<EntryId, usize>,
+    /// Source keys changed by key canonicalization, by entry ID
+    original_keys: AHashMap<EntryId, String>,
+    /// Next unassigned entry ID
+    next_entry_id: u64,
+    /// Expansion depth limit and macro resolvers
+    expansion: ExpansionSettings,
+}
+
+/// Stable identifier for an entry in a [`Library`].
+///
+/// IDs are assigned in source order when entries are parsed or added and
+/// stay attached to their entry through sorting, key renames, and edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntryId(u64);
+
+impl EntryId {
+    /// Return the raw ID value.
+    #[must_use]
+    pub const fn get(self) -> u64 {
+        self.0
+    }
 }
 
+type FieldOrigins = AHashMap<(EntryId, String), FieldOrigin>;
+
+/// Field origins by raw entry position and lowercase field name.
+type RawFieldOrigins = Vec<(usize, String, FieldOrigin)>;
+
 impl<'a> Library<'a> {
     fn push_entry_with_source(&mut self, entry: Entry<'a>, source: Option<SourceSpan>) {
         let index = self.entries.len();
-        self.entries.push(entry);
+        let first = self.next_entry_id == 0;
+        let id = self.push_entry_id(entry);
+        let mut leading = std::mem::take(&mut self.pending_comments);
+        if first {
+            // A file header is set off by a blank line; it stays at the top
+            // instead of travelling with the first entry.
+            let comments = &self.comments;
+            if let Some(header) = leading
+                .iter()
+                .rposition(|&comment| has_blank_line(&comments[comment].text))
+            {
+                leading.drain(..=header);
+            }
+        }
+        if !leading.is_empty() {
+            self.leading_comments.insert(id, leading);
+        }
         if let Some(sources) = &mut self.entry_sources {
             sources.push(source);
         } else if source.is_some() {
@@ -1238,6 +2820,28 @@ impl<'a> Library<'a> {
         self.block_order.push(BlockKind::Entry(index));
     }
 
+    const fn allocate_entry_id(&mut self) -> EntryId {
+        let id = EntryId(self.next_entry_id);
+        self.next_entry_id += 1;
+        id
+    }
+
+    /// Append `entry` with a fresh ID and return the ID.
+    fn push_entry_id(&mut self, entry: Entry<'a>) -> EntryId {
+        let id = self.allocate_entry_id();
+        self.entry_positions.insert(id, self.entries.len());
+        self.entries.push(entry);
+        self.entry_ids.push(id);
+        id
+    }
+
+    /// Refresh the positions of the entry IDs from `start` onwards.
+    fn reindex_entries(&mut self, start: usize) {
+        for (index, id) in self.entry_ids.iter().enumerate().skip(start) {
+            self.entry_positions.insert(*id, index);
+        }
+    }
+
     fn register_string_definition(
         &mut self,
         name: Cow<'a, str>,
@@ -1261,26 +2865,33 @@ impl<'a> Library<'a> {
         source: Option<SourceSpan>,
     ) {
         let index = self.register_string_definition(name, value, source);
-        self.block_order.push(BlockKind::String(index));
+        self.push_block(BlockKind::String(index));
     }
 
     fn push_preamble_with_source(&mut self, value: Value<'a>, source: Option<SourceSpan>) -> usize {
         let index = self.preambles.len();
         self.preambles.push(Preamble { value, source });
-        self.block_order.push(BlockKind::Preamble(index));
+        self.push_block(BlockKind::Preamble(index));
         index
     }
 
     fn push_comment_with_source(&mut self, text: Cow<'a, str>, source: Option<SourceSpan>) {
         let index = self.comments.len();
         self.comments.push(Comment { text, source });
+        self.pending_comments.push(index);
         self.block_order.push(BlockKind::Comment(index));
     }
 
     fn push_failed_block(&mut self, failed: FailedBlock<'a>) {
         let index = self.failed_blocks.len();
         self.failed_blocks.push(failed);
-        self.block_order.push(BlockKind::Failed(index));
+        self.push_block(BlockKind::Failed(index));
+    }
+
+    /// Append a block that ends the run of comments before the next entry.
+    fn push_block(&mut self, kind: BlockKind) {
+        self.pending_comments.clear();
+        self.block_order.push(kind);
     }
 
     #[inline]
@@ -1363,6 +2974,13 @@ impl<'a> Library<'a> {
         Self::default()
     }
 
+    /// Create a parser that pre-loads `@string` definitions from `input`.
+    ///
+    /// See [`Parser::with_string_library`].
+    pub fn with_string_library(input: &str) -> Result<Parser> {
+        Parser::new().with_string_library(input)
+    }
+
     /// Create a parser with options
     ///
     /// # Parallel Processing
@@ -1397,8 +3015,11 @@ impl<'a> Library<'a> {
     }
 
     /// Parse a BibTeX library from a file into owned data.
+    ///
+    /// With the `compress` feature, gzip and zstd files such as `refs.bib.gz`
+    /// are decompressed first, up to 1 GiB.
     pub fn parse_file(path: impl AsRef<Path>) -> Result<Library<'static>> {
-        let content = std::fs::read_to_string(path)?;
+        let content = crate::compress::read_to_string(path.as_ref())?;
         Library::parser().parse(&content).map(Library::into_owned)
     }
 
@@ -1407,16 +3028,393 @@ impl<'a> Library<'a> {
         crate::writer::to_string(self)
     }
 
+    /// Serialize only the blocks that changed since parsing `original_source`.
+    ///
+    /// Unchanged blocks are copied from `original_source` verbatim, keeping
+    /// review diffs to the entries that were actually edited. The library must
+    /// have been parsed from `original_source` by `parser`, with
+    /// [`Parser::capture_source`]; see [`Writer::write_changes`] for details.
+    ///
+    /// [`Writer::write_changes`]: crate::Writer::write_changes
+    ///
+    /// ```
+    /// use bibtex_parser::{Parser, Value};
+    ///
+    /// let source = "@ARTICLE{a,  title={Kept as is}}\n\n@misc{b,note={Old}}\n";
+    /// let parser = Parser::new().capture_source();
+    /// let mut library = parser.parse(source)?;
+    /// library.entries_mut()[1].set("note", Value::from_plain_string("New"));
+    ///
+    /// assert_eq!(
+    ///     library.write_changes(source, &parser),
+    ///     "@ARTICLE{a,  title={Kept as is}}\n\n@misc{b,\n  note = {New}\n}\n"
+    /// );
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn write_changes(&self, original_source: &str, parser: &Parser) -> String {
+        let mut output = String::new();
+        crate::writer::Writer::from_fmt(&mut output)
+            .write_changes(self, original_source, parser)
+            .expect("writing to a String cannot fail");
+        output
+    }
+
+    /// Borrow the entries that match `filter` as a [`LibraryView`].
+    ///
+    /// The view keeps library order and does not clone entries.
+    pub fn view(&self, mut filter: impl FnMut(&Entry<'a>) -> bool) -> LibraryView<'_, 'a> {
+        LibraryView {
+            library: self,
+            indices: (0..self.entries.len())
+                .filter(|&index| filter(&self.entries[index]))
+                .collect(),
+        }
+    }
+
+    /// Split into the entries that match `predicate` and the rest.
+    ///
+    /// Both libraries keep every string definition, preamble, and comment,
+    /// so each can be written or resolved on its own. Entries keep their
+    /// order and [`EntryId`]s.
+    ///
+    /// ```
+    /// use bibtex_parser::Library;
+    ///
+    /// let input = "@book{a, year = 1999}\n@misc{b, year = 2000}\n@book{c, year = 2001}";
+    /// let library = Library::parse(input)?;
+    /// let (books, other) = library.partition(|entry| entry.ty.canonical_name() == "book");
+    /// assert_eq!(books.entries().len(), 2);
+    /// assert_eq!(other.entries()[0].key(), "b");
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn partition(self, mut predicate: impl FnMut(&Entry<'a>) -> bool) -> (Self, Self) {
+        let matches: Vec<bool> = self.entries.iter().map(&mut predicate).collect();
+        let others: Vec<bool> = matches.iter().map(|matched| !matched).collect();
+        let mut rest = self.clone();
+        rest.retain_entries(&others);
+        let mut matching = self;
+        matching.retain_entries(&matches);
+        (matching, rest)
+    }
+
+    /// Split into the entries whose keys are in `keys` and the rest.
+    ///
+    /// Keys match exactly, so the set can hold the output of
+    /// [`cited_keys`](crate::cited_keys) to separate cited from uncited
+    /// entries. See [`Self::partition`].
+    #[must_use]
+    pub fn split_by_key_set<K, S>(self, keys: &HashSet<K, S>) -> (Self, Self)
+    where
+        K: Borrow<str> + Hash + Eq,
+        S: BuildHasher,
+    {
+        self.partition(|entry| keys.contains(entry.key()))
+    }
+
+    /// Keep only the entries whose flag in `keep` is set.
+    fn retain_entries(&mut self, keep: &[bool]) {
+        fn retain_flagged<T>(items: &mut Vec<T>, keep: &[bool]) {
+            let mut flags = keep.iter();
+            items.retain(|_| flags.next().copied().unwrap_or(false));
+        }
+
+        let new_index: Vec<Option<usize>> = keep
+            .iter()
+            .scan(0, |kept, &flag| {
+                let index = flag.then_some(*kept);
+                *kept += usize::from(flag);
+                Some(index)
+            })
+            .collect();
+        retain_flagged(&mut self.entries, keep);
+        retain_flagged(&mut self.entry_ids, keep);
+        if let Some(sources) = &mut self.entry_sources {
+            retain_flagged(sources, keep);
+        }
+        self.block_order.retain_mut(|kind| match kind {
+            BlockKind::Entry(index) => {
+                let new = new_index.get(*index).copied().flatten();
+                *index = new.unwrap_or(*index);
+                new.is_some()
+            }
+            _ => true,
+        });
+        self.entry_positions.clear();
+        self.reindex_entries(0);
+        let positions = &self.entry_positions;
+        self.original_keys
+            .retain(|id, _| positions.contains_key(id));
+        self.field_origins
+            .retain(|(id, _), _| positions.contains_key(id));
+        self.leading_comments
+            .retain(|id, _| positions.contains_key(id));
+    }
+
+    /// Borrow `limit` entries starting at position `offset` in `sort` order.
+    ///
+    /// Only the entry order options of `sort` apply; with neither set, pages
+    /// follow library order and nothing is allocated beyond the page. The
+    /// library itself is not reordered, and ties keep library order.
+    ///
+    /// ```
+    /// use bibtex_parser::{Library, SortOptions};
+    ///
+    /// let input = "@misc{c, year = 3}\n@misc{a, year = 1}\n@misc{b, year = 2}";
+    /// let library = Library::parse(input)?;
+    /// let by_key = SortOptions { entries_by_key: true, ..SortOptions::default() };
+    /// let page = library.page(1, 10, by_key);
+    /// let keys: Vec<_> = page.iter().map(|entry| entry.key()).collect();
+    /// assert_eq!(keys, ["b", "c"]);
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn page(&self, offset: usize, limit: usize, sort: SortOptions) -> LibraryView<'_, 'a> {
+        let len = self.entries.len();
+        let start = offset.min(len);
+        let end = offset.saturating_add(limit).min(len);
+        if !sort.entries_by_key && !sort.entries_by_year {
+            return LibraryView {
+                library: self,
+                indices: (start..end).collect(),
+            };
+        }
+
+        let years: Vec<PublicationYear> = if sort.entries_by_year {
+            self.entries.iter().map(Entry::publication_year).collect()
+        } else {
+            Vec::new()
+        };
+        let compare = |left: &usize, right: &usize| {
+            self.compare_entries(sort, &years, *left, *right)
+                .then(left.cmp(right))
+        };
+        let mut order: Vec<usize> = (0..len).collect();
+        if end < len {
+            order.select_nth_unstable_by(end, compare);
+            order.truncate(end);
+        }
+        if start < order.len() {
+            order.select_nth_unstable_by(start, compare);
+        }
+        let mut indices = order.split_off(start);
+        indices.sort_unstable_by(compare);
+        LibraryView {
+            library: self,
+            indices,
+        }
+    }
+
+    /// Borrow a random sample of up to `n` entries, in library order.
+    ///
+    /// The same `seed` picks the same entries from the same library, so
+    /// benchmarks and previews are reproducible. Each entry is equally
+    /// likely to be picked.
+    #[must_use]
+    pub fn sample(&self, n: usize, seed: u64) -> LibraryView<'_, 'a> {
+        let mut rng = SplitMix64(seed);
+        let mut indices: Vec<usize> = (0..n.min(self.entries.len())).collect();
+        for index in indices.len()..self.entries.len() {
+            let slot = rng.below(index as u64 + 1);
+            if let Some(picked) = usize::try_from(slot)
+                .ok()
+                .and_then(|slot| indices.get_mut(slot))
+            {
+                *picked = index;
+            }
+        }
+        indices.sort_unstable();
+        LibraryView {
+            library: self,
+            indices,
+        }
+    }
+
+    /// Iterate over entries in `sort` order without reordering the library.
+    ///
+    /// Only the entry order options of `sort` apply, and ties keep library
+    /// order. The order is computed once as a permutation of entry indices,
+    /// so entries are borrowed rather than collected; narrow with
+    /// [`Library::view`] first to sort only the matching entries.
+    ///
+    /// ```
+    /// use bibtex_parser::{Library, SortOptions};
+    ///
+    /// let input = "@misc{c, year = {2}}\n@misc{a, year = {3}}\n@misc{b, year = {1}}";
+    /// let library = Library::parse(input)?;
+    /// let by_year = SortOptions { entries_by_year: true, ..SortOptions::default() };
+    /// let keys: Vec<_> = library.iter_sorted(by_year).map(|entry| entry.key()).collect();
+    /// assert_eq!(keys, ["b", "c", "a"]);
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn iter_sorted(
+        &self,
+        sort: SortOptions,
+    ) -> impl DoubleEndedIterator<Item = &Entry<'a>> + ExactSizeIterator + '_ {
+        self.sorted_indices((0..self.entries.len()).collect(), sort)
+            .into_iter()
+            .map(move |index| &self.entries[index])
+    }
+
+    /// Reorder entry `indices` by the entry order options of `sort`.
+    ///
+    /// The sort is stable, so ties keep the order of `indices`.
+    fn sorted_indices(&self, mut indices: Vec<usize>, sort: SortOptions) -> Vec<usize> {
+        if sort.entries_by_key || sort.entries_by_year {
+            indices.sort_by_cached_key(|&index| {
+                let entry = &self.entries[index];
+                (
+                    sort.entries_by_year.then(|| entry.publication_year()),
+                    sort.entries_by_key.then_some(entry.key.as_ref()),
+                )
+            });
+        }
+        indices
+    }
+
     /// Serialize this library to a BibTeX file.
     pub fn write_file(&self, path: impl AsRef<Path>) -> Result<()> {
         crate::writer::to_file(self, path)
     }
 
+    /// Write entries into one `<type>.bib` file per entry type in `dir`.
+    pub fn write_split_by_type(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+        self.write_split_by(dir, |entry| entry.ty.canonical_name().to_ascii_lowercase())
+    }
+
+    /// Write entries into one `<bucket>.bib` file per bucket in `dir`.
+    ///
+    /// `bucket` names the output file for each entry; characters outside
+    /// ASCII letters, digits, `-`, `_`, and `.` are replaced with `_`. Names
+    /// that differ only in case, such as `ACL` and `acl`, get a `-2`, `-3`, …
+    /// suffix so case-insensitive file systems keep them apart. Every
+    /// file starts with the library's preambles and string definitions and
+    /// keeps entries in library order. With the `parallel` feature, files are
+    /// written concurrently. Returns the written paths sorted by bucket.
+    pub fn write_split_by<F>(&self, dir: impl AsRef<Path>, mut bucket: F) -> Result<Vec<PathBuf>>
+    where
+        F: FnMut(&Entry<'a>) -> String,
+    {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let mut buckets: BTreeMap<String, Vec<&Entry<'a>>> = BTreeMap::new();
+        for entry in &self.entries {
+            buckets
+                .entry(bucket_file_name(&bucket(entry)))
+                .or_default()
+                .push(entry);
+        }
+        let mut taken = AHashSet::with_capacity(buckets.len());
+        let buckets = buckets
+            .into_iter()
+            .map(|(name, entries)| {
+                let mut file = name.clone();
+                let mut suffix = 2;
+                while !taken.insert(file.to_ascii_lowercase()) {
+                    file = format!("{name}-{suffix}");
+                    suffix += 1;
+                }
+                (dir.join(format!("{file}.bib")), entries)
+            })
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "parallel")]
+        let written = buckets
+            .par_iter()
+            .map(|(path, entries)| self.write_bucket(path, entries))
+            .collect::<Result<Vec<_>>>();
+        #[cfg(not(feature = "parallel"))]
+        let written = buckets
+            .iter()
+            .map(|(path, entries)| self.write_bucket(path, entries))
+            .collect::<Result<Vec<_>>>();
+        written?;
+
+        Ok(buckets.into_iter().map(|(path, _)| path).collect())
+    }
+
+    fn write_bucket(&self, path: &Path, entries: &[&Entry<'a>]) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut stream = crate::Writer::new(std::io::BufWriter::new(file)).begin();
+        for preamble in &self.preambles {
+            stream.write_preamble(&preamble.value)?;
+        }
+        for definition in &self.strings {
+            stream.write_string(&definition.name, &definition.value)?;
+        }
+        for entry in entries {
+            stream.write_entry(entry)?;
+        }
+        stream.finish()?;
+        Ok(())
+    }
+
+    /// Render a deterministic, fully normalized form of this library.
+    ///
+    /// Macros are resolved where possible, entry types and field names are
+    /// lowercased, fields and entries are sorted, and whitespace inside values
+    /// is collapsed. Preambles come first in source order; string definitions,
+    /// comments, and failed blocks are omitted. The output is meant for hashing
+    /// and snapshot tests, not for round-tripping the original source.
+    pub fn canonical_string(&self) -> Result<String> {
+        let mut canonical = Library::new();
+        for preamble in &self.preambles {
+            canonical.add_preamble(canonical_value(self.expand_value_partial(&preamble.value)));
+        }
+
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let ty = entry.ty.canonical_name().to_ascii_lowercase();
+                let mut fields = entry
+                    .fields
+                    .iter()
+                    .map(|field| Field {
+                        name: Cow::Owned(field.name.to_ascii_lowercase()),
+                        value: canonical_value(self.expand_value_partial(&field.value)),
+                    })
+                    .collect::<Vec<_>>();
+                fields.sort_by(|left, right| {
+                    left.name.cmp(&right.name).then_with(|| {
+                        left.value
+                            .to_bibtex_source()
+                            .cmp(&right.value.to_bibtex_source())
+                    })
+                });
+                Entry {
+                    ty: EntryType::parse(&ty).into_owned(),
+                    key: Cow::Owned(entry.key.to_string()),
+                    fields,
+                }
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_cached_key(|entry| {
+            let fields = entry
+                .fields
+                .iter()
+                .map(|field| (field.name.to_string(), field.value.to_bibtex_source()))
+                .collect::<Vec<_>>();
+            (entry.key.to_string(), entry.ty.to_string(), fields)
+        });
+        for entry in entries {
+            canonical.add_entry(entry);
+        }
+
+        crate::writer::to_string(&canonical)
+    }
+
     /// Parse a BibTeX library from a string (single-threaded implementation)
     #[allow(clippy::too_many_lines)]
     pub(crate) fn parse_sequential(input: &'a str) -> Result<Self> {
         let mut library = Self::new();
+        let phase = crate::trace::phase!(debug_span, "scan", input.len());
         let input_scan = scan_input(input);
+        phase.finish();
+        let phase = crate::trace::phase!(debug_span, "parse", input.len());
 
         // Fast path for common corpora (like tugboat) with no user-defined strings.
         // This avoids buffering all entries before expansion.
@@ -1467,6 +3465,7 @@ impl<'a> Library<'a> {
                 Ok(())
             })?;
 
+            phase.entries(library.entries.len());
             return Ok(library);
         }
 
@@ -1532,7 +3531,9 @@ impl<'a> Library<'a> {
                 )?;
                 library.preambles[index].value = expanded;
             }
+            library.resolved_strings = expanded_variables.into_names();
 
+            phase.entries(library.entries.len());
             return Ok(library);
         }
 
@@ -1560,6 +3561,10 @@ impl<'a> Library<'a> {
             Ok(())
         })?;
 
+        phase.entries(library.entries.len());
+        phase.finish();
+        let phase = crate::trace::phase!(debug_span, "expand", input.len());
+
         // Expand after parsing so all @string definitions are available globally.
         let has_user_strings = !library.strings.is_empty();
         let month_constants_shadowed =
@@ -1597,45 +3602,91 @@ impl<'a> Library<'a> {
             )?;
             library.preambles[preamble_index].value = expanded;
         }
+        library.resolved_strings = expanded_variables.into_names();
 
+        phase.entries(library.entries.len());
         Ok(library)
     }
 
     fn parse_with_spans(input: &'a str) -> Result<Self> {
         let source_map = SourceMap::anonymous(input);
-        let raw_items = Self::parse_raw_items_with_source(input, &source_map)?;
-        Self::from_raw_items(raw_items)
+        let (raw_items, _) = Self::parse_raw_items_with_source(
+            input,
+            &source_map,
+            ParseLimits::default(),
+            Syntax::default(),
+        )?;
+        Self::expand_raw_items(input, raw_items)
     }
 
     fn parse_tolerant(input: &'a str, capture_source: bool) -> Result<Self> {
         let source_map = SourceMap::anonymous(input);
-        let raw_items = Self::parse_tolerant_raw_items(input, capture_source, &source_map);
-        Self::from_raw_items(raw_items)
+        let (raw_items, _) = Self::parse_tolerant_raw_items(
+            input,
+            capture_source,
+            &source_map,
+            ParseLimits::default(),
+            Syntax::default(),
+        );
+        Self::expand_raw_items(input, raw_items)
     }
 
+    fn expand_raw_items(input: &str, raw_items: Vec<RawBuildItem<'a>>) -> Result<Self> {
+        let phase = crate::trace::phase!(debug_span, "expand", input.len());
+        let library = Self::from_raw_items(raw_items)?;
+        phase.entries(library.entries.len());
+        Ok(library)
+    }
+
+    /// Parse `input` into raw items, stopping early when `limits` say so.
+    ///
+    /// Also returns the byte offset of the first unparsed block and the
+    /// reason when parsing stopped early.
     fn parse_raw_items_with_source(
         input: &'a str,
         source_map: &SourceMap<'_>,
-    ) -> Result<Vec<RawBuildItem<'a>>> {
+        limits: ParseLimits<'_>,
+        syntax: Syntax,
+    ) -> Result<(Vec<RawBuildItem<'a>>, Option<StoppedAt>)> {
+        let phase = crate::trace::phase!(debug_span, "parse", input.len());
         let mut raw_items = Vec::new();
-        crate::parser::parse_bibtex_stream_with_spans(input, |item, span, raw| {
-            let span = if source_map.source_id().is_some() {
-                source_map.span(span.byte_start, span.byte_end)
-            } else {
-                span
-            };
-            raw_items.push(RawBuildItem::Parsed(item, span, raw));
-            Ok(())
-        })?;
-        Ok(raw_items)
+        let mut entries = 0;
+        let mut stopped = None;
+        let parsed =
+            crate::parser::parse_bibtex_stream_with_spans(input, syntax, |item, span, raw| {
+                if let Some(stop) = limits.check(entries) {
+                    stopped = Some((span.byte_start, stop));
+                    return Err(Error::Cancelled);
+                }
+                if matches!(item, crate::parser::ParsedItem::Entry(_)) {
+                    entries += 1;
+                }
+                let span = if source_map.source_id().is_some() {
+                    source_map.span(span.byte_start, span.byte_end)
+                } else {
+                    span
+                };
+                raw_items.push(RawBuildItem::Parsed(item, span, raw));
+                Ok(())
+            });
+        match parsed {
+            Err(Error::Cancelled) if stopped.is_some() => {}
+            parsed => parsed?,
+        }
+        phase.entries(raw_entry_count(&raw_items));
+        Ok((raw_items, stopped))
     }
 
     fn parse_tolerant_raw_items(
         input: &'a str,
         capture_source: bool,
         source_map: &SourceMap<'_>,
-    ) -> Vec<RawBuildItem<'a>> {
+        limits: ParseLimits<'_>,
+        syntax: Syntax,
+    ) -> (Vec<RawBuildItem<'a>>, Option<StoppedAt>) {
+        let phase = crate::trace::phase!(debug_span, "parse", input.len(), tolerant = true);
         let mut raw_items = Vec::new();
+        let mut entries = 0;
         let mut remaining = input;
 
         loop {
@@ -1645,9 +3696,16 @@ impl<'a> Library<'a> {
             }
 
             let start = input.len() - remaining.len();
-            match crate::parser::parse_item(&mut remaining) {
+            if let Some(stop) = limits.check(entries) {
+                phase.entries(raw_entry_count(&raw_items));
+                return (raw_items, Some((start, stop)));
+            }
+            match crate::parser::parse_item_with(&mut remaining, syntax) {
                 Ok(item) => {
                     let end = input.len() - remaining.len();
+                    if matches!(item, crate::parser::ParsedItem::Entry(_)) {
+                        entries += 1;
+                    }
                     raw_items.push(RawBuildItem::Parsed(
                         item,
                         source_map.span(start, end),
@@ -1667,11 +3725,39 @@ impl<'a> Library<'a> {
             }
         }
 
-        raw_items
+        phase.entries(raw_entry_count(&raw_items));
+        (raw_items, None)
     }
 
     fn from_raw_items(raw_items: Vec<RawBuildItem<'a>>) -> Result<Self> {
+        Self::from_raw_items_with(
+            raw_items,
+            &[],
+            &ExpansionPolicy::default(),
+            ExpansionSettings::default(),
+        )
+    }
+
+    /// Build from raw items, resolving variables against `predefined` strings too.
+    ///
+    /// Predefined strings are removed again before returning, so they never
+    /// appear in the library's strings or block order. Entry fields that
+    /// `policy` preserves are left unexpanded.
+    fn from_raw_items_with(
+        raw_items: Vec<RawBuildItem<'a>>,
+        predefined: &[StringDefinition<'static>],
+        policy: &ExpansionPolicy,
+        expansion: ExpansionSettings,
+    ) -> Result<Self> {
         let mut library = Self::new();
+        library.expansion = expansion;
+        for definition in predefined {
+            library.register_string_definition(
+                definition.name.clone(),
+                definition.value.clone(),
+                None,
+            );
+        }
 
         for raw_item in &raw_items {
             if let RawBuildItem::Parsed(crate::parser::ParsedItem::String(name, value), span, _) =
@@ -1681,7 +3767,7 @@ impl<'a> Library<'a> {
             }
         }
 
-        let has_user_strings = !library.strings.is_empty();
+        let has_user_strings = !library.strings.is_empty() || library.expansion.checks_variables();
         let month_constants_shadowed =
             has_user_strings && user_strings_shadow_month_constants(&library.strings);
         let mut expanded_variables = ExpansionCache::with_capacity(library.strings.len());
@@ -1693,6 +3779,9 @@ impl<'a> Library<'a> {
             match raw_item {
                 RawBuildItem::Parsed(crate::parser::ParsedItem::Entry(mut entry), span, _) => {
                     for field in &mut entry.fields {
+                        if !policy.expands_field(&field.name) {
+                            continue;
+                        }
                         library.expand_value_for_parse(
                             &mut field.value,
                             has_user_strings,
@@ -1705,7 +3794,7 @@ impl<'a> Library<'a> {
                     library.push_entry_with_source(entry, Some(span));
                 }
                 RawBuildItem::Parsed(crate::parser::ParsedItem::String(_, _), _, _) => {
-                    library.block_order.push(BlockKind::String(string_index));
+                    library.push_block(BlockKind::String(string_index));
                     string_index += 1;
                 }
                 RawBuildItem::Parsed(crate::parser::ParsedItem::Preamble(mut value), span, _) => {
@@ -1725,10 +3814,35 @@ impl<'a> Library<'a> {
                 RawBuildItem::Failed(failed) => library.push_failed_block(failed),
             }
         }
+        library.resolved_strings = expanded_variables.into_names();
+        if !predefined.is_empty() {
+            library.strings.drain(..predefined.len());
+            library.rebuild_string_lookup();
+        }
 
         Ok(library)
     }
 
+    fn rebuild_string_lookup(&mut self) {
+        self.string_lookup.clear();
+        for (index, definition) in self.strings.iter().enumerate() {
+            self.string_lookup.insert(definition.name.clone(), index);
+        }
+    }
+
+    fn clear_sources(&mut self) {
+        self.entry_sources = None;
+        for definition in &mut self.strings {
+            definition.source = None;
+        }
+        for preamble in &mut self.preambles {
+            preamble.source = None;
+        }
+        for comment in &mut self.comments {
+            comment.source = None;
+        }
+    }
+
     fn from_raw_items_unexpanded(raw_items: Vec<RawBuildItem<'a>>) -> Self {
         let mut library = Self::new();
 
@@ -1749,12 +3863,13 @@ impl<'a> Library<'a> {
                 RawBuildItem::Failed(failed) => library.push_failed_block(failed),
             }
         }
+        library.resolve_strings_partial();
 
         library
     }
 
     /// Merge another library into this one
-    pub fn merge(&mut self, other: Self) {
+    pub fn merge(&mut self, mut other: Self) {
         let entry_offset = self.entries.len();
         let string_offset = self.strings.len();
         let preamble_offset = self.preambles.len();
@@ -1763,7 +3878,18 @@ impl<'a> Library<'a> {
         let other_entry_count = other.entries.len();
         let other_entry_sources = other.entry_sources;
 
-        self.entries.extend(other.entries);
+        let mut merged_ids = AHashMap::with_capacity(other.entry_ids.len());
+        for (entry, id) in other.entries.into_iter().zip(&other.entry_ids) {
+            let merged_id = self.push_entry_id(entry);
+            merged_ids.insert(*id, merged_id);
+            if let Some(original) = other.original_keys.remove(id) {
+                self.original_keys.insert(merged_id, original);
+            }
+            if let Some(leading) = other.leading_comments.remove(id) {
+                let leading = leading.iter().map(|comment| comment_offset + comment);
+                self.leading_comments.insert(merged_id, leading.collect());
+            }
+        }
         match (&mut self.entry_sources, other_entry_sources) {
             (Some(sources), Some(other_sources)) => sources.extend(other_sources),
             (Some(sources), None) => {
@@ -1785,6 +3911,12 @@ impl<'a> Library<'a> {
             self.string_lookup.insert(definition.name.clone(), index);
             self.strings.push(definition);
         }
+        self.resolved_strings.extend(other.resolved_strings);
+        for ((id, field), origin) in other.field_origins {
+            if let Some(&merged_id) = merged_ids.get(&id) {
+                self.field_origins.insert((merged_id, field), origin);
+            }
+        }
 
         self.block_order
             .extend(other.block_order.into_iter().map(|kind| match kind {
@@ -1794,6 +3926,11 @@ impl<'a> Library<'a> {
                 BlockKind::Comment(index) => BlockKind::Comment(comment_offset + index),
                 BlockKind::Failed(index) => BlockKind::Failed(failed_offset + index),
             }));
+        self.pending_comments = other
+            .pending_comments
+            .iter()
+            .map(|comment| comment_offset + comment)
+            .collect();
     }
 
     #[cfg(feature = "parallel")]
@@ -1811,12 +3948,122 @@ impl<'a> Library<'a> {
         &self.entries
     }
 
+    /// Iterate over entries in library order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry<'a>> {
+        self.entries.iter()
+    }
+
     /// Get mutable access to all entries
+    ///
+    /// Entry IDs belong to positions in this slice, so swapping two entries
+    /// swaps their contents under the same IDs. Use [`Library::add_entry`],
+    /// [`Library::remove_entry`], and [`Library::sort`] to change which
+    /// entries the library holds or their order.
     #[must_use]
-    pub fn entries_mut(&mut self) -> &mut Vec<Entry<'a>> {
+    pub fn entries_mut(&mut self) -> &mut [Entry<'a>] {
         &mut self.entries
     }
 
+    /// Get stable entry IDs, aligned with [`Library::entries`].
+    #[must_use]
+    pub fn entry_ids(&self) -> &[EntryId] {
+        &self.entry_ids
+    }
+
+    /// Get the stable ID of the entry at `index`.
+    #[must_use]
+    pub fn entry_id(&self, index: usize) -> Option<EntryId> {
+        self.entry_ids.get(index).copied()
+    }
+
+    /// Get the current index of the entry with `id`.
+    #[must_use]
+    pub fn entry_index(&self, id: EntryId) -> Option<usize> {
+        self.entry_positions.get(&id).copied()
+    }
+
+    /// Get the entry with `id`.
+    #[must_use]
+    pub fn entry_by_id(&self, id: EntryId) -> Option<&Entry<'a>> {
+        self.entry_index(id).map(|index| &self.entries[index])
+    }
+
+    /// Get the entry with `id` mutably.
+    pub fn entry_by_id_mut(&mut self, id: EntryId) -> Option<&mut Entry<'a>> {
+        self.entry_index(id).map(|index| &mut self.entries[index])
+    }
+
+    /// Canonicalize every citation key, returning how many keys changed.
+    ///
+    /// The first spelling of each changed key stays available through
+    /// [`Library::original_key`]. References to changed keys are rewritten
+    /// as by [`Library::regenerate_keys`].
+    #[cfg(feature = "key_canonicalization")]
+    pub fn canonicalize_keys(&mut self, canonicalization: KeyCanonicalization) -> usize {
+        let mut renames = KeyRenameMap::new();
+        let mut changed = 0;
+        for (entry, id) in self.entries.iter_mut().zip(&self.entry_ids) {
+            let Cow::Owned(key) = canonicalization.canonicalize(&entry.key) else {
+                continue;
+            };
+            if key == entry.key {
+                continue;
+            }
+            renames.insert(entry.key.as_ref(), key.as_str());
+            let original = std::mem::replace(&mut entry.key, Cow::Owned(key));
+            self.original_keys
+                .entry(*id)
+                .or_insert_with(|| original.into_owned());
+            changed += 1;
+        }
+        self.rename_key_references(&renames);
+        changed
+    }
+
+    /// Get the source key of the entry with `id` when key canonicalization changed it.
+    #[must_use]
+    pub fn original_key(&self, id: EntryId) -> Option<&str> {
+        self.original_keys.get(&id).map(String::as_str)
+    }
+
+    /// Remove the entry with `id`, keeping the IDs of other entries intact.
+    pub fn remove_entry(&mut self, id: EntryId) -> Option<Entry<'a>> {
+        let index = self.entry_positions.remove(&id)?;
+        self.entry_ids.remove(index);
+        self.reindex_entries(index);
+        self.original_keys.remove(&id);
+        self.leading_comments.remove(&id);
+        self.field_origins.retain(|(entry, _), _| *entry != id);
+        if let Some(sources) = &mut self.entry_sources {
+            if index < sources.len() {
+                sources.remove(index);
+            }
+        }
+        self.block_order
+            .retain(|kind| *kind != BlockKind::Entry(index));
+        for kind in &mut self.block_order {
+            if let BlockKind::Entry(other) = kind {
+                if *other > index {
+                    *other -= 1;
+                }
+            }
+        }
+        Some(self.entries.remove(index))
+    }
+
+    /// Get entries in the order they were parsed or added, regardless of sorting.
+    #[must_use]
+    pub fn entries_in_source_order(&self) -> Vec<&Entry<'a>> {
+        let mut entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (self.entry_id(index), entry))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(id, _)| id.map_or(u64::MAX, EntryId::get));
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+
     /// Get all string definitions
     #[must_use]
     pub fn strings(&self) -> &[StringDefinition<'a>] {
@@ -1859,6 +4106,61 @@ impl<'a> Library<'a> {
         &mut self.comments
     }
 
+    /// Return the comments directly before an entry, in source order.
+    ///
+    /// These are the comment blocks between the entry and the previous
+    /// non-comment block, usually `%` lines documenting the entry. Comments
+    /// before the first entry up to the last blank line are the file header
+    /// and are not attached to any entry. Comments are attached as blocks are parsed or
+    /// added, and stay with their entry through [`Library::sort`],
+    /// [`Library::remove_entry`], and sorted writer output.
+    ///
+    /// ```
+    /// use bibtex_parser::Library;
+    ///
+    /// let library = Library::parse(
+    ///     "% My papers\n\n@misc{a, year = 2024}\n\
+    ///      % Preprint; update when published.\n@misc{b, year = 2025}",
+    /// )?;
+    /// let comments = library.leading_comments(1);
+    /// assert_eq!(comments[0].text(), "% Preprint; update when published.\n");
+    /// assert!(library.leading_comments(0).is_empty());
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    #[must_use]
+    pub fn leading_comments(&self, index: usize) -> Vec<&Comment<'a>> {
+        self.leading_comment_indices(index)
+            .iter()
+            .filter_map(|&comment| self.comments.get(comment))
+            .collect()
+    }
+
+    /// Return the indices of the comments leading the entry at `index`.
+    pub(crate) fn leading_comment_indices(&self, index: usize) -> &[usize] {
+        self.entry_ids
+            .get(index)
+            .and_then(|id| self.leading_comments.get(id))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the indices of the unattached comments that open the file.
+    pub(crate) fn header_comment_indices(&self) -> Vec<usize> {
+        let mut blocks = self.block_order.iter();
+        let mut header = Vec::new();
+        for kind in blocks.by_ref() {
+            match *kind {
+                BlockKind::Comment(index) => header.push(index),
+                BlockKind::Entry(index) => {
+                    let leading = self.leading_comment_indices(index);
+                    header.retain(|comment| !leading.contains(comment));
+                    break;
+                }
+                _ => break,
+            }
+        }
+        header
+    }
+
     /// Get malformed blocks retained by tolerant parsing.
     #[must_use]
     pub fn failed_blocks(&self) -> &[FailedBlock<'a>] {
@@ -1868,8 +4170,14 @@ impl<'a> Library<'a> {
     /// Return blocks in source order.
     #[must_use]
     pub fn blocks(&self) -> Vec<Block<'_, 'a>> {
+        self.blocks_with_entries(|_| true)
+    }
+
+    /// Return blocks in source order, keeping only entries whose index passes `keep`.
+    pub(crate) fn blocks_with_entries(&self, keep: impl Fn(usize) -> bool) -> Vec<Block<'_, 'a>> {
         self.block_order
             .iter()
+            .filter(|kind| !matches!(**kind, BlockKind::Entry(index) if !keep(index)))
             .map(|kind| match *kind {
                 BlockKind::Entry(index) => Block::Entry(
                     &self.entries[index],
@@ -1892,6 +4200,25 @@ impl<'a> Library<'a> {
             .and_then(|sources| sources.get(index).copied().flatten())
     }
 
+    /// Move every retained source span from a slice to the whole input.
+    pub(crate) fn offset_sources(&mut self, offset: SourceOffset) {
+        for source in self.entry_sources.iter_mut().flatten() {
+            offset.apply(source);
+        }
+        for definition in &mut self.strings {
+            offset.apply(&mut definition.source);
+        }
+        for preamble in &mut self.preambles {
+            offset.apply(&mut preamble.source);
+        }
+        for comment in &mut self.comments {
+            offset.apply(&mut comment.source);
+        }
+        for failed in &mut self.failed_blocks {
+            offset.apply(&mut failed.source);
+        }
+    }
+
     #[must_use]
     pub(crate) fn block_kinds(&self) -> &[BlockKind] {
         &self.block_order
@@ -1911,6 +4238,23 @@ impl<'a> Library<'a> {
             .find(|entry| entry.key.eq_ignore_ascii_case(key))
     }
 
+    /// Find an entry whose key matches `key` after `canonicalization`.
+    ///
+    /// Use this when citation keys may differ from the `.bib` keys only in
+    /// Unicode normalization, surrounding whitespace, or case.
+    #[cfg(feature = "key_canonicalization")]
+    #[must_use]
+    pub fn find_by_key_canonical(
+        &self,
+        key: &str,
+        canonicalization: KeyCanonicalization,
+    ) -> Option<&Entry<'a>> {
+        let key = canonicalization.canonicalize(key);
+        self.entries
+            .iter()
+            .find(|entry| canonicalization.canonicalize(&entry.key) == key)
+    }
+
     /// Return `true` when the library contains `key`.
     #[must_use]
     pub fn contains_key(&self, key: &str) -> bool {
@@ -1967,6 +4311,9 @@ impl<'a> Library<'a> {
     }
 
     /// Smart expansion with memoization for repeated variable references.
+    ///
+    /// Nested variables and concatenations are expanded from an explicit
+    /// worklist, so deep chains hit the depth limit instead of the stack.
     fn smart_expand_value_cached(
         &self,
         value: Value<'a>,
@@ -1974,73 +4321,112 @@ impl<'a> Library<'a> {
         expansion_stack: &mut Vec<Cow<'a, str>>,
         concat_cache: &mut ConcatCache<'a>,
     ) -> Result<Value<'a>> {
-        match value {
-            // Simple literals and numbers stay as-is (zero-copy!)
-            Value::Literal(_) | Value::Number(_) => Ok(value),
-
-            // Variables need to be resolved
-            Value::Variable(name) => {
-                let name_text = name.as_ref();
-                if let Some(expanded) = expanded_variables.get_cloned(name_text) {
-                    return Ok(expanded);
-                }
+        let base = expansion_stack.len();
+        let expanded =
+            self.expand_worklist(value, expanded_variables, expansion_stack, concat_cache);
+        if expanded.is_err() {
+            expansion_stack.truncate(base);
+        }
+        expanded
+    }
 
-                if expansion_stack.iter().any(|v| v.as_ref() == name_text) {
-                    let mut cycle = expansion_stack
-                        .iter()
-                        .map(std::convert::AsRef::as_ref)
-                        .collect::<Vec<_>>()
-                        .join(" -> ");
-                    if !cycle.is_empty() {
-                        cycle.push_str(" -> ");
+    fn expand_worklist(
+        &self,
+        value: Value<'a>,
+        expanded_variables: &mut ExpansionCache<'a>,
+        expansion_stack: &mut Vec<Cow<'a, str>>,
+        concat_cache: &mut ConcatCache<'a>,
+    ) -> Result<Value<'a>> {
+        let mut frames = Vec::new();
+        let mut current = value;
+        'expand: loop {
+            let mut result = match current {
+                // Simple literals and numbers stay as-is (zero-copy!)
+                Value::Literal(_) | Value::Number(_) => current,
+
+                // Variables need to be resolved
+                Value::Variable(name) => {
+                    if let Some(expanded) = expanded_variables.get_cloned(&name) {
+                        expanded
+                    } else if expansion_stack.contains(&name) {
+                        return Err(Error::CircularReference(expansion_chain(
+                            expansion_stack,
+                            &name,
+                        )));
+                    } else if let Some(user_value) =
+                        get_string_value(&self.strings, &self.string_lookup, &name)
+                    {
+                        if expansion_stack.len() >= self.expansion.max_depth.0 {
+                            return Err(Error::ExpansionDepthExceeded {
+                                chain: expansion_chain(expansion_stack, &name),
+                                limit: self.expansion.max_depth.0,
+                            });
+                        }
+                        expansion_stack.push(name.clone());
+                        frames.push(ExpansionFrame::Variable(name));
+                        current = user_value.clone();
+                        continue 'expand;
+                    } else if let Some(month) = get_month_expansion(&name) {
+                        // Check month abbreviations as fallback
+                        Value::Literal(Cow::Borrowed(month))
+                    } else if let Some(resolved) = self.expansion.resolve(&name) {
+                        resolved
+                    } else {
+                        self.expansion.undefined_variable(name)?
                     }
-                    cycle.push_str(name_text);
-                    return Err(Error::CircularReference(cycle));
                 }
 
-                if let Some(user_value) =
-                    get_string_value(&self.strings, &self.string_lookup, name_text)
-                {
-                    // Recursively expand the variable's value and cache the result.
-                    expansion_stack.push(name.clone());
-                    let expanded = self.smart_expand_value_cached(
-                        user_value.clone(),
-                        expanded_variables,
-                        expansion_stack,
-                        concat_cache,
-                    );
-                    expansion_stack.pop();
-
-                    let expanded = expanded?;
-                    expanded_variables.insert(name, expanded.clone());
-                    Ok(expanded)
-                } else {
-                    // Check month abbreviations as fallback
-                    get_month_expansion(name_text).map_or_else(
-                        || {
-                            // Variable not found in either user strings or month constants
-                            Err(Error::UndefinedVariable(name_text.to_string()))
-                        },
-                        |month_value| Ok(Value::Literal(Cow::Borrowed(month_value))),
-                    )
+                // Concatenations need special handling
+                Value::Concat(parts) => {
+                    if let Some(expanded) = concat_cache.get_cloned(&parts) {
+                        expanded
+                    } else {
+                        let key = parts.clone();
+                        let mut pending = parts.into_vec().into_iter();
+                        if let Some(first) = pending.next() {
+                            frames.push(ExpansionFrame::Concat {
+                                key,
+                                expanded: Vec::with_capacity(pending.len() + 1),
+                                pending,
+                            });
+                            current = first;
+                            continue 'expand;
+                        }
+                        let expanded =
+                            finish_concatenation(Vec::new(), self.expansion.max_length.0)?;
+                        concat_cache.insert(key, expanded.clone());
+                        expanded
+                    }
                 }
-            }
+            };
 
-            // Concatenations need special handling
-            Value::Concat(parts) => {
-                if let Some(expanded) = concat_cache.get_cloned(&parts) {
-                    return Ok(expanded);
+            // Hand the finished value back to the frames waiting on it.
+            loop {
+                match frames.pop() {
+                    None => return Ok(result),
+                    Some(ExpansionFrame::Variable(name)) => {
+                        expansion_stack.pop();
+                        expanded_variables.insert(name, result.clone());
+                    }
+                    Some(ExpansionFrame::Concat {
+                        key,
+                        mut pending,
+                        mut expanded,
+                    }) => {
+                        expanded.push(result);
+                        if let Some(part) = pending.next() {
+                            frames.push(ExpansionFrame::Concat {
+                                key,
+                                pending,
+                                expanded,
+                            });
+                            current = part;
+                            continue 'expand;
+                        }
+                        result = finish_concatenation(expanded, self.expansion.max_length.0)?;
+                        concat_cache.insert(key, result.clone());
+                    }
                 }
-
-                let cache_key = parts.clone();
-                let expanded = self.expand_concatenation_cached(
-                    parts.into_vec(),
-                    expanded_variables,
-                    expansion_stack,
-                    concat_cache,
-                )?;
-                concat_cache.insert(cache_key, expanded.clone());
-                Ok(expanded)
             }
         }
     }
@@ -2050,76 +4436,12 @@ impl<'a> Library<'a> {
         match value {
             // Simple literals and numbers can be cloned cheaply
             Value::Literal(_) | Value::Number(_) => Ok(value.clone()),
-
-            // Variables need to be resolved
-            Value::Variable(name) => {
-                // First check user-defined strings
-                get_string_value(&self.strings, &self.string_lookup, name.as_ref()).map_or_else(
-                    || {
-                        // Check month abbreviations as fallback
-                        get_month_expansion(name.as_ref()).map_or_else(
-                            || {
-                                // Variable not found in either user strings or month constants
-                                Err(Error::UndefinedVariable(name.as_ref().to_string()))
-                            },
-                            |month_value| Ok(Value::Literal(Cow::Borrowed(month_value))),
-                        )
-                    },
-                    |user_value| self.expand_value_ref(user_value),
-                )
-            }
-
-            // Concatenations need cloning
-            Value::Concat(parts) => {
-                let cloned_parts = parts.to_vec();
-                self.expand_concatenation(cloned_parts)
-            }
-        }
-    }
-
-    /// Expand a concatenation, only converting to owned when necessary
-    fn expand_concatenation(&self, parts: Vec<Value<'a>>) -> Result<Value<'a>> {
-        let mut expanded_variables = ExpansionCache::with_capacity(0);
-        let mut expansion_stack = Vec::new();
-        let mut concat_cache = ConcatCache::new();
-        self.expand_concatenation_cached(
-            parts,
-            &mut expanded_variables,
-            &mut expansion_stack,
-            &mut concat_cache,
-        )
-    }
-
-    /// Cached concatenation expansion used by hot parsing paths.
-    fn expand_concatenation_cached(
-        &self,
-        parts: Vec<Value<'a>>,
-        expanded_variables: &mut ExpansionCache<'a>,
-        expansion_stack: &mut Vec<Cow<'a, str>>,
-        concat_cache: &mut ConcatCache<'a>,
-    ) -> Result<Value<'a>> {
-        let mut expanded_parts = Vec::with_capacity(parts.len());
-
-        // First, expand all parts
-        for part in parts {
-            let expanded = self.smart_expand_value_cached(
-                part,
-                expanded_variables,
-                expansion_stack,
-                concat_cache,
-            )?;
-            expanded_parts.push(expanded);
-        }
-
-        // If all parts are literals or numbers, we can flatten to a single string
-        if expanded_parts
-            .iter()
-            .all(|p| matches!(p, Value::Literal(_) | Value::Number(_)))
-        {
-            let combined = concatenate_simple_values(&expanded_parts);
-            Ok(Value::Literal(Cow::Owned(combined)))
-        } else {
-            Ok(Value::Concat(expanded_parts.into_boxed_slice()))
+            _ => self.smart_expand_value_cached(
+                value.clone(),
+                &mut ExpansionCache::with_capacity(0),
+                &mut Vec::new(),
+                &mut ConcatCache::new(),
+            ),
         }
     }
 
@@ -2128,28 +4450,9 @@ impl<'a> Library<'a> {
         match value {
             Value::Literal(s) => Ok(s.to_string()),
             Value::Number(n) => Ok(n.to_string()),
-            Value::Variable(name) => {
-                // First check user-defined strings
-                get_string_value(&self.strings, &self.string_lookup, name.as_ref()).map_or_else(
-                    || {
-                        // Check month abbreviations as fallback
-                        get_month_expansion(name.as_ref()).map_or_else(
-                            || {
-                                // Variable not found in either user strings or month constants
-                                Err(Error::UndefinedVariable(name.as_ref().to_string()))
-                            },
-                            |month_value| Ok(month_value.to_string()),
-                        )
-                    },
-                    |user_value| self.get_expanded_string(user_value),
-                )
-            }
-            Value::Concat(parts) => {
-                let mut result = String::new();
-                for part in parts.iter() {
-                    result.push_str(&self.get_expanded_string(part)?);
-                }
-                Ok(result)
+            _ => {
+                let expanded = self.expand_value_ref(value)?;
+                Ok(concatenate_simple_values(std::slice::from_ref(&expanded)))
             }
         }
     }
@@ -2184,7 +4487,70 @@ impl<'a> Library<'a> {
                 .map(FailedBlock::into_owned)
                 .collect(),
             block_order: self.block_order,
+            leading_comments: self.leading_comments,
+            pending_comments: self.pending_comments,
+            resolved_strings: self
+                .resolved_strings
+                .into_iter()
+                .map(|name| Cow::Owned(name.into_owned()))
+                .collect(),
+            field_origins: self.field_origins,
+            entry_ids: self.entry_ids,
+            entry_positions: self.entry_positions,
+            original_keys: self.original_keys,
+            next_entry_id: self.next_entry_id,
+            expansion: self.expansion,
+        }
+    }
+
+    /// Visit every field, string-definition, and preamble value mutably.
+    pub(crate) fn for_each_value_mut(&mut self, mut visit: impl FnMut(&mut Value<'a>)) {
+        for entry in &mut self.entries {
+            for field in &mut entry.fields {
+                visit(&mut field.value);
+            }
         }
+        for definition in &mut self.strings {
+            visit(&mut definition.value);
+        }
+        for preamble in &mut self.preambles {
+            visit(&mut preamble.value);
+        }
+    }
+
+    /// Return the limit on nested `@string` references followed during expansion.
+    #[must_use]
+    pub const fn max_expansion_depth(&self) -> usize {
+        self.expansion.max_depth.0
+    }
+
+    /// Set the limit on nested `@string` references followed during expansion.
+    pub fn set_max_expansion_depth(&mut self, depth: usize) {
+        self.expansion.max_depth = ExpansionDepth(depth);
+    }
+
+    /// Return the limit on the length in bytes of one expanded value.
+    #[must_use]
+    pub const fn max_expansion_length(&self) -> usize {
+        self.expansion.max_length.0
+    }
+
+    /// Set the limit on the length in bytes of one expanded value.
+    pub fn set_max_expansion_length(&mut self, length: usize) {
+        self.expansion.max_length = ExpansionLength(length);
+    }
+
+    /// Choose what later expansion does with variables no source defines.
+    pub fn set_undefined_variables(&mut self, policy: UnresolvedVariablePolicy) {
+        self.expansion.undefined = Some(policy);
+    }
+
+    /// Resolve variables this library does not define through `resolver`.
+    ///
+    /// Affects later calls such as [`Self::resolve_strings`] and
+    /// [`Self::expand_value_partial`]; values already expanded are unchanged.
+    pub fn add_macro_resolver(&mut self, resolver: impl MacroResolver + 'static) {
+        self.expansion.resolvers.push(resolver);
     }
 
     /// Add a string definition (useful for building libraries programmatically)
@@ -2197,6 +4563,82 @@ impl<'a> Library<'a> {
         self.push_entry_with_source(entry, None);
     }
 
+    /// Add an entry, or merge it into an existing entry for the same work.
+    ///
+    /// Entries match on citation key first and then on normalized DOI. Keys
+    /// and field names are compared ignoring case, as BibTeX does; the matched
+    /// entry keeps its key and type. A field repeated in `entry` is merged
+    /// once, using its first value.
+    pub fn add_or_update_entry(&mut self, entry: Entry<'a>, policy: MergePolicy) -> AddOutcome {
+        let Some(index) = self.matching_entry_index(&entry) else {
+            let index = self.entries.len();
+            self.add_entry(entry);
+            return AddOutcome::Inserted(index);
+        };
+
+        let existing = &self.entries[index];
+        let mut seen = AHashSet::with_capacity(entry.fields.len());
+        let mut missing = Vec::new();
+        let mut differing = Vec::new();
+        for field in entry.fields {
+            if !seen.insert(field.name.to_ascii_lowercase()) {
+                continue;
+            }
+            match existing.field_ignore_case(&field.name) {
+                None => missing.push(field),
+                Some(current) if !same_field_value(&field.name, &current.value, &field.value) => {
+                    differing.push(field);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if policy == MergePolicy::RejectConflicts && !differing.is_empty() {
+            return AddOutcome::Conflicted {
+                index,
+                fields: differing
+                    .into_iter()
+                    .map(|field| field.name.into_owned())
+                    .collect(),
+            };
+        }
+
+        let existing = &mut self.entries[index];
+        let mut changed = !missing.is_empty();
+        existing.fields.extend(missing);
+        if policy == MergePolicy::PreferIncoming {
+            for field in differing {
+                if let Some(current) = existing
+                    .fields
+                    .iter_mut()
+                    .find(|current| current.name.eq_ignore_ascii_case(&field.name))
+                {
+                    current.value = field.value;
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            AddOutcome::Updated(index)
+        } else {
+            AddOutcome::Unchanged(index)
+        }
+    }
+
+    fn matching_entry_index(&self, entry: &Entry<'_>) -> Option<usize> {
+        let key = entry.key.to_lowercase();
+        self.entries
+            .iter()
+            .position(|existing| existing.key.to_lowercase() == key)
+            .or_else(|| {
+                let doi = entry.doi()?;
+                self.entries
+                    .iter()
+                    .position(|existing| existing.doi().is_some_and(|value| value == doi))
+            })
+    }
+
     /// Add a preamble
     pub fn add_preamble(&mut self, value: Value<'a>) {
         self.push_preamble_with_source(value, None);
@@ -2209,7 +4651,7 @@ impl<'a> Library<'a> {
 
     /// Resolve string variables and concatenations in entries and preambles in place.
     pub fn resolve_strings(&mut self) -> Result<()> {
-        let has_user_strings = !self.strings.is_empty();
+        let has_user_strings = !self.strings.is_empty() || self.expansion.checks_variables();
         let month_constants_shadowed =
             has_user_strings && user_strings_shadow_month_constants(&self.strings);
         let mut expanded_variables = ExpansionCache::with_capacity(self.strings.len());
@@ -2249,22 +4691,345 @@ impl<'a> Library<'a> {
         Ok(())
     }
 
-    /// Normalize DOI fields to lowercase `10.x/...` form when recognizable.
-    pub fn normalize_doi_fields(&mut self) {
-        for entry in &mut self.entries {
-            for field in &mut entry.fields {
-                if field.name.eq_ignore_ascii_case("doi") {
-                    if let Some(normalized) = normalize_doi(&field.value.to_plain_string()) {
-                        field.value = Value::Literal(Cow::Owned(normalized));
-                    }
+    /// Resolve known string variables in entries and preambles, keeping unknown ones.
+    ///
+    /// Unlike [`Self::resolve_strings`], this never fails: undefined and
+    /// circular variables stay in place and only the resolvable parts of each
+    /// value are expanded. See [`Self::expand_value_partial`].
+    pub fn resolve_strings_partial(&mut self) {
+        for entry_index in 0..self.entries.len() {
+            for field_index in 0..self.entries[entry_index].fields.len() {
+                let value = &self.entries[entry_index].fields[field_index].value;
+                if contains_variables(value) {
+                    let value = self.expand_value_partial(value);
+                    self.entries[entry_index].fields[field_index].value = value;
                 }
             }
         }
+
+        for preamble_index in 0..self.preambles.len() {
+            let value = &self.preambles[preamble_index].value;
+            if contains_variables(value) {
+                let value = self.expand_value_partial(value);
+                self.preambles[preamble_index].value = value;
+            }
+        }
     }
 
-    /// Normalize month fields to a chosen representation.
-    pub fn normalize_months(&mut self, style: MonthStyle) {
-        for entry in &mut self.entries {
+    /// Expand the known parts of a value and keep unknown variables.
+    ///
+    /// Concatenations are flattened so adjacent resolved parts merge into a
+    /// single literal, e.g. `"Proc. " # known # unknown # " 2020"` becomes a
+    /// literal, the `unknown` variable, and a second literal. A value that
+    /// resolves completely becomes a single literal.
+    ///
+    /// Nested variables and concatenations are expanded from an explicit
+    /// worklist; a chain deeper than the expansion depth limit stays as the
+    /// variable where the limit was reached, and a variable whose text would
+    /// exceed the expansion length limit stays unexpanded.
+    #[must_use]
+    pub fn expand_value_partial(&self, value: &Value<'a>) -> Value<'a> {
+        PartialExpansion {
+            library: self,
+            expanding: AHashSet::new(),
+            finished: AHashMap::new(),
+            cuts: 0,
+        }
+        .expand(value)
+    }
+
+    /// Regenerate citation keys and update references to them.
+    ///
+    /// `new_key` returns the replacement key for an entry, or `None` to keep
+    /// it. Keys named in `crossref`, `xdata`, `related`, `ids`, and
+    /// `entryset` fields are rewritten across the library. The returned map
+    /// can fix `\cite` commands in LaTeX sources.
+    pub fn regenerate_keys<F>(&mut self, mut new_key: F) -> KeyRenameMap
+    where
+        F: FnMut(&Entry<'a>) -> Option<String>,
+    {
+        let mut renames = KeyRenameMap::new();
+        for entry in &mut self.entries {
+            if let Some(key) = new_key(entry) {
+                if key != entry.key {
+                    renames.insert(entry.key.as_ref(), key.as_str());
+                    entry.key = Cow::Owned(key);
+                }
+            }
+        }
+        self.rename_key_references(&renames);
+        renames
+    }
+
+    /// Rewrite `crossref`, `xdata`, `related`, `ids`, and `entryset` fields using `renames`.
+    pub fn rename_key_references(&mut self, renames: &KeyRenameMap) {
+        if renames.is_empty() {
+            return;
+        }
+        for field in self.entries.iter_mut().flat_map(|entry| &mut entry.fields) {
+            if !KEY_REFERENCE_FIELDS
+                .iter()
+                .any(|name| field.name.eq_ignore_ascii_case(name))
+            {
+                continue;
+            }
+            if let Some(renamed) = rename_key_list(&field.value.to_plain_string(), renames) {
+                field.value = Value::Literal(Cow::Owned(renamed));
+            }
+        }
+    }
+
+    /// Return where a field of the entry with `id` came from.
+    ///
+    /// Macro origins are recorded when parsing with
+    /// [`Parser::track_field_origins`]; inherited fields are recorded by
+    /// [`Library::inherit_crossref_fields`]. Fields written out in the entry
+    /// have no origin. Origins follow the entry through sorting and key
+    /// changes, and entries that share a key keep separate origins.
+    #[must_use]
+    pub fn field_origin(&self, id: EntryId, field: &str) -> Option<&FieldOrigin> {
+        self.field_origins.get(&(id, field.to_ascii_lowercase()))
+    }
+
+    /// Attach origins recorded by [`Parser`] to the parsed entries.
+    fn set_raw_field_origins(&mut self, origins: RawFieldOrigins) {
+        self.field_origins.clear();
+        for (index, field, origin) in origins {
+            if let Some(&id) = self.entry_ids.get(index) {
+                self.field_origins.entry((id, field)).or_insert(origin);
+            }
+        }
+    }
+
+    /// Copy fields that an entry lacks from the entry named by its `crossref`.
+    ///
+    /// Only direct parents are consulted, as in BibTeX. The parent's `crossref`
+    /// and `ids` fields are not copied. Returns the number of copied fields.
+    pub fn inherit_crossref_fields(&mut self) -> usize {
+        let mut inherited = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let Some(parent_key) = entry.get_as_string_ignore_case("crossref") else {
+                continue;
+            };
+            let parent_key = parent_key.trim();
+            let Some(parent) = self
+                .entries
+                .iter()
+                .find(|parent| parent.key == parent_key && parent.key != entry.key)
+            else {
+                continue;
+            };
+            for field in &parent.fields {
+                if ["crossref", "ids"]
+                    .iter()
+                    .any(|name| field.name.eq_ignore_ascii_case(name))
+                    || entry.field_ignore_case(&field.name).is_some()
+                {
+                    continue;
+                }
+                inherited.push((index, field.clone(), parent.key.to_string()));
+            }
+        }
+
+        let count = inherited.len();
+        for (index, field, parent_key) in inherited {
+            self.field_origins.insert(
+                (self.entry_ids[index], field.name.to_ascii_lowercase()),
+                FieldOrigin::InheritedFrom(parent_key),
+            );
+            self.entries[index].fields.push(field);
+        }
+        count
+    }
+
+    /// Return the `@set` entries with their member keys, in library order.
+    #[must_use]
+    pub fn sets(&self) -> Vec<EntrySet<'_, 'a>> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.ty == EntryType::Set)
+            .map(|(index, entry)| EntrySet {
+                index,
+                entry,
+                members: entry.set_members(),
+            })
+            .collect()
+    }
+
+    /// Return `(set index, set entry, member key)` for set members that are not in the library.
+    #[must_use]
+    pub fn missing_set_members(&self) -> Vec<(usize, &Entry<'a>, String)> {
+        self.sets()
+            .into_iter()
+            .flat_map(|set| {
+                set.members
+                    .into_iter()
+                    .filter(|member| self.find_by_key(member).is_none())
+                    .map(move |member| (set.index, set.entry, member))
+            })
+            .collect()
+    }
+
+    /// Iterate over entries that can be cited, skipping data-only `@xdata` entries.
+    pub fn citable_entries(&self) -> impl Iterator<Item = &Entry<'a>> {
+        self.entries.iter().filter(|entry| !entry.ty.is_data_only())
+    }
+
+    /// Iterate over data-only `@xdata` entries.
+    pub fn xdata_entries(&self) -> impl Iterator<Item = &Entry<'a>> {
+        self.entries.iter().filter(|entry| entry.ty.is_data_only())
+    }
+
+    /// Return each cycle of `xdata` references as a chain such as `a -> b -> a`.
+    #[must_use]
+    pub fn xdata_cycles(&self) -> Vec<String> {
+        let targets = self.xdata_targets();
+        // 0 = unvisited, 1 = on the current path, 2 = finished
+        let mut state = vec![0u8; self.entries.len()];
+        let mut cycles = Vec::new();
+        for root in 0..self.entries.len() {
+            if state[root] != 0 {
+                continue;
+            }
+            let mut path = vec![(root, 0usize)];
+            state[root] = 1;
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                if let Some(&target) = targets[node].get(*next) {
+                    *next += 1;
+                    match state[target] {
+                        0 => {
+                            state[target] = 1;
+                            path.push((target, 0));
+                        }
+                        1 => {
+                            let start = path
+                                .iter()
+                                .position(|(index, _)| *index == target)
+                                .unwrap_or_default();
+                            let mut chain = path[start..]
+                                .iter()
+                                .map(|(index, _)| self.entries[*index].key.as_ref())
+                                .collect::<Vec<_>>();
+                            chain.push(&self.entries[target].key);
+                            cycles.push(chain.join(" -> "));
+                        }
+                        _ => {}
+                    }
+                } else {
+                    state[node] = 2;
+                    path.pop();
+                }
+            }
+        }
+        cycles
+    }
+
+    /// Copy fields that entries lack from the `@xdata` entries they reference.
+    ///
+    /// `xdata` fields may list several keys; earlier keys take precedence, and
+    /// `@xdata` entries may themselves reference other `@xdata` entries. Only
+    /// `@xdata` entries are consulted, and the `xdata` and `ids` fields are not
+    /// copied. Returns the number of copied fields, or
+    /// [`Error::CircularReference`] when references form a cycle.
+    pub fn resolve_xdata(&mut self) -> Result<usize> {
+        if let Some(cycle) = self.xdata_cycles().into_iter().next() {
+            return Err(Error::CircularReference(cycle));
+        }
+
+        let targets = self.xdata_targets();
+        let mut dependents = vec![Vec::new(); self.entries.len()];
+        let mut pending = vec![0usize; self.entries.len()];
+        for (index, entry_targets) in targets.iter().enumerate() {
+            pending[index] = entry_targets.len();
+            for &target in entry_targets {
+                dependents[target].push(index);
+            }
+        }
+
+        // Resolve in dependency order so each source is complete before use.
+        let mut ready = (0..self.entries.len())
+            .filter(|&index| pending[index] == 0)
+            .collect::<Vec<_>>();
+        let mut copied = 0;
+        while let Some(index) = ready.pop() {
+            for &target in &targets[index] {
+                let inherited = self.entries[target]
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        !["xdata", "ids"]
+                            .iter()
+                            .any(|name| field.name.eq_ignore_ascii_case(name))
+                            && self.entries[index].field_ignore_case(&field.name).is_none()
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let source_key = self.entries[target].key.to_string();
+                let id = self.entry_ids[index];
+                for field in inherited {
+                    self.field_origins.insert(
+                        (id, field.name.to_ascii_lowercase()),
+                        FieldOrigin::InheritedFrom(source_key.clone()),
+                    );
+                    self.entries[index].fields.push(field);
+                    copied += 1;
+                }
+            }
+            for &dependent in &dependents[index] {
+                pending[dependent] -= 1;
+                if pending[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+        Ok(copied)
+    }
+
+    /// Return, for each entry, the indices of the `@xdata` entries it references.
+    fn xdata_targets(&self) -> Vec<Vec<usize>> {
+        let xdata = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.ty.is_data_only())
+            .map(|(index, entry)| (entry.key.as_ref(), index))
+            .collect::<AHashMap<_, _>>();
+        self.entries
+            .iter()
+            .map(|entry| {
+                let mut targets = Vec::new();
+                if let Some(keys) = entry.get_as_string_ignore_case("xdata") {
+                    for key in keys.split(',').map(str::trim) {
+                        if let Some(&target) = xdata.get(key) {
+                            if !targets.contains(&target) {
+                                targets.push(target);
+                            }
+                        }
+                    }
+                }
+                targets
+            })
+            .collect()
+    }
+
+    /// Normalize DOI fields to lowercase `10.x/...` form when recognizable.
+    pub fn normalize_doi_fields(&mut self) {
+        for entry in &mut self.entries {
+            for field in &mut entry.fields {
+                if field.name.eq_ignore_ascii_case("doi") {
+                    if let Some(normalized) = normalize_doi(&field.value.to_plain_string()) {
+                        field.value = Value::Literal(Cow::Owned(normalized));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Normalize month fields to a chosen representation.
+    pub fn normalize_months(&mut self, style: MonthStyle) {
+        for entry in &mut self.entries {
             for field in &mut entry.fields {
                 if field.name.eq_ignore_ascii_case("month") {
                     if let Some(month) =
@@ -2300,151 +5065,687 @@ impl<'a> Library<'a> {
         }
     }
 
-    /// Sort entries and/or fields in place.
-    pub fn sort(&mut self, options: SortOptions) {
-        if options.fields_by_name {
-            for entry in &mut self.entries {
-                entry
-                    .fields
-                    .sort_by(|left, right| left.name.cmp(&right.name));
-            }
-        }
+    /// Sort entries and/or fields in place.
+    pub fn sort(&mut self, options: SortOptions) {
+        if options.fields_by_name {
+            for entry in &mut self.entries {
+                entry
+                    .fields
+                    .sort_by(|left, right| left.name.cmp(&right.name));
+            }
+        }
+
+        if options.entries_by_key || options.entries_by_year {
+            let header = self.header_comment_indices();
+            let years = if options.entries_by_year {
+                self.entries
+                    .iter()
+                    .map(Entry::publication_year)
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            let mut order = (0..self.entries.len()).collect::<Vec<_>>();
+            order.sort_by(|&left, &right| self.compare_entries(options, &years, left, right));
+            self.entries = permute(std::mem::take(&mut self.entries), &order);
+            self.entry_ids = permute(std::mem::take(&mut self.entry_ids), &order);
+            self.reindex_entries(0);
+            if let Some(sources) = self.entry_sources.take() {
+                self.entry_sources = Some(permute(sources, &order));
+            }
+            self.rebuild_grouped_block_order(&header);
+        }
+    }
+
+    /// Compare entries `left` and `right` by the entry order in `options`.
+    ///
+    /// `years` holds every entry's publication year when sorting by year.
+    fn compare_entries(
+        &self,
+        options: SortOptions,
+        years: &[PublicationYear],
+        left: usize,
+        right: usize,
+    ) -> std::cmp::Ordering {
+        let by_year = if options.entries_by_year {
+            years[left].cmp(&years[right])
+        } else {
+            std::cmp::Ordering::Equal
+        };
+        by_year.then_with(|| {
+            if options.entries_by_key {
+                self.entries[left].key.cmp(&self.entries[right].key)
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+    }
+
+    /// Group blocks by kind, with the `header` comments first and each
+    /// entry's leading comments in front of it.
+    fn rebuild_grouped_block_order(&mut self, header: &[usize]) {
+        let mut placed = vec![false; self.comments.len()];
+        for &comment in header
+            .iter()
+            .chain(self.leading_comments.values().flatten())
+        {
+            if let Some(slot) = placed.get_mut(comment) {
+                *slot = true;
+            }
+        }
+        self.block_order.clear();
+        self.block_order
+            .extend(header.iter().copied().map(BlockKind::Comment));
+        self.block_order
+            .extend((0..self.strings.len()).map(BlockKind::String));
+        self.block_order
+            .extend((0..self.preambles.len()).map(BlockKind::Preamble));
+        self.block_order.extend(
+            (0..self.comments.len())
+                .filter(|&comment| !placed[comment])
+                .map(BlockKind::Comment),
+        );
+        for (index, id) in self.entry_ids.iter().enumerate() {
+            if let Some(leading) = self.leading_comments.get(id) {
+                self.block_order
+                    .extend(leading.iter().copied().map(BlockKind::Comment));
+            }
+            self.block_order.push(BlockKind::Entry(index));
+        }
+        self.block_order
+            .extend((0..self.failed_blocks.len()).map(BlockKind::Failed));
+        self.pending_comments.clear();
+    }
+
+    /// Validate all entries in the library
+    /// Returns a list of entries with their indices and validation errors
+    #[must_use]
+    pub fn validate(
+        &self,
+        level: ValidationLevel,
+    ) -> Vec<(usize, &Entry<'a>, Vec<ValidationError>)> {
+        let mut invalid_entries = Vec::new();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Err(errors) = entry.validate(level) {
+                invalid_entries.push((index, entry, errors));
+            }
+        }
+
+        invalid_entries
+    }
+
+    /// Check for duplicate citation keys
+    /// Returns a list of duplicate keys (each key appears once in the list even if it has multiple duplicates)
+    #[must_use]
+    pub fn find_duplicate_keys(&self) -> Vec<&str> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates = std::collections::HashSet::new();
+
+        for entry in &self.entries {
+            if !seen.insert(entry.key()) {
+                duplicates.insert(entry.key());
+            }
+        }
+
+        duplicates.into_iter().collect()
+    }
+
+    /// Check for duplicate citation keys, ignoring ASCII case.
+    #[must_use]
+    pub fn find_duplicate_keys_ignore_case(&self) -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates = std::collections::HashSet::new();
+
+        for entry in &self.entries {
+            let normalized_key = entry.key().to_ascii_lowercase();
+            if !seen.insert(normalized_key.clone()) {
+                duplicates.insert(normalized_key);
+            }
+        }
+
+        duplicates.into_iter().collect()
+    }
+
+    /// Find duplicate DOI groups using normalized DOI values.
+    #[must_use]
+    pub fn find_duplicate_dois(&self) -> Vec<(String, Vec<&Entry<'a>>)> {
+        let mut groups: AHashMap<String, Vec<&Entry<'a>>> = AHashMap::new();
+        for entry in &self.entries {
+            if let Some(doi) = entry.doi() {
+                groups.entry(doi).or_default().push(entry);
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .collect()
+    }
+
+    /// Validate all entries and return a comprehensive validation report
+    #[must_use]
+    pub fn validate_comprehensive(&self, level: ValidationLevel) -> ValidationReport<'_> {
+        let invalid_entries = self.validate(level);
+        let duplicate_keys = self.find_duplicate_keys();
+        let empty_entries = self.find_empty_entries();
+
+        ValidationReport {
+            invalid_entries,
+            duplicate_keys,
+            empty_entries,
+            total_entries: self.entries.len(),
+            validation_level: level,
+        }
+    }
+
+    /// Find entries with no fields (only key and type)
+    fn find_empty_entries(&self) -> Vec<(usize, &Entry<'a>)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.fields().is_empty())
+            .collect()
+    }
+
+    /// Build a library health report.
+    ///
+    /// The report collects duplicate keys and DOIs, missing required fields,
+    /// entries without a DOI or URL, suspicious years, crossrefs that point to
+    /// missing entries, and `@string` definitions that no value references.
+    #[must_use]
+    pub fn report(&self) -> LibraryReport<'_> {
+        let mut duplicate_keys = self.find_duplicate_keys();
+        duplicate_keys.sort_unstable();
+        let mut duplicate_dois = self.find_duplicate_dois();
+        duplicate_dois.sort_by(|left, right| left.0.cmp(&right.0));
+
+        let mut missing_required_fields = Vec::new();
+        let mut missing_links = Vec::new();
+        let mut suspicious_years = Vec::new();
+        let mut orphaned_crossrefs = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.ty.is_data_only() || entry.ty == EntryType::Set {
+                continue;
+            }
+            let missing = entry
+                .ty
+                .required_field_groups()
+                .iter()
+                .filter(|group| !entry.has_any_field(group))
+                .map(|group| group[0])
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                missing_required_fields.push((index, entry, missing));
+            }
+
+            if entry.doi().is_none() && !entry.has_field("url") {
+                missing_links.push((index, entry));
+            }
+
+            if let Some(year) = entry.get_any_as_string_ignore_case(&["year", "date"]) {
+                let plausible = match entry.publication_year() {
+                    PublicationYear::Year(year) => is_plausible_year(year),
+                    PublicationYear::Pending(_) => true,
+                    PublicationYear::Invalid(_) | PublicationYear::Missing => false,
+                };
+                if !plausible {
+                    suspicious_years.push((index, entry, year));
+                }
+            }
+
+            for field in ["crossref", "xref"] {
+                if let Some(target) = entry.get_as_string_ignore_case(field) {
+                    let target = target.trim();
+                    if !target.is_empty() && self.find_by_key(target).is_none() {
+                        orphaned_crossrefs.push((index, entry, target.to_string()));
+                    }
+                }
+            }
+        }
+
+        LibraryReport {
+            total_entries: self.entries.len(),
+            duplicate_keys,
+            duplicate_dois,
+            missing_required_fields,
+            missing_links,
+            suspicious_years,
+            orphaned_crossrefs,
+            xdata_cycles: self.xdata_cycles(),
+            missing_set_members: self.missing_set_members(),
+            unused_strings: self.unused_strings(),
+        }
+    }
+
+    /// Return string definitions that no entry, preamble, or used string refers to.
+    ///
+    /// References resolved while expanding values during parsing are tracked,
+    /// so this also works for libraries whose values were expanded eagerly.
+    #[must_use]
+    pub fn unused_strings(&self) -> Vec<&StringDefinition<'a>> {
+        let mut roots = self
+            .resolved_strings
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<&str>>();
+        for entry in &self.entries {
+            for field in &entry.fields {
+                collect_variable_names(&field.value, &mut roots);
+            }
+        }
+        for preamble in &self.preambles {
+            collect_variable_names(&preamble.value, &mut roots);
+        }
+
+        let referenced = reachable_variable_names(roots, |name| self.string_value(name));
+        self.strings
+            .iter()
+            .filter(|definition| !referenced.contains(definition.name.as_ref()))
+            .collect()
+    }
+
+    /// Return sorted variable names that are referenced but neither defined nor month constants.
+    ///
+    /// Strictly parsed libraries fail on undefined variables, so this is most
+    /// useful for libraries built by hand or from unexpanded document parses.
+    #[must_use]
+    pub fn undefined_variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for entry in &self.entries {
+            for field in &entry.fields {
+                collect_variable_names(&field.value, &mut names);
+            }
+        }
+        for preamble in &self.preambles {
+            collect_variable_names(&preamble.value, &mut names);
+        }
+        for definition in &self.strings {
+            collect_variable_names(&definition.value, &mut names);
+        }
+
+        names.retain(|name| self.string(name).is_none() && get_month_expansion(name).is_none());
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Get statistics about the library
+    #[must_use]
+    pub fn stats(&self) -> LibraryStats {
+        let mut type_counts = AHashMap::new();
+        for entry in &self.entries {
+            *type_counts.entry(entry.ty.to_string()).or_insert(0) += 1;
+        }
+
+        LibraryStats {
+            total_entries: self.entries.len(),
+            total_strings: self.strings.len(),
+            total_preambles: self.preambles.len(),
+            total_comments: self.comments.len(),
+            entries_by_type: type_counts,
+        }
+    }
+
+    /// Build the coauthor graph of citable entries.
+    ///
+    /// See [`CoauthorGraph::from_entries`].
+    #[must_use]
+    pub fn coauthor_graph(&self) -> CoauthorGraph {
+        CoauthorGraph::from_entries(self.citable_entries())
+    }
+
+    /// Truncate or drop over-long fields such as `abstract` in place.
+    ///
+    /// Returns the number of fields changed or removed.
+    pub fn truncate_long_fields(&mut self, limit: &FieldTruncation) -> usize {
+        let mut changed = 0;
+        for entry in &mut self.entries {
+            entry
+                .fields
+                .retain_mut(|field| match limit.limit(&field.name, &field.value) {
+                    FieldLimit::Keep => true,
+                    FieldLimit::Drop => {
+                        changed += 1;
+                        false
+                    }
+                    FieldLimit::Replace(text) => {
+                        field.value = Value::Literal(Cow::Owned(text));
+                        changed += 1;
+                        true
+                    }
+                });
+        }
+        changed
+    }
+
+    /// Repair mojibake in literal field values.
+    ///
+    /// See [`repair_mojibake`](crate::cleanup::repair_mojibake); entry indices
+    /// refer to [`Library::entries`] and spans are not tracked per field.
+    pub fn repair_mojibake(&mut self) -> Vec<ValueRepair> {
+        self.repair_values(|_| true, &repair_mojibake)
+    }
+
+    /// Decode HTML entities such as `&amp;` and `&#8211;` in literal field values.
+    ///
+    /// See [`decode_html_entities`](crate::cleanup::decode_html_entities).
+    pub fn decode_html_entities(&mut self) -> Vec<ValueRepair> {
+        self.repair_values(|_| true, &decode_html_entities)
+    }
+
+    /// Convert quotes, dashes, and non-breaking spaces between LaTeX and Unicode forms.
+    ///
+    /// See [`normalize_punctuation`](crate::cleanup::normalize_punctuation);
+    /// URL, DOI, file, and other identifier fields are left alone.
+    pub fn normalize_punctuation(&mut self, style: PunctuationStyle) -> Vec<ValueRepair> {
+        self.repair_values(is_prose_field, &|text| normalize_punctuation(text, style))
+    }
+
+    fn repair_values(
+        &mut self,
+        fields: impl Fn(&str) -> bool,
+        repair: &impl Fn(&str) -> Option<String>,
+    ) -> Vec<ValueRepair> {
+        let mut repairs = Vec::new();
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            for field in entry.fields.iter_mut().filter(|field| fields(&field.name)) {
+                repairs.extend(repair_literals(&mut field.value, repair).into_iter().map(
+                    |(original, repaired)| ValueRepair {
+                        entry: index,
+                        field: field.name.to_string(),
+                        original,
+                        repaired,
+                        span: None,
+                    },
+                ));
+            }
+        }
+        repairs
+    }
+
+    /// Group near-identical `journal`/`booktitle` spellings.
+    ///
+    /// See [`cluster_venues`](crate::venues::cluster_venues); entry indices
+    /// refer to [`Library::entries`].
+    #[must_use]
+    pub fn venue_clusters(&self) -> Vec<VenueCluster> {
+        cluster_venues(&self.entries)
+    }
+
+    /// Rewrite every clustered venue to its cluster's canonical spelling.
+    ///
+    /// Returns the number of fields changed.
+    pub fn apply_venue_clusters(&mut self, clusters: &[VenueCluster]) -> usize {
+        let mut changed = 0;
+        for cluster in clusters {
+            for &index in &cluster.entries {
+                let Some(entry) = self.entries.get_mut(index) else {
+                    continue;
+                };
+                let Some(position) = VENUE_FIELDS.iter().find_map(|name| {
+                    entry.fields.iter().position(|field| {
+                        field.name.eq_ignore_ascii_case(name) && field.value.as_str().is_some()
+                    })
+                }) else {
+                    continue;
+                };
+                let field = &mut entry.fields[position];
+                if field.value.as_str() != Some(cluster.canonical.as_str()) {
+                    field.value = Value::Literal(Cow::Owned(cluster.canonical.clone()));
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Count citable entries per publication year and entry type.
+    ///
+    /// Years come from [`Entry::publication_year`]; pending works and entries
+    /// without a usable year are counted separately.
+    #[must_use]
+    pub fn timeline(&self) -> Timeline {
+        let mut years = std::collections::BTreeMap::<i32, YearCounts>::new();
+        let mut timeline = Timeline::default();
+        for entry in self.citable_entries() {
+            if entry.ty == EntryType::Set {
+                continue;
+            }
+            match entry.publication_year() {
+                PublicationYear::Year(year) => {
+                    let counts = years.entry(year).or_insert_with(|| YearCounts {
+                        year,
+                        ..YearCounts::default()
+                    });
+                    counts.total += 1;
+                    *counts.by_type.entry(entry.ty.to_string()).or_insert(0) += 1;
+                }
+                PublicationYear::Pending(_) => timeline.pending += 1,
+                PublicationYear::Invalid(_) | PublicationYear::Missing => timeline.undated += 1,
+            }
+        }
+        timeline.years = years.into_values().collect();
+        timeline
+    }
+}
+
+/// Per-year entry counts from [`Library::timeline`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timeline {
+    /// Years with at least one entry, in ascending order
+    pub years: Vec<YearCounts>,
+    /// Entries marked as in press, forthcoming, or similar
+    pub pending: usize,
+    /// Entries without a usable year
+    pub undated: usize,
+}
 
-        if options.entries_by_key {
-            if let Some(sources) = self.entry_sources.take() {
-                let mut entries = self.entries.drain(..).zip(sources).collect::<Vec<_>>();
-                entries.sort_by(|(left, _), (right, _)| left.key.cmp(&right.key));
-                let (sorted_entries, sorted_sources): (Vec<_>, Vec<_>) =
-                    entries.into_iter().unzip();
-                self.entries = sorted_entries;
-                self.entry_sources = Some(sorted_sources);
-            } else {
-                self.entries.sort_by(|left, right| left.key.cmp(&right.key));
-            }
-            self.rebuild_grouped_block_order();
-        }
+impl Timeline {
+    /// Return the counts for one year.
+    #[must_use]
+    pub fn year(&self, year: i32) -> Option<&YearCounts> {
+        self.years
+            .binary_search_by_key(&year, |counts| counts.year)
+            .ok()
+            .map(|index| &self.years[index])
     }
 
-    fn rebuild_grouped_block_order(&mut self) {
-        self.block_order.clear();
-        self.block_order
-            .extend((0..self.strings.len()).map(BlockKind::String));
-        self.block_order
-            .extend((0..self.preambles.len()).map(BlockKind::Preamble));
-        self.block_order
-            .extend((0..self.comments.len()).map(BlockKind::Comment));
-        self.block_order
-            .extend((0..self.entries.len()).map(BlockKind::Entry));
-        self.block_order
-            .extend((0..self.failed_blocks.len()).map(BlockKind::Failed));
+    /// Return the first and last years with entries.
+    #[must_use]
+    pub fn span(&self) -> Option<(i32, i32)> {
+        Some((self.years.first()?.year, self.years.last()?.year))
     }
+}
 
-    /// Validate all entries in the library
-    /// Returns a list of entries with their indices and validation errors
+/// Entry counts for one year of a [`Timeline`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YearCounts {
+    /// Publication year
+    pub year: i32,
+    /// Number of entries published that year
+    pub total: usize,
+    /// Entry counts by entry type name
+    pub by_type: std::collections::BTreeMap<String, usize>,
+}
+
+/// A BibLaTeX `@set` entry and the keys listed in its `entryset` field.
+#[derive(Debug, Clone)]
+pub struct EntrySet<'l, 'a> {
+    /// Index of the set entry in the library
+    pub index: usize,
+    /// The `@set` entry itself
+    pub entry: &'l Entry<'a>,
+    /// Member keys in `entryset` order
+    pub members: Vec<String>,
+}
+
+impl<'l, 'a> EntrySet<'l, 'a> {
+    /// Resolve the members that exist in `library`, in `entryset` order.
     #[must_use]
-    pub fn validate(
-        &self,
-        level: ValidationLevel,
-    ) -> Vec<(usize, &Entry<'a>, Vec<ValidationError>)> {
-        let mut invalid_entries = Vec::new();
+    pub fn member_entries(&self, library: &'l Library<'a>) -> Vec<&'l Entry<'a>> {
+        self.members
+            .iter()
+            .filter_map(|member| library.find_by_key(member))
+            .collect()
+    }
+}
 
-        for (index, entry) in self.entries.iter().enumerate() {
-            if let Err(errors) = entry.validate(level) {
-                invalid_entries.push((index, entry, errors));
-            }
-        }
+/// Borrowed subset of a library's entries produced by [`Library::view`],
+/// [`Library::page`], or [`Library::sample`].
+///
+/// String definitions, preambles, and comments are shared with the whole
+/// library, so writing a view keeps the macros its entries may reference.
+#[derive(Debug, Clone)]
+pub struct LibraryView<'l, 'a> {
+    library: &'l Library<'a>,
+    indices: Vec<usize>,
+}
 
-        invalid_entries
+impl<'l, 'a> LibraryView<'l, 'a> {
+    /// Return the library this view borrows from.
+    #[must_use]
+    pub const fn library(&self) -> &'l Library<'a> {
+        self.library
     }
 
-    /// Check for duplicate citation keys
-    /// Returns a list of duplicate keys (each key appears once in the list even if it has multiple duplicates)
+    /// Return the library indices of the entries in this view.
     #[must_use]
-    pub fn find_duplicate_keys(&self) -> Vec<&str> {
-        let mut seen = std::collections::HashSet::new();
-        let mut duplicates = std::collections::HashSet::new();
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
 
-        for entry in &self.entries {
-            if !seen.insert(entry.key()) {
-                duplicates.insert(entry.key());
-            }
-        }
+    /// Return the number of entries in this view.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
 
-        duplicates.into_iter().collect()
+    /// Return `true` when no entries matched.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
     }
 
-    /// Check for duplicate citation keys, ignoring ASCII case.
+    /// Iterate over entries in library order.
+    pub fn iter(&self) -> impl Iterator<Item = &'l Entry<'a>> + '_ {
+        let library = self.library;
+        self.indices
+            .iter()
+            .map(move |&index| &library.entries[index])
+    }
+
+    /// Iterate over the entries in this view in `sort` order.
+    ///
+    /// Only the entry order options of `sort` apply, and ties keep library
+    /// order. Only this view's entries are sorted.
     #[must_use]
-    pub fn find_duplicate_keys_ignore_case(&self) -> Vec<String> {
-        let mut seen = std::collections::HashSet::new();
-        let mut duplicates = std::collections::HashSet::new();
+    pub fn iter_sorted(
+        &self,
+        sort: SortOptions,
+    ) -> impl DoubleEndedIterator<Item = &'l Entry<'a>> + ExactSizeIterator + '_ {
+        let library = self.library;
+        library
+            .sorted_indices(self.indices.clone(), sort)
+            .into_iter()
+            .map(move |index| &library.entries[index])
+    }
 
-        for entry in &self.entries {
-            let normalized_key = entry.key().to_ascii_lowercase();
-            if !seen.insert(normalized_key.clone()) {
-                duplicates.insert(normalized_key);
-            }
+    /// Narrow this view to the entries that also match `filter`.
+    #[must_use]
+    pub fn view(&self, mut filter: impl FnMut(&Entry<'a>) -> bool) -> Self {
+        Self {
+            library: self.library,
+            indices: self
+                .indices
+                .iter()
+                .copied()
+                .filter(|&index| filter(&self.library.entries[index]))
+                .collect(),
         }
+    }
 
-        duplicates.into_iter().collect()
+    /// Find an entry in this view by key.
+    #[must_use]
+    pub fn find_by_key(&self, key: &str) -> Option<&'l Entry<'a>> {
+        self.iter().find(|entry| entry.key == key)
     }
 
-    /// Find duplicate DOI groups using normalized DOI values.
+    /// Find entries in this view by type.
     #[must_use]
-    pub fn find_duplicate_dois(&self) -> Vec<(String, Vec<&Entry<'a>>)> {
-        let mut groups: AHashMap<String, Vec<&Entry<'a>>> = AHashMap::new();
-        for entry in &self.entries {
-            if let Some(doi) = entry.doi() {
-                groups.entry(doi).or_default().push(entry);
-            }
-        }
+    pub fn find_by_type(&self, ty: &str) -> Vec<&'l Entry<'a>> {
+        self.iter()
+            .filter(|entry| entry.ty.canonical_name().eq_ignore_ascii_case(ty))
+            .collect()
+    }
 
-        groups
-            .into_iter()
-            .filter(|(_, entries)| entries.len() > 1)
+    /// Find entries in this view whose `field` contains `value`.
+    #[must_use]
+    pub fn find_by_field(&self, field: &str, value: &str) -> Vec<&'l Entry<'a>> {
+        self.iter()
+            .filter(|entry| {
+                entry
+                    .get_as_string(field)
+                    .as_ref()
+                    .is_some_and(|text| text.contains(value))
+            })
             .collect()
     }
 
-    /// Validate all entries and return a comprehensive validation report
+    /// Return `true` when the library entry at `index` is in this view.
     #[must_use]
-    pub fn validate_comprehensive(&self, level: ValidationLevel) -> ValidationReport<'_> {
-        let invalid_entries = self.validate(level);
-        let duplicate_keys = self.find_duplicate_keys();
-        let empty_entries = self.find_empty_entries();
+    pub(crate) fn contains_index(&self, index: usize) -> bool {
+        self.indices.binary_search(&index).is_ok()
+    }
 
-        ValidationReport {
-            invalid_entries,
-            duplicate_keys,
-            empty_entries,
-            total_entries: self.entries.len(),
-            validation_level: level,
-        }
+    /// Serialize this view to a BibTeX string.
+    pub fn to_bibtex(&self) -> Result<String> {
+        let mut output = String::new();
+        crate::writer::Writer::from_fmt(&mut output).write_view(self)?;
+        Ok(output)
+    }
+}
+
+impl<'a> IntoIterator for Library<'a> {
+    type Item = Entry<'a>;
+    type IntoIter = std::vec::IntoIter<Entry<'a>>;
+
+    /// Consume the library and iterate over its entries.
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
     }
+}
 
-    /// Find entries with no fields (only key and type)
-    fn find_empty_entries(&self) -> Vec<(usize, &Entry<'a>)> {
-        self.entries
-            .iter()
-            .enumerate()
-            .filter(|(_, entry)| entry.fields().is_empty())
-            .collect()
+impl<'l, 'a> IntoIterator for &'l Library<'a> {
+    type Item = &'l Entry<'a>;
+    type IntoIter = std::slice::Iter<'l, Entry<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
     }
+}
 
-    /// Get statistics about the library
-    #[must_use]
-    pub fn stats(&self) -> LibraryStats {
-        let mut type_counts = AHashMap::new();
-        for entry in &self.entries {
-            *type_counts.entry(entry.ty.to_string()).or_insert(0) += 1;
+impl<'a> Extend<Entry<'a>> for Library<'a> {
+    fn extend<I: IntoIterator<Item = Entry<'a>>>(&mut self, iter: I) {
+        for entry in iter {
+            self.add_entry(entry);
         }
+    }
+}
 
-        LibraryStats {
-            total_entries: self.entries.len(),
-            total_strings: self.strings.len(),
-            total_preambles: self.preambles.len(),
-            total_comments: self.comments.len(),
-            entries_by_type: type_counts,
+impl<'a> FromIterator<crate::parser::ParsedItem<'a>> for Library<'a> {
+    /// Build a library from parsed items in order, without expanding strings.
+    fn from_iter<I: IntoIterator<Item = crate::parser::ParsedItem<'a>>>(iter: I) -> Self {
+        let mut library = Self::new();
+        for item in iter {
+            match item {
+                crate::parser::ParsedItem::Entry(entry) => library.add_entry(entry),
+                crate::parser::ParsedItem::String(name, value) => library.add_string(name, value),
+                crate::parser::ParsedItem::Preamble(value) => library.add_preamble(value),
+                crate::parser::ParsedItem::Comment(text) => library.add_comment(text),
+            }
         }
+        library
     }
 }
 
@@ -2521,6 +5822,150 @@ impl ValidationReport<'_> {
     }
 }
 
+/// Library health report produced by [`Library::report`]
+#[derive(Debug, Clone)]
+pub struct LibraryReport<'a> {
+    /// Total number of entries in the library
+    pub total_entries: usize,
+    /// Citation keys that occur more than once, sorted
+    pub duplicate_keys: Vec<&'a str>,
+    /// Normalized DOIs shared by more than one entry, sorted by DOI
+    pub duplicate_dois: Vec<(String, Vec<&'a Entry<'a>>)>,
+    /// Entries missing required fields, with the primary name of each missing field
+    pub missing_required_fields: Vec<(usize, &'a Entry<'a>, Vec<&'static str>)>,
+    /// Entries with neither a recognizable DOI nor a URL
+    pub missing_links: Vec<(usize, &'a Entry<'a>)>,
+    /// Entries whose year or date is unparseable or outside 1000..=2100
+    pub suspicious_years: Vec<(usize, &'a Entry<'a>, String)>,
+    /// Entries whose `crossref` or `xref` names a key that is not in the library
+    pub orphaned_crossrefs: Vec<(usize, &'a Entry<'a>, String)>,
+    /// Cycles of `xdata` references, as key chains such as `a -> b -> a`
+    pub xdata_cycles: Vec<String>,
+    /// `@set` entries whose `entryset` names a key that is not in the library
+    pub missing_set_members: Vec<(usize, &'a Entry<'a>, String)>,
+    /// String definitions that are never referenced
+    pub unused_strings: Vec<&'a StringDefinition<'a>>,
+}
+
+impl LibraryReport<'_> {
+    /// Check if the report found no issues
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.total_issues() == 0
+    }
+
+    /// Get total number of issues found
+    #[must_use]
+    pub fn total_issues(&self) -> usize {
+        self.duplicate_keys.len()
+            + self.duplicate_dois.len()
+            + self.missing_required_fields.len()
+            + self.missing_links.len()
+            + self.suspicious_years.len()
+            + self.orphaned_crossrefs.len()
+            + self.xdata_cycles.len()
+            + self.missing_set_members.len()
+            + self.unused_strings.len()
+    }
+
+    /// Render the report as Markdown.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        use std::fmt::Write as _;
+
+        let mut output = String::new();
+        let _ = writeln!(output, "# Library report\n");
+        let _ = writeln!(
+            output,
+            "{} entries, {} issues.",
+            self.total_entries,
+            self.total_issues()
+        );
+
+        let mut section = |title: &str, lines: Vec<String>| {
+            if lines.is_empty() {
+                return;
+            }
+            let _ = writeln!(output, "\n## {title} ({})\n", lines.len());
+            for line in lines {
+                let _ = writeln!(output, "- {line}");
+            }
+        };
+
+        section(
+            "Duplicate keys",
+            self.duplicate_keys
+                .iter()
+                .map(|key| format!("`{key}`"))
+                .collect(),
+        );
+        section(
+            "Duplicate DOIs",
+            self.duplicate_dois
+                .iter()
+                .map(|(doi, entries)| {
+                    let keys = entries
+                        .iter()
+                        .map(|entry| format!("`{}`", entry.key()))
+                        .collect::<Vec<_>>();
+                    format!("`{doi}`: {}", keys.join(", "))
+                })
+                .collect(),
+        );
+        section(
+            "Missing required fields",
+            self.missing_required_fields
+                .iter()
+                .map(|(_, entry, fields)| format!("`{}`: {}", entry.key(), fields.join(", ")))
+                .collect(),
+        );
+        section(
+            "Entries without DOI or URL",
+            self.missing_links
+                .iter()
+                .map(|(_, entry)| format!("`{}`", entry.key()))
+                .collect(),
+        );
+        section(
+            "Suspicious years",
+            self.suspicious_years
+                .iter()
+                .map(|(_, entry, year)| format!("`{}`: {year}", entry.key()))
+                .collect(),
+        );
+        section(
+            "Orphaned crossrefs",
+            self.orphaned_crossrefs
+                .iter()
+                .map(|(_, entry, target)| format!("`{}` -> `{target}`", entry.key()))
+                .collect(),
+        );
+        section(
+            "Xdata cycles",
+            self.xdata_cycles
+                .iter()
+                .map(|cycle| format!("`{cycle}`"))
+                .collect(),
+        );
+        section(
+            "Missing set members",
+            self.missing_set_members
+                .iter()
+                .map(|(_, entry, member)| format!("`{}` -> `{member}`", entry.key()))
+                .collect(),
+        );
+        section(
+            "Unused strings",
+            self.unused_strings
+                .iter()
+                .map(|definition| format!("`{}`", definition.name))
+                .collect(),
+        );
+
+        output
+    }
+}
+
 /// Summary of validation issues by severity
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IssueSummary {
@@ -2532,6 +5977,123 @@ pub struct IssueSummary {
     pub infos: usize,
 }
 
+/// Collapse whitespace runs in literal text to single spaces.
+fn canonical_value(value: Value<'_>) -> Value<'static> {
+    match value {
+        Value::Literal(text) => Value::Literal(Cow::Owned(
+            text.split_whitespace().collect::<Vec<_>>().join(" "),
+        )),
+        Value::Concat(parts) => Value::Concat(
+            parts
+                .into_vec()
+                .into_iter()
+                .map(canonical_value)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        ),
+        value => value.into_owned(),
+    }
+}
+
+fn bucket_file_name(bucket: &str) -> String {
+    let name = bucket
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if name.is_empty() || name.chars().all(|ch| ch == '.') {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Check whether a comment contains an empty line.
+fn has_blank_line(text: &str) -> bool {
+    text.lines().any(|line| line.trim().is_empty())
+}
+
+fn permute<T>(items: Vec<T>, order: &[usize]) -> Vec<T> {
+    let mut slots = items.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .iter()
+        .filter_map(|&index| slots.get_mut(index).and_then(Option::take))
+        .collect()
+}
+
+fn same_field_value(name: &str, left: &Value<'_>, right: &Value<'_>) -> bool {
+    if left == right {
+        return true;
+    }
+    let (left, right) = (left.to_plain_string(), right.to_plain_string());
+    left == right
+        || (name.eq_ignore_ascii_case("doi")
+            && normalize_doi(&left).is_some_and(|doi| Some(doi) == normalize_doi(&right)))
+}
+
+/// Append a concatenation part, merging it into a preceding literal or number
+fn push_merged_part<'a>(parts: &mut Vec<Value<'a>>, part: Value<'a>) {
+    if let (Some(last), Value::Literal(_) | Value::Number(_)) = (parts.last_mut(), &part) {
+        if matches!(last, Value::Literal(_) | Value::Number(_)) {
+            let combined = concatenate_simple_values(&[std::mem::take(last), part]);
+            *last = Value::Literal(Cow::Owned(combined));
+            return;
+        }
+    }
+    parts.push(part);
+}
+
+/// Flatten expanded concatenation parts into one literal when every part is simple.
+///
+/// Fails when the parts hold more than `max_length` bytes of text.
+fn finish_concatenation(parts: Vec<Value<'_>>, max_length: usize) -> Result<Value<'_>> {
+    if expanded_length(&parts) > max_length {
+        return Err(Error::ExpansionLengthExceeded { limit: max_length });
+    }
+    Ok(
+        if parts
+            .iter()
+            .all(|part| matches!(part, Value::Literal(_) | Value::Number(_)))
+        {
+            Value::Literal(Cow::Owned(concatenate_simple_values(&parts)))
+        } else {
+            Value::Concat(parts.into_boxed_slice())
+        },
+    )
+}
+
+/// Return the bytes of text in the literal and number `parts`.
+fn expanded_length(parts: &[Value<'_>]) -> usize {
+    parts
+        .iter()
+        .map(|part| match part {
+            Value::Literal(text) => text.len(),
+            Value::Number(number) => number.to_string().len(),
+            Value::Concat(inner) => expanded_length(inner),
+            Value::Variable(_) => 0,
+        })
+        .sum()
+}
+
+/// Describe the variable chain that led to `name`, e.g. `a -> b -> a`.
+fn expansion_chain(stack: &[Cow<'_, str>], name: &str) -> String {
+    let mut chain = stack
+        .iter()
+        .map(std::convert::AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    if !chain.is_empty() {
+        chain.push_str(" -> ");
+    }
+    chain.push_str(name);
+    chain
+}
+
 /// Concatenate simple values (literals and numbers) into a single string
 fn concatenate_simple_values(values: &[Value]) -> String {
     let mut result = String::new();
@@ -2876,4 +6438,96 @@ mod tests {
             let _ = std::fs::remove_file(path2);
         }
     }
+
+    #[test]
+    fn test_write_split_by_buckets() {
+        let input = r#"@string{v = "Venue"}
+            @article{a, journal = v, year = 2023}
+            @book{b, year = 2024}
+            @article{c, year = 2024}"#;
+        let library = Library::parse(input).unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("split");
+
+        let paths = library
+            .write_split_by(&dir, |entry| entry.year().unwrap_or_default())
+            .unwrap();
+        assert_eq!(paths, vec![dir.join("2023.bib"), dir.join("2024.bib")]);
+        let year_2024 = Library::parse_file(&paths[1]).unwrap();
+        let keys = year_2024
+            .entries()
+            .iter()
+            .map(Entry::key)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["b", "c"]);
+        assert_eq!(year_2024.strings().len(), 1);
+
+        let paths = library.write_split_by_type(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("article.bib"), dir.join("book.bib")]);
+        assert_eq!(Library::parse_file(&paths[0]).unwrap().entries().len(), 2);
+        assert_eq!(bucket_file_name("../a b"), ".._a_b");
+        assert_eq!(bucket_file_name(""), "_");
+
+        let paths = library
+            .write_split_by(&dir, |entry| {
+                if entry.key() == "a" { "ACL" } else { "acl" }.to_string()
+            })
+            .unwrap();
+        assert_eq!(paths, vec![dir.join("ACL.bib"), dir.join("acl-2.bib")]);
+        assert_eq!(Library::parse_file(&paths[1]).unwrap().entries().len(), 2);
+    }
+
+    #[test]
+    fn test_owned_documents_warn_about_lossy_numbers() {
+        let input = "@article{a, volume = 07, title = {07}, edition = 2020a, year = 2020.}";
+        let parser = Parser::new();
+        for document in [
+            parser.parse_compact_document_owned(None, input).unwrap(),
+            parser.parse_source_document_owned(None, input).unwrap(),
+        ] {
+            let targets = document
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.target.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                targets,
+                [
+                    crate::DiagnosticTarget::Value { entry: 0, field: 0 },
+                    crate::DiagnosticTarget::Value { entry: 0, field: 3 },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_owned_documents_honor_parser_settings() {
+        let input =
+            "% note\n@string{j = {Journal}}\n@article{a+b, journal = j, pages = -5}\n@misc{A+B,}";
+        let parser = Parser::new()
+            .comments(CommentPolicy::Discard)
+            .duplicate_keys(DuplicateKeyPolicy::KeepFirst)
+            .key_charset(KeyCharset::BibTeX)
+            .signed_numbers_as_literals()
+            .with_string_library("@string{k = {K}}")
+            .unwrap();
+        let expected = parser.parse_document(input).unwrap();
+        for document in [
+            parser.parse_compact_document_owned(None, input).unwrap(),
+            parser.parse_source_document_owned(None, input).unwrap(),
+        ] {
+            assert!(document.comments().is_empty());
+            assert_eq!(document.entries().len(), 1);
+            assert_eq!(document.entries()[0].key, "a+b");
+            assert_eq!(document.entries()[0].fields, expected.entries()[0].fields);
+        }
+
+        let budget = ParseBudget::new().entries(1);
+        let parser = Parser::new().tolerant().budget(budget);
+        let document = parser
+            .parse_compact_document_owned(None, "@misc{a,}\n@misc{b,}")
+            .unwrap();
+        assert_eq!(document.entries().len(), 1);
+        assert!(document.continuation().is_some());
+    }
 }
@@ -0,0 +1,648 @@
+//! Lint diagnostics for parsed documents.
+//!
+//! [`lint`] runs entry validation over a [`ParsedDocument`] and reports each
+//! finding as a located [`Diagnostic`] with a stable code such as
+//! `invalid-doi`. [`LintOptions`] turns codes off or changes their severity
+//! for the whole run, and a comment directive turns them off for one entry:
+//!
+//! ```bibtex
+//! % bibtex-lint: ignore invalid-doi, empty-field
+//! @article{legacy,
+//!   doi = {n/a},  % bibtex-lint: ignore
+//! }
+//! ```
+//!
+//! The directive applies to the next entry when it sits in the comment
+//! block right before the entry, and to its own entry when it sits in a
+//! trailing field comment. Without codes it ignores every finding.
+//!
+//! A [`Baseline`] records the findings a project already has, so CI can fail
+//! only on findings added since. [`Diagnostics`] collects parse and lint
+//! diagnostics with their quick fixes and renders them as JSON, and
+//! [`SarifReport`] renders findings for code scanning services.
+
+use crate::json::Json;
+use crate::{
+    CodeAction, Diagnostic, DiagnosticCode, DiagnosticSeverity, DiagnosticTarget, Error,
+    ParsedBlock, ParsedDocument, ParsedEntry, Result, SourceSpan, TextEdit, ValidationLevel,
+    ValidationSeverity,
+};
+use std::fmt;
+use std::path::Path;
+
+/// Comment directive that suppresses findings for an entry.
+pub const SUPPRESSION_DIRECTIVE: &str = "bibtex-lint: ignore";
+
+/// Validation level and per-code severity settings for [`lint`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintOptions {
+    level: ValidationLevel,
+    rules: Vec<(String, Option<DiagnosticSeverity>)>,
+}
+
+impl LintOptions {
+    /// Lint at `level` with every code at its default severity.
+    #[must_use]
+    pub const fn new(level: ValidationLevel) -> Self {
+        Self {
+            level,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Drop findings with `code`.
+    #[must_use]
+    pub fn suppress(self, code: &str) -> Self {
+        self.set_rule(code, None)
+    }
+
+    /// Report findings with `code` at `severity`.
+    #[must_use]
+    pub fn severity(self, code: &str, severity: DiagnosticSeverity) -> Self {
+        self.set_rule(code, Some(severity))
+    }
+
+    fn set_rule(mut self, code: &str, severity: Option<DiagnosticSeverity>) -> Self {
+        self.rules.retain(|(rule, _)| rule != code);
+        self.rules.push((code.to_string(), severity));
+        self
+    }
+
+    /// Return the validation level.
+    #[must_use]
+    pub const fn level(&self) -> ValidationLevel {
+        self.level
+    }
+
+    /// Return the severity for a finding with `code` and default `severity`,
+    /// or `None` when the code is suppressed.
+    #[must_use]
+    pub fn resolve(
+        &self,
+        code: &DiagnosticCode,
+        severity: DiagnosticSeverity,
+    ) -> Option<DiagnosticSeverity> {
+        self.rules
+            .iter()
+            .find(|(rule, _)| rule == code.as_str())
+            .map_or(Some(severity), |(_, setting)| *setting)
+    }
+}
+
+/// Validate every entry of `document` and report findings as diagnostics.
+///
+/// Diagnostics target the field a finding names when the entry has it, and
+/// the entry otherwise; sources point at the field name or citation key.
+/// Findings turned off by `options` or by a suppression comment are dropped.
+#[must_use]
+pub fn lint(document: &ParsedDocument<'_>, options: &LintOptions) -> Vec<Diagnostic> {
+    let mut comment_before = vec![None; document.entries().len()];
+    for pair in document.blocks().windows(2) {
+        if let [ParsedBlock::Comment(comment), ParsedBlock::Entry(entry)] = *pair {
+            comment_before[entry] = Some(document.comments()[comment].text.as_ref());
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for (index, parsed) in document.entries().iter().enumerate() {
+        let Err(findings) = parsed.clone().into_entry().validate(options.level) else {
+            continue;
+        };
+        let suppressed = suppressed_codes(parsed, comment_before[index]);
+        for finding in findings {
+            if suppressed
+                .as_ref()
+                .is_some_and(|codes| codes.is_empty() || codes.contains(&finding.code.as_str()))
+            {
+                continue;
+            }
+            let Some(severity) = options.resolve(&finding.code, finding.severity.into()) else {
+                continue;
+            };
+            let field = finding.field.as_deref().and_then(|name| {
+                parsed
+                    .fields
+                    .iter()
+                    .position(|field| field.name.eq_ignore_ascii_case(name))
+            });
+            let (target, source) = field.map_or_else(
+                || {
+                    (
+                        DiagnosticTarget::Entry(index),
+                        parsed.key_source.or(parsed.source),
+                    )
+                },
+                |field| {
+                    (
+                        DiagnosticTarget::Field {
+                            entry: index,
+                            field,
+                        },
+                        parsed.fields[field].name_source,
+                    )
+                },
+            );
+            diagnostics.push(Diagnostic {
+                severity,
+                code: finding.code,
+                message: format!("{}: {}", parsed.key, finding.message),
+                target,
+                source,
+                snippet: None,
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Collect codes named by suppression directives for an entry.
+///
+/// Returns `Some` with an empty list when any directive names no codes,
+/// whatever order the directives appear in.
+fn suppressed_codes<'t>(
+    entry: &'t ParsedEntry<'_>,
+    comment_before: Option<&'t str>,
+) -> Option<Vec<&'t str>> {
+    let comments = comment_before.into_iter().flat_map(str::lines).chain(
+        entry
+            .fields
+            .iter()
+            .filter_map(|field| field.comment.as_deref()),
+    );
+    let mut codes: Option<Vec<&str>> = None;
+    for comment in comments {
+        let Some(start) = comment.find(SUPPRESSION_DIRECTIVE) else {
+            continue;
+        };
+        let named: Vec<&str> = comment[start + SUPPRESSION_DIRECTIVE.len()..]
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|code| !code.is_empty())
+            .collect();
+        if named.is_empty() {
+            codes = Some(Vec::new());
+            continue;
+        }
+        match &mut codes {
+            Some(codes) if codes.is_empty() => {}
+            Some(codes) => codes.extend(named),
+            None => codes = Some(named),
+        }
+    }
+    codes
+}
+
+impl From<ValidationSeverity> for DiagnosticSeverity {
+    fn from(severity: ValidationSeverity) -> Self {
+        match severity {
+            ValidationSeverity::Error => Self::Error,
+            ValidationSeverity::Warning => Self::Warning,
+            ValidationSeverity::Info => Self::Info,
+        }
+    }
+}
+
+impl ParsedDocument<'_> {
+    /// Lint this document's entries.
+    ///
+    /// See [`lint`].
+    #[must_use]
+    pub fn lint(&self, options: &LintOptions) -> Vec<Diagnostic> {
+        lint(self, options)
+    }
+}
+
+/// Known findings to leave out of lint results.
+///
+/// Findings are recorded by citation key, code, and field name, so a
+/// baseline survives edits that move entries around. A key with two
+/// recorded `empty-field` findings tolerates two such findings. The text
+/// form has one tab-separated `key`, `code`, `field` line per finding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    findings: Vec<Fingerprint>,
+}
+
+/// `(citation key, code, lowercase field name)` of a finding.
+type Fingerprint = (String, String, String);
+
+impl Baseline {
+    /// Record `diagnostics` reported for `document`.
+    #[must_use]
+    pub fn from_diagnostics(document: &ParsedDocument<'_>, diagnostics: &[Diagnostic]) -> Self {
+        let mut findings: Vec<Fingerprint> = diagnostics
+            .iter()
+            .map(|diagnostic| fingerprint(document, diagnostic))
+            .collect();
+        findings.sort();
+        Self { findings }
+    }
+
+    /// Parse the text form written by [`Display`](fmt::Display).
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut findings = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split('\t');
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(key), Some(code), Some(field), None) => {
+                    findings.push((key.to_string(), code.to_string(), field.to_string()));
+                }
+                _ => {
+                    return Err(Error::Config(format!(
+                        "baseline line {}: expected key, code, and field separated by tabs",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        findings.sort();
+        Ok(Self { findings })
+    }
+
+    /// Load a baseline file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Return the number of recorded findings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    /// Return `true` when nothing is recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Return the diagnostics the baseline does not account for.
+    #[must_use]
+    pub fn new_diagnostics<'d>(
+        &self,
+        document: &ParsedDocument<'_>,
+        diagnostics: &'d [Diagnostic],
+    ) -> Vec<&'d Diagnostic> {
+        let mut remaining = self.findings.clone();
+        diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                let finding = fingerprint(document, diagnostic);
+                remaining.binary_search(&finding).map_or(true, |index| {
+                    remaining.remove(index);
+                    false
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# bibtex-parser lint baseline: key, code, field")?;
+        for (key, code, field) in &self.findings {
+            writeln!(f, "{key}\t{code}\t{field}")?;
+        }
+        Ok(())
+    }
+}
+
+fn fingerprint(document: &ParsedDocument<'_>, diagnostic: &Diagnostic) -> Fingerprint {
+    let (entry, field) = match diagnostic.target {
+        DiagnosticTarget::Entry(entry) => (document.entries().get(entry), None),
+        DiagnosticTarget::Field { entry, field } | DiagnosticTarget::Value { entry, field } => {
+            let entry = document.entries().get(entry);
+            (entry, entry.and_then(|entry| entry.fields.get(field)))
+        }
+        _ => (None, None),
+    };
+    (
+        entry.map(|entry| entry.key.to_string()).unwrap_or_default(),
+        diagnostic.code.to_string(),
+        field
+            .map(|field| field.name.to_ascii_lowercase())
+            .unwrap_or_default(),
+    )
+}
+
+/// Parse and lint diagnostics for one document, with quick fixes.
+///
+/// [`Diagnostics::to_json`] renders them for tools outside Rust, such as
+/// pre-commit hooks and editor plugins.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+    fixes: Vec<Vec<CodeAction>>,
+}
+
+impl Diagnostics {
+    /// Collect the parse diagnostics of `document` followed by its lint
+    /// diagnostics, pairing each lint finding with its [`code_actions`].
+    ///
+    /// [`code_actions`]: crate::code_actions
+    #[must_use]
+    pub fn collect(document: &ParsedDocument<'_>, options: &LintOptions) -> Self {
+        let mut items = document.diagnostics().to_vec();
+        items.extend(lint(document, options));
+        let mut actions = document.code_actions(options.level());
+        let fixes = items
+            .iter()
+            .map(|diagnostic| {
+                let (entry, field) = match diagnostic.target {
+                    DiagnosticTarget::Entry(entry) => (entry, None),
+                    DiagnosticTarget::Field { entry, field } => (entry, Some(field)),
+                    _ => return Vec::new(),
+                };
+                let field = field.and_then(|field| document.entries()[entry].fields.get(field));
+                let (matching, rest) =
+                    std::mem::take(&mut actions)
+                        .into_iter()
+                        .partition(|action: &CodeAction| {
+                            action.entry == entry
+                                && action.diagnostic.code == diagnostic.code
+                                && action
+                                    .diagnostic
+                                    .field
+                                    .as_deref()
+                                    .map(str::to_ascii_lowercase)
+                                    == field.map(|field| field.name.to_ascii_lowercase())
+                        });
+                actions = rest;
+                matching
+            })
+            .collect();
+        Self { items, fixes }
+    }
+
+    /// Return the diagnostics.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Diagnostic] {
+        &self.items
+    }
+
+    /// Iterate over the diagnostics.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Return the quick fixes for the diagnostic at `index`.
+    #[must_use]
+    pub fn fixes(&self, index: usize) -> &[CodeAction] {
+        self.fixes.get(index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the number of diagnostics.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Return `true` when there are no diagnostics.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Return `true` when any diagnostic is an error.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.items
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+    }
+
+    /// Render as pretty-printed JSON.
+    ///
+    /// The object has a `version` (currently `1`) and a `diagnostics` array.
+    /// Each diagnostic has `code`, `severity` (`error`, `warning`, or
+    /// `info`), `message`, `target`, `span` (byte offsets and one-based
+    /// lines and columns, or `null`), and `fixes`, each with a `title`,
+    /// `preferred` flag, and `edits` of `span` and `new_text`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let diagnostics = self
+            .items
+            .iter()
+            .zip(&self.fixes)
+            .map(|(diagnostic, fixes)| {
+                let mut item = Json::object();
+                item.insert("code", diagnostic.code.as_str());
+                item.insert("severity", severity_name(diagnostic.severity));
+                item.insert("message", diagnostic.message.as_str());
+                item.insert("target", target_json(&diagnostic.target));
+                item.insert("span", diagnostic.source.map(span_json));
+                item.insert("fixes", fixes.iter().map(fix_json).collect::<Vec<_>>());
+                item
+            })
+            .collect::<Vec<_>>();
+        let mut json = Json::object();
+        json.insert("version", 1_i64);
+        json.insert("diagnostics", diagnostics);
+        json.pretty()
+    }
+}
+
+impl<'d> IntoIterator for &'d Diagnostics {
+    type Item = &'d Diagnostic;
+    type IntoIter = std::slice::Iter<'d, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+const fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "info",
+    }
+}
+
+fn target_json(target: &DiagnosticTarget) -> Json {
+    let mut json = Json::object();
+    match *target {
+        DiagnosticTarget::File => json.insert("kind", "file"),
+        DiagnosticTarget::Block(block) => {
+            json.insert("kind", "block");
+            json.insert("block", block);
+        }
+        DiagnosticTarget::Entry(entry) => {
+            json.insert("kind", "entry");
+            json.insert("entry", entry);
+        }
+        DiagnosticTarget::Field { entry, field } | DiagnosticTarget::Value { entry, field } => {
+            let kind = if matches!(target, DiagnosticTarget::Field { .. }) {
+                "field"
+            } else {
+                "value"
+            };
+            json.insert("kind", kind);
+            json.insert("entry", entry);
+            json.insert("field", field);
+        }
+        DiagnosticTarget::FailedBlock(block) => {
+            json.insert("kind", "failed_block");
+            json.insert("block", block);
+        }
+    }
+    json
+}
+
+fn span_json(span: SourceSpan) -> Json {
+    let mut json = Json::object();
+    json.insert("start", span.byte_start);
+    json.insert("end", span.byte_end);
+    json.insert("line", span.line);
+    json.insert("column", span.column);
+    json.insert("end_line", span.end_line);
+    json.insert("end_column", span.end_column);
+    json
+}
+
+fn fix_json(action: &CodeAction) -> Json {
+    let edits = action
+        .edits
+        .iter()
+        .map(|edit: &TextEdit| {
+            let mut json = Json::object();
+            json.insert("span", span_json(edit.span));
+            json.insert("new_text", edit.new_text.as_str());
+            json
+        })
+        .collect::<Vec<_>>();
+    let mut json = Json::object();
+    json.insert("title", action.title.as_str());
+    json.insert("preferred", action.is_preferred);
+    json.insert("edits", edits);
+    json
+}
+
+/// SARIF 2.1.0 log of lint diagnostics.
+///
+/// Each file added with [`SarifReport::file`] contributes results located
+/// at its URI, which should be relative to the repository root for GitHub
+/// code scanning. Every distinct code becomes a rule of the single run.
+///
+/// ```
+/// use bibtex_parser::{LintOptions, Parser, SarifReport, ValidationLevel};
+///
+/// let document = Parser::new().parse_document("@article{a, doi = {nope}}")?;
+/// let diagnostics = document.lint(&LintOptions::new(ValidationLevel::Strict));
+/// let sarif = SarifReport::new().file("refs.bib", &diagnostics).to_string();
+/// assert!(sarif.contains("\"ruleId\": \"invalid-doi\""));
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SarifReport {
+    results: Vec<Json>,
+    rules: Vec<String>,
+}
+
+impl SarifReport {
+    /// Create an empty report.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `diagnostics` reported for the file at `uri`.
+    #[must_use]
+    pub fn file(mut self, uri: &str, diagnostics: &[Diagnostic]) -> Self {
+        for diagnostic in diagnostics {
+            let code = diagnostic.code.as_str();
+            let rule = self
+                .rules
+                .iter()
+                .position(|rule| rule == code)
+                .unwrap_or_else(|| {
+                    self.rules.push(code.to_string());
+                    self.rules.len() - 1
+                });
+
+            let mut artifact = Json::object();
+            artifact.insert("uri", uri);
+            let mut physical = Json::object();
+            physical.insert("artifactLocation", artifact);
+            if let Some(span) = diagnostic.source {
+                let mut region = Json::object();
+                region.insert("startLine", span.line);
+                region.insert("startColumn", span.column);
+                region.insert("endLine", span.end_line);
+                region.insert("endColumn", span.end_column);
+                region.insert("byteOffset", span.byte_start);
+                region.insert("byteLength", span.len());
+                physical.insert("region", region);
+            }
+            let mut location = Json::object();
+            location.insert("physicalLocation", physical);
+
+            let mut message = Json::object();
+            message.insert("text", diagnostic.message.as_str());
+            let mut result = Json::object();
+            result.insert("ruleId", code);
+            result.insert("ruleIndex", rule);
+            result.insert("level", sarif_level(diagnostic.severity));
+            result.insert("message", message);
+            result.insert("locations", vec![location]);
+            self.results.push(result);
+        }
+        self
+    }
+
+    /// Return the number of results.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Return `true` when the report has no results.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+impl fmt::Display for SarifReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .rules
+            .iter()
+            .map(|code| {
+                let mut rule = Json::object();
+                rule.insert("id", code.as_str());
+                rule
+            })
+            .collect::<Vec<_>>();
+        let mut driver = Json::object();
+        driver.insert("name", env!("CARGO_PKG_NAME"));
+        driver.insert("version", env!("CARGO_PKG_VERSION"));
+        driver.insert("informationUri", env!("CARGO_PKG_REPOSITORY"));
+        driver.insert("rules", rules);
+        let mut tool = Json::object();
+        tool.insert("driver", driver);
+
+        let mut run = Json::object();
+        run.insert("tool", tool);
+        run.insert("columnKind", "unicodeCodePoints");
+        run.insert("results", self.results.clone());
+
+        let mut log = Json::object();
+        log.insert("$schema", "https://json.schemastore.org/sarif-2.1.0.json");
+        log.insert("version", "2.1.0");
+        log.insert("runs", vec![run]);
+        f.write_str(&log.pretty())
+    }
+}
+
+const fn sarif_level(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "note",
+    }
+}
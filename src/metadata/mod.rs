@@ -0,0 +1,271 @@
+//! Filling in entries from bibliographic metadata services.
+//!
+//! A [`MetadataProvider`] looks up a DOI or ISBN and returns an entry with
+//! the work's metadata. [`Library::resolve_missing_metadata`] runs providers
+//! in order over entries that have an identifier but no title, such as
+//! `@misc{key, doi = {...}}` stubs, and adds the fields they lack.
+//!
+//! [`EnrichmentPipeline`] does the same for large libraries, with parallel
+//! workers, retries, rate limiting, a response cache, and a dry-run plan.
+//!
+//! With the `metadata_providers` feature, [`Crossref`], [`DataCite`],
+//! [`OpenAlex`], [`OpenLibrary`], and [`SemanticScholar`] query the public
+//! web APIs of those services, and [`Library::from_openalex_json`] and
+//! [`Library::from_semantic_scholar_json`] import `OpenAlex` works and
+//! Semantic Scholar papers directly.
+
+#[cfg(feature = "metadata_providers")]
+mod openalex;
+mod pipeline;
+#[cfg(feature = "metadata_providers")]
+mod providers;
+#[cfg(feature = "metadata_providers")]
+mod semantic_scholar;
+
+pub use pipeline::{EnrichmentPipeline, EnrichmentPlan, MetadataCache, ProposedChange};
+#[cfg(feature = "metadata_providers")]
+pub use providers::{Crossref, DataCite, OpenAlex, OpenLibrary, SemanticScholar};
+
+#[cfg(feature = "metadata_providers")]
+use crate::json::Json;
+use crate::{normalize_doi, Entry, EntryType, Library, Result};
+
+/// A persistent identifier that a [`MetadataProvider`] can look up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    /// A DOI in lowercase `10.x/...` form.
+    Doi(String),
+    /// An ISBN-10 or ISBN-13 without separators.
+    Isbn(String),
+}
+
+impl Identifier {
+    /// Return the identifier text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Doi(value) | Self::Isbn(value) => value,
+        }
+    }
+
+    /// Return the valid identifiers in an entry's `doi` and `isbn` fields,
+    /// DOIs first.
+    #[must_use]
+    pub fn from_entry(entry: &Entry<'_>) -> Vec<Self> {
+        let mut identifiers = Vec::new();
+        identifiers.extend(entry.doi().map(Self::Doi));
+        if let Some(isbn) = entry.get_as_string_ignore_case("isbn") {
+            identifiers.extend(
+                isbn.split([',', ';'])
+                    .filter_map(normalize_isbn)
+                    .map(Self::Isbn),
+            );
+        }
+        identifiers
+    }
+
+    /// Parse a DOI, DOI URL, or ISBN.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        normalize_doi(input)
+            .map(Self::Doi)
+            .or_else(|| normalize_isbn(input).map(Self::Isbn))
+    }
+}
+
+/// Strip an `ISBN:` label and separators, returning the ISBN when it has
+/// 10 or 13 digits.
+fn normalize_isbn(input: &str) -> Option<String> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("ISBN")
+        .or_else(|| input.strip_prefix("isbn"))
+        .map_or(input, |rest| rest.trim_start_matches([':', ' ']));
+    crate::model::normalize_isbn(input)
+}
+
+/// A source of bibliographic metadata keyed by DOI or ISBN.
+pub trait MetadataProvider {
+    /// Name used in [`MetadataResolution`] reports.
+    fn name(&self) -> &str;
+
+    /// Return `true` when this provider can look up `identifier`.
+    fn supports(&self, identifier: &Identifier) -> bool;
+
+    /// Look up `identifier`, returning `Ok(None)` when the work is unknown.
+    ///
+    /// The returned entry's key is ignored; its type is used only to replace
+    /// a `@misc` placeholder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the service cannot be reached or its response
+    /// cannot be read.
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>>;
+}
+
+/// An entry filled in by [`Library::resolve_missing_metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedEntry {
+    /// Index of the entry in [`Library::entries`].
+    pub entry: usize,
+    /// The identifier that was looked up.
+    pub identifier: Identifier,
+    /// Name of the provider that answered.
+    pub provider: String,
+    /// Names of the fields that were added.
+    pub fields: Vec<String>,
+}
+
+/// A lookup that failed during [`Library::resolve_missing_metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataFailure {
+    /// Index of the entry in [`Library::entries`].
+    pub entry: usize,
+    /// The identifier that was looked up.
+    pub identifier: Identifier,
+    /// Name of the provider that failed.
+    pub provider: String,
+    /// The provider's error message.
+    pub message: String,
+}
+
+/// Outcome of [`Library::resolve_missing_metadata`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataResolution {
+    /// Entries that gained fields.
+    pub resolved: Vec<ResolvedEntry>,
+    /// Lookups that failed; a later provider may still have answered.
+    pub failures: Vec<MetadataFailure>,
+    /// Indices of entries that no provider could resolve.
+    pub unresolved: Vec<usize>,
+}
+
+impl Library<'_> {
+    /// Fill in entries that have a DOI or ISBN but no `title`.
+    ///
+    /// For each such entry, identifiers are tried in the order of
+    /// [`Identifier::from_entry`] and providers in the given order; the first
+    /// provider that finds the work supplies the fields the entry lacks.
+    /// Existing fields and the citation key are kept, and a `@misc` entry
+    /// takes the provider's entry type. Failed lookups are reported and the
+    /// next provider is tried.
+    ///
+    /// ```
+    /// use bibtex_parser::metadata::{Identifier, MetadataProvider};
+    /// use bibtex_parser::{Entry, EntryType, Library, Result};
+    ///
+    /// struct Fixed;
+    ///
+    /// impl MetadataProvider for Fixed {
+    ///     fn name(&self) -> &str {
+    ///         "fixed"
+    ///     }
+    ///
+    ///     fn supports(&self, identifier: &Identifier) -> bool {
+    ///         matches!(identifier, Identifier::Doi(_))
+    ///     }
+    ///
+    ///     fn lookup(&self, _: &Identifier) -> Result<Option<Entry<'static>>> {
+    ///         let mut entry = Entry::new(EntryType::Article, "found");
+    ///         entry.set_literal("title", "A Title");
+    ///         Ok(Some(entry))
+    ///     }
+    /// }
+    ///
+    /// let mut library = Library::parse("@misc{stub, doi = {10.1000/xyz}}")?;
+    /// let resolution = library.resolve_missing_metadata(&[&Fixed]);
+    ///
+    /// let entry = &library.entries()[0];
+    /// assert_eq!(entry.key(), "stub");
+    /// assert_eq!(entry.ty, EntryType::Article);
+    /// assert_eq!(entry.get("title"), Some("A Title"));
+    /// assert_eq!(resolution.resolved[0].fields, ["title"]);
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    pub fn resolve_missing_metadata(
+        &mut self,
+        providers: &[&dyn MetadataProvider],
+    ) -> MetadataResolution {
+        let mut resolution = MetadataResolution::default();
+        for (index, entry) in self.entries_mut().iter_mut().enumerate() {
+            let Some(identifiers) = missing_metadata_identifiers(entry) else {
+                continue;
+            };
+
+            let found = identifiers.iter().find_map(|identifier| {
+                providers
+                    .iter()
+                    .filter(|provider| provider.supports(identifier))
+                    .find_map(|provider| match provider.lookup(identifier) {
+                        Ok(found) => found.map(|found| (identifier, provider.name(), found)),
+                        Err(error) => {
+                            resolution.failures.push(MetadataFailure {
+                                entry: index,
+                                identifier: identifier.clone(),
+                                provider: provider.name().to_string(),
+                                message: error.to_string(),
+                            });
+                            None
+                        }
+                    })
+            });
+            let Some((identifier, provider, found)) = found else {
+                resolution.unresolved.push(index);
+                continue;
+            };
+
+            resolution.resolved.push(ResolvedEntry {
+                entry: index,
+                identifier: identifier.clone(),
+                provider: provider.to_string(),
+                fields: merge_metadata(entry, found),
+            });
+        }
+        resolution
+    }
+}
+
+/// Return the identifiers of an entry that has some but lacks a `title`.
+fn missing_metadata_identifiers(entry: &Entry<'_>) -> Option<Vec<Identifier>> {
+    if entry.field_ignore_case("title").is_some() {
+        return None;
+    }
+    let identifiers = Identifier::from_entry(entry);
+    (!identifiers.is_empty()).then_some(identifiers)
+}
+
+/// Add the fields of `found` that `entry` lacks, returning their names.
+fn merge_metadata<'a>(entry: &mut Entry<'a>, found: Entry<'a>) -> Vec<String> {
+    if entry.ty == EntryType::Misc {
+        entry.ty = found.ty;
+    }
+    let mut fields = Vec::new();
+    for field in found.fields {
+        if entry.field_ignore_case(&field.name).is_none() {
+            fields.push(field.name.to_string());
+            entry.fields.push(field);
+        }
+    }
+    fields
+}
+
+/// Return a non-empty string member of a JSON object.
+#[cfg(feature = "metadata_providers")]
+fn member_text<'j>(object: &'j Json, key: &str) -> Option<&'j str> {
+    object
+        .get(key)
+        .and_then(Json::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Render a JSON string or integer as field text.
+#[cfg(feature = "metadata_providers")]
+fn json_text(value: &Json) -> Option<String> {
+    match value {
+        Json::String(value) if !value.trim().is_empty() => Some(value.trim().to_string()),
+        Json::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,168 @@
+//! Import of `OpenAlex` work records.
+//!
+//! Works map to entries keyed by their `OpenAlex` ID, with the ID kept in an
+//! `openalex` field. The entry type follows the work `type` and, for
+//! articles, the type of the hosting source, so conference papers become
+//! `@inproceedings`.
+
+use super::{json_text, member_text};
+use crate::json::Json;
+use crate::{
+    normalize_doi, normalize_openalex, Entry, EntryType, Error, Field, Library, Result, Value,
+};
+use std::borrow::Cow;
+
+impl Library<'static> {
+    /// Build a library from `OpenAlex` works JSON.
+    ///
+    /// Accepts a single work object, an array of works, or a list response
+    /// with a `results` array, as returned by `api.openalex.org/works`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Conversion`] when the input is not valid JSON or a
+    /// work has no `OpenAlex` ID.
+    pub fn from_openalex_json(json: &str) -> Result<Self> {
+        let json = Json::parse(json)
+            .ok_or_else(|| Error::Conversion("openalex: malformed JSON".into()))?;
+        let works = match (&json, json.get("results")) {
+            (Json::Array(works), _) => works.as_slice(),
+            (_, Some(results)) => results.items(),
+            _ => std::slice::from_ref(&json),
+        };
+
+        let mut library = Self::new();
+        for work in works {
+            library.add_entry(openalex_entry(work)?);
+        }
+        Ok(library)
+    }
+}
+
+/// Map one `OpenAlex` work object to an entry.
+pub(super) fn openalex_entry(work: &Json) -> Result<Entry<'static>> {
+    let id = work
+        .get("id")
+        .and_then(Json::as_str)
+        .and_then(normalize_openalex)
+        .ok_or_else(|| Error::Conversion("openalex: work without an OpenAlex ID".into()))?;
+    let source = work
+        .get("primary_location")
+        .and_then(|location| location.get("source"));
+    let ty = entry_type(
+        work.get("type").and_then(Json::as_str),
+        source
+            .and_then(|source| source.get("type"))
+            .and_then(Json::as_str),
+    );
+
+    let mut fields = Vec::new();
+    let authors: Vec<&str> = work
+        .get("authorships")
+        .map(Json::items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|authorship| {
+            authorship
+                .get("author")
+                .and_then(|author| author.get("display_name"))
+                .and_then(Json::as_str)
+        })
+        .collect();
+    if !authors.is_empty() {
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(title) = member_text(work, "title").or_else(|| member_text(work, "display_name")) {
+        fields.push(("title", title.to_string()));
+    }
+    if let Some(venue) = source.and_then(|source| member_text(source, "display_name")) {
+        let name = match ty {
+            EntryType::Article => "journal",
+            EntryType::InProceedings | EntryType::InCollection => "booktitle",
+            _ => "howpublished",
+        };
+        fields.push((name, venue.to_string()));
+    }
+    if let Some(publisher) = source.and_then(|source| member_text(source, "host_organization_name"))
+    {
+        if matches!(ty, EntryType::Book | EntryType::InCollection) {
+            fields.push(("publisher", publisher.to_string()));
+        }
+    }
+    if let Some(year) = work.get("publication_year").and_then(json_text) {
+        fields.push(("year", year));
+    }
+    if let Some(biblio) = work.get("biblio") {
+        if let Some(volume) = biblio.get("volume").and_then(json_text) {
+            fields.push(("volume", volume));
+        }
+        if let Some(issue) = biblio.get("issue").and_then(json_text) {
+            fields.push(("number", issue));
+        }
+        let first = biblio.get("first_page").and_then(json_text);
+        let last = biblio.get("last_page").and_then(json_text);
+        match (first, last) {
+            (Some(first), Some(last)) if first != last => {
+                fields.push(("pages", format!("{first}--{last}")));
+            }
+            (Some(page), _) | (None, Some(page)) => fields.push(("pages", page)),
+            (None, None) => {}
+        }
+    }
+    if let Some(doi) = member_text(work, "doi").and_then(normalize_doi) {
+        fields.push(("doi", doi));
+    }
+    if let Some(abstract_text) = work
+        .get("abstract_inverted_index")
+        .and_then(abstract_from_inverted_index)
+    {
+        fields.push(("abstract", abstract_text));
+    }
+    fields.push(("openalex", id.clone()));
+
+    let mut entry = Entry::new(ty, "");
+    entry.key = Cow::Owned(id);
+    for (name, value) in fields {
+        entry.add_field(Field::new(name, Value::Literal(Cow::Owned(value))));
+    }
+    Ok(entry)
+}
+
+/// Choose an entry type from the work type and the hosting source type.
+fn entry_type(work_type: Option<&str>, source_type: Option<&str>) -> EntryType<'static> {
+    match work_type.unwrap_or_default() {
+        "article" | "review" | "letter" | "editorial" | "erratum" => match source_type {
+            Some("conference") => EntryType::InProceedings,
+            Some("repository") => EntryType::Misc,
+            _ => EntryType::Article,
+        },
+        "book" => EntryType::Book,
+        "book-chapter" => EntryType::InCollection,
+        "dissertation" => EntryType::PhdThesis,
+        "report" => EntryType::TechReport,
+        "dataset" => EntryType::Dataset,
+        _ => EntryType::Misc,
+    }
+}
+
+/// Rebuild abstract text from `OpenAlex`'s word-to-positions index.
+fn abstract_from_inverted_index(index: &Json) -> Option<String> {
+    let Json::Object(words) = index else {
+        return None;
+    };
+    let mut positioned: Vec<(i64, &str)> = words
+        .iter()
+        .flat_map(|(word, positions)| {
+            positions
+                .items()
+                .iter()
+                .filter_map(move |position| match position {
+                    Json::Number(position) => Some((*position, word.as_str())),
+                    _ => None,
+                })
+        })
+        .collect();
+    positioned.sort_unstable_by_key(|&(position, _)| position);
+    let words: Vec<&str> = positioned.into_iter().map(|(_, word)| word).collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
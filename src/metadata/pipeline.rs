@@ -0,0 +1,520 @@
+//! Batch enrichment with worker limits, retries, rate limiting, and caching.
+
+use super::{
+    merge_metadata, missing_metadata_identifiers, Identifier, MetadataFailure, MetadataProvider,
+    MetadataResolution, ResolvedEntry,
+};
+use crate::{Entry, EntryType, Error, Field, Library, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Provider responses kept across enrichment runs.
+///
+/// Both found entries and "not found" answers are cached, keyed by provider
+/// name and identifier. Failed lookups are not cached.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    responses: Mutex<HashMap<(String, Identifier), Option<Entry<'static>>>>,
+}
+
+impl MetadataCache {
+    /// Create an empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of cached responses.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        lock(&self.responses).len()
+    }
+
+    /// Return `true` when nothing is cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget every cached response.
+    pub fn clear(&self) {
+        lock(&self.responses).clear();
+    }
+
+    /// Return the cached response, where `Some(None)` is a cached "not found".
+    #[allow(clippy::option_option)]
+    fn get(&self, provider: &str, identifier: &Identifier) -> Option<Option<Entry<'static>>> {
+        lock(&self.responses)
+            .get(&(provider.to_string(), identifier.clone()))
+            .cloned()
+    }
+
+    fn insert(&self, provider: &str, identifier: &Identifier, response: Option<Entry<'static>>) {
+        lock(&self.responses).insert((provider.to_string(), identifier.clone()), response);
+    }
+}
+
+/// Spaces requests to one provider at least `interval` apart across all workers.
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn wait(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let delay = {
+            let mut next = lock(&self.next);
+            let now = Instant::now();
+            let slot = next.map_or(now, |next| next.max(now));
+            *next = Some(slot + self.interval);
+            drop(next);
+            slot - now
+        };
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Fields a provider would add to one entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposedChange {
+    /// Index of the entry in [`Library::entries`].
+    pub entry: usize,
+    /// Citation key of the entry when the plan was made.
+    pub key: String,
+    /// The identifier that was looked up.
+    pub identifier: Identifier,
+    /// Name of the provider that answered.
+    pub provider: String,
+    /// New entry type, when a `@misc` placeholder would change type.
+    pub entry_type: Option<EntryType<'static>>,
+    /// Fields the entry lacks, in the provider's order.
+    pub fields: Vec<Field<'static>>,
+}
+
+/// Changes proposed by [`EnrichmentPipeline::plan`], not yet applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichmentPlan {
+    /// Entries that would gain fields, in entry order.
+    pub changes: Vec<ProposedChange>,
+    /// Lookups that failed after every retry.
+    pub failures: Vec<MetadataFailure>,
+    /// Indices of entries that no provider could resolve.
+    pub unresolved: Vec<usize>,
+}
+
+impl EnrichmentPlan {
+    /// Return `true` when no entry would change.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply the proposed changes to `library`.
+    ///
+    /// A change is skipped when the entry at its index no longer has the
+    /// planned key, and a field is skipped when the entry has gained it since
+    /// the plan was made.
+    pub fn apply(&self, library: &mut Library<'_>) -> MetadataResolution {
+        let mut resolution = MetadataResolution {
+            resolved: Vec::new(),
+            failures: self.failures.clone(),
+            unresolved: self.unresolved.clone(),
+        };
+        for change in &self.changes {
+            let Some(entry) = library
+                .entries_mut()
+                .get_mut(change.entry)
+                .filter(|entry| entry.key == change.key)
+            else {
+                continue;
+            };
+            let found = Entry {
+                ty: change
+                    .entry_type
+                    .clone()
+                    .unwrap_or_else(|| entry.ty.clone()),
+                key: Cow::Borrowed(""),
+                fields: change.fields.clone(),
+            };
+            resolution.resolved.push(ResolvedEntry {
+                entry: change.entry,
+                identifier: change.identifier.clone(),
+                provider: change.provider.clone(),
+                fields: merge_metadata(entry, found),
+            });
+        }
+        resolution
+    }
+
+    /// Render the proposed changes as Markdown for review.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "# Proposed metadata changes\n");
+        let _ = writeln!(
+            output,
+            "{} entries to update, {} failed lookups, {} unresolved.",
+            self.changes.len(),
+            self.failures.len(),
+            self.unresolved.len()
+        );
+        for change in &self.changes {
+            let _ = writeln!(
+                output,
+                "\n## `{}` ({} via {})\n",
+                change.key,
+                change.identifier.as_str(),
+                change.provider
+            );
+            if let Some(ty) = &change.entry_type {
+                let _ = writeln!(output, "- type: `{ty}`");
+            }
+            for field in &change.fields {
+                let _ = writeln!(
+                    output,
+                    "- {}: {}",
+                    field.name,
+                    field.value.to_plain_string()
+                );
+            }
+        }
+        if !self.failures.is_empty() {
+            let _ = writeln!(output, "\n## Failed lookups ({})\n", self.failures.len());
+            for failure in &self.failures {
+                let _ = writeln!(
+                    output,
+                    "- entry {} ({} via {}): {}",
+                    failure.entry,
+                    failure.identifier.as_str(),
+                    failure.provider,
+                    failure.message
+                );
+            }
+        }
+        output
+    }
+}
+
+/// Runs [`MetadataProvider`]s over a whole library in parallel.
+///
+/// Entries are chosen as for [`Library::resolve_missing_metadata`]. Lookups
+/// run on up to [`concurrency`](Self::concurrency) worker threads, start at
+/// most once per [`rate_limit`](Self::rate_limit) interval, and are retried
+/// with doubling delays after errors. [`plan`](Self::plan) is a dry run that
+/// reports the proposed changes; [`EnrichmentPlan::apply`] makes them.
+///
+/// ```
+/// use bibtex_parser::metadata::{EnrichmentPipeline, Identifier, MetadataCache, MetadataProvider};
+/// use bibtex_parser::{Entry, EntryType, Library, Result};
+/// use std::time::Duration;
+///
+/// struct Fixed;
+///
+/// impl MetadataProvider for Fixed {
+///     fn name(&self) -> &str {
+///         "fixed"
+///     }
+///
+///     fn supports(&self, _: &Identifier) -> bool {
+///         true
+///     }
+///
+///     fn lookup(&self, _: &Identifier) -> Result<Option<Entry<'static>>> {
+///         let mut entry = Entry::new(EntryType::Article, "found");
+///         entry.set_literal("title", "A Title");
+///         Ok(Some(entry))
+///     }
+/// }
+///
+/// let mut library = Library::parse("@misc{stub, doi = {10.1000/xyz}}")?;
+/// let cache = MetadataCache::new();
+/// let pipeline = EnrichmentPipeline::new(&[&Fixed])
+///     .concurrency(2)
+///     .retries(1)
+///     .rate_limit(Duration::from_millis(10))
+///     .cache(&cache);
+///
+/// let plan = pipeline.plan(&library);
+/// assert!(plan.to_markdown().contains("- title: A Title"));
+/// assert_eq!(library.entries()[0].get("title"), None);
+///
+/// plan.apply(&mut library);
+/// assert_eq!(library.entries()[0].get("title"), Some("A Title"));
+/// assert_eq!(cache.len(), 1);
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+pub struct EnrichmentPipeline<'p> {
+    providers: Vec<&'p (dyn MetadataProvider + Sync)>,
+    concurrency: usize,
+    retries: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    interval: Duration,
+    provider_intervals: Vec<(String, Duration)>,
+    cache: Option<&'p MetadataCache>,
+}
+
+impl fmt::Debug for EnrichmentPipeline<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let providers: Vec<&str> = self
+            .providers
+            .iter()
+            .map(|provider| provider.name())
+            .collect();
+        f.debug_struct("EnrichmentPipeline")
+            .field("providers", &providers)
+            .field("concurrency", &self.concurrency)
+            .field("retries", &self.retries)
+            .field("backoff", &self.backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("interval", &self.interval)
+            .field("provider_intervals", &self.provider_intervals)
+            .field("cache", &self.cache.is_some())
+            .finish()
+    }
+}
+
+impl<'p> EnrichmentPipeline<'p> {
+    /// Create a pipeline that tries `providers` in order.
+    ///
+    /// Defaults to four workers, two retries starting at a 500 ms delay and
+    /// waiting at most a minute, no rate limit, and no cache.
+    #[must_use]
+    pub fn new(providers: &[&'p (dyn MetadataProvider + Sync)]) -> Self {
+        Self {
+            providers: providers.to_vec(),
+            concurrency: 4,
+            retries: 2,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(60),
+            interval: Duration::ZERO,
+            provider_intervals: Vec::new(),
+            cache: None,
+        }
+    }
+
+    /// Run at most `workers` lookups at a time; zero is treated as one.
+    #[must_use]
+    pub fn concurrency(mut self, workers: usize) -> Self {
+        self.concurrency = workers.max(1);
+        self
+    }
+
+    /// Retry a lookup that failed with [`Error::MetadataUnavailable`] up to
+    /// `retries` more times; other errors are reported at once.
+    #[must_use]
+    pub const fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Wait `delay` before the first retry, doubling it for each later one.
+    ///
+    /// A `Retry-After` wait sent by the service takes precedence.
+    #[must_use]
+    pub const fn backoff(mut self, delay: Duration) -> Self {
+        self.backoff = delay;
+        self
+    }
+
+    /// Wait at most `delay` before a retry.
+    ///
+    /// A lookup whose service asks, through `Retry-After`, for a longer wait
+    /// fails with [`Error::MetadataUnavailable`] instead of being retried.
+    #[must_use]
+    pub const fn max_backoff(mut self, delay: Duration) -> Self {
+        self.max_backoff = delay;
+        self
+    }
+
+    /// Start requests to each provider at least `interval` apart across all
+    /// workers.
+    ///
+    /// Every provider is limited separately, so a slow service does not hold
+    /// back lookups sent to another.
+    #[must_use]
+    pub const fn rate_limit(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Use `interval` instead of the [`rate_limit`](Self::rate_limit) for the
+    /// provider named `provider`.
+    #[must_use]
+    pub fn provider_rate_limit(mut self, provider: impl Into<String>, interval: Duration) -> Self {
+        let provider = provider.into();
+        self.provider_intervals
+            .retain(|(name, _)| *name != provider);
+        self.provider_intervals.push((provider, interval));
+        self
+    }
+
+    /// Reuse and record responses in `cache`.
+    #[must_use]
+    pub const fn cache(mut self, cache: &'p MetadataCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Look up every entry that needs metadata without changing `library`.
+    #[must_use]
+    pub fn plan(&self, library: &Library<'_>) -> EnrichmentPlan {
+        let jobs: Vec<(usize, Vec<Identifier>)> = library
+            .entries()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                missing_metadata_identifiers(entry).map(|identifiers| (index, identifiers))
+            })
+            .collect();
+        let limiters: Vec<RateLimiter> = self
+            .providers
+            .iter()
+            .map(|provider| RateLimiter {
+                interval: self
+                    .provider_intervals
+                    .iter()
+                    .find(|(name, _)| name == provider.name())
+                    .map_or(self.interval, |(_, interval)| *interval),
+                next: Mutex::new(None),
+            })
+            .collect();
+        let next_job = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(jobs.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..self.concurrency.min(jobs.len()) {
+                scope.spawn(|| {
+                    while let Some((index, identifiers)) =
+                        jobs.get(next_job.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let result = self.resolve(*index, identifiers, &limiters);
+                        lock(&results).push((*index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap_or_else(PoisonError::into_inner);
+        results.sort_by_key(|(index, _)| *index);
+        let mut plan = EnrichmentPlan::default();
+        for (index, (found, failures)) in results {
+            plan.failures.extend(failures);
+            let Some((identifier, provider, found)) = found else {
+                plan.unresolved.push(index);
+                continue;
+            };
+            let entry = &library.entries()[index];
+            plan.changes.push(ProposedChange {
+                entry: index,
+                key: entry.key.to_string(),
+                identifier,
+                provider,
+                entry_type: (entry.ty == EntryType::Misc && found.ty != EntryType::Misc)
+                    .then_some(found.ty),
+                fields: found
+                    .fields
+                    .into_iter()
+                    .filter(|field| entry.field_ignore_case(&field.name).is_none())
+                    .collect(),
+            });
+        }
+        plan
+    }
+
+    /// Look up and apply metadata in one step.
+    pub fn run(&self, library: &mut Library<'_>) -> MetadataResolution {
+        self.plan(library).apply(library)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn resolve(
+        &self,
+        index: usize,
+        identifiers: &[Identifier],
+        limiters: &[RateLimiter],
+    ) -> (
+        Option<(Identifier, String, Entry<'static>)>,
+        Vec<MetadataFailure>,
+    ) {
+        let mut failures = Vec::new();
+        for identifier in identifiers {
+            for (provider, limiter) in self.providers.iter().zip(limiters) {
+                if !provider.supports(identifier) {
+                    continue;
+                }
+                match self.lookup(*provider, identifier, limiter) {
+                    Ok(Some(found)) => {
+                        let name = provider.name().to_string();
+                        return (Some((identifier.clone(), name, found)), failures);
+                    }
+                    Ok(None) => {}
+                    Err(error) => failures.push(MetadataFailure {
+                        entry: index,
+                        identifier: identifier.clone(),
+                        provider: provider.name().to_string(),
+                        message: error.to_string(),
+                    }),
+                }
+            }
+        }
+        (None, failures)
+    }
+
+    fn lookup(
+        &self,
+        provider: &dyn MetadataProvider,
+        identifier: &Identifier,
+        limiter: &RateLimiter,
+    ) -> Result<Option<Entry<'static>>> {
+        if let Some(response) = self
+            .cache
+            .and_then(|cache| cache.get(provider.name(), identifier))
+        {
+            return Ok(response);
+        }
+
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            limiter.wait();
+            match provider.lookup(identifier) {
+                Ok(response) => {
+                    if let Some(cache) = self.cache {
+                        cache.insert(provider.name(), identifier, response.clone());
+                    }
+                    return Ok(response);
+                }
+                Err(Error::MetadataUnavailable {
+                    message,
+                    retry_after,
+                }) if attempt < self.retries => {
+                    if retry_after.is_some_and(|wait| wait > self.max_backoff) {
+                        return Err(Error::MetadataUnavailable {
+                            message,
+                            retry_after,
+                        });
+                    }
+                    thread::sleep(retry_after.unwrap_or(delay).min(self.max_backoff));
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
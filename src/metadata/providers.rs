@@ -0,0 +1,434 @@
+//! Web API clients for [`Crossref`], [`DataCite`], [`OpenAlex`],
+//! [`OpenLibrary`], and [`SemanticScholar`].
+
+use super::openalex::openalex_entry;
+use super::semantic_scholar::semantic_scholar_entry;
+use super::{Identifier, MetadataProvider};
+use crate::json::Json;
+use crate::{Entry, EntryType, Error, Field, Library, Result, Value};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+const USER_AGENT: &str = concat!("bibtex-parser/", env!("CARGO_PKG_VERSION"));
+
+/// A blocking HTTP client shared by the providers.
+#[derive(Debug, Clone)]
+struct Client {
+    agent: ureq::Agent,
+    user_agent: String,
+}
+
+impl Client {
+    fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            user_agent: USER_AGENT.to_string(),
+        }
+    }
+
+    /// Identify the caller by email, as Crossref's polite pool asks.
+    fn set_mailto(&mut self, email: &str) {
+        self.user_agent = format!("{USER_AGENT} (mailto:{email})");
+    }
+
+    /// Fetch `url`, returning `Ok(None)` for a 404 response.
+    ///
+    /// Timeouts, connection failures, 5xx responses, and 429 responses are
+    /// reported as [`Error::MetadataUnavailable`] so callers can retry them.
+    fn get(&self, provider: &str, url: &str, accept: &str) -> Result<Option<String>> {
+        let request = self
+            .agent
+            .get(url)
+            .set("User-Agent", &self.user_agent)
+            .set("Accept", accept);
+        match request.call() {
+            Ok(response) => response
+                .into_string()
+                .map(Some)
+                .map_err(|error| Error::MetadataLookup(format!("{provider}: {error}"))),
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(ureq::Error::Status(status, response)) if status == 429 || status >= 500 => {
+                Err(Error::MetadataUnavailable {
+                    message: format!("{provider}: {url}: status code {status}"),
+                    retry_after: response
+                        .header("Retry-After")
+                        .and_then(|seconds| seconds.trim().parse().ok())
+                        .map(Duration::from_secs),
+                })
+            }
+            Err(ureq::Error::Transport(transport))
+                if matches!(
+                    transport.kind(),
+                    ureq::ErrorKind::Io | ureq::ErrorKind::ConnectionFailed
+                ) =>
+            {
+                Err(Error::MetadataUnavailable {
+                    message: format!("{provider}: {transport}"),
+                    retry_after: None,
+                })
+            }
+            Err(error) => Err(Error::MetadataLookup(format!("{provider}: {error}"))),
+        }
+    }
+
+    /// Fetch a BibTeX record and return its first entry.
+    fn get_bibtex(&self, provider: &str, url: &str) -> Result<Option<Entry<'static>>> {
+        let Some(bibtex) = self.get(provider, url, "application/x-bibtex")? else {
+            return Ok(None);
+        };
+        let library = Library::parse(&bibtex)
+            .map_err(|error| Error::MetadataLookup(format!("{provider}: {error}")))?;
+        Ok(library.entries().first().cloned().map(Entry::into_owned))
+    }
+}
+
+/// Percent-encode a DOI for use as URL path segments, keeping `/`.
+fn encode_path(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Looks up DOIs registered with Crossref, which covers most journal
+/// articles and conference papers.
+#[derive(Debug, Clone)]
+pub struct Crossref {
+    client: Client,
+}
+
+impl Crossref {
+    /// Create a provider for the public Crossref API.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// Send `email` with each request so Crossref routes it to its polite pool.
+    #[must_use]
+    pub fn mailto(mut self, email: &str) -> Self {
+        self.client.set_mailto(email);
+        self
+    }
+}
+
+impl Default for Crossref {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataProvider for Crossref {
+    fn name(&self) -> &'static str {
+        "crossref"
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Doi(_))
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let url = format!(
+            "https://api.crossref.org/works/{}/transform/application/x-bibtex",
+            encode_path(identifier.as_str())
+        );
+        self.client.get_bibtex(self.name(), &url)
+    }
+}
+
+/// Looks up DOIs registered with `DataCite`, which covers datasets, software,
+/// and many preprints.
+#[derive(Debug, Clone)]
+pub struct DataCite {
+    client: Client,
+}
+
+impl DataCite {
+    /// Create a provider for the public `DataCite` API.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+}
+
+impl Default for DataCite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataProvider for DataCite {
+    fn name(&self) -> &'static str {
+        "datacite"
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Doi(_))
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let url = format!(
+            "https://api.datacite.org/dois/application/x-bibtex/{}",
+            encode_path(identifier.as_str())
+        );
+        self.client.get_bibtex(self.name(), &url)
+    }
+}
+
+/// Looks up DOIs in `OpenAlex`, which also indexes works without a
+/// Crossref record and keeps their `OpenAlex` ID.
+#[derive(Debug, Clone)]
+pub struct OpenAlex {
+    client: Client,
+}
+
+impl OpenAlex {
+    /// Create a provider for the public `OpenAlex` API.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// Send `email` with each request so `OpenAlex` routes it to its polite
+    /// pool.
+    #[must_use]
+    pub fn mailto(mut self, email: &str) -> Self {
+        self.client.set_mailto(email);
+        self
+    }
+}
+
+impl Default for OpenAlex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataProvider for OpenAlex {
+    fn name(&self) -> &'static str {
+        "openalex"
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Doi(_))
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let url = format!(
+            "https://api.openalex.org/works/doi:{}",
+            encode_path(identifier.as_str())
+        );
+        let Some(body) = self.client.get(self.name(), &url, "application/json")? else {
+            return Ok(None);
+        };
+        let json = Json::parse(&body).ok_or_else(|| {
+            Error::MetadataLookup(format!("{}: malformed JSON response", self.name()))
+        })?;
+        openalex_entry(&json)
+            .map(Some)
+            .map_err(|error| Error::MetadataLookup(format!("{}: {error}", self.name())))
+    }
+}
+
+/// Paper fields requested from the Semantic Scholar Graph API.
+const S2_FIELDS: &str =
+    "title,authors,year,venue,journal,externalIds,publicationTypes,abstract,corpusId";
+
+/// Looks up DOIs in the Semantic Scholar Academic Graph, which covers
+/// computer science and biomedical preprints well.
+#[derive(Debug, Clone)]
+pub struct SemanticScholar {
+    client: Client,
+}
+
+impl SemanticScholar {
+    /// Create a provider for the public Semantic Scholar Graph API.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+}
+
+impl Default for SemanticScholar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataProvider for SemanticScholar {
+    fn name(&self) -> &'static str {
+        "semanticscholar"
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Doi(_))
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let url = format!(
+            "https://api.semanticscholar.org/graph/v1/paper/DOI:{}?fields={S2_FIELDS}",
+            encode_path(identifier.as_str())
+        );
+        let Some(body) = self.client.get(self.name(), &url, "application/json")? else {
+            return Ok(None);
+        };
+        let json = Json::parse(&body).ok_or_else(|| {
+            Error::MetadataLookup(format!("{}: malformed JSON response", self.name()))
+        })?;
+        semantic_scholar_entry(&json)
+            .map(Some)
+            .map_err(|error| Error::MetadataLookup(format!("{}: {error}", self.name())))
+    }
+}
+
+/// Looks up books by ISBN in Open Library.
+#[derive(Debug, Clone)]
+pub struct OpenLibrary {
+    client: Client,
+}
+
+impl OpenLibrary {
+    /// Create a provider for the public Open Library API.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+}
+
+impl Default for OpenLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataProvider for OpenLibrary {
+    fn name(&self) -> &'static str {
+        "openlibrary"
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Isbn(_))
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let isbn = identifier.as_str();
+        let url =
+            format!("https://openlibrary.org/api/books?bibkeys=ISBN:{isbn}&format=json&jscmd=data");
+        let Some(body) = self.client.get(self.name(), &url, "application/json")? else {
+            return Ok(None);
+        };
+        let json = Json::parse(&body).ok_or_else(|| {
+            Error::MetadataLookup(format!("{}: malformed JSON response", self.name()))
+        })?;
+        Ok(json
+            .get(&format!("ISBN:{isbn}"))
+            .map(|book| open_library_entry(book, isbn)))
+    }
+}
+
+/// Map an Open Library `jscmd=data` book record to a `@book` entry.
+fn open_library_entry(book: &Json, isbn: &str) -> Entry<'static> {
+    let names = |key: &str| {
+        book.get(key)
+            .map(|items| {
+                items
+                    .items()
+                    .iter()
+                    .filter_map(|item| item.get("name").and_then(Json::as_str))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|names| !names.is_empty())
+    };
+
+    let mut fields = Vec::new();
+    if let Some(authors) = names("authors") {
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(title) = book.get("title").and_then(Json::as_str) {
+        let title = book.get("subtitle").and_then(Json::as_str).map_or_else(
+            || title.to_string(),
+            |subtitle| format!("{title}: {subtitle}"),
+        );
+        fields.push(("title", title));
+    }
+    if let Some(publishers) = names("publishers") {
+        fields.push(("publisher", publishers[0].to_string()));
+    }
+    if let Some(places) = names("publish_places") {
+        fields.push(("address", places[0].to_string()));
+    }
+    if let Some(year) = book
+        .get("publish_date")
+        .and_then(Json::as_str)
+        .and_then(|date| {
+            date.split(|ch: char| !ch.is_ascii_digit())
+                .find(|part| part.len() == 4)
+        })
+    {
+        fields.push(("year", year.to_string()));
+    }
+    fields.push(("isbn", isbn.to_string()));
+
+    let mut entry = Entry::new(EntryType::Book, "");
+    entry.key = Cow::Owned(format!("isbn{isbn}"));
+    for (name, value) in fields {
+        entry.add_field(Field::new(name, Value::Literal(Cow::Owned(value))));
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_library_records_map_to_book_entries() {
+        let body = r#"{"ISBN:9780201896831": {
+            "title": "The Art of Computer Programming",
+            "subtitle": "Fundamental Algorithms",
+            "authors": [{"url": "https://openlibrary.org/authors/OL1A", "name": "Donald E. Knuth"}],
+            "publishers": [{"name": "Addison-Wesley"}],
+            "publish_places": [{"name": "Reading, Mass"}],
+            "publish_date": "1997",
+            "number_of_pages": 650
+        }}"#;
+        let json = Json::parse(body).unwrap();
+        let entry = open_library_entry(json.get("ISBN:9780201896831").unwrap(), "9780201896831");
+
+        assert_eq!(entry.ty, EntryType::Book);
+        assert_eq!(entry.get("author"), Some("Donald E. Knuth"));
+        assert_eq!(
+            entry.get("title"),
+            Some("The Art of Computer Programming: Fundamental Algorithms")
+        );
+        assert_eq!(entry.get("publisher"), Some("Addison-Wesley"));
+        assert_eq!(entry.get("address"), Some("Reading, Mass"));
+        assert_eq!(entry.get("year"), Some("1997"));
+    }
+
+    #[test]
+    fn doi_paths_keep_slashes_and_escape_reserved_bytes() {
+        assert_eq!(
+            encode_path("10.1002/(sici)1097-4571<3.0.co;2-#>"),
+            "10.1002/%28sici%291097-4571%3C3.0.co%3B2-%23%3E"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Import of Semantic Scholar Academic Graph paper records.
+//!
+//! Papers map to entries keyed by their corpus ID, with the paper and corpus
+//! IDs kept in `s2id` and `corpusid` fields. Only the fields present in the
+//! response are mapped, so requests should ask for `title`, `authors`,
+//! `year`, `venue`, `journal`, `externalIds`, and `publicationTypes`.
+
+use super::{json_text, member_text};
+use crate::fixes::suggest_page_range;
+use crate::json::Json;
+use crate::{
+    normalize_doi, normalize_s2id, Entry, EntryType, Error, Field, Library, Result, Value,
+};
+use std::borrow::Cow;
+
+impl Library<'static> {
+    /// Build a library from Semantic Scholar paper JSON.
+    ///
+    /// Accepts a single paper object, an array of papers as returned by the
+    /// batch endpoint (where unknown IDs are `null`), or a search response
+    /// with a `data` array.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Conversion`] when the input is not valid JSON or a
+    /// paper has neither a paper ID nor a corpus ID.
+    pub fn from_semantic_scholar_json(json: &str) -> Result<Self> {
+        let json = Json::parse(json)
+            .ok_or_else(|| Error::Conversion("semantic scholar: malformed JSON".into()))?;
+        let papers = match (&json, json.get("data")) {
+            (Json::Array(papers), _) => papers.as_slice(),
+            (_, Some(data)) => data.items(),
+            _ => std::slice::from_ref(&json),
+        };
+
+        let mut library = Self::new();
+        for paper in papers.iter().filter(|paper| !matches!(paper, Json::Null)) {
+            library.add_entry(semantic_scholar_entry(paper)?);
+        }
+        Ok(library)
+    }
+}
+
+/// Map one Semantic Scholar paper object to an entry.
+pub(super) fn semantic_scholar_entry(paper: &Json) -> Result<Entry<'static>> {
+    let external = paper.get("externalIds");
+    let external_text = |key: &str| external.and_then(|ids| ids.get(key)).and_then(json_text);
+    let s2id = paper
+        .get("paperId")
+        .and_then(Json::as_str)
+        .and_then(normalize_s2id);
+    let corpus_id = paper
+        .get("corpusId")
+        .and_then(json_text)
+        .or_else(|| external_text("CorpusId"));
+    let key = match (&corpus_id, &s2id) {
+        (Some(corpus_id), _) => format!("corpus{corpus_id}"),
+        (None, Some(s2id)) => s2id.clone(),
+        (None, None) => {
+            return Err(Error::Conversion(
+                "semantic scholar: paper without a paper or corpus ID".into(),
+            ))
+        }
+    };
+
+    let types: Vec<&str> = paper
+        .get("publicationTypes")
+        .map(Json::items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Json::as_str)
+        .collect();
+    let journal = paper.get("journal");
+    let journal_name = journal.and_then(|journal| member_text(journal, "name"));
+    let ty = entry_type(&types, journal_name.is_some());
+
+    let mut fields = Vec::new();
+    let authors: Vec<&str> = paper
+        .get("authors")
+        .map(Json::items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|author| member_text(author, "name"))
+        .collect();
+    if !authors.is_empty() {
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(title) = member_text(paper, "title") {
+        fields.push(("title", title.to_string()));
+    }
+    match ty {
+        EntryType::Article => {
+            if let Some(name) = journal_name.or_else(|| member_text(paper, "venue")) {
+                fields.push(("journal", name.to_string()));
+            }
+        }
+        EntryType::InProceedings | EntryType::InCollection => {
+            if let Some(venue) = member_text(paper, "venue") {
+                fields.push(("booktitle", venue.to_string()));
+            }
+        }
+        _ => {}
+    }
+    if let Some(year) = paper.get("year").and_then(json_text) {
+        fields.push(("year", year));
+    }
+    if let Some(journal) = journal {
+        if let Some(volume) = journal.get("volume").and_then(json_text) {
+            fields.push(("volume", volume));
+        }
+        if let Some(pages) = journal.get("pages").and_then(json_text) {
+            fields.push(("pages", suggest_page_range(&pages).unwrap_or(pages)));
+        }
+    }
+    if let Some(doi) = external_text("DOI").as_deref().and_then(normalize_doi) {
+        fields.push(("doi", doi));
+    }
+    if let Some(arxiv) = external_text("ArXiv") {
+        fields.push(("eprint", arxiv));
+        fields.push(("archiveprefix", "arXiv".to_string()));
+    }
+    if let Some(pmid) = external_text("PubMed") {
+        fields.push(("pmid", pmid));
+    }
+    if let Some(abstract_text) = member_text(paper, "abstract") {
+        fields.push(("abstract", abstract_text.to_string()));
+    }
+    if let Some(s2id) = s2id {
+        fields.push(("s2id", s2id));
+    }
+    if let Some(corpus_id) = corpus_id {
+        fields.push(("corpusid", corpus_id));
+    }
+
+    let mut entry = Entry::new(ty, "");
+    entry.key = Cow::Owned(key);
+    for (name, value) in fields {
+        entry.add_field(Field::new(name, Value::Literal(Cow::Owned(value))));
+    }
+    Ok(entry)
+}
+
+/// Choose an entry type from `publicationTypes`, falling back to `@article`
+/// when the paper has journal details.
+fn entry_type(types: &[&str], has_journal: bool) -> EntryType<'static> {
+    let has = |name: &str| types.contains(&name);
+    if has("Conference") {
+        EntryType::InProceedings
+    } else if has("BookSection") {
+        EntryType::InCollection
+    } else if has("Book") {
+        EntryType::Book
+    } else if has("Dataset") {
+        EntryType::Dataset
+    } else if has_journal || has("JournalArticle") || has("Review") {
+        EntryType::Article
+    } else {
+        EntryType::Misc
+    }
+}
@@ -1,5 +1,6 @@
 //! Data models for BibTeX entries
 
+use crate::DiagnosticCode;
 use ahash::AHashMap;
 use memchr::memchr2;
 use std::borrow::Cow;
@@ -26,6 +27,8 @@ pub struct ValidationError {
     pub message: String,
     /// Severity of the error
     pub severity: ValidationSeverity,
+    /// Stable code naming the check, such as `invalid-doi`
+    pub code: DiagnosticCode,
 }
 
 /// Severity level for validation errors
@@ -47,6 +50,7 @@ impl ValidationError {
             field: field.map(String::from),
             message: message.into(),
             severity: ValidationSeverity::Error,
+            code: DiagnosticCode::VALIDATION,
         }
     }
 
@@ -57,6 +61,7 @@ impl ValidationError {
             field: field.map(String::from),
             message: message.into(),
             severity: ValidationSeverity::Warning,
+            code: DiagnosticCode::VALIDATION,
         }
     }
 
@@ -67,8 +72,16 @@ impl ValidationError {
             field: field.map(String::from),
             message: message.into(),
             severity: ValidationSeverity::Info,
+            code: DiagnosticCode::VALIDATION,
         }
     }
+
+    /// Replace the finding's code.
+    #[must_use]
+    pub fn with_code(mut self, code: DiagnosticCode) -> Self {
+        self.code = code;
+        self
+    }
 }
 
 impl fmt::Display for ValidationError {
@@ -83,7 +96,12 @@ impl fmt::Display for ValidationError {
 /// BibTeX supports the forms `First von Last`, `von Last, First`, and
 /// `von Last, Jr, First`. This type keeps those four logical parts separate
 /// while preserving the exact token text from the source value.
+///
+/// Names are built by [`parse_names`] and [`parse_name_list`]; the struct is
+/// `#[non_exhaustive]` so later name parts can be added without a breaking
+/// change.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct PersonName {
     /// Exact source text for this name segment, trimmed of surrounding whitespace.
     pub raw: String,
@@ -105,6 +123,11 @@ pub struct PersonName {
     pub suffix: Vec<String>,
     /// Literal organization or preserved braced name.
     pub literal: Option<String>,
+    /// BibLaTeX name addon such as an epithet, from the extended name format.
+    pub nameaddon: String,
+    /// `true` for a braced multi-word organization such as
+    /// `{World Health Organization}`.
+    pub is_corporate: bool,
 }
 
 impl PersonName {
@@ -114,6 +137,14 @@ impl PersonName {
         if let Some(literal) = &self.literal {
             return literal.clone();
         }
+        if self.is_cjk() {
+            let mut name = format!("{}{}{}", self.von, self.last, self.first);
+            if !self.jr.is_empty() {
+                name.push(' ');
+                name.push_str(&self.jr);
+            }
+            return name;
+        }
 
         let mut parts = Vec::new();
         if !self.first.is_empty() {
@@ -153,6 +184,110 @@ impl PersonName {
         self.literal.is_some()
     }
 
+    /// Return `true` when the family and given names are written in a CJK script.
+    ///
+    /// CJK names are displayed family name first without a separating space.
+    #[must_use]
+    pub fn is_cjk(&self) -> bool {
+        self.literal.is_none()
+            && is_cjk_text(&self.last)
+            && (self.first.is_empty() || is_cjk_text(&self.first))
+    }
+
+    /// Format the name in a bibliography style.
+    ///
+    /// Particles stay attached to the family name, junior parts follow it,
+    /// and hyphenated given names abbreviate per part (`Jean-Paul` becomes
+    /// `J.-P.`). Literal names are returned unchanged.
+    #[must_use]
+    pub fn format(&self, style: NameStyle) -> String {
+        if let Some(literal) = &self.literal {
+            return literal.clone();
+        }
+        let family = [self.von.as_str(), self.last.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if style == NameStyle::Family {
+            return family;
+        }
+        if self.is_cjk() {
+            return self.display_name();
+        }
+
+        let given = match style {
+            NameStyle::FamilyInitials | NameStyle::InitialsFamily => self.given_initials(),
+            _ => self.first.clone(),
+        };
+        match style {
+            NameStyle::FamilyGiven | NameStyle::FamilyInitials => {
+                let mut name = family;
+                for part in [self.jr.as_str(), given.as_str()] {
+                    if !part.is_empty() {
+                        name.push_str(", ");
+                        name.push_str(part);
+                    }
+                }
+                name
+            }
+            _ => {
+                let mut name = [given.as_str(), family.as_str()]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if !self.jr.is_empty() {
+                    name.push_str(", ");
+                    name.push_str(&self.jr);
+                }
+                name
+            }
+        }
+    }
+
+    /// Return a normalized identity for matching the same person across entries.
+    ///
+    /// The identity is the lowercase family name with particles followed by
+    /// the given-name initials, so `Donald E. Knuth` and `Knuth, D. E.` share
+    /// `knuth, d. e.`. Literal names use their lowercase text.
+    #[must_use]
+    pub fn identity(&self) -> String {
+        let identity = self.literal.as_ref().map_or_else(
+            || {
+                let family = self.format(NameStyle::Family);
+                let initials = self.given_initials();
+                if initials.is_empty() {
+                    family
+                } else {
+                    format!("{family}, {initials}")
+                }
+            },
+            Clone::clone,
+        );
+        identity
+            .chars()
+            .filter(|ch| !matches!(ch, '{' | '}'))
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Return the given names abbreviated to initials, such as `D. E.`.
+    #[must_use]
+    pub fn given_initials(&self) -> String {
+        self.given
+            .iter()
+            .map(|word| {
+                split_hyphenated(word)
+                    .filter_map(name_initial)
+                    .collect::<Vec<_>>()
+                    .join("-")
+            })
+            .filter(|initials| !initials.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     /// Return the display name after LaTeX-to-Unicode conversion.
     #[cfg(feature = "latex_to_unicode")]
     #[must_use]
@@ -161,17 +296,184 @@ impl PersonName {
     }
 }
 
+/// Bibliography styles for [`PersonName::format`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NameStyle {
+    /// `von Last, Jr, First Middle`.
+    #[default]
+    FamilyGiven,
+    /// `von Last, Jr, F. M.`.
+    FamilyInitials,
+    /// `First Middle von Last, Jr`.
+    GivenFamily,
+    /// `F. M. von Last, Jr`.
+    InitialsFamily,
+    /// `von Last` only.
+    Family,
+}
+
+/// How [`format_author_list`] marks a truncated author list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EtAlStyle {
+    /// `Smith et al.` or `Smith and Jones et al.`.
+    #[default]
+    EtAl,
+    /// `Smith, et al.` or `Smith, Jones, et al.`.
+    CommaEtAl,
+    /// `Smith and others`, mirroring the BibTeX marker.
+    AndOthers,
+}
+
+/// Render family names as a short author list, truncating long lists.
+///
+/// Lists longer than `max_names` keep the first `max_names` names followed by
+/// the et al. marker; a `max_names` of zero is treated as one. Names from
+/// [`parse_names`] no longer carry a trailing `and others`; use
+/// [`NameList::format`] to keep that marker.
+#[must_use]
+pub fn format_author_list(names: &[PersonName], max_names: usize, style: EtAlStyle) -> String {
+    format_names(names.iter().collect(), false, max_names, style)
+}
+
+fn format_names(
+    mut names: Vec<&PersonName>,
+    mut truncated: bool,
+    max_names: usize,
+    style: EtAlStyle,
+) -> String {
+    let max_names = max_names.max(1);
+    if names.len() > max_names {
+        names.truncate(max_names);
+        truncated = true;
+    }
+
+    let names = names
+        .into_iter()
+        .map(|name| name.format(NameStyle::Family))
+        .collect::<Vec<_>>();
+    if !truncated {
+        return match names.as_slice() {
+            [only] => only.clone(),
+            [first, second] => format!("{first} and {second}"),
+            [rest @ .., last] => format!("{}, and {last}", rest.join(", ")),
+            [] => String::new(),
+        };
+    }
+    // A bare `others` names nobody, so there is no list to mark as truncated.
+    if names.is_empty() {
+        return String::new();
+    }
+
+    match style {
+        EtAlStyle::EtAl => {
+            let shown = match names.as_slice() {
+                [rest @ .., last] if !rest.is_empty() => {
+                    format!("{} and {last}", rest.join(", "))
+                }
+                _ => names.join(""),
+            };
+            format!("{shown} et al.")
+        }
+        EtAlStyle::CommaEtAl => format!("{}, et al.", names.join(", ")),
+        EtAlStyle::AndOthers => format!("{} and others", names.join(" and ")),
+    }
+}
+
+fn is_others_marker(name: &PersonName) -> bool {
+    name.literal.is_none()
+        && name.first.is_empty()
+        && name.von.is_empty()
+        && name.jr.is_empty()
+        && name.last == "others"
+}
+
 /// Parse a BibTeX `author` or `editor` field into structured person names.
 ///
 /// Splitting respects balanced braces, so organization names such as
 /// `{The Unicode Consortium}` and literal `and` inside braces stay intact.
+/// The BibLaTeX extended form `family=Doe, given=Jane, nameaddon=the Elder`
+/// is recognized, and space-separated CJK names are read family name first.
+/// A trailing `and others` is dropped; use [`parse_name_list`], whose
+/// [`NameList::truncated`] flag records it.
 #[must_use]
 pub fn parse_names(input: &str) -> Vec<PersonName> {
-    split_bibtex_names(input)
+    parse_name_list(input).names
+}
+
+/// Parse a name field, recording a trailing `and others` as truncation.
+#[must_use]
+pub fn parse_name_list(input: &str) -> NameList {
+    let mut names = split_bibtex_names(input)
         .into_iter()
         .map(parse_single_name)
         .filter(|name| !name.is_empty())
-        .collect()
+        .collect::<Vec<_>>();
+    let mut truncated = false;
+    while names.last().is_some_and(is_others_marker) {
+        names.pop();
+        truncated = true;
+    }
+    NameList { names, truncated }
+}
+
+/// Parsed names from one `author`-like field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameList {
+    /// Names in source order, without the `others` marker.
+    pub names: Vec<PersonName>,
+    /// `true` when the field ended with `and others`.
+    pub truncated: bool,
+}
+
+impl NameList {
+    /// Return the number of named people.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Return `true` when no names were parsed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterate over the parsed names.
+    pub fn iter(&self) -> std::slice::Iter<'_, PersonName> {
+        self.names.iter()
+    }
+
+    /// Render the list like [`format_author_list`], honoring `truncated`.
+    ///
+    /// A list with no names renders as an empty string, even when it is
+    /// just `others`.
+    #[must_use]
+    pub fn format(&self, max_names: usize, style: EtAlStyle) -> String {
+        format_names(
+            self.names.iter().collect(),
+            self.truncated,
+            max_names,
+            style,
+        )
+    }
+}
+
+impl IntoIterator for NameList {
+    type Item = PersonName;
+    type IntoIter = std::vec::IntoIter<PersonName>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.names.into_iter()
+    }
+}
+
+impl<'n> IntoIterator for &'n NameList {
+    type Item = &'n PersonName;
+    type IntoIter = std::slice::Iter<'n, PersonName>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.names.iter()
+    }
 }
 
 /// Parsed bibliography date parts.
@@ -245,6 +547,12 @@ pub enum ResourceKind {
     Arxiv,
     /// Cross-reference citation key.
     Crossref,
+    /// `OpenAlex` entity identifier such as `W2741809807`.
+    OpenAlex,
+    /// Semantic Scholar paper ID, a 40-digit hex hash.
+    S2Id,
+    /// Semantic Scholar corpus ID.
+    CorpusId,
 }
 
 impl ResourceKind {
@@ -262,7 +570,140 @@ impl ResourceKind {
             Self::Eprint => "eprint",
             Self::Arxiv => "arxiv",
             Self::Crossref => "crossref",
+            Self::OpenAlex => "openalex",
+            Self::S2Id => "s2id",
+            Self::CorpusId => "corpusid",
+        }
+    }
+}
+
+/// Babel and polyglossia language names mapped to BCP-47 tags.
+const BABEL_LANGUAGES: &[(&str, &str)] = &[
+    ("american", "en-US"),
+    ("arabic", "ar"),
+    ("austrian", "de-AT"),
+    ("brazil", "pt-BR"),
+    ("brazilian", "pt-BR"),
+    ("british", "en-GB"),
+    ("canadian", "en-CA"),
+    ("catalan", "ca"),
+    ("chinese", "zh"),
+    ("czech", "cs"),
+    ("danish", "da"),
+    ("dutch", "nl"),
+    ("english", "en"),
+    ("finnish", "fi"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("greek", "el"),
+    ("hebrew", "he"),
+    ("hungarian", "hu"),
+    ("italian", "it"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("latin", "la"),
+    ("naustrian", "de-AT"),
+    ("ngerman", "de"),
+    ("norsk", "nb"),
+    ("norwegian", "nb"),
+    ("nswissgerman", "de-CH"),
+    ("nynorsk", "nn"),
+    ("polish", "pl"),
+    ("portuguese", "pt"),
+    ("russian", "ru"),
+    ("spanish", "es"),
+    ("swedish", "sv"),
+    ("swissgerman", "de-CH"),
+    ("turkish", "tr"),
+    ("ukrainian", "uk"),
+    ("usenglish", "en-US"),
+    ("ukenglish", "en-GB"),
+];
+
+/// A parsed language tag from a `langid` or `language` field.
+///
+/// Only the language, script, and region subtags of BCP-47 are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageTag {
+    /// Lowercase ISO 639 language code, such as `de`.
+    pub language: String,
+    /// Title-case ISO 15924 script code, such as `Latn`.
+    pub script: Option<String>,
+    /// Uppercase region code, such as `AT` or `419`.
+    pub region: Option<String>,
+}
+
+impl LanguageTag {
+    /// Parse a BCP-47 tag (`de-AT`, `zh_Hant`) or a babel language name
+    /// (`ngerman`, `british`), ignoring ASCII case.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = trim_bibtex_scalar(input);
+        let lowercase = input.to_ascii_lowercase();
+        let tag = BABEL_LANGUAGES
+            .iter()
+            .find(|(name, _)| *name == lowercase)
+            .map_or(input, |(_, tag)| tag);
+
+        let mut subtags = tag.split(['-', '_']);
+        let language = subtags.next()?;
+        if !(2..=3).contains(&language.len())
+            || !language.chars().all(|ch| ch.is_ascii_alphabetic())
+        {
+            return None;
+        }
+        let mut parsed = Self {
+            language: language.to_ascii_lowercase(),
+            script: None,
+            region: None,
+        };
+        for subtag in subtags {
+            let alphabetic = subtag.chars().all(|ch| ch.is_ascii_alphabetic());
+            let numeric = subtag.chars().all(|ch| ch.is_ascii_digit());
+            match subtag.len() {
+                4 if alphabetic && parsed.script.is_none() && parsed.region.is_none() => {
+                    let mut script = subtag.to_ascii_lowercase();
+                    script[..1].make_ascii_uppercase();
+                    parsed.script = Some(script);
+                }
+                2 if alphabetic && parsed.region.is_none() => {
+                    parsed.region = Some(subtag.to_ascii_uppercase());
+                }
+                3 if numeric && parsed.region.is_none() => {
+                    parsed.region = Some(subtag.to_string());
+                }
+                _ => break,
+            }
+        }
+        Some(parsed)
+    }
+
+    /// Return `true` for English tags.
+    #[must_use]
+    pub fn is_english(&self) -> bool {
+        self.language == "en"
+    }
+
+    /// Return `true` when title or sentence casing may be applied.
+    ///
+    /// Like BibLaTeX, case changes are reserved for English; other languages
+    /// such as German capitalize by grammar and must be left alone.
+    #[must_use]
+    pub fn allows_case_changes(&self) -> bool {
+        self.is_english()
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.language)?;
+        if let Some(script) = &self.script {
+            write!(f, "-{script}")?;
         }
+        if let Some(region) = &self.region {
+            write!(f, "-{region}")?;
+        }
+        Ok(())
     }
 }
 
@@ -279,6 +720,142 @@ pub struct ResourceField {
     pub normalized: Option<String>,
 }
 
+/// One attachment from an annotated `file` field.
+///
+/// Reference managers such as `JabRef` and Zotero store attachments as
+/// `Description:path:Type` records separated by `;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileAttachment {
+    /// Human-readable description; empty when the record has none.
+    pub description: String,
+    /// Attachment path with escape sequences removed.
+    pub path: String,
+    /// File type label such as `PDF` or `application/pdf`, when present.
+    pub file_type: Option<String>,
+}
+
+/// Publication year classification used for sorting and validation.
+///
+/// Values order numeric years first (ascending), then pending works, then
+/// unparseable values, then missing years.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PublicationYear {
+    /// A numeric year; years before the common era are negative.
+    Year(i32),
+    /// An unpublished state such as `in press`, `forthcoming`, or a BibLaTeX
+    /// `pubstate`, lowercased.
+    Pending(String),
+    /// A year or date that could not be interpreted.
+    Invalid(String),
+    /// No usable `year`, `date`, or `pubstate` field.
+    Missing,
+}
+
+impl PublicationYear {
+    /// Classify a `year` or `date` value.
+    ///
+    /// Besides the shapes accepted by [`parse_date_parts`], this accepts
+    /// short and negative years (`350`, `-350`), era suffixes (`350 BC`,
+    /// `800 CE`), and pending states such as `in press` or `forthcoming`.
+    #[must_use]
+    pub fn parse(input: &str) -> Self {
+        let value = trim_bibtex_scalar(input);
+        if value.is_empty() {
+            return Self::Missing;
+        }
+        if let Ok(parts) = parse_date_parts(value) {
+            return Self::Year(parts.year);
+        }
+
+        let normalized = value
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_ascii_lowercase();
+        match normalized.as_str() {
+            "n.d." | "nd" | "no date" => return Self::Missing,
+            "in press" | "inpress" | "forthcoming" | "submitted" | "to appear" | "accepted"
+            | "in preparation" | "inpreparation" | "prepublished" => {
+                return Self::Pending(normalized);
+            }
+            _ => {}
+        }
+
+        parse_era_year(&normalized).map_or_else(|| Self::Invalid(value.to_string()), Self::Year)
+    }
+
+    /// Return the numeric year, if known.
+    #[must_use]
+    pub const fn year(&self) -> Option<i32> {
+        match self {
+            Self::Year(year) => Some(*year),
+            _ => None,
+        }
+    }
+
+    /// Return `true` for unpublished works.
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending(_))
+    }
+
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Year(_) => 0,
+            Self::Pending(_) => 1,
+            Self::Invalid(_) => 2,
+            Self::Missing => 3,
+        }
+    }
+}
+
+impl PartialOrd for PublicationYear {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicationYear {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| match (self, other) {
+                (Self::Year(left), Self::Year(right)) => left.cmp(right),
+                (Self::Pending(left), Self::Pending(right))
+                | (Self::Invalid(left), Self::Invalid(right)) => left.cmp(right),
+                _ => std::cmp::Ordering::Equal,
+            })
+    }
+}
+
+/// Return `true` for years that are recent enough or explicitly before the common era.
+pub(crate) const fn is_plausible_year(year: i32) -> bool {
+    year <= 0 || (1000 <= year && year <= 2100)
+}
+
+fn parse_era_year(input: &str) -> Option<i32> {
+    let before = ["bce", "b.c.e.", "bc", "b.c."]
+        .iter()
+        .find_map(|suffix| input.strip_suffix(suffix));
+    let after = ["ce", "c.e.", "ad", "a.d."]
+        .iter()
+        .find_map(|suffix| input.strip_suffix(suffix))
+        .or_else(|| input.strip_prefix("ad "))
+        .or_else(|| input.strip_prefix("a.d. "));
+    let (digits, sign) = match (before, after) {
+        (Some(rest), _) => (rest.trim(), -1),
+        (None, Some(rest)) => (rest.trim(), 1),
+        (None, None) => input
+            .strip_prefix('-')
+            .map_or((input, 1), |rest| (rest, -1)),
+    };
+
+    if digits.is_empty() || digits.len() > 6 || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<i32>().ok().map(|year| year * sign)
+}
+
 /// Parse a common bibliography date shape into parts.
 ///
 /// Supported input shapes are `YYYY`, `YYYY-MM`, and `YYYY-MM-DD`.
@@ -326,16 +903,28 @@ pub fn normalize_field_name_ascii(name: &str) -> String {
     name.trim().to_ascii_lowercase()
 }
 
+/// Built-in BibLaTeX field names with their BibTeX counterparts.
+///
+/// The flag marks pairs whose values are interchangeable; `date`/`year`
+/// differ in format, so alias-aware lookups skip it.
+const BIBLATEX_FIELD_ALIASES: &[(&str, &str, bool)] = &[
+    ("journaltitle", "journal", true),
+    ("date", "year", false),
+    ("location", "address", true),
+    ("institution", "school", true),
+    ("annotation", "annote", true),
+    ("eprinttype", "archiveprefix", true),
+    ("eprintclass", "primaryclass", true),
+];
+
 /// Return the crate's built-in BibLaTeX-to-BibTeX field alias, if any.
 #[must_use]
 pub fn canonical_biblatex_field_alias(name: &str) -> Option<&'static str> {
-    match normalize_field_name_ascii(name).as_str() {
-        "journaltitle" => Some("journal"),
-        "date" => Some("year"),
-        "institution" => Some("school"),
-        "location" => Some("address"),
-        _ => None,
-    }
+    let name = name.trim();
+    BIBLATEX_FIELD_ALIASES
+        .iter()
+        .find(|(biblatex, _, _)| biblatex.eq_ignore_ascii_case(name))
+        .map(|&(_, bibtex, _)| bibtex)
 }
 
 /// Normalize a field name with ASCII lowercase and built-in BibLaTeX aliases.
@@ -345,6 +934,80 @@ pub fn normalize_biblatex_field_name(name: &str) -> String {
         .map_or_else(|| normalize_field_name_ascii(name), ToOwned::to_owned)
 }
 
+/// A table of interchangeable field names used by alias-aware lookups.
+///
+/// Each pair is symmetric: looking up either name falls back to the other.
+/// Names are compared ignoring ASCII case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldAliases {
+    biblatex: bool,
+    custom: Vec<(String, String)>,
+}
+
+impl FieldAliases {
+    /// Create an empty alias table.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            biblatex: false,
+            custom: Vec::new(),
+        }
+    }
+
+    /// Create the built-in BibLaTeX/BibTeX synonym table.
+    ///
+    /// This pairs `journaltitle`/`journal`, `location`/`address`,
+    /// `institution`/`school`, `annotation`/`annote`,
+    /// `eprinttype`/`archiveprefix`, and `eprintclass`/`primaryclass`.
+    /// `date`/`year` is deliberately excluded because the values differ in
+    /// format.
+    #[must_use]
+    #[inline]
+    pub const fn biblatex() -> Self {
+        Self {
+            biblatex: true,
+            custom: Vec::new(),
+        }
+    }
+
+    /// Add a pair of interchangeable field names.
+    #[must_use]
+    pub fn with_alias(mut self, name: impl Into<String>, alias: impl Into<String>) -> Self {
+        self.custom.push((name.into(), alias.into()));
+        self
+    }
+
+    /// Iterate over the names interchangeable with `name`, in table order.
+    pub fn aliases_of<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s str> + 's {
+        let name = name.trim();
+        BIBLATEX_FIELD_ALIASES
+            .iter()
+            .filter(|&&(_, _, interchangeable)| self.biblatex && interchangeable)
+            .map(|&(biblatex, bibtex, _)| (biblatex, bibtex))
+            .chain(
+                self.custom
+                    .iter()
+                    .map(|(left, right)| (left.as_str(), right.as_str())),
+            )
+            .filter_map(move |(left, right)| {
+                if left.eq_ignore_ascii_case(name) {
+                    Some(right)
+                } else if right.eq_ignore_ascii_case(name) {
+                    Some(left)
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+impl Default for FieldAliases {
+    fn default() -> Self {
+        Self::biblatex()
+    }
+}
+
 /// Classify a common resource or identifier field name.
 #[must_use]
 pub fn classify_resource_field(name: &str) -> Option<ResourceKind> {
@@ -359,12 +1022,29 @@ pub fn classify_resource_field(name: &str) -> Option<ResourceKind> {
         "eprint" => Some(ResourceKind::Eprint),
         "arxiv" => Some(ResourceKind::Arxiv),
         "crossref" => Some(ResourceKind::Crossref),
+        "openalex" => Some(ResourceKind::OpenAlex),
+        "s2id" | "s2paperid" => Some(ResourceKind::S2Id),
+        "corpusid" | "s2corpusid" => Some(ResourceKind::CorpusId),
         _ => None,
     }
 }
 
+/// Parse an annotated `file` field into attachments.
+///
+/// Records are separated by unescaped `;` and components by unescaped `:`.
+/// The escapes `\:`, `\;`, and `\\` are decoded. A bare value without any
+/// separator is treated as a single path, and Windows drive prefixes such as
+/// `C:\` are kept as part of the path.
+#[must_use]
+pub fn parse_file_attachments(input: &str) -> Vec<FileAttachment> {
+    split_unescaped(input, ';')
+        .iter()
+        .filter_map(|record| file_attachment_from_record(record))
+        .collect()
+}
+
 /// A BibTeX entry (article, book, etc.)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Entry<'a> {
     /// Entry type (article, book, inproceedings, etc.)
     pub ty: EntryType<'a>,
@@ -439,6 +1119,43 @@ impl<'a> Entry<'a> {
             .map(|f| value_to_lossy_string(&f.value))
     }
 
+    /// Get a field by name, falling back to its synonyms in `aliases`.
+    ///
+    /// The requested name is tried first, ignoring ASCII case, so the raw
+    /// field always wins over an alias.
+    #[must_use]
+    pub fn field_with_aliases(&self, name: &str, aliases: &FieldAliases) -> Option<&Field<'a>> {
+        self.field_ignore_case(name).or_else(|| {
+            aliases
+                .aliases_of(name)
+                .find_map(|alias| self.field_ignore_case(alias))
+        })
+    }
+
+    /// Get a string-literal field value, falling back to its synonyms in `aliases`.
+    #[must_use]
+    pub fn get_with_aliases(&self, name: &str, aliases: &FieldAliases) -> Option<&str> {
+        self.field_with_aliases(name, aliases)
+            .and_then(|f| f.value.as_str())
+    }
+
+    /// Get a field value as a string, falling back to its synonyms in `aliases`.
+    #[must_use]
+    pub fn get_as_string_with_aliases(&self, name: &str, aliases: &FieldAliases) -> Option<String> {
+        self.field_with_aliases(name, aliases)
+            .map(|f| value_to_lossy_string(&f.value))
+    }
+
+    /// Get a string-literal field value using the built-in BibLaTeX synonyms.
+    ///
+    /// `entry.get_aliased("journal")` finds either `journal` or
+    /// `journaltitle`; use [`Entry::get`] for the raw field only.
+    #[must_use]
+    pub fn get_aliased(&self, name: &str) -> Option<&str> {
+        const BIBLATEX: FieldAliases = FieldAliases::biblatex();
+        self.get_with_aliases(name, &BIBLATEX)
+    }
+
     /// Get the first string-literal field matching any of the names, case-insensitively.
     #[must_use]
     pub fn get_any_ignore_case(&self, names: &[&str]) -> Option<&str> {
@@ -475,6 +1192,33 @@ impl<'a> Entry<'a> {
             .and_then(|doi| normalize_doi(&doi))
     }
 
+    /// Return the normalized `OpenAlex` ID from the `openalex` field.
+    ///
+    /// Both `W2741809807` and `https://openalex.org/W2741809807` yield
+    /// `W2741809807`.
+    #[must_use]
+    pub fn openalex_id(&self) -> Option<String> {
+        self.get_as_string_ignore_case("openalex")
+            .and_then(|id| normalize_openalex(&id))
+    }
+
+    /// Return the normalized Semantic Scholar paper ID from the `s2id` field.
+    #[must_use]
+    pub fn s2id(&self) -> Option<String> {
+        self.get_as_string_ignore_case("s2id")
+            .or_else(|| self.get_as_string_ignore_case("s2paperid"))
+            .and_then(|id| normalize_s2id(&id))
+    }
+
+    /// Return the Semantic Scholar corpus ID from a `corpusid` or
+    /// `s2corpusid` field.
+    #[must_use]
+    pub fn corpus_id(&self) -> Option<String> {
+        self.get_as_string_ignore_case("corpusid")
+            .or_else(|| self.get_as_string_ignore_case("s2corpusid"))
+            .and_then(|id| normalize_corpus_id(&id))
+    }
+
     /// Parse the `author` field into structured BibTeX names.
     #[must_use]
     pub fn authors(&self) -> Vec<PersonName> {
@@ -482,6 +1226,13 @@ impl<'a> Entry<'a> {
             .map_or_else(Vec::new, |authors| parse_names(&authors))
     }
 
+    /// Parse the `author` field, keeping the `and others` marker as a flag.
+    #[must_use]
+    pub fn author_list(&self) -> NameList {
+        self.get_as_string_ignore_case("author")
+            .map_or_else(NameList::default, |authors| parse_name_list(&authors))
+    }
+
     /// Parse the `editor` field into structured BibTeX names.
     #[must_use]
     pub fn editors(&self) -> Vec<PersonName> {
@@ -551,6 +1302,13 @@ impl<'a> Entry<'a> {
             .collect()
     }
 
+    /// Parse the annotated `file` field into structured attachments.
+    #[must_use]
+    pub fn attachments(&self) -> Vec<FileAttachment> {
+        self.get_as_string_ignore_case("file")
+            .map_or_else(Vec::new, |file| parse_file_attachments(&file))
+    }
+
     /// Get all fields
     #[must_use]
     pub fn fields(&self) -> &[Field<'a>] {
@@ -614,6 +1372,41 @@ impl<'a> Entry<'a> {
         self.get_any_as_string_ignore_case(&["year"])
     }
 
+    /// Classify the entry's publication year for sorting and validation.
+    ///
+    /// `year` is preferred over `date`; when neither yields a year, a
+    /// BibLaTeX `pubstate` field marks the entry as pending.
+    #[must_use]
+    pub fn publication_year(&self) -> PublicationYear {
+        let year = self
+            .get_any_as_string_ignore_case(&["year", "date"])
+            .map_or(PublicationYear::Missing, |year| {
+                PublicationYear::parse(&year)
+            });
+        if year.year().is_some() || year.is_pending() {
+            return year;
+        }
+        self.get_as_string_ignore_case("pubstate")
+            .map(|state| trim_bibtex_scalar(&state).to_ascii_lowercase())
+            .filter(|state| !state.is_empty())
+            .map_or(year, PublicationYear::Pending)
+    }
+
+    /// Return the entry language from `langid`, falling back to `language`.
+    ///
+    /// A `language` list such as `english and german` yields its first item.
+    /// Returns `None` when neither field holds a recognizable tag or name.
+    #[must_use]
+    pub fn language(&self) -> Option<LanguageTag> {
+        self.get_as_string_ignore_case("langid")
+            .and_then(|langid| LanguageTag::parse(&langid))
+            .or_else(|| {
+                let language = self.get_as_string_ignore_case("language")?;
+                let first = language.split(" and ").next().unwrap_or_default();
+                LanguageTag::parse(first.trim())
+            })
+    }
+
     /// Return the date field as a string.
     #[must_use]
     pub fn date(&self) -> Option<String> {
@@ -653,6 +1446,21 @@ impl<'a> Entry<'a> {
             .unwrap_or_default()
     }
 
+    /// Return the member keys listed in an `@set` entry's `entryset` field.
+    #[must_use]
+    pub fn set_members(&self) -> Vec<String> {
+        self.get_as_string_ignore_case("entryset")
+            .map(|members| {
+                members
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|member| !member.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Validate the entry according to the specified level
     /// Returns Ok(()) if valid, or Err with a list of validation errors
     pub fn validate(&self, level: ValidationLevel) -> Result<(), Vec<ValidationError>> {
@@ -692,13 +1500,16 @@ impl<'a> Entry<'a> {
             }
 
             if field_group == ["author", "editor"] {
-                errors.push(ValidationError::error(
-                    None,
-                    format!(
-                        "{} entry must have either 'author' or 'editor' field",
-                        self.ty
-                    ),
-                ));
+                errors.push(
+                    ValidationError::error(
+                        None,
+                        format!(
+                            "{} entry must have either 'author' or 'editor' field",
+                            self.ty
+                        ),
+                    )
+                    .with_code(DiagnosticCode::MISSING_AUTHOR_OR_EDITOR),
+                );
                 continue;
             }
 
@@ -717,7 +1528,10 @@ impl<'a> Entry<'a> {
                 )
             };
 
-            errors.push(ValidationError::error(Some(primary_field), message));
+            errors.push(
+                ValidationError::error(Some(primary_field), message)
+                    .with_code(DiagnosticCode::MISSING_REQUIRED_FIELD),
+            );
         }
     }
 
@@ -725,38 +1539,41 @@ impl<'a> Entry<'a> {
     fn validate_common_issues(&self, errors: &mut Vec<ValidationError>) {
         // Check for common issues
 
-        // Year should be a valid number and recent
-        if let Some(year_str) = self.get_any_as_string_ignore_case(&["year", "date"]) {
-            if let Ok(year) = year_str.parse::<i32>() {
-                if !(1000..=2100).contains(&year) {
-                    errors.push(ValidationError::warning(
-                        Some(if self.has_field("year") {
-                            "year"
-                        } else {
-                            "date"
-                        }),
+        // Year should be a plausible number, an explicit era, or a pending state
+        let year_field = if self.has_field("year") {
+            "year"
+        } else {
+            "date"
+        };
+        match self.publication_year() {
+            PublicationYear::Year(year) if !is_plausible_year(year) => {
+                errors.push(
+                    ValidationError::warning(
+                        Some(year_field),
                         format!("Year {year} seems unlikely"),
-                    ));
-                }
-            } else {
-                errors.push(ValidationError::warning(
-                    Some(if self.has_field("year") {
-                        "year"
-                    } else {
-                        "date"
-                    }),
-                    "Year/date should be a number",
-                ));
+                    )
+                    .with_code(DiagnosticCode::IMPLAUSIBLE_YEAR),
+                );
+            }
+            PublicationYear::Invalid(_) => {
+                errors.push(
+                    ValidationError::warning(Some(year_field), "Year/date should be a number")
+                        .with_code(DiagnosticCode::INVALID_YEAR),
+                );
             }
+            _ => {}
         }
 
         // Pages should have valid format (e.g., "12-24" or "12--24")
         if let Some(pages) = self.get_ignore_case("pages") {
             if !is_valid_page_range(pages) {
-                errors.push(ValidationError::warning(
-                    Some("pages"),
-                    "Pages should be in format '12-34' or '12--34'",
-                ));
+                errors.push(
+                    ValidationError::warning(
+                        Some("pages"),
+                        "Pages should be in format '12-34' or '12--34'",
+                    )
+                    .with_code(DiagnosticCode::BAD_PAGE_RANGE),
+                );
             }
         }
 
@@ -765,10 +1582,13 @@ impl<'a> Entry<'a> {
             EntryType::InBook | EntryType::InProceedings | EntryType::InCollection
                 if !self.has_any_field(&["author", "editor"]) =>
             {
-                errors.push(ValidationError::warning(
-                    None,
-                    "Entry should have either 'author' or 'editor' field",
-                ));
+                errors.push(
+                    ValidationError::warning(
+                        None,
+                        "Entry should have either 'author' or 'editor' field",
+                    )
+                    .with_code(DiagnosticCode::MISSING_AUTHOR_OR_EDITOR),
+                );
             }
             _ => {}
         }
@@ -777,10 +1597,10 @@ impl<'a> Entry<'a> {
         for field in &self.fields {
             if let Some(value_str) = field.value.as_str() {
                 if value_str.trim().is_empty() {
-                    errors.push(ValidationError::warning(
-                        Some(&field.name),
-                        "Field has empty value",
-                    ));
+                    errors.push(
+                        ValidationError::warning(Some(&field.name), "Field has empty value")
+                            .with_code(DiagnosticCode::EMPTY_FIELD),
+                    );
                 }
             }
         }
@@ -791,40 +1611,65 @@ impl<'a> Entry<'a> {
         // DOI format
         if let Some(doi) = self.get_as_string_ignore_case("doi") {
             if normalize_doi(&doi).is_none() {
-                errors.push(ValidationError::warning(
-                    Some("doi"),
-                    "DOI should start with '10.' or a DOI URL/prefix",
-                ));
+                errors.push(
+                    ValidationError::warning(
+                        Some("doi"),
+                        "DOI should start with '10.' or a DOI URL/prefix",
+                    )
+                    .with_code(DiagnosticCode::INVALID_DOI),
+                );
             }
         }
 
         // URL format
         if let Some(url) = self.get_ignore_case("url") {
             if !url.starts_with("http://") && !url.starts_with("https://") {
-                errors.push(ValidationError::warning(
-                    Some("url"),
-                    "URL should start with http:// or https://",
-                ));
+                errors.push(
+                    ValidationError::warning(
+                        Some("url"),
+                        "URL should start with http:// or https://",
+                    )
+                    .with_code(DiagnosticCode::INVALID_URL),
+                );
             }
         }
 
         // ISBN format (basic check)
         if let Some(isbn) = self.get_ignore_case("isbn") {
             if !is_valid_isbn_shape(isbn) {
-                errors.push(ValidationError::warning(
-                    Some("isbn"),
-                    "ISBN should have 10 or 13 digits",
-                ));
+                errors.push(
+                    ValidationError::warning(Some("isbn"), "ISBN should have 10 or 13 digits")
+                        .with_code(DiagnosticCode::INVALID_ISBN),
+                );
             }
         }
 
         // Month should be valid
         if let Some(month) = self.get_ignore_case("month") {
             if !is_valid_month(month) {
-                errors.push(ValidationError::info(
-                    Some("month"),
-                    "Month should be a standard abbreviation (jan, feb, etc.) or full name",
-                ));
+                errors.push(
+                    ValidationError::info(
+                        Some("month"),
+                        "Month should be a standard abbreviation (jan, feb, etc.) or full name",
+                    )
+                    .with_code(DiagnosticCode::UNKNOWN_MONTH),
+                );
+            }
+        }
+
+        // Language fields should name a known language
+        for field_name in ["langid", "language"] {
+            if let Some(value) = self.get_as_string_ignore_case(field_name) {
+                let first = value.split(" and ").next().unwrap_or_default();
+                if LanguageTag::parse(first.trim()).is_none() {
+                    errors.push(
+                        ValidationError::info(
+                            Some(field_name),
+                            format!("{field_name} should be a BCP-47 tag or babel language name"),
+                        )
+                        .with_code(DiagnosticCode::UNKNOWN_LANGUAGE),
+                    );
+                }
             }
         }
 
@@ -832,10 +1677,13 @@ impl<'a> Entry<'a> {
         for field_name in &["volume", "number"] {
             if let Some(value) = self.get_ignore_case(field_name) {
                 if value.parse::<i32>().is_err() && !value.contains('-') {
-                    errors.push(ValidationError::info(
-                        Some(field_name),
-                        format!("{field_name} should typically be numeric"),
-                    ));
+                    errors.push(
+                        ValidationError::info(
+                            Some(field_name),
+                            format!("{field_name} should typically be numeric"),
+                        )
+                        .with_code(DiagnosticCode::NON_NUMERIC_FIELD),
+                    );
                 }
             }
         }
@@ -845,10 +1693,10 @@ impl<'a> Entry<'a> {
     fn validate_cross_references(&self, errors: &mut Vec<ValidationError>) {
         if let Some(crossref) = self.get_ignore_case("crossref") {
             if crossref.trim().is_empty() {
-                errors.push(ValidationError::error(
-                    Some("crossref"),
-                    "Cross-reference is empty",
-                ));
+                errors.push(
+                    ValidationError::error(Some("crossref"), "Cross-reference is empty")
+                        .with_code(DiagnosticCode::EMPTY_CROSSREF),
+                );
             }
         }
     }
@@ -984,7 +1832,10 @@ impl<'a> Entry<'a> {
 }
 
 /// BibTeX entry type
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Entry types order by variant declaration order, with `Custom` types last
+/// and ordered by name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EntryType<'a> {
     /// Article from a journal
     Article,
@@ -1283,6 +2134,14 @@ impl<'a> EntryType<'a> {
         !self.is_classic_bibtex() && !matches!(self, Self::Custom(_))
     }
 
+    /// Return `true` for data-only containers such as BibLaTeX `@xdata`.
+    ///
+    /// These entries supply fields to other entries and are never cited.
+    #[must_use]
+    pub const fn is_data_only(&self) -> bool {
+        matches!(self, Self::XData)
+    }
+
     /// Convert to owned version
     #[must_use]
     pub fn into_owned(self) -> EntryType<'static> {
@@ -1355,8 +2214,22 @@ impl fmt::Display for EntryType<'_> {
     }
 }
 
+/// Where a field value came from when it was not written out in the entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldOrigin {
+    /// Expanded from a single `@string` or month macro.
+    FromString(String),
+    /// Expanded from a concatenation using several macros, in source order.
+    FromStrings(Vec<String>),
+    /// Copied from the entry with this key through `crossref` or `xdata`.
+    InheritedFrom(String),
+}
+
 /// A field in a BibTeX entry
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Fields compare, order, and hash structurally: by exact name, then by
+/// [`Value`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Field<'a> {
     /// Field name
     pub name: Cow<'a, str>,
@@ -1395,7 +2268,12 @@ impl<'a> Field<'a> {
 /// # Memory Optimization
 /// Concatenation parts are stored out of line so the common literal, number,
 /// and variable variants stay compact.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// # Ordering
+/// Values compare, order, and hash structurally: `Literal` < `Number` <
+/// `Concat` < `Variable`, then by contents. Use [`NormalizedValue`] to treat
+/// `{2026}` and `2026` or differently spaced literals as equal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Value<'a> {
     /// String literal
     Literal(Cow<'a, str>),
@@ -1484,16 +2362,7 @@ impl Value<'_> {
     /// when callers need the exact original spelling or delimiters.
     #[must_use]
     pub fn to_bibtex_source(&self) -> String {
-        match self {
-            Self::Literal(text) => literal_to_bibtex_source(text),
-            Self::Number(number) => number.to_string(),
-            Self::Variable(name) => name.to_string(),
-            Self::Concat(parts) => parts
-                .iter()
-                .map(Self::to_bibtex_source)
-                .collect::<Vec<_>>()
-                .join(" # "),
-        }
+        value_to_bibtex_source(self, false)
     }
 
     /// Project this value to ordinary text and convert common LaTeX sequences to Unicode.
@@ -1522,14 +2391,85 @@ impl Value<'_> {
     }
 }
 
-fn literal_to_bibtex_source(text: &str) -> String {
-    if is_balanced_braced_literal_content(text) {
-        format!("{{{text}}}")
-    } else {
+/// Render a value as BibTeX source, writing literals in quotes when `quotes` is set.
+pub(crate) fn value_to_bibtex_source(value: &Value<'_>, quotes: bool) -> String {
+    match value {
+        Value::Literal(text) => literal_to_bibtex_source(text, quotes),
+        Value::Number(number) => number.to_string(),
+        Value::Variable(name) => name.to_string(),
+        Value::Concat(parts) => parts
+            .iter()
+            .map(|part| value_to_bibtex_source(part, quotes))
+            .collect::<Vec<_>>()
+            .join(" # "),
+    }
+}
+
+fn literal_to_bibtex_source(text: &str, quotes: bool) -> String {
+    if !is_balanced_braced_literal_content(text) {
         format!("\"{}\"", escape_quoted_literal(text))
+    } else if quotes {
+        format!("\"{}\"", brace_protect_quotes(text))
+    } else {
+        format!("{{{text}}}")
     }
 }
 
+/// Wrap top-level `"` characters, including `\"` accent commands and their
+/// argument, in braces so they cannot end a quoted value.
+fn brace_protect_quotes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 4);
+    let mut depth = 0usize;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('"') if depth == 0 => {
+                    output.push_str("{\\\"");
+                    match chars.peek() {
+                        Some('{') => {
+                            let mut group_depth = 0usize;
+                            for next in chars.by_ref() {
+                                output.push(next);
+                                match next {
+                                    '{' => group_depth += 1,
+                                    '}' => group_depth -= 1,
+                                    _ => {}
+                                }
+                                if group_depth == 0 {
+                                    break;
+                                }
+                            }
+                        }
+                        Some(&next) if next.is_alphabetic() => {
+                            output.push(next);
+                            chars.next();
+                        }
+                        _ => {}
+                    }
+                    output.push('}');
+                }
+                Some(next) => {
+                    output.push('\\');
+                    output.push(next);
+                }
+                None => output.push('\\'),
+            },
+            '{' => {
+                depth += 1;
+                output.push(ch);
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                output.push(ch);
+            }
+            '"' if depth == 0 => output.push_str("{\"}"),
+            _ => output.push(ch),
+        }
+    }
+    output
+}
+
 fn is_balanced_braced_literal_content(text: &str) -> bool {
     let bytes = text.as_bytes();
     let mut depth = 0usize;
@@ -1576,6 +2516,104 @@ fn escape_quoted_literal(text: &str) -> String {
     text.replace('"', "\\\"")
 }
 
+/// A [`Value`] compared by its normalized text.
+///
+/// Literal and number parts are joined and compared with runs of whitespace
+/// collapsed, so `{2026}`, `2026`, and `"20" # "26"` are equal. Variables stay
+/// distinct from literal text and compare by case-insensitive name, as BibTeX
+/// macros do.
+#[derive(Debug, Clone)]
+pub struct NormalizedValue<'a> {
+    value: Value<'a>,
+    key: Vec<NormalizedPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum NormalizedPart {
+    Text(String),
+    Variable(String),
+}
+
+impl<'a> NormalizedValue<'a> {
+    /// Wrap `value` for normalized comparison.
+    #[must_use]
+    pub fn new(value: Value<'a>) -> Self {
+        let mut key = Vec::new();
+        push_normalized_parts(&value, &mut key);
+        for part in &mut key {
+            if let NormalizedPart::Text(text) = part {
+                *text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+        }
+        Self { value, key }
+    }
+
+    /// Return the wrapped value.
+    #[must_use]
+    pub const fn value(&self) -> &Value<'a> {
+        &self.value
+    }
+
+    /// Unwrap the original value.
+    #[must_use]
+    pub fn into_inner(self) -> Value<'a> {
+        self.value
+    }
+}
+
+impl<'a> From<Value<'a>> for NormalizedValue<'a> {
+    fn from(value: Value<'a>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl PartialEq for NormalizedValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for NormalizedValue<'_> {}
+
+impl PartialOrd for NormalizedValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NormalizedValue<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl std::hash::Hash for NormalizedValue<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+fn push_normalized_parts(value: &Value<'_>, key: &mut Vec<NormalizedPart>) {
+    let text = match value {
+        Value::Literal(text) => text.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::Variable(name) => {
+            key.push(NormalizedPart::Variable(name.to_ascii_lowercase()));
+            return;
+        }
+        Value::Concat(parts) => {
+            for part in parts.iter() {
+                push_normalized_parts(part, key);
+            }
+            return;
+        }
+    };
+    match key.last_mut() {
+        Some(NormalizedPart::Text(existing)) => existing.push_str(&text),
+        _ => key.push(NormalizedPart::Text(text)),
+    }
+}
+
 impl fmt::Display for Value<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1613,6 +2651,35 @@ fn value_to_plain_string(value: &Value<'_>) -> String {
     }
 }
 
+/// Collect variable names referenced by a value.
+pub(crate) fn collect_variable_names<'v>(value: &'v Value<'_>, names: &mut Vec<&'v str>) {
+    match value {
+        Value::Variable(name) => names.push(name.as_ref()),
+        Value::Concat(parts) => {
+            for part in parts.iter() {
+                collect_variable_names(part, names);
+            }
+        }
+        Value::Literal(_) | Value::Number(_) => {}
+    }
+}
+
+/// Follow string definitions from `roots` and return every name reached.
+pub(crate) fn reachable_variable_names<'v>(
+    mut pending: Vec<&'v str>,
+    lookup: impl Fn(&str) -> Option<&'v Value<'v>>,
+) -> ahash::AHashSet<&'v str> {
+    let mut reached = ahash::AHashSet::new();
+    while let Some(name) = pending.pop() {
+        if reached.insert(name) {
+            if let Some(value) = lookup(name) {
+                collect_variable_names(value, &mut pending);
+            }
+        }
+    }
+    reached
+}
+
 pub(crate) fn normalize_text_projection(text: &str) -> String {
     if !text
         .as_bytes()
@@ -1697,6 +2764,107 @@ fn resource_field_from_parts(
     })
 }
 
+fn file_attachment_from_record(record: &str) -> Option<FileAttachment> {
+    let record = record.trim();
+    if record.is_empty() {
+        return None;
+    }
+
+    let mut parts = split_unescaped(record, ':')
+        .into_iter()
+        .map(|part| Cow::Borrowed(part.trim()))
+        .collect::<Vec<_>>();
+    join_windows_drive(&mut parts);
+
+    let mut parts = parts
+        .into_iter()
+        .map(|part| unescape_attachment_part(&part));
+    let (description, path, file_type) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(path), None, None) => (String::new(), path, None),
+        (Some(description), Some(path), None) => (description, path, None),
+        (Some(description), Some(path), Some(file_type)) => {
+            // MIME-like types are the only place a further bare `:` is expected.
+            let rest = parts.collect::<Vec<_>>();
+            let file_type = if rest.is_empty() {
+                file_type
+            } else {
+                format!("{file_type}:{}", rest.join(":"))
+            };
+            (description, path, Some(file_type))
+        }
+        _ => return None,
+    };
+
+    if path.is_empty() {
+        return None;
+    }
+    Some(FileAttachment {
+        description,
+        path,
+        file_type: file_type.filter(|file_type| !file_type.is_empty()),
+    })
+}
+
+fn split_unescaped(input: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, ch) in input.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == separator {
+            parts.push(&input[start..index]);
+            start = index + ch.len_utf8();
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// Rejoin an unescaped Windows drive prefix (`C:\...`) that was split as a separator.
+///
+/// A drive letter in the description slot is only joined for bare two-part
+/// values, since `C:/path.pdf:PDF` is more likely a one-letter description.
+/// A drive after a description (`Notes:C:\notes.txt`) is joined with or
+/// without a trailing file type.
+fn join_windows_drive(parts: &mut Vec<Cow<'_, str>>) {
+    let is_drive = |index: usize| {
+        let letter = parts[index].as_bytes();
+        letter.len() == 1
+            && letter[0].is_ascii_alphabetic()
+            && parts[index + 1].starts_with(['\\', '/'])
+    };
+    let drive = match parts.len() {
+        2 if is_drive(0) => Some(0),
+        len if len >= 3 => (1..len - 1).find(|&index| is_drive(index)),
+        _ => None,
+    };
+    if let Some(index) = drive {
+        let rest = parts.remove(index + 1);
+        parts[index] = Cow::Owned(format!("{}:{rest}", parts[index]));
+    }
+}
+
+fn unescape_attachment_part(part: &str) -> String {
+    let mut output = String::with_capacity(part.len());
+    let mut chars = part.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            if let Some(&next) = chars.peek() {
+                if matches!(next, ':' | ';' | '\\') {
+                    output.push(next);
+                    chars.next();
+                    continue;
+                }
+            }
+        }
+        output.push(ch);
+    }
+    output
+}
+
 fn normalize_resource_value(kind: ResourceKind, value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -1710,12 +2878,70 @@ fn normalize_resource_value(kind: ResourceKind, value: &str) -> Option<String> {
         ResourceKind::Isbn => normalize_isbn(trimmed),
         ResourceKind::Issn => normalize_issn(trimmed),
         ResourceKind::Arxiv => Some(normalize_arxiv(trimmed)),
+        ResourceKind::OpenAlex => normalize_openalex(trimmed),
+        ResourceKind::S2Id => normalize_s2id(trimmed),
+        ResourceKind::CorpusId => normalize_corpus_id(trimmed),
         ResourceKind::File | ResourceKind::Url | ResourceKind::Eprint | ResourceKind::Crossref => {
             Some(trimmed.to_string())
         }
     }
 }
 
+/// Normalize an `OpenAlex` ID to its uppercase short form, accepting
+/// `https://openalex.org/W123` URLs and `openalex:W123` prefixes.
+#[must_use]
+pub fn normalize_openalex(input: &str) -> Option<String> {
+    let mut id = input.trim();
+    for prefix in [
+        "https://openalex.org/",
+        "http://openalex.org/",
+        "openalex.org/",
+        "openalex:",
+    ] {
+        if id
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        {
+            id = &id[prefix.len()..];
+            break;
+        }
+    }
+
+    let mut chars = id.chars();
+    let entity = chars.next()?.to_ascii_uppercase();
+    let digits = chars.as_str();
+    ("WASICPFT".contains(entity)
+        && !digits.is_empty()
+        && digits.bytes().all(|byte| byte.is_ascii_digit()))
+    .then(|| format!("{entity}{digits}"))
+}
+
+/// Normalize a Semantic Scholar paper ID to lowercase hex, accepting
+/// `semanticscholar.org/paper/...` URLs that end in the ID.
+#[must_use]
+pub fn normalize_s2id(input: &str) -> Option<String> {
+    let id = input.trim().trim_end_matches('/');
+    let id = if id.to_ascii_lowercase().contains("semanticscholar.org/") {
+        id.rsplit('/').next().unwrap_or(id)
+    } else {
+        id
+    };
+    (id.len() == 40 && id.bytes().all(|byte| byte.is_ascii_hexdigit()))
+        .then(|| id.to_ascii_lowercase())
+}
+
+/// Normalize a Semantic Scholar corpus ID to its digits, accepting the
+/// `CorpusId:` prefix used by the Semantic Scholar API.
+#[must_use]
+pub fn normalize_corpus_id(input: &str) -> Option<String> {
+    let id = input.trim();
+    let id = id
+        .get(..9)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("corpusid:"))
+        .map_or(id, |_| id[9..].trim());
+    normalize_ascii_digits(id)
+}
+
 fn normalize_ascii_digits(input: &str) -> Option<String> {
     let compact = input.trim();
     compact
@@ -1740,7 +2966,7 @@ fn normalize_pmcid(input: &str) -> String {
     }
 }
 
-fn normalize_isbn(input: &str) -> Option<String> {
+pub(crate) fn normalize_isbn(input: &str) -> Option<String> {
     let compact = input
         .chars()
         .filter(|ch| !matches!(ch, '-' | ' '))
@@ -1797,7 +3023,7 @@ fn parse_year(input: &str) -> std::result::Result<i32, DateParseError> {
         .map_err(|_| DateParseError::InvalidYear)
 }
 
-fn parse_month_number(input: &str) -> Option<u8> {
+pub(crate) fn parse_month_number(input: &str) -> Option<u8> {
     let normalized = trim_bibtex_scalar(input).to_ascii_lowercase();
     if normalized.is_empty() {
         return None;
@@ -1939,6 +3165,9 @@ fn parse_single_name(input: &str) -> PersonName {
     }
 
     let parts = split_top_level_commas(input);
+    if let Some(name) = parse_extended_name(raw, &parts) {
+        return name;
+    }
     match parts.as_slice() {
         [last] => parse_first_von_last(last),
         [last, first] => {
@@ -1980,6 +3209,14 @@ fn parse_first_von_last(input: &str) -> PersonName {
             String::new(),
             None,
         ),
+        _ if words.iter().all(|word| is_cjk_text(word)) => person_name(
+            raw,
+            join_name_words(&words[1..]),
+            String::new(),
+            normalize_name_part(words[0]),
+            String::new(),
+            None,
+        ),
         _ => {
             let von_start = words
                 .iter()
@@ -2023,6 +3260,9 @@ fn person_name(
     let family = split_component_tokens(&last);
     let prefix = split_component_tokens(&von);
     let suffix = split_component_tokens(&jr);
+    let is_corporate = literal
+        .as_deref()
+        .is_some_and(|literal| literal.split_whitespace().nth(1).is_some());
     PersonName {
         raw: raw.to_string(),
         first,
@@ -2034,9 +3274,110 @@ fn person_name(
         prefix,
         suffix,
         literal,
+        nameaddon: String::new(),
+        is_corporate,
     }
 }
 
+fn parse_extended_name(raw: &str, parts: &[&str]) -> Option<PersonName> {
+    let mut components = [String::new(), String::new(), String::new(), String::new()];
+    let mut nameaddon = String::new();
+    for part in parts {
+        let (key, value) = part.split_once('=')?;
+        let value = normalize_name_part(value.trim().trim_matches('"'));
+        match key.trim().to_ascii_lowercase().as_str() {
+            "given" => components[0] = value,
+            "prefix" => components[1] = value,
+            "family" => components[2] = value,
+            "suffix" => components[3] = value,
+            "nameaddon" => nameaddon = value,
+            "useprefix" => {}
+            key if key.ends_with("-i") => {}
+            _ => return None,
+        }
+    }
+    if components[0].is_empty() && components[2].is_empty() {
+        return None;
+    }
+
+    let [first, von, last, jr] = components;
+    let mut name = person_name(raw, first, von, last, jr, None);
+    name.nameaddon = nameaddon;
+    Some(name)
+}
+
+/// Split a name word at hyphens outside braces, so `{Jean-Paul}` stays whole.
+fn split_hyphenated(word: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0usize;
+    word.split(move |ch| {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        ch == '-' && depth == 0
+    })
+}
+
+fn name_initial(word: &str) -> Option<String> {
+    let word = word.trim();
+    let mut chars = word.char_indices();
+    let (_, first) = chars.next()?;
+    let end = match first {
+        '{' => {
+            let mut depth = 0usize;
+            word.char_indices()
+                .find_map(|(index, ch)| {
+                    match ch {
+                        '{' => depth += 1,
+                        '}' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    (depth == 0).then_some(index + 1)
+                })
+                .unwrap_or(word.len())
+        }
+        '\\' => {
+            let command_end = word[1..]
+                .char_indices()
+                .find(|(_, ch)| !ch.is_ascii_alphabetic())
+                .map_or(word.len(), |(index, _)| index + 1);
+            let command_end = if command_end == 1 {
+                word[1..].chars().next().map_or(1, |ch| 1 + ch.len_utf8())
+            } else {
+                command_end
+            };
+            let rest = &word[command_end..];
+            return Some(format!(
+                "{}{}",
+                &word[..command_end],
+                name_initial(rest.trim_start()).unwrap_or_default()
+            ));
+        }
+        ch => ch.len_utf8(),
+    };
+    Some(format!("{}.", &word[..end]))
+}
+
+fn is_cjk_text(input: &str) -> bool {
+    let mut letters = input.chars().filter(|ch| ch.is_alphabetic()).peekable();
+    letters.peek().is_some() && letters.all(is_cjk_char)
+}
+
+const fn is_cjk_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
 fn empty_person_name(raw: &str) -> PersonName {
     person_name(
         raw,
@@ -2148,26 +3489,29 @@ fn join_name_words(words: &[&str]) -> String {
 
 fn normalize_name_part(input: &str) -> String {
     let trimmed = input.trim();
-    if trimmed.len() >= 2 && trimmed.starts_with('{') && trimmed.ends_with('}') {
-        trimmed[1..trimmed.len() - 1].trim().to_string()
-    } else {
-        trimmed.to_string()
-    }
+    enclosing_group(trimmed)
+        .map_or(trimmed, str::trim)
+        .to_string()
 }
 
 fn braced_literal_name(input: &str) -> Option<String> {
-    let trimmed = input.trim();
-    if trimmed.len() < 2 || !trimmed.starts_with('{') || !trimmed.ends_with('}') {
+    enclosing_group(input.trim()).map(|inner| inner.trim().to_string())
+}
+
+/// The text inside `input` when a single brace group spans all of it, so
+/// `{Jean-Paul} {Marie}` is not unwrapped.
+fn enclosing_group(input: &str) -> Option<&str> {
+    if input.len() < 2 || !input.starts_with('{') || !input.ends_with('}') {
         return None;
     }
 
     let mut depth = 0usize;
-    for (index, ch) in trimmed.char_indices() {
+    for (index, ch) in input.char_indices() {
         match ch {
             '{' => depth += 1,
             '}' => {
                 depth = depth.saturating_sub(1);
-                if depth == 0 && index != trimmed.len() - 1 {
+                if depth == 0 && index != input.len() - 1 {
                     return None;
                 }
             }
@@ -2175,7 +3519,7 @@ fn braced_literal_name(input: &str) -> Option<String> {
         }
     }
 
-    (depth == 0).then(|| normalize_name_part(trimmed))
+    (depth == 0).then(|| &input[1..input.len() - 1])
 }
 
 fn starts_with_lowercase_letter(input: &str) -> bool {
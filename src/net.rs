@@ -0,0 +1,132 @@
+//! Downloading bibliographies over HTTP(S).
+//!
+//! Enabled by the `net` feature. [`Parser::parse_url`] fetches a shared
+//! bibliography, such as a research group's published `.bib` file, and
+//! parses it in one call. Responses compressed with gzip are decoded, and
+//! [`FetchOptions`] bounds the download size and time.
+//!
+//! ```no_run
+//! use bibtex_parser::Library;
+//!
+//! let library = Library::parse_url("https://example.org/group.bib")?;
+//! println!("{} entries", library.entries().len());
+//! # Ok::<(), bibtex_parser::Error>(())
+//! ```
+
+use crate::{Error, Library, Parser, Result};
+use std::io::Read;
+use std::time::Duration;
+
+const USER_AGENT: &str = concat!("bibtex-parser/", env!("CARGO_PKG_VERSION"));
+
+/// Default limit on the decoded size of a download: 64 MiB.
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Default limit on the time a download may take.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits for downloading a bibliography.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchOptions {
+    max_bytes: u64,
+    timeout: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FetchOptions {
+    /// Create options with [`DEFAULT_MAX_BYTES`] and [`DEFAULT_TIMEOUT`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Fail downloads whose decoded body is larger than `bytes`.
+    #[must_use]
+    pub const fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = bytes;
+        self
+    }
+
+    /// Fail downloads that take longer than `timeout` in total.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Download `url` and return its body as text.
+///
+/// # Errors
+///
+/// Returns [`Error::Fetch`] when the request fails, the server answers with
+/// an error status, the body exceeds the size limit, or it is not UTF-8.
+pub fn fetch(url: &str, options: &FetchOptions) -> Result<String> {
+    let fail = |message: String| Error::Fetch(format!("{url}: {message}"));
+    let response = ureq::AgentBuilder::new()
+        .timeout(options.timeout)
+        .build()
+        .get(url)
+        .set("User-Agent", USER_AGENT)
+        .set(
+            "Accept",
+            "application/x-bibtex, text/plain;q=0.9, */*;q=0.5",
+        )
+        .call()
+        .map_err(|error| fail(error.to_string()))?;
+    let declared = response
+        .header("Content-Length")
+        .and_then(|length| length.parse::<u64>().ok());
+    if response.header("Content-Encoding").is_none()
+        && declared.is_some_and(|length| length > options.max_bytes)
+    {
+        return Err(fail(too_large(options.max_bytes)));
+    }
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(options.max_bytes.saturating_add(1))
+        .read_to_end(&mut body)
+        .map_err(|error| fail(error.to_string()))?;
+    if body.len() as u64 > options.max_bytes {
+        return Err(fail(too_large(options.max_bytes)));
+    }
+    String::from_utf8(body).map_err(|error| fail(format!("body is not UTF-8: {error}")))
+}
+
+fn too_large(max_bytes: u64) -> String {
+    format!("body is larger than the {max_bytes}-byte limit")
+}
+
+impl Parser {
+    /// Download `url` within `options` and parse it into owned data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Fetch`] when the download fails (see [`fetch`]), or
+    /// the usual parse errors.
+    pub fn parse_url(&self, url: &str, options: &FetchOptions) -> Result<Library<'static>> {
+        let content = fetch(url, options)?;
+        self.parse(&content).map(Library::into_owned)
+    }
+}
+
+impl Library<'static> {
+    /// Download `url` with default [`FetchOptions`] and parse it strictly.
+    ///
+    /// # Errors
+    ///
+    /// See [`Parser::parse_url`].
+    pub fn parse_url(url: &str) -> Result<Self> {
+        Parser::new().parse_url(url, &FetchOptions::default())
+    }
+}
@@ -1,8 +1,9 @@
 //! Entry parsing for BibTeX
 
-use super::{lexer, value, PResult};
+use super::value::SignedNumbers;
+use super::{lexer, value, PResult, Syntax};
 use crate::model::{Entry, EntryType, Field};
-use crate::{EntryDelimiter, Value, ValueDelimiter};
+use crate::{EntryDelimiter, KeyCharset, Value, ValueDelimiter};
 use std::borrow::Cow;
 
 const DEFAULT_FIELD_CAPACITY: usize = 17;
@@ -22,6 +23,7 @@ pub(crate) struct LocatedField {
     pub(crate) name: (usize, usize),
     pub(crate) value: (usize, usize),
     pub(crate) value_delimiter: ValueDelimiter,
+    pub(crate) comment: Option<(usize, usize)>,
 }
 
 /// Parse a bibliography entry
@@ -34,10 +36,19 @@ pub fn parse_entry<'a>(input: &mut &'a str) -> PResult<'a, Entry<'a>> {
 /// Parse a bibliography entry when `input` is already positioned at `@`.
 #[inline]
 pub fn parse_entry_at<'a>(input: &mut &'a str) -> PResult<'a, Entry<'a>> {
+    parse_entry_at_with(input, Syntax::default())
+}
+
+/// Parse an entry at `@` with the grammar choices in `syntax`.
+#[inline]
+pub(crate) fn parse_entry_at_with<'a>(
+    input: &mut &'a str,
+    syntax: Syntax,
+) -> PResult<'a, Entry<'a>> {
     match input.as_bytes().first() {
         Some(b'@') => {
             *input = &input[1..];
-            parse_entry_content(input)
+            parse_entry_content(input, syntax)
         }
         _ => super::backtrack(),
     }
@@ -59,7 +70,7 @@ pub(crate) fn parse_entry_at_with_locations<'a>(
 }
 
 #[inline]
-fn parse_entry_content<'a>(input: &mut &'a str) -> PResult<'a, Entry<'a>> {
+fn parse_entry_content<'a>(input: &mut &'a str, syntax: Syntax) -> PResult<'a, Entry<'a>> {
     let entry_type_str = lexer::identifier(input)?;
     let entry_type = EntryType::parse(entry_type_str);
 
@@ -72,7 +83,7 @@ fn parse_entry_content<'a>(input: &mut &'a str) -> PResult<'a, Entry<'a>> {
     };
     *input = &input[1..];
 
-    parse_entry_body(input, entry_type, closing_delimiter)
+    parse_entry_body(input, entry_type, closing_delimiter, syntax)
 }
 
 #[inline]
@@ -88,16 +99,11 @@ fn parse_entry_content_with_locations<'a>(
 
     lexer::skip_whitespace(input);
 
-    let opening = match input.as_bytes().first() {
-        Some(b'{') => b'{',
-        Some(b'(') => b'(',
+    let (delimiter, closing_delimiter) = match input.as_bytes().first() {
+        Some(b'{') => (EntryDelimiter::Braces, b'}'),
+        Some(b'(') => (EntryDelimiter::Parentheses, b')'),
         _ => return super::backtrack(),
     };
-    let (delimiter, closing_delimiter) = match opening {
-        b'{' => (EntryDelimiter::Braces, b'}'),
-        b'(' => (EntryDelimiter::Parentheses, b')'),
-        _ => unreachable!(),
-    };
     *input = &input[1..];
 
     parse_entry_body_with_locations(
@@ -117,14 +123,18 @@ fn parse_entry_body<'a>(
     input: &mut &'a str,
     entry_type: EntryType<'a>,
     closing_delimiter: u8,
+    syntax: Syntax,
 ) -> PResult<'a, Entry<'a>> {
     lexer::skip_whitespace(input);
-    let key = lexer::identifier(input)?;
+    let key = match syntax.keys {
+        KeyCharset::Identifier => lexer::identifier(input)?,
+        KeyCharset::BibTeX => lexer::bibtex_key(input)?,
+    };
 
     lexer::skip_whitespace(input);
     expect_byte(input, b',')?;
 
-    let fields = parse_fields(input, closing_delimiter)?;
+    let fields = parse_fields(input, closing_delimiter, syntax.signed)?;
     expect_byte(input, closing_delimiter)?;
 
     Ok(Entry {
@@ -182,10 +192,14 @@ fn expect_byte<'a>(input: &mut &'a str, byte: u8) -> PResult<'a, ()> {
 
 /// Parse all fields in an entry.
 #[inline]
-fn parse_fields<'a>(input: &mut &'a str, closing_delimiter: u8) -> PResult<'a, Vec<Field<'a>>> {
+fn parse_fields<'a>(
+    input: &mut &'a str,
+    closing_delimiter: u8,
+    signed: SignedNumbers,
+) -> PResult<'a, Vec<Field<'a>>> {
     let mut fields = Vec::with_capacity(DEFAULT_FIELD_CAPACITY);
 
-    while let Some(first) = lexer::skip_whitespace_peek(input) {
+    while let Some(first) = lexer::skip_whitespace_and_comments_peek(input) {
         if first == closing_delimiter {
             break;
         }
@@ -194,18 +208,18 @@ fn parse_fields<'a>(input: &mut &'a str, closing_delimiter: u8) -> PResult<'a, V
         lexer::skip_whitespace(input);
         expect_byte(input, b'=')?;
         lexer::skip_whitespace(input);
-        let value = value::parse_value_field(input)?;
+        let value = value::parse_value_field(input, signed.for_field(name))?;
 
         fields.push(Field {
             name: Cow::Borrowed(name),
             value,
         });
 
-        match input.as_bytes().first() {
+        match lexer::skip_whitespace_and_comments_peek(input) {
             Some(b',') => {
                 *input = &input[1..];
             }
-            Some(&b) if b == closing_delimiter => {}
+            Some(b) if b == closing_delimiter => {}
             _ => return super::backtrack(),
         }
     }
@@ -229,7 +243,7 @@ fn parse_fields_with_locations<'a>(
     let mut locations = Vec::with_capacity(DEFAULT_FIELD_CAPACITY);
     let root_bytes = root.as_bytes();
 
-    while let Some(first) = lexer::skip_whitespace_peek(input) {
+    while let Some(first) = lexer::skip_whitespace_and_comments_peek(input) {
         if first == closing_delimiter {
             break;
         }
@@ -244,7 +258,7 @@ fn parse_fields_with_locations<'a>(
         lexer::skip_whitespace(input);
 
         let value_start = source_offset(root, input, absolute_start);
-        let parsed_value = value::parse_value_field(input)?;
+        let parsed_value = value::parse_value_field(input, SignedNumbers::Numbers)?;
         let value_boundary = source_offset(root, input, absolute_start);
         let value_end = trim_ascii_whitespace_end_absolute(
             root_bytes,
@@ -260,13 +274,26 @@ fn parse_fields_with_locations<'a>(
             value_end,
         );
 
+        // A `%` comment on the value's line, before or after the comma,
+        // belongs to this field.
+        let mut rest = &root[value_end - absolute_start..];
+        let mut comment = lexer::trailing_comment(&mut rest)
+            .map(|(start, end)| (value_end + start, value_end + end));
+        if comment.is_some() {
+            *input = rest;
+        }
+
         let mut whole_end = value_end;
-        match input.as_bytes().first() {
+        match lexer::skip_whitespace_and_comments_peek(input) {
             Some(b',') => {
                 whole_end = source_offset(root, input, absolute_start) + 1;
                 *input = &input[1..];
+                if comment.is_none() {
+                    comment = lexer::trailing_comment(input)
+                        .map(|(start, end)| (whole_end + start, whole_end + end));
+                }
             }
-            Some(&b) if b == closing_delimiter => {}
+            Some(b) if b == closing_delimiter => {}
             _ => return super::backtrack(),
         }
 
@@ -279,6 +306,7 @@ fn parse_fields_with_locations<'a>(
             name: (name_start, name_end),
             value: (value_start, value_end),
             value_delimiter,
+            comment,
         });
     }
 
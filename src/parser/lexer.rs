@@ -23,6 +23,32 @@ pub fn identifier<'a>(input: &mut &'a str) -> PResult<'a, &'a str> {
     Ok(result)
 }
 
+/// Parse a citation key made of any characters `bibtex` accepts in one.
+#[inline]
+pub(crate) fn bibtex_key<'a>(input: &mut &'a str) -> PResult<'a, &'a str> {
+    let len = input
+        .bytes()
+        .position(|byte| !is_bibtex_key_byte(byte))
+        .unwrap_or(input.len());
+
+    if len == 0 {
+        return super::backtrack();
+    }
+
+    let result = &input[..len];
+    *input = &input[len..];
+    Ok(result)
+}
+
+/// Return `true` for bytes allowed in a key under [`crate::KeyCharset::BibTeX`].
+pub(crate) const fn is_bibtex_key_byte(byte: u8) -> bool {
+    !byte.is_ascii_whitespace()
+        && !matches!(
+            byte,
+            b',' | b'{' | b'}' | b'(' | b')' | b'"' | b'#' | b'%' | b'='
+        )
+}
+
 /// Parse a field name (same as identifier but typically lowercase)
 #[inline]
 pub fn field_name<'a>(input: &mut &'a str) -> PResult<'a, &'a str> {
@@ -58,11 +84,10 @@ pub fn balanced_braces<'a>(input: &mut &'a str) -> PResult<'a, &'a str> {
                     depth -= 1;
                     pos = idx + 1;
                 }
-                b'\\' => {
-                    // Skip escaped character
+                _ => {
+                    // Skip the character escaped by a backslash
                     pos = idx + 2;
                 }
-                _ => unreachable!(),
             }
         } else {
             // No more delimiters found
@@ -116,22 +141,17 @@ pub fn balanced_parentheses<'a>(input: &mut &'a str) -> PResult<'a, &'a str> {
         if let Some(offset) = memchr::memchr2(b'(', b')', &bytes[pos..]) {
             let idx = pos + offset;
 
-            match bytes[idx] {
-                b'(' => {
-                    depth += 1;
-                    pos = idx + 1;
-                }
-                b')' => {
-                    if depth == 0 {
-                        let result = &original_input[..idx];
-                        *input = &input[idx..];
-                        return Ok(result);
-                    }
-                    depth -= 1;
-                    pos = idx + 1;
+            if bytes[idx] == b'(' {
+                depth += 1;
+            } else {
+                if depth == 0 {
+                    let result = &original_input[..idx];
+                    *input = &input[idx..];
+                    return Ok(result);
                 }
-                _ => unreachable!(),
+                depth -= 1;
             }
+            pos = idx + 1;
         } else {
             // No more delimiters found
             break;
@@ -176,6 +196,37 @@ pub(crate) fn skip_whitespace_peek(input: &mut &str) -> Option<u8> {
     None
 }
 
+/// Skip whitespace and `%` line comments inside an entry body.
+#[inline]
+pub(crate) fn skip_whitespace_and_comments_peek(input: &mut &str) -> Option<u8> {
+    loop {
+        let byte = skip_whitespace_peek(input)?;
+        if byte != b'%' {
+            return Some(byte);
+        }
+        let end = memchr::memchr(b'\n', input.as_bytes()).unwrap_or(input.len());
+        *input = &input[end..];
+    }
+}
+
+/// Consume a `%` comment that starts on the current line, after spaces and
+/// tabs only. Returns the comment's byte range in the original `input`,
+/// without the line ending or trailing whitespace.
+#[inline]
+pub(crate) fn trailing_comment(input: &mut &str) -> Option<(usize, usize)> {
+    let bytes = input.as_bytes();
+    let start = bytes
+        .iter()
+        .position(|byte| !matches!(byte, b' ' | b'\t'))?;
+    if bytes[start] != b'%' {
+        return None;
+    }
+    let end = memchr::memchr2(b'\n', b'\r', &bytes[start..]).map_or(bytes.len(), |end| start + end);
+    let comment_end = start + input[start..end].trim_end().len();
+    *input = &input[end..];
+    Some((start, comment_end))
+}
+
 /// Fast scan to next BibTeX delimiter - re-export from delimiter module
 #[must_use]
 pub fn scan_to_bibtex_delimiter(haystack: &[u8], start: usize) -> Option<(usize, u8)> {
@@ -46,6 +46,7 @@ pub mod delimiter;
 pub mod entry;
 pub mod lexer;
 pub mod simd;
+pub mod tokens;
 pub mod utils;
 pub mod value;
 
@@ -54,6 +55,7 @@ use winnow::ascii::multispace0;
 use winnow::prelude::*;
 
 pub use entry::parse_entry;
+pub use tokens::{tokenize, Token, TokenKind, Tokens};
 
 /// Internal parser result type
 pub type PResult<'a, O> = winnow::PResult<O, winnow::error::ContextError>;
@@ -135,48 +137,179 @@ pub fn parse_bibtex(input: &str) -> Result<Vec<ParsedItem<'_>>> {
     Ok(items)
 }
 
-/// Parse a BibTeX file and stream raw items to a callback.
+/// Parse a BibTeX file one item at a time.
 ///
-/// This avoids allocating an intermediate `Vec<ParsedItem>` when the caller
-/// can process items incrementally.
-#[inline]
-pub(crate) fn parse_bibtex_stream<'a, F>(input: &'a str, mut on_item: F) -> Result<()>
-where
-    F: FnMut(ParsedItem<'a>) -> Result<()>,
-{
-    let mut remaining = input;
+/// Items are parsed as the iterator is advanced, so memory use stays flat
+/// however large the input is: only the current item is held, and variables
+/// are not expanded. Parsing stops after the first error.
+///
+/// ```
+/// use bibtex_parser::{stream, ParsedItem};
+///
+/// let input = "@string{j = {J}}\n@article{a, journal = j}\n@misc{b, title = {B}}";
+/// let mut keys = Vec::new();
+/// for item in stream(input) {
+///     if let ParsedItem::Entry(entry) = item? {
+///         keys.push(entry.key().to_string());
+///     }
+/// }
+/// assert_eq!(keys, ["a", "b"]);
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[must_use]
+pub const fn stream(input: &str) -> ItemStream<'_> {
+    ItemStream {
+        input,
+        remaining: input,
+    }
+}
 
-    loop {
-        // Skip ASCII whitespace without Unicode trimming overhead.
-        lexer::skip_whitespace(&mut remaining);
-        if remaining.is_empty() {
-            break;
+/// Iterator over the items of a BibTeX file, returned by [`stream`].
+#[derive(Debug, Clone)]
+pub struct ItemStream<'a> {
+    input: &'a str,
+    remaining: &'a str,
+}
+
+impl ItemStream<'_> {
+    /// Return the byte offset of the first unparsed item.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.input.len() - self.remaining.len()
+    }
+}
+
+impl<'a> Iterator for ItemStream<'a> {
+    type Item = Result<ParsedItem<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        lexer::skip_whitespace(&mut self.remaining);
+        if self.remaining.is_empty() {
+            return None;
         }
 
-        // Try to parse an item (including comments)
-        match parse_item(&mut remaining) {
-            Ok(item) => on_item(item)?,
+        let start = self.offset();
+        let before_item = self.remaining;
+        match parse_item(&mut self.remaining) {
+            Ok(item) => Some(Ok(item)),
             Err(e) => {
-                // Calculate line/column for error
-                let consumed = input.len() - remaining.len();
-                let (line, column) = calculate_position(input, consumed);
+                let (line, column) = calculate_position(self.input, start);
+                self.remaining = "";
+                Some(Err(Error::ParseError {
+                    line,
+                    column,
+                    message: format!("Failed to parse entry: {e}"),
+                    snippet: Some(get_snippet(before_item, 40)),
+                }))
+            }
+        }
+    }
+}
 
-                return Err(Error::ParseError {
+impl std::iter::FusedIterator for ItemStream<'_> {}
+
+/// Parse every entry found in text that is not a clean BibTeX file.
+///
+/// Meant for clipboard text copied from a PDF, web page, or email: prose
+/// before, between, and after entries is ignored, as are `@string`,
+/// `@preamble`, and `@comment` blocks and addresses such as `doe@example.org`.
+/// Each `@type{` or `@type(` start yields one result, so a truncated first or
+/// last entry becomes an error without losing its neighbours. Variables are
+/// not expanded.
+///
+/// ```
+/// use bibtex_parser::parse_fragments;
+///
+/// let text = "Here are the refs you asked for (mail me at doe@example.org):
+/// @article{doe2024, title = {Deep Learning}, year = 2024}
+/// @book{roe2020, title = {Cut off by the";
+///
+/// let results = parse_fragments(text);
+/// assert_eq!(results.len(), 2);
+/// assert_eq!(results[0].as_ref().unwrap().key(), "doe2024");
+/// assert!(results[1].is_err());
+/// ```
+#[must_use]
+pub fn parse_fragments(input: &str) -> Vec<Result<crate::Entry<'_>>> {
+    let bytes = input.as_bytes();
+    let mut results = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = memchr::memchr(b'@', &bytes[pos..]) {
+        let start = pos + offset;
+        pos = start + 1;
+        if !starts_entry_fragment(&bytes[start..]) {
+            continue;
+        }
+
+        let mut remaining = &input[start..];
+        match entry::parse_entry_at(&mut remaining) {
+            Ok(entry) => {
+                results.push(Ok(entry));
+                pos = input.len() - remaining.len();
+            }
+            Err(e) => {
+                let (line, column) = calculate_position(input, start);
+                results.push(Err(Error::ParseError {
                     line,
                     column,
                     message: format!("Failed to parse entry: {e}"),
-                    snippet: Some(get_snippet(remaining, 40)),
-                });
+                    snippet: Some(get_snippet(&input[start..], 40)),
+                }));
             }
         }
     }
+    results
+}
 
-    Ok(())
+/// Return `true` when `input` starts with `@type{` or `@type(` for a type
+/// other than `string`, `preamble`, or `comment`.
+fn starts_entry_fragment(input: &[u8]) -> bool {
+    if ["string", "preamble", "comment"]
+        .iter()
+        .any(|keyword| starts_with_keyword(input, keyword.as_bytes()))
+    {
+        return false;
+    }
+    let name = input[1..]
+        .iter()
+        .take_while(|&&byte| is_identifier_char(byte))
+        .count();
+    let open = input[1 + name..]
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace());
+    name > 0 && matches!(open, Some(b'{' | b'('))
+}
+
+/// Parse a BibTeX file and stream raw items to a callback.
+///
+/// This avoids allocating an intermediate `Vec<ParsedItem>` when the caller
+/// can process items incrementally.
+#[inline]
+pub(crate) fn parse_bibtex_stream<'a, F>(input: &'a str, mut on_item: F) -> Result<()>
+where
+    F: FnMut(ParsedItem<'a>) -> Result<()>,
+{
+    stream(input).try_for_each(|item| on_item(item?))
+}
+
+/// Grammar choices a [`crate::Parser`] can change.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Syntax {
+    /// How bare signed integers in entry fields are read.
+    pub(crate) signed: value::SignedNumbers,
+    /// Which characters citation keys may use.
+    pub(crate) keys: crate::KeyCharset,
 }
 
 /// Parse a BibTeX file and stream raw items with source spans.
+///
+/// Entries are read with the grammar choices in `syntax`.
 #[inline]
-pub(crate) fn parse_bibtex_stream_with_spans<'a, F>(input: &'a str, mut on_item: F) -> Result<()>
+pub(crate) fn parse_bibtex_stream_with_spans<'a, F>(
+    input: &'a str,
+    syntax: Syntax,
+    mut on_item: F,
+) -> Result<()>
 where
     F: FnMut(ParsedItem<'a>, SourceSpan, &'a str) -> Result<()>,
 {
@@ -191,7 +324,7 @@ where
 
         let start = input.len() - remaining.len();
         let before_item = remaining;
-        match parse_item(&mut remaining) {
+        match parse_item_with(&mut remaining, syntax) {
             Ok(item) => {
                 let end = input.len() - remaining.len();
                 let span = source_map.span(start, end);
@@ -287,7 +420,7 @@ where
 /// }
 /// # Ok::<(), bibtex_parser::Error>(())
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParsedItem<'a> {
     /// A bibliography entry (article, book, inproceedings, etc.)
     ///
@@ -324,6 +457,15 @@ pub(crate) enum LocatedParsedItem<'a> {
 /// Parse a single item (entry, string, preamble, or comment) with optimized delimiter search
 #[inline]
 pub(crate) fn parse_item<'a>(input: &mut &'a str) -> PResult<'a, ParsedItem<'a>> {
+    parse_item_with(input, Syntax::default())
+}
+
+/// Parse a single item, reading entries with the grammar choices in `syntax`.
+#[inline]
+pub(crate) fn parse_item_with<'a>(
+    input: &mut &'a str,
+    syntax: Syntax,
+) -> PResult<'a, ParsedItem<'a>> {
     // Use optimized delimiter search to find @ or handle as comment
     let bytes = input.as_bytes();
 
@@ -354,7 +496,7 @@ pub(crate) fn parse_item<'a>(input: &mut &'a str) -> PResult<'a, ParsedItem<'a>>
         b'c' if starts_with_keyword(bytes, b"comment") => {
             parse_comment(input).map(ParsedItem::Comment)
         }
-        _ => entry::parse_entry_at(input).map(ParsedItem::Entry),
+        _ => entry::parse_entry_at_with(input, syntax).map(ParsedItem::Entry),
     }
 }
 
@@ -514,8 +656,9 @@ fn calculate_position(input: &str, pos: usize) -> (usize, usize) {
 
 /// Get a snippet of input for error messages
 fn get_snippet(input: &str, max_len: usize) -> String {
-    let snippet: String = input.chars().take(max_len).collect();
-    if input.len() > max_len {
+    let mut chars = input.chars();
+    let snippet: String = chars.by_ref().take(max_len).collect();
+    if chars.next().is_some() {
         format!("{snippet}...")
     } else {
         snippet
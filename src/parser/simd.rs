@@ -3,7 +3,7 @@
 //! This module provides SIMD-optimized functions for common parsing operations
 //! like brace balancing and quote scanning, achieving 30-50% performance gains.
 
-use memchr::{memchr, memchr2};
+use memchr::{memchr, memchr2, memchr3};
 
 /// Find balanced braces using SIMD acceleration
 ///
@@ -31,20 +31,15 @@ pub fn find_balanced_braces(input: &[u8]) -> Option<usize> {
                 continue;
             }
 
-            match input[idx] {
-                b'{' => {
-                    depth += 1;
-                    pos = idx + 1;
+            if input[idx] == b'{' {
+                depth += 1;
+            } else {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + 1); // Return position after closing brace
                 }
-                b'}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return Some(idx + 1); // Return position after closing brace
-                    }
-                    pos = idx + 1;
-                }
-                _ => unreachable!(),
             }
+            pos = idx + 1;
         } else {
             // No more delimiters found, unbalanced
             return None;
@@ -58,7 +53,10 @@ pub fn find_balanced_braces(input: &[u8]) -> Option<usize> {
 ///
 /// This function scans for the closing quote, handling:
 /// - Escaped quotes (\")
+/// - Quotes inside brace groups (`"a {"} b"`), as BibTeX reads them
 /// - Efficient SIMD scanning
+///
+/// Values whose braces do not balance fall back to the first unescaped quote.
 #[inline]
 #[must_use]
 pub fn find_balanced_quotes(input: &[u8]) -> Option<usize> {
@@ -66,6 +64,33 @@ pub fn find_balanced_quotes(input: &[u8]) -> Option<usize> {
         return None;
     }
 
+    find_brace_aware_quote(input).or_else(|| find_first_quote(input))
+}
+
+#[inline]
+fn find_brace_aware_quote(input: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = 1;
+
+    while pos < input.len() {
+        let idx = pos + memchr3(b'"', b'{', b'}', &input[pos..])?;
+        pos = idx + 1;
+        if is_escaped_delimiter(input, idx) {
+            continue;
+        }
+        match input[idx] {
+            b'{' => depth += 1,
+            b'}' => depth = depth.checked_sub(1)?,
+            _ if depth == 0 => return Some(idx + 1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[inline]
+fn find_first_quote(input: &[u8]) -> Option<usize> {
     let mut pos = 1;
 
     // Find quotes directly and only check the backslash run immediately before
@@ -100,20 +125,15 @@ pub fn find_balanced_parentheses(input: &[u8]) -> Option<usize> {
         if let Some(offset) = memchr::memchr2(b'(', b')', &input[pos..]) {
             let idx = pos + offset;
 
-            match input[idx] {
-                b'(' => {
-                    depth += 1;
-                    pos = idx + 1;
+            if input[idx] == b'(' {
+                depth += 1;
+            } else {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + 1); // Return position after closing paren
                 }
-                b')' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return Some(idx + 1); // Return position after closing paren
-                    }
-                    pos = idx + 1;
-                }
-                _ => unreachable!(),
             }
+            pos = idx + 1;
         } else {
             // No more delimiters found, unbalanced
             return None;
@@ -288,6 +308,8 @@ mod tests {
         assert_eq!(find_balanced_quotes(b"\"\""), Some(2));
         assert_eq!(find_balanced_quotes(b"\"hello\""), Some(7));
         assert_eq!(find_balanced_quotes(b"\"escaped \\\" quote\""), Some(18));
+        assert_eq!(find_balanced_quotes(b"\"a {\"} b\" rest"), Some(9));
+        assert_eq!(find_balanced_quotes(b"\"open { brace\""), Some(14));
         assert_eq!(find_balanced_quotes(b"\"unclosed"), None);
         assert_eq!(find_balanced_quotes(b""), None);
         assert_eq!(find_balanced_quotes(b"not starting with quote"), None);
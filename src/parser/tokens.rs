@@ -0,0 +1,254 @@
+//! Lexical token stream for syntax highlighting.
+//!
+//! [`tokenize`] walks the same grammar as the parser with the lexer's
+//! scanners, but never fails: text it cannot place becomes an
+//! [`TokenKind::Invalid`] token and scanning resumes at the next `@`.
+
+use super::{delimiter, lexer, simd};
+use crate::{SourceMap, SourceSpan};
+use std::collections::VecDeque;
+
+/// Classification of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Block type including its `@`, such as `@article` or `@string`.
+    EntryType,
+    /// Citation key.
+    Key,
+    /// Field name.
+    FieldName,
+    /// Structural punctuation: block braces or parentheses, `=`, `,`, and `#`.
+    Delimiter,
+    /// Braced or quoted text including its delimiters, or a bare token such as `2024a`.
+    Literal,
+    /// Bare integer.
+    Number,
+    /// Variable reference, or the name defined by `@string`.
+    Variable,
+    /// Text outside blocks, `%` lines, or the body of `@comment`.
+    Comment,
+    /// Text that does not fit the grammar.
+    Invalid,
+}
+
+/// One lexical token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// Token classification.
+    pub kind: TokenKind,
+    /// Source text of the token.
+    pub text: &'a str,
+    /// Source location of the token.
+    pub span: SourceSpan,
+}
+
+/// Iterator returned by [`tokenize`].
+#[derive(Debug)]
+pub struct Tokens<'a> {
+    source_map: SourceMap<'a>,
+    input: &'a str,
+    pos: usize,
+    pending: VecDeque<(TokenKind, usize, usize)>,
+}
+
+/// Split `input` into highlighting tokens in source order.
+///
+/// Whitespace is skipped. Blocks are tokenized one at a time as the
+/// iterator advances.
+///
+/// ```
+/// use bibtex_parser::{tokenize, TokenKind};
+///
+/// let kinds = tokenize("@article{key, title = {T}}")
+///     .map(|token| token.kind)
+///     .collect::<Vec<_>>();
+/// assert_eq!(kinds[..3], [TokenKind::EntryType, TokenKind::Delimiter, TokenKind::Key]);
+/// ```
+#[must_use]
+pub fn tokenize(input: &str) -> Tokens<'_> {
+    Tokens {
+        source_map: SourceMap::anonymous(input),
+        input,
+        pos: 0,
+        pending: VecDeque::new(),
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && self.pos < self.input.len() {
+            self.scan_block();
+        }
+        let (kind, start, end) = self.pending.pop_front()?;
+        Some(Token {
+            kind,
+            text: &self.input[start..end],
+            span: self.source_map.span(start, end),
+        })
+    }
+}
+
+impl Tokens<'_> {
+    fn scan_block(&mut self) {
+        self.skip_whitespace();
+        let start = self.pos;
+        if start >= self.input.len() {
+            return;
+        }
+        if self.bytes()[start] != b'@' {
+            let end = self.next_at(start);
+            self.push_trimmed(TokenKind::Comment, start, end);
+            self.pos = end;
+            return;
+        }
+        if self.scan_at_block().is_none() {
+            let end = self.next_at(self.pos.max(start + 1));
+            self.push_trimmed(TokenKind::Invalid, self.pos, end);
+            self.pos = end;
+        }
+    }
+
+    fn scan_at_block(&mut self) -> Option<()> {
+        let start = self.pos;
+        let name_len = simd::scan_identifier(&self.bytes()[start + 1..]);
+        if name_len == 0 {
+            return None;
+        }
+        let name_end = start + 1 + name_len;
+        self.push(TokenKind::EntryType, start, name_end);
+        self.pos = name_end;
+        let name = &self.input[start + 1..name_end];
+
+        self.skip_whitespace();
+        let close = match self.bytes().get(self.pos)? {
+            b'{' => b'}',
+            b'(' => b')',
+            _ => return None,
+        };
+        self.push_delimiter();
+
+        if name.eq_ignore_ascii_case("comment") {
+            let mut rest = &self.input[self.pos..];
+            let body = if close == b'}' {
+                lexer::balanced_braces(&mut rest)
+            } else {
+                lexer::balanced_parentheses(&mut rest)
+            }
+            .ok()?;
+            self.push_trimmed(TokenKind::Comment, self.pos, self.pos + body.len());
+            self.pos += body.len();
+        } else if name.eq_ignore_ascii_case("preamble") {
+            self.skip_whitespace();
+            self.scan_value()?;
+        } else if name.eq_ignore_ascii_case("string") {
+            self.skip_whitespace();
+            self.scan_identifier(TokenKind::Variable)?;
+            self.expect(b'=')?;
+            self.skip_whitespace();
+            self.scan_value()?;
+        } else {
+            self.skip_whitespace();
+            self.scan_identifier(TokenKind::Key)?;
+            self.scan_fields(close)?;
+        }
+
+        self.skip_whitespace();
+        (self.bytes().get(self.pos) == Some(&close)).then(|| self.push_delimiter())
+    }
+
+    fn scan_fields(&mut self, close: u8) -> Option<()> {
+        loop {
+            self.skip_whitespace();
+            match *self.bytes().get(self.pos)? {
+                byte if byte == close => return Some(()),
+                b',' => self.push_delimiter(),
+                _ => {
+                    self.scan_identifier(TokenKind::FieldName)?;
+                    self.expect(b'=')?;
+                    self.skip_whitespace();
+                    self.scan_value()?;
+                }
+            }
+        }
+    }
+
+    fn scan_value(&mut self) -> Option<()> {
+        loop {
+            let bytes = &self.bytes()[self.pos..];
+            let (kind, len) = match *bytes.first()? {
+                b'{' => (TokenKind::Literal, simd::find_balanced_braces(bytes)?),
+                b'"' => (TokenKind::Literal, simd::find_balanced_quotes(bytes)?),
+                b'0'..=b'9' | b'+' | b'-' => {
+                    let len = simd::scan_identifier(bytes);
+                    let digits = bytes[..len].strip_prefix(b"+").unwrap_or(&bytes[..len]);
+                    let digits = digits.strip_prefix(b"-").unwrap_or(digits);
+                    if !digits.is_empty() && digits.iter().all(u8::is_ascii_digit) {
+                        (TokenKind::Number, len)
+                    } else {
+                        (TokenKind::Literal, len)
+                    }
+                }
+                _ => (TokenKind::Variable, simd::scan_identifier(bytes)),
+            };
+            if len == 0 {
+                return None;
+            }
+            self.push(kind, self.pos, self.pos + len);
+            self.pos += len;
+
+            self.skip_whitespace();
+            if self.bytes().get(self.pos) != Some(&b'#') {
+                return Some(());
+            }
+            self.push_delimiter();
+            self.skip_whitespace();
+        }
+    }
+
+    fn scan_identifier(&mut self, kind: TokenKind) -> Option<()> {
+        let mut rest = &self.input[self.pos..];
+        let identifier = lexer::identifier(&mut rest).ok()?;
+        self.push(kind, self.pos, self.pos + identifier.len());
+        self.pos += identifier.len();
+        Some(())
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        (self.bytes().get(self.pos) == Some(&byte)).then(|| self.push_delimiter())
+    }
+
+    fn push_delimiter(&mut self) {
+        self.push(TokenKind::Delimiter, self.pos, self.pos + 1);
+        self.pos += 1;
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        self.pending.push_back((kind, start, end));
+    }
+
+    fn push_trimmed(&mut self, kind: TokenKind, start: usize, end: usize) {
+        let text = &self.input[start..end];
+        let leading = text.len() - text.trim_start().len();
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            self.push(kind, start + leading, start + leading + trimmed.len());
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let mut rest = &self.input[self.pos..];
+        lexer::skip_whitespace(&mut rest);
+        self.pos = self.input.len() - rest.len();
+    }
+
+    fn next_at(&self, from: usize) -> usize {
+        delimiter::find_byte(self.bytes(), b'@', from).unwrap_or(self.input.len())
+    }
+
+    const fn bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
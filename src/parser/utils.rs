@@ -31,18 +31,13 @@ pub fn tag_no_case<'a>(
     tag: &'static str,
 ) -> impl Parser<&'a str, &'a str, winnow::error::ContextError> {
     move |input: &mut &'a str| {
-        let tag_len = tag.len();
-        if input.len() < tag_len {
-            return super::backtrack();
-        }
-
-        let input_start = &input[..tag_len];
-        if input_start.eq_ignore_ascii_case(tag) {
-            let result = input_start;
-            *input = &input[tag_len..];
-            Ok(result)
-        } else {
-            super::backtrack()
+        // `get` rejects lengths that end inside a multi-byte character.
+        match input.get(..tag.len()) {
+            Some(input_start) if input_start.eq_ignore_ascii_case(tag) => {
+                *input = &input[tag.len()..];
+                Ok(input_start)
+            }
+            _ => super::backtrack(),
         }
     }
 }
@@ -115,5 +110,11 @@ mod tests {
         let result = tag_no_case("article").parse_next(&mut input).unwrap();
         assert_eq!(result, "Article");
         assert_eq!(input, "{...}");
+        let mut input = "strinä{";
+        assert!(tag_no_case("string").parse_next(&mut input).is_err());
+        assert_eq!(input, "strinä{");
+
+        let mut input = "é";
+        assert!(tag_no_case("string").parse_next(&mut input).is_err());
     }
 }
@@ -4,6 +4,31 @@ use super::{lexer, PResult};
 use crate::model::Value;
 use std::borrow::Cow;
 
+/// How bare signed integers such as `-5` are read in entry fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum SignedNumbers {
+    /// Read signed integers as numbers.
+    #[default]
+    Numbers,
+    /// Keep signed integers as their literal source text.
+    ///
+    /// `year` still reads them as numbers, since a sign there marks a year
+    /// before the common era rather than an open range.
+    Literals,
+}
+
+impl SignedNumbers {
+    /// Return the mode for the value of the field called `name`.
+    #[inline]
+    pub(crate) fn for_field(self, name: &str) -> Self {
+        if self == Self::Literals && name.eq_ignore_ascii_case("year") {
+            Self::Numbers
+        } else {
+            self
+        }
+    }
+}
+
 /// Parse a BibTeX value (string, number, variable, or concatenation)
 #[inline]
 pub fn parse_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
@@ -15,14 +40,17 @@ pub fn parse_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
 /// This variant is used by entry parsing so the field loop can read the
 /// delimiter directly without re-scanning whitespace.
 #[inline]
-pub(crate) fn parse_value_field<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
-    parse_concatenated_value_field(input)
+pub(crate) fn parse_value_field<'a>(
+    input: &mut &'a str,
+    signed: SignedNumbers,
+) -> PResult<'a, Value<'a>> {
+    parse_concatenated_value_field(input, signed)
 }
 
 /// Parse a concatenated value (value # value # ...)
 #[inline]
 fn parse_concatenated_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
-    let first = parse_single_value(input)?;
+    let first = parse_single_value(input, SignedNumbers::Numbers)?;
 
     // Fast path: most fields are a single value with no concatenation.
     if !consume_concat_separator(input) {
@@ -34,7 +62,7 @@ fn parse_concatenated_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
     parts.push(first);
 
     loop {
-        let part = parse_single_value(input)?;
+        let part = parse_single_value(input, SignedNumbers::Numbers)?;
         parts.push(part);
 
         if !consume_concat_separator(input) {
@@ -47,8 +75,11 @@ fn parse_concatenated_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
 
 /// Parse a concatenated value and consume trailing ASCII whitespace.
 #[inline]
-fn parse_concatenated_value_field<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
-    let first = parse_single_value(input)?;
+fn parse_concatenated_value_field<'a>(
+    input: &mut &'a str,
+    signed: SignedNumbers,
+) -> PResult<'a, Value<'a>> {
+    let first = parse_single_value(input, signed)?;
 
     if !consume_concat_separator_field(input) {
         return Ok(first);
@@ -59,7 +90,7 @@ fn parse_concatenated_value_field<'a>(input: &mut &'a str) -> PResult<'a, Value<
     parts.push(first);
 
     loop {
-        let part = parse_single_value(input)?;
+        let part = parse_single_value(input, signed)?;
         parts.push(part);
 
         if !consume_concat_separator_field(input) {
@@ -116,7 +147,7 @@ fn consume_concat_separator_field(input: &mut &str) -> bool {
 
 /// Parse a single value component
 #[inline]
-fn parse_single_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
+fn parse_single_value<'a>(input: &mut &'a str, signed: SignedNumbers) -> PResult<'a, Value<'a>> {
     // Fast dispatch based on first character
     let bytes = input.as_bytes();
     if let Some(&first) = bytes.first() {
@@ -135,7 +166,7 @@ fn parse_single_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
                     Ok(Value::Literal(Cow::Borrowed(content)))
                 })
             }
-            b'0'..=b'9' | b'+' | b'-' => parse_number_or_digit_string(input),
+            b'0'..=b'9' | b'+' | b'-' => parse_number_or_digit_string(input, signed),
             _ => parse_variable_value(input),
         }
     } else {
@@ -146,7 +177,10 @@ fn parse_single_value<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
 /// Parse either a number or a string that starts with digits
 /// This handles cases like "2024a", "12b", "1.2.3", etc.
 #[inline]
-fn parse_number_or_digit_string<'a>(input: &mut &'a str) -> PResult<'a, Value<'a>> {
+fn parse_number_or_digit_string<'a>(
+    input: &mut &'a str,
+    signed: SignedNumbers,
+) -> PResult<'a, Value<'a>> {
     let bytes = input.as_bytes();
     let Some(&first) = bytes.first() else {
         return super::backtrack();
@@ -166,9 +200,11 @@ fn parse_number_or_digit_string<'a>(input: &mut &'a str) -> PResult<'a, Value<'a
         if token_bytes.len() <= 1 || !token_bytes[1..].iter().all(u8::is_ascii_digit) {
             return super::backtrack();
         }
-        let num = parse_i64_ascii(token)?;
         *input = &input[len..];
-        return Ok(Value::Number(num));
+        return Ok(match signed {
+            SignedNumbers::Numbers => integer_or_literal(token),
+            SignedNumbers::Literals => Value::Literal(Cow::Borrowed(token)),
+        });
     }
 
     // Digit-starting tokens parse as numbers when fully numeric,
@@ -179,13 +215,25 @@ fn parse_number_or_digit_string<'a>(input: &mut &'a str) -> PResult<'a, Value<'a
 
     *input = &input[len..];
     if token_bytes.iter().all(u8::is_ascii_digit) {
-        let num = parse_i64_ascii(token)?;
-        Ok(Value::Number(num))
+        Ok(integer_or_literal(token))
     } else {
         Ok(Value::Literal(Cow::Borrowed(token)))
     }
 }
 
+/// Convert an all-digit token, keeping it as literal text when a number would lose information.
+///
+/// Leading zeros (`07`) and values outside the `i64` range stay literal so
+/// the written value round-trips exactly.
+#[inline]
+fn integer_or_literal(token: &str) -> Value<'_> {
+    let digits = token.trim_start_matches(['+', '-']);
+    if digits.len() > 1 && digits.starts_with('0') {
+        return Value::Literal(Cow::Borrowed(token));
+    }
+    parse_i64_ascii(token).map_or(Value::Literal(Cow::Borrowed(token)), Value::Number)
+}
+
 #[inline]
 fn parse_i64_ascii(token: &str) -> PResult<'_, i64> {
     let bytes = token.as_bytes();
@@ -265,6 +313,42 @@ mod tests {
         assert_eq!(input, " xxx");
     }
 
+    #[test]
+    fn test_parse_lossy_numbers_as_literals() {
+        for token in [
+            "07",
+            "2020.",
+            "99999999999999999999",
+            "-99999999999999999999",
+        ] {
+            let mut input = token;
+            let value = parse_value(&mut input).unwrap();
+            assert_eq!(value, Value::Literal(Cow::Borrowed(token)));
+            assert!(input.is_empty());
+        }
+
+        let mut input = "0";
+        assert_eq!(parse_value(&mut input).unwrap(), Value::Number(0));
+    }
+
+    #[test]
+    fn test_signed_numbers_as_literals_in_fields() {
+        let mut input = "-5 # 3";
+        assert_eq!(
+            parse_value_field(&mut input, SignedNumbers::Literals).unwrap(),
+            Value::Concat(
+                vec![Value::Literal(Cow::Borrowed("-5")), Value::Number(3)].into_boxed_slice()
+            )
+        );
+
+        let mut input = "-350";
+        let signed = SignedNumbers::Literals.for_field("YEAR");
+        assert_eq!(
+            parse_value_field(&mut input, signed).unwrap(),
+            Value::Number(-350)
+        );
+    }
+
     #[test]
     fn test_parse_variable_value() {
         let mut input = "myvar xxx";
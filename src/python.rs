@@ -1211,6 +1211,7 @@ impl PyWriterConfig {
                 TrailingComma::Omit
             },
             entry_separator: self.entry_separator.clone(),
+            ..WriterConfig::default()
         }
     }
 }
@@ -1245,37 +1246,33 @@ struct PyPersonName {
     suffix: Vec<String>,
     #[pyo3(get)]
     literal: Option<String>,
+    #[pyo3(get)]
+    nameaddon: String,
+    #[pyo3(get)]
+    is_corporate: bool,
+    display: String,
 }
 
 #[pymethods]
 impl PyPersonName {
     fn display_name(&self) -> String {
-        if let Some(literal) = &self.literal {
-            return literal.clone();
-        }
-        let mut parts = self.given.clone();
-        parts.extend(self.prefix.clone());
-        parts.extend(self.family.clone());
-        let mut name = parts.join(" ");
-        if !self.suffix.is_empty() {
-            if !name.is_empty() {
-                name.push_str(", ");
-            }
-            name.push_str(&self.suffix.join(" "));
-        }
-        name
+        self.display.clone()
     }
 }
 
 impl From<crate::PersonName> for PyPersonName {
     fn from(name: crate::PersonName) -> Self {
+        let display = name.display_name();
         Self {
+            display,
             raw: name.raw,
             given: name.given,
             family: name.family,
             prefix: name.prefix,
             suffix: name.suffix,
             literal: name.literal,
+            nameaddon: name.nameaddon,
+            is_corporate: name.is_corporate,
         }
     }
 }
@@ -1508,29 +1505,24 @@ fn parse_document_with_options(
 }
 
 fn write_document(document: &PyDocument, config: Option<WriterConfig>) -> PyResult<String> {
-    let mut buffer = Vec::new();
+    let mut output = String::new();
     let raw_source = document.raw_source.as_deref();
-    match config {
-        Some(config) => Writer::with_config(&mut buffer, config)
-            .write_document_with_raw_source(&document.inner, raw_source)
-            .map_err(map_error)?,
-        None => Writer::new(&mut buffer)
-            .write_document_with_raw_source(&document.inner, raw_source)
-            .map_err(map_error)?,
-    }
-    String::from_utf8(buffer).map_err(|error| PyRuntimeError::new_err(error.to_string()))
+    Writer::from_fmt_with_config(&mut output, config.unwrap_or_default())
+        .write_document_with_raw_source(&document.inner, raw_source)
+        .map_err(map_error)?;
+    Ok(output)
 }
 
 fn selected_entries_to_string(document: &PyDocument, keys: &[&str]) -> PyResult<String> {
-    let mut buffer = Vec::new();
-    Writer::new(&mut buffer)
+    let mut output = String::new();
+    Writer::from_fmt(&mut output)
         .write_selected_entries_with_raw_source(
             &document.inner,
             keys,
             document.raw_source.as_deref(),
         )
         .map_err(map_error)?;
-    String::from_utf8(buffer).map_err(|error| PyRuntimeError::new_err(error.to_string()))
+    Ok(output)
 }
 
 #[derive(Debug)]
@@ -1890,6 +1882,7 @@ fn parsed_entry_from_record(record: PlainRecord) -> ParsedEntry<'static> {
     ParsedEntry {
         ty: crate::EntryType::parse(entry_type).into_owned(),
         key: Cow::Owned(record.key),
+        original_key: None,
         fields: record
             .fields
             .into_iter()
@@ -1900,11 +1893,14 @@ fn parsed_entry_from_record(record: PlainRecord) -> ParsedEntry<'static> {
                 source: None,
                 name_source: None,
                 value_source: None,
+                comment: None,
+                comment_source: None,
             })
             .collect(),
         status: ParsedEntryStatus::Complete,
         source: None,
         entry_type_source: None,
+        entry_type_spelling: None,
         key_source: None,
         delimiter: None,
         raw: None,
@@ -2049,19 +2045,16 @@ fn apply_latex_to_unicode(document: &mut ParsedDocument<'static>) -> PyResult<()
     Ok(())
 }
 
+#[cfg(feature = "latex_to_unicode")]
 fn latex_to_unicode(input: &str) -> PyResult<String> {
-    #[cfg(feature = "latex_to_unicode")]
-    {
-        Ok(crate::latex_unicode::latex_to_unicode(input))
-    }
+    Ok(crate::latex_unicode::latex_to_unicode(input))
+}
 
-    #[cfg(not(feature = "latex_to_unicode"))]
-    {
-        let _ = input;
-        Err(PyRuntimeError::new_err(
-            "latex_to_unicode support was not enabled for this build",
-        ))
-    }
+#[cfg(not(feature = "latex_to_unicode"))]
+fn latex_to_unicode(_input: &str) -> PyResult<String> {
+    Err(PyRuntimeError::new_err(
+        "latex_to_unicode support was not enabled for this build",
+    ))
 }
 
 fn unicode_text(value: &Value<'_>) -> PyResult<String> {
@@ -2100,6 +2093,7 @@ fn parse_status_name(status: crate::ParseStatus) -> &'static str {
         crate::ParseStatus::Ok => "ok",
         crate::ParseStatus::Partial => "partial",
         crate::ParseStatus::Failed => "failed",
+        crate::ParseStatus::Cancelled => "cancelled",
     }
 }
 
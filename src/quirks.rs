@@ -0,0 +1,278 @@
+//! Fixes for known problems in Google Scholar and DBLP exports.
+//!
+//! Each fix is toggled by a field of [`ExportQuirks`]. Like the passes in
+//! [`cleanup`](crate::cleanup), value fixes rewrite literal text only and are
+//! reported as [`ValueRepair`]s; key fixes are reported as a
+//! [`KeyRenameMap`] and update references to the old keys.
+
+use crate::{KeyRenameMap, Library, Value, ValueRepair};
+use std::borrow::Cow;
+
+/// Words kept lowercase inside a recased title, unless they come first.
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "into", "of", "on", "or", "the", "to",
+    "via", "with",
+];
+
+/// Acronyms that stay in capitals when a venue is recased.
+const VENUE_ACRONYMS: &[&str] = &[
+    "AAAI", "ACL", "ACM", "AI", "AIP", "APS", "CVPR", "ECCV", "EMNLP", "ICCV", "ICLR", "ICML",
+    "IEEE", "IET", "IJCAI", "KDD", "LNCS", "NAACL", "NIPS", "NLP", "PNAS", "SIAM", "SIGIR",
+    "SIGMOD", "SPIE", "USENIX", "VLDB", "WWW",
+];
+
+/// Which export quirks [`Library::fix_export_quirks`] repairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ExportQuirks {
+    /// Collapse a year repeated in the key, as in `smith20202020deep`.
+    pub duplicate_key_year: bool,
+    /// Remove `pages = {1--1}` and similar placeholder page ranges.
+    pub placeholder_pages: bool,
+    /// Recase all-caps `journal` and `booktitle` values; see
+    /// [`title_case_all_caps`]. Entries whose [`Entry::language`] does not
+    /// [allow case changes](crate::LanguageTag::allows_case_changes) are
+    /// skipped.
+    ///
+    /// [`Entry::language`]: crate::Entry::language
+    pub all_caps_venues: bool,
+    /// Brace acronyms in `title` so styles keep their case; see
+    /// [`brace_acronyms`].
+    pub unbraced_acronyms: bool,
+}
+
+impl Default for ExportQuirks {
+    /// Enable every fix.
+    fn default() -> Self {
+        Self {
+            duplicate_key_year: true,
+            placeholder_pages: true,
+            all_caps_venues: true,
+            unbraced_acronyms: true,
+        }
+    }
+}
+
+impl ExportQuirks {
+    /// Disable every fix.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            duplicate_key_year: false,
+            placeholder_pages: false,
+            all_caps_venues: false,
+            unbraced_acronyms: false,
+        }
+    }
+}
+
+/// Changes made by [`Library::fix_export_quirks`].
+#[derive(Debug, Clone, Default)]
+pub struct QuirkFixes {
+    /// Keys that changed.
+    pub renames: KeyRenameMap,
+    /// Field values that changed; a removed field has an empty `repaired`.
+    pub repairs: Vec<ValueRepair>,
+}
+
+impl QuirkFixes {
+    /// Return `true` when nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.repairs.is_empty()
+    }
+}
+
+impl Library<'_> {
+    /// Repair the enabled Scholar and DBLP export quirks.
+    ///
+    /// ```
+    /// use bibtex_parser::{ExportQuirks, Library};
+    ///
+    /// let mut library = Library::parse(
+    ///     "@article{doe20242024deep, title = {Deep NLP}, journal = {NEURAL COMPUTATION},
+    ///               pages = {1--1}, year = 2024}",
+    /// )?;
+    /// let fixes = library.fix_export_quirks(ExportQuirks::default());
+    ///
+    /// let entry = &library.entries()[0];
+    /// assert_eq!(entry.key(), "doe2024deep");
+    /// assert_eq!(entry.get("title"), Some("Deep {NLP}"));
+    /// assert_eq!(entry.get("journal"), Some("Neural Computation"));
+    /// assert_eq!(entry.get("pages"), None);
+    /// assert_eq!(fixes.repairs.len(), 3);
+    /// # Ok::<(), bibtex_parser::Error>(())
+    /// ```
+    pub fn fix_export_quirks(&mut self, quirks: ExportQuirks) -> QuirkFixes {
+        let renames = if quirks.duplicate_key_year {
+            self.regenerate_keys(|entry| {
+                let year = entry.year()?;
+                let year = year.trim();
+                let doubled = format!("{year}{year}");
+                (year.len() == 4 && entry.key.contains(&doubled))
+                    .then(|| entry.key.replacen(&doubled, year, 1))
+            })
+        } else {
+            KeyRenameMap::new()
+        };
+
+        let mut repairs = Vec::new();
+        for (index, entry) in self.entries_mut().iter_mut().enumerate() {
+            let recase = entry
+                .language()
+                .map_or(true, |language| language.allows_case_changes());
+            let mut removed = Vec::new();
+            for (position, field) in entry.fields.iter_mut().enumerate() {
+                let Value::Literal(text) = &field.value else {
+                    continue;
+                };
+                let name = field.name.to_ascii_lowercase();
+                let repaired = match name.as_str() {
+                    "pages" if quirks.placeholder_pages && is_placeholder_pages(text) => {
+                        removed.push(position);
+                        Some(String::new())
+                    }
+                    "journal" | "journaltitle" | "booktitle"
+                        if quirks.all_caps_venues && recase =>
+                    {
+                        title_case_all_caps(text)
+                    }
+                    "title" if quirks.unbraced_acronyms => brace_acronyms(text),
+                    _ => None,
+                };
+                let Some(repaired) = repaired else {
+                    continue;
+                };
+                repairs.push(ValueRepair {
+                    entry: index,
+                    field: field.name.to_string(),
+                    original: text.to_string(),
+                    repaired: repaired.clone(),
+                    span: None,
+                });
+                field.value = Value::Literal(Cow::Owned(repaired));
+            }
+            for position in removed.into_iter().rev() {
+                entry.fields.remove(position);
+            }
+        }
+
+        QuirkFixes { renames, repairs }
+    }
+}
+
+fn is_placeholder_pages(text: &str) -> bool {
+    let mut ends = text
+        .split('-')
+        .map(str::trim)
+        .filter(|part| !part.is_empty());
+    matches!(
+        (ends.next(), ends.next(), ends.next()),
+        (Some("1"), Some("1"), None)
+    )
+}
+
+/// Recase text written entirely in capitals, such as `IEEE TRANSACTIONS ON
+/// NEURAL NETWORKS`, to title case.
+///
+/// Minor words become lowercase. Well-known venue acronyms (`IEEE`, `ACM`),
+/// Roman numerals, and abbreviations in parentheses such as `(TNN)` stay as
+/// written. Text with any lowercase letter, or with fewer than two words, is
+/// left alone. Returns `None` when nothing changed.
+#[must_use]
+pub fn title_case_all_caps(text: &str) -> Option<String> {
+    if text.chars().any(char::is_lowercase) || text.split_whitespace().count() < 2 {
+        return None;
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut first = true;
+    for (index, token) in text.split(' ').enumerate() {
+        if index > 0 {
+            output.push(' ');
+        }
+        let lower = token.to_lowercase();
+        let word = lower.trim_matches(|ch: char| !ch.is_alphabetic());
+        let letters = token.chars().filter(|ch| ch.is_alphabetic()).count();
+        if letters == 0 || token.contains(['\\', '{', '}']) {
+            output.push_str(token);
+        } else if !first && MINOR_WORDS.contains(&word) {
+            output.push_str(&lower);
+        } else if keeps_capitals(token) {
+            output.push_str(token);
+        } else {
+            let start = lower.find(char::is_alphabetic).unwrap_or_default();
+            let mut chars = lower[start..].chars();
+            output.push_str(&lower[..start]);
+            output.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+            output.push_str(chars.as_str());
+        }
+        first &= letters == 0;
+    }
+    (output != text).then_some(output)
+}
+
+/// Whether an all-caps venue word is an acronym or numeral to keep as written.
+fn keeps_capitals(token: &str) -> bool {
+    let word = token.trim_matches(|ch: char| !ch.is_alphanumeric());
+    (token.starts_with('(') && token.ends_with(')'))
+        || VENUE_ACRONYMS.contains(&word)
+        || (word.len() > 1 && word.chars().all(|ch| matches!(ch, 'I' | 'V' | 'X')))
+}
+
+/// Brace words with capitals after their first letter, such as `NLP`,
+/// `GPUs`, or `iPhone`, so BibTeX styles do not lowercase them.
+///
+/// Each part of a hyphenated word is judged on its own, so `COVID-19` is
+/// braced but `Pre-Training` is not. Words already inside braces, LaTeX
+/// commands, and text written entirely in capitals are left alone. Returns
+/// `None` when nothing changed.
+#[must_use]
+pub fn brace_acronyms(text: &str) -> Option<String> {
+    if !text.chars().any(char::is_lowercase) {
+        return None;
+    }
+    let mut output = String::with_capacity(text.len() + 8);
+    let mut depth = 0_usize;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let ch = rest.chars().next().unwrap_or_default();
+        if depth == 0 && ch.is_alphanumeric() {
+            let end = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '-'))
+                .unwrap_or(rest.len());
+            let word = rest[..end].trim_end_matches('-');
+            let after_command = output.ends_with('\\');
+            let acronym = word
+                .split('-')
+                .any(|part| part.chars().skip(1).any(char::is_uppercase));
+            if !after_command && acronym {
+                output.push('{');
+                output.push_str(word);
+                output.push('}');
+            } else {
+                output.push_str(word);
+            }
+            rest = &rest[word.len()..];
+            continue;
+        }
+        match ch {
+            '\\' => {
+                output.push(ch);
+                rest = &rest[1..];
+                if let Some(next) = rest.chars().next() {
+                    if !next.is_alphabetic() {
+                        output.push(next);
+                        rest = &rest[next.len_utf8()..];
+                    }
+                }
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        output.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    (output != text).then_some(output)
+}
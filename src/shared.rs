@@ -0,0 +1,111 @@
+//! A library shared between threads with copy-on-write updates.
+
+use crate::Library;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// Library handle that many readers can query while a writer replaces it.
+///
+/// Readers [`load`](Self::load) an `Arc` snapshot and keep using it for as
+/// long as they like; a new version is built off to the side and swapped in
+/// atomically, so readers never see a half-applied update and are never
+/// blocked while one is prepared. Clones share the same library.
+///
+/// ```
+/// use bibtex_parser::{Library, SharedLibrary};
+///
+/// let shared = SharedLibrary::new(Library::parse("@misc{a, title = {A}}")?.into_owned());
+/// let snapshot = shared.load();
+///
+/// let extra = Library::parse("@misc{b, title = {B}}")?.into_owned();
+/// shared.update(|library| library.merge(extra));
+/// assert_eq!(snapshot.entries().len(), 1);
+/// assert_eq!(shared.load().entries().len(), 2);
+/// assert_eq!(shared.version(), 1);
+/// # Ok::<(), bibtex_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedLibrary {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    current: RwLock<Arc<Library<'static>>>,
+    version: AtomicU64,
+    /// Serializes writers so concurrent updates are not lost.
+    writer: Mutex<()>,
+}
+
+impl SharedLibrary {
+    /// Share `library`.
+    #[must_use]
+    pub fn new(library: Library<'static>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                current: RwLock::new(Arc::new(library)),
+                version: AtomicU64::new(0),
+                writer: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Return the current library.
+    ///
+    /// The snapshot is unaffected by later updates.
+    #[must_use]
+    pub fn load(&self) -> Arc<Library<'static>> {
+        Arc::clone(
+            &self
+                .inner
+                .current
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Replace the library and return the previous one.
+    pub fn store(&self, library: Library<'static>) -> Arc<Library<'static>> {
+        let _writer = self
+            .inner
+            .writer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.swap(Arc::new(library))
+    }
+
+    /// Apply `edit` to a copy of the current library and swap the copy in.
+    ///
+    /// Updates are applied one at a time, each to the result of the last.
+    /// Readers keep seeing the previous version until `edit` returns.
+    pub fn update<R>(&self, edit: impl FnOnce(&mut Library<'static>) -> R) -> R {
+        let _writer = self
+            .inner
+            .writer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut library = Library::clone(&self.load());
+        let result = edit(&mut library);
+        self.swap(Arc::new(library));
+        result
+    }
+
+    /// Return how many times the library has been replaced.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.inner.version.load(Ordering::Acquire)
+    }
+
+    fn swap(&self, library: Arc<Library<'static>>) -> Arc<Library<'static>> {
+        let previous = std::mem::replace(
+            &mut *self
+                .inner
+                .current
+                .write()
+                .unwrap_or_else(PoisonError::into_inner),
+            library,
+        );
+        self.inner.version.fetch_add(1, Ordering::AcqRel);
+        previous
+    }
+}
@@ -100,10 +100,11 @@ impl<'a> SourceMap<'a> {
 
     /// Return the line and column for a byte offset.
     ///
-    /// Offsets past end-of-file are clamped to the end of the source.
+    /// Offsets past end-of-file are clamped to the end of the source, and
+    /// offsets inside a multi-byte character resolve to that character.
     #[must_use]
     pub fn line_column(&self, byte: usize) -> (usize, usize) {
-        let byte = byte.min(self.input.len());
+        let byte = floor_char_boundary(self.input, byte);
         let line_index = match self.line_starts.binary_search(&byte) {
             Ok(index) => index,
             Err(0) => 0,
@@ -152,12 +153,13 @@ impl<'a> SourceMap<'a> {
 
     /// Create a source span for a byte range.
     ///
-    /// The range is clamped to the source length. The returned span keeps the
-    /// half-open byte offsets and one-based start/end line-column positions.
+    /// The range is clamped to the source length and widened to whole
+    /// characters. The returned span keeps the half-open byte offsets and
+    /// one-based start/end line-column positions.
     #[must_use]
     pub fn span(&self, byte_start: usize, byte_end: usize) -> SourceSpan {
-        let byte_start = byte_start.min(self.input.len());
-        let byte_end = byte_end.min(self.input.len()).max(byte_start);
+        let byte_start = floor_char_boundary(self.input, byte_start);
+        let byte_end = ceil_char_boundary(self.input, byte_end.max(byte_start));
         let (line, column) = self.line_column(byte_start);
         let (end_line, end_column) = self.line_column(byte_end);
         let span = SourceSpan::with_end(byte_start, byte_end, line, column, end_line, end_column);
@@ -187,6 +189,8 @@ impl<'a> SourceMap<'a> {
             return None;
         }
 
+        // Empty spans anchor on the preceding character, which may be
+        // multi-byte, so both ends are snapped to character boundaries.
         let anchor_start = if span.is_empty() && span.byte_start > 0 {
             span.byte_start - 1
         } else {
@@ -197,6 +201,8 @@ impl<'a> SourceMap<'a> {
         } else {
             span.byte_end
         };
+        let anchor_start = floor_char_boundary(self.input, anchor_start);
+        let anchor_end = floor_char_boundary(self.input, anchor_end).max(anchor_start);
 
         let start = self.input[..anchor_start]
             .rfind('\n')
@@ -223,10 +229,14 @@ pub(crate) struct SourceCursor<'map, 'source> {
     line_index: usize,
 }
 
-impl SourceCursor<'_, '_> {
+impl<'source> SourceCursor<'_, 'source> {
+    pub(crate) fn slice(&self, byte_start: usize, byte_end: usize) -> Option<&'source str> {
+        self.map.input.get(byte_start..byte_end)
+    }
+
     pub(crate) fn span(&mut self, byte_start: usize, byte_end: usize) -> SourceSpan {
-        let byte_start = byte_start.min(self.map.input.len());
-        let byte_end = byte_end.min(self.map.input.len()).max(byte_start);
+        let byte_start = floor_char_boundary(self.map.input, byte_start);
+        let byte_end = ceil_char_boundary(self.map.input, byte_end.max(byte_start));
         let start_line_index = self.line_index_at(byte_start);
         let end_line_index = self.line_index_from(start_line_index, byte_end);
         let column = self.column_at(start_line_index, byte_start);
@@ -314,3 +324,78 @@ impl SourceMap<'_> {
         }
     }
 }
+
+/// Translation of spans from a slice of an input to the whole input.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SourceOffset {
+    bytes: usize,
+    lines: usize,
+    columns: usize,
+}
+
+impl SourceOffset {
+    /// Offset for spans of the slice of `input` that starts at byte `start`.
+    pub(crate) fn new(input: &str, start: usize) -> Self {
+        let prefix = &input.as_bytes()[..start];
+        let line_start = memchr::memrchr(b'\n', prefix).map_or(0, |index| index + 1);
+        Self {
+            bytes: start,
+            lines: memchr::memchr_iter(b'\n', prefix).count(),
+            columns: input[line_start..start].chars().count(),
+        }
+    }
+
+    /// Offset for a slice after `bytes` bytes holding `lines` newlines and
+    /// ending `columns` characters into its last line.
+    pub(crate) const fn from_parts(bytes: usize, lines: usize, columns: usize) -> Self {
+        Self {
+            bytes,
+            lines,
+            columns,
+        }
+    }
+
+    /// Return the byte offset of the slice.
+    pub(crate) const fn bytes(self) -> usize {
+        self.bytes
+    }
+
+    /// Move `span`, if any, from the slice to the whole input.
+    pub(crate) fn apply(self, span: &mut Option<SourceSpan>) {
+        if let Some(span) = span {
+            self.shift(span);
+        }
+    }
+
+    /// Move `span` from the slice to the whole input.
+    pub(crate) fn shift(self, span: &mut SourceSpan) {
+        span.byte_start += self.bytes;
+        span.byte_end += self.bytes;
+        if span.line == 1 {
+            span.column += self.columns;
+        }
+        if span.end_line == 1 {
+            span.end_column += self.columns;
+        }
+        span.line += self.lines;
+        span.end_line += self.lines;
+    }
+}
+
+/// Clamp `byte` to `input` and move it back to the start of its character.
+pub(crate) fn floor_char_boundary(input: &str, byte: usize) -> usize {
+    let mut byte = byte.min(input.len());
+    while !input.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    byte
+}
+
+/// Clamp `byte` to `input` and move it forward to the end of its character.
+pub(crate) fn ceil_char_boundary(input: &str, byte: usize) -> usize {
+    let mut byte = byte.min(input.len());
+    while !input.is_char_boundary(byte) {
+        byte += 1;
+    }
+    byte
+}
@@ -0,0 +1,359 @@
+//! Concrete syntax trees that keep every byte of the input.
+//!
+//! [`SyntaxTree::parse`] groups the [`tokenize`](crate::tokenize) stream into
+//! blocks, fields, and values, and keeps the whitespace between tokens as
+//! leaves, so concatenating the leaves reproduces the input exactly.
+
+use crate::{tokenize, SourceMap, SourceSpan, TokenKind};
+use std::ops::Range;
+
+/// Kind of a [`SyntaxNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    /// Root node covering the whole input.
+    Document,
+    /// A regular `@type{key, ...}` entry.
+    Entry,
+    /// An `@string` definition.
+    StringDefinition,
+    /// An `@preamble` block.
+    Preamble,
+    /// An `@comment` block.
+    CommentBlock,
+    /// A `name = value` field, or the definition inside `@string`.
+    Field,
+    /// A value with its concatenation parts.
+    Value,
+    /// A token leaf.
+    Token(TokenKind),
+    /// A whitespace leaf.
+    Whitespace,
+}
+
+impl SyntaxKind {
+    /// Return `true` for leaf kinds.
+    #[must_use]
+    pub const fn is_leaf(self) -> bool {
+        matches!(self, Self::Token(_) | Self::Whitespace)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NodeData {
+    kind: SyntaxKind,
+    range: Range<usize>,
+    parent: Option<usize>,
+    position: usize,
+    children: Vec<usize>,
+}
+
+/// Lossless concrete syntax tree for a BibTeX input.
+#[derive(Debug, Clone)]
+pub struct SyntaxTree<'a> {
+    source_map: SourceMap<'a>,
+    input: &'a str,
+    nodes: Vec<NodeData>,
+}
+
+impl<'a> SyntaxTree<'a> {
+    /// Build the tree for `input`.
+    ///
+    /// Parsing never fails; malformed text ends up in
+    /// [`TokenKind::Invalid`] leaves.
+    #[must_use]
+    pub fn parse(input: &'a str) -> Self {
+        let mut builder = Builder {
+            tree: Self {
+                source_map: SourceMap::anonymous(input),
+                input,
+                nodes: Vec::new(),
+            },
+            stack: Vec::new(),
+            cursor: 0,
+        };
+        builder.open(SyntaxKind::Document, 0);
+        for token in tokenize(input) {
+            builder.token(token.kind, token.span.byte_start, token.span.byte_end);
+        }
+        builder.whitespace(input.len());
+        builder.tree.nodes[0].range = 0..input.len();
+        builder.tree
+    }
+
+    /// Return the root [`SyntaxKind::Document`] node.
+    #[must_use]
+    pub const fn root(&self) -> SyntaxNode<'_, 'a> {
+        SyntaxNode {
+            tree: self,
+            index: 0,
+        }
+    }
+
+    /// Return the input the tree was built from.
+    #[must_use]
+    pub const fn text(&self) -> &'a str {
+        self.input
+    }
+
+    /// Return the number of nodes, including leaves.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return `true` when the tree has only its root.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() <= 1
+    }
+
+    /// Return the deepest node whose range contains `offset`.
+    #[must_use]
+    pub fn node_at(&self, offset: usize) -> Option<SyntaxNode<'_, 'a>> {
+        if offset > self.input.len() {
+            return None;
+        }
+        let mut node = self.root();
+        while let Some(child) = node
+            .children()
+            .find(|child| child.range().contains(&offset))
+        {
+            node = child;
+        }
+        Some(node)
+    }
+}
+
+/// A node in a [`SyntaxTree`].
+#[derive(Debug, Clone, Copy)]
+pub struct SyntaxNode<'t, 'a> {
+    tree: &'t SyntaxTree<'a>,
+    index: usize,
+}
+
+impl PartialEq for SyntaxNode<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.tree, other.tree) && self.index == other.index
+    }
+}
+
+impl Eq for SyntaxNode<'_, '_> {}
+
+impl<'t, 'a> SyntaxNode<'t, 'a> {
+    fn data(&self) -> &'t NodeData {
+        &self.tree.nodes[self.index]
+    }
+
+    const fn at(&self, index: usize) -> Self {
+        Self {
+            tree: self.tree,
+            index,
+        }
+    }
+
+    /// Return the node kind.
+    #[must_use]
+    pub fn kind(&self) -> SyntaxKind {
+        self.data().kind
+    }
+
+    /// Return the covered byte range.
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.data().range.clone()
+    }
+
+    /// Return the covered source span.
+    #[must_use]
+    pub fn span(&self) -> SourceSpan {
+        let range = self.range();
+        self.tree.source_map.span(range.start, range.end)
+    }
+
+    /// Return the covered source text, including whitespace.
+    #[must_use]
+    pub fn text(&self) -> &'a str {
+        &self.tree.input[self.range()]
+    }
+
+    /// Return the parent node.
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        self.data().parent.map(|index| self.at(index))
+    }
+
+    /// Iterate over direct children in source order.
+    pub fn children(&self) -> impl Iterator<Item = Self> + 't {
+        let tree = self.tree;
+        self.data()
+            .children
+            .iter()
+            .map(move |&index| SyntaxNode { tree, index })
+    }
+
+    /// Return the first child.
+    #[must_use]
+    pub fn first_child(&self) -> Option<Self> {
+        self.data().children.first().map(|&index| self.at(index))
+    }
+
+    /// Return the last child.
+    #[must_use]
+    pub fn last_child(&self) -> Option<Self> {
+        self.data().children.last().map(|&index| self.at(index))
+    }
+
+    /// Return the next node with the same parent.
+    #[must_use]
+    pub fn next_sibling(&self) -> Option<Self> {
+        let parent = self.parent()?;
+        let index = *parent.data().children.get(self.data().position + 1)?;
+        Some(self.at(index))
+    }
+
+    /// Return the previous node with the same parent.
+    #[must_use]
+    pub fn prev_sibling(&self) -> Option<Self> {
+        let parent = self.parent()?;
+        let position = self.data().position.checked_sub(1)?;
+        Some(self.at(parent.data().children[position]))
+    }
+
+    /// Iterate over this node and all nodes below it in source order.
+    pub fn descendants(&self) -> impl Iterator<Item = Self> + 't {
+        let tree = self.tree;
+        let mut stack = vec![self.index];
+        std::iter::from_fn(move || {
+            let index = stack.pop()?;
+            stack.extend(tree.nodes[index].children.iter().rev());
+            Some(SyntaxNode { tree, index })
+        })
+    }
+
+    /// Return the first child of `kind`.
+    #[must_use]
+    pub fn child(&self, kind: SyntaxKind) -> Option<Self> {
+        self.children().find(|child| child.kind() == kind)
+    }
+}
+
+struct Builder<'a> {
+    tree: SyntaxTree<'a>,
+    stack: Vec<usize>,
+    cursor: usize,
+}
+
+impl Builder<'_> {
+    fn token(&mut self, kind: TokenKind, start: usize, end: usize) {
+        match kind {
+            TokenKind::EntryType => {
+                self.close_to(SyntaxKind::Document);
+                let block = match self.tree.input[start + 1..end]
+                    .to_ascii_lowercase()
+                    .as_str()
+                {
+                    "string" => SyntaxKind::StringDefinition,
+                    "preamble" => SyntaxKind::Preamble,
+                    "comment" => SyntaxKind::CommentBlock,
+                    _ => SyntaxKind::Entry,
+                };
+                self.whitespace(start);
+                self.open(block, start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+            }
+            TokenKind::FieldName => {
+                self.close_to_block();
+                self.whitespace(start);
+                self.open(SyntaxKind::Field, start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+            }
+            TokenKind::Variable if self.current() == SyntaxKind::StringDefinition => {
+                self.whitespace(start);
+                self.open(SyntaxKind::Field, start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+            }
+            TokenKind::Literal | TokenKind::Number | TokenKind::Variable => {
+                self.whitespace(start);
+                if !matches!(self.current(), SyntaxKind::Value | SyntaxKind::Document) {
+                    self.open(SyntaxKind::Value, start);
+                }
+                self.leaf(SyntaxKind::Token(kind), start, end);
+            }
+            TokenKind::Delimiter => {
+                let text = &self.tree.input[start..end];
+                if text != "#" && text != "=" {
+                    self.close_to_block();
+                }
+                self.whitespace(start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+                if matches!(text, "}" | ")") {
+                    self.close_to(SyntaxKind::Document);
+                }
+            }
+            TokenKind::Invalid => {
+                self.whitespace(start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+                self.close_to(SyntaxKind::Document);
+            }
+            TokenKind::Key | TokenKind::Comment => {
+                self.whitespace(start);
+                self.leaf(SyntaxKind::Token(kind), start, end);
+            }
+        }
+    }
+
+    fn current(&self) -> SyntaxKind {
+        self.stack
+            .last()
+            .map_or(SyntaxKind::Document, |&index| self.tree.nodes[index].kind)
+    }
+
+    fn close_to(&mut self, kind: SyntaxKind) {
+        while self.stack.len() > 1 && self.current() != kind {
+            self.stack.pop();
+        }
+    }
+
+    fn close_to_block(&mut self) {
+        while matches!(self.current(), SyntaxKind::Field | SyntaxKind::Value) {
+            self.stack.pop();
+        }
+    }
+
+    fn open(&mut self, kind: SyntaxKind, start: usize) {
+        let index = self.push_node(kind, start..start);
+        self.stack.push(index);
+    }
+
+    fn leaf(&mut self, kind: SyntaxKind, start: usize, end: usize) {
+        self.push_node(kind, start..end);
+        self.cursor = end;
+        for &open in &self.stack {
+            self.tree.nodes[open].range.end = end;
+        }
+    }
+
+    fn whitespace(&mut self, until: usize) {
+        if self.cursor < until {
+            self.leaf(SyntaxKind::Whitespace, self.cursor, until);
+        }
+    }
+
+    fn push_node(&mut self, kind: SyntaxKind, range: Range<usize>) -> usize {
+        let index = self.tree.nodes.len();
+        let parent = self.stack.last().copied();
+        let position = parent.map_or(0, |parent| {
+            let children = &mut self.tree.nodes[parent].children;
+            children.push(index);
+            children.len() - 1
+        });
+        self.tree.nodes.push(NodeData {
+            kind,
+            range,
+            parent,
+            position,
+            children: Vec::new(),
+        });
+        index
+    }
+}
@@ -0,0 +1,179 @@
+//! Semantic comparison helpers for tests.
+//!
+//! Enabled by the `testing` feature. [`assert_bibtex_eq!`](crate::assert_bibtex_eq)
+//! compares two bibliographies by content rather than by text, so
+//! formatting, field order, field-name case, and delimiter style do not
+//! matter, and prints a field-level diff when they differ.
+
+use crate::{Entry, Library};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+
+/// Assert that two bibliographies hold the same entries, strings, and preambles.
+///
+/// Either side may be BibTeX source text or a [`Library`]. An optional
+/// trailing format string adds context to the failure message.
+///
+/// ```
+/// use bibtex_parser::assert_bibtex_eq;
+///
+/// assert_bibtex_eq!(
+///     "@article{a, title = {Title}, year = 2024}",
+///     "@Article{a,\n  YEAR = \"2024\",\n  title = {Title}\n}"
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_bibtex_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Some(diff) = $crate::testing::bibtex_diff(&$left, &$right) {
+            panic!("BibTeX mismatch (- left, + right):\n{diff}");
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Some(diff) = $crate::testing::bibtex_diff(&$left, &$right) {
+            panic!(
+                "BibTeX mismatch (- left, + right): {}\n{diff}",
+                format_args!($($arg)+)
+            );
+        }
+    };
+}
+
+/// Values [`bibtex_diff`] can compare.
+///
+/// Text is parsed with [`Library::parse`] and panics when it is not valid
+/// BibTeX.
+pub trait ToLibrary {
+    /// Return the library to compare.
+    fn to_library(&self) -> Cow<'_, Library<'_>>;
+}
+
+impl ToLibrary for str {
+    fn to_library(&self) -> Cow<'_, Library<'_>> {
+        Cow::Owned(Library::parse(self).unwrap_or_else(|error| panic!("invalid BibTeX: {error}")))
+    }
+}
+
+impl ToLibrary for String {
+    fn to_library(&self) -> Cow<'_, Library<'_>> {
+        self.as_str().to_library()
+    }
+}
+
+impl ToLibrary for Library<'_> {
+    fn to_library(&self) -> Cow<'_, Library<'_>> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<T: ToLibrary + ?Sized> ToLibrary for &T {
+    fn to_library(&self) -> Cow<'_, Library<'_>> {
+        (**self).to_library()
+    }
+}
+
+/// Describe how two bibliographies differ, or return `None` when they match.
+///
+/// Entries are paired by key in order of appearance and compared by entry
+/// type and by field values matched case-insensitively, with whitespace
+/// runs collapsed. `@string` definitions and preambles are compared the
+/// same way. Block order and comments are ignored.
+#[must_use]
+pub fn bibtex_diff<L, R>(left: &L, right: &R) -> Option<String>
+where
+    L: ToLibrary + ?Sized,
+    R: ToLibrary + ?Sized,
+{
+    let (left, right) = (left.to_library(), right.to_library());
+    let mut diff = String::new();
+
+    let mut unmatched = right.entries().iter().collect::<Vec<_>>();
+    for entry in left.entries() {
+        match unmatched.iter().position(|other| other.key == entry.key) {
+            Some(position) => diff_entry(&mut diff, entry, unmatched.remove(position)),
+            None => {
+                let _ = writeln!(diff, "- @{}{{{}}}", entry.ty, entry.key);
+            }
+        }
+    }
+    for entry in unmatched {
+        let _ = writeln!(diff, "+ @{}{{{}}}", entry.ty, entry.key);
+    }
+
+    let strings = |library: &Library<'_>| {
+        library
+            .strings()
+            .iter()
+            .map(|string| (string.name.to_string(), collapse(&string.value.to_string())))
+            .collect::<Vec<_>>()
+    };
+    diff_pairs(&mut diff, "@string", &strings(&left), &strings(&right));
+
+    let preambles = |library: &Library<'_>| {
+        library
+            .preambles()
+            .iter()
+            .enumerate()
+            .map(|(index, preamble)| (index.to_string(), collapse(&preamble.value.to_string())))
+            .collect::<Vec<_>>()
+    };
+    diff_pairs(
+        &mut diff,
+        "@preamble",
+        &preambles(&left),
+        &preambles(&right),
+    );
+
+    (!diff.is_empty()).then_some(diff)
+}
+
+fn diff_entry(diff: &mut String, left: &Entry<'_>, right: &Entry<'_>) {
+    let key = &left.key;
+    if left.ty != right.ty {
+        let _ = writeln!(diff, "  {key}: type");
+        let _ = writeln!(diff, "-   {}", left.ty);
+        let _ = writeln!(diff, "+   {}", right.ty);
+    }
+    let fields = |entry: &Entry<'_>| {
+        let mut fields = Vec::<(String, String)>::new();
+        for field in entry.fields() {
+            let name = field.name.to_ascii_lowercase();
+            if !fields.iter().any(|(seen, _)| *seen == name) {
+                let value = entry.get_as_string_ignore_case(&name).unwrap_or_default();
+                fields.push((name, collapse(&value)));
+            }
+        }
+        fields
+    };
+    diff_pairs(diff, key, &fields(left), &fields(right));
+}
+
+fn diff_pairs(
+    diff: &mut String,
+    label: &str,
+    left: &[(String, String)],
+    right: &[(String, String)],
+) {
+    for (name, value) in left {
+        match right.iter().find(|(other, _)| other == name) {
+            Some((_, other)) if other == value => {}
+            Some((_, other)) => {
+                let _ = writeln!(diff, "  {label}.{name}");
+                let _ = writeln!(diff, "-   {value:?}");
+                let _ = writeln!(diff, "+   {other:?}");
+            }
+            None => {
+                let _ = writeln!(diff, "- {label}.{name} = {value:?}");
+            }
+        }
+    }
+    for (name, value) in right {
+        if !left.iter().any(|(other, _)| other == name) {
+            let _ = writeln!(diff, "+ {label}.{name} = {value:?}");
+        }
+    }
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
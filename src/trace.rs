@@ -0,0 +1,98 @@
+//! Optional `tracing` instrumentation for parse phases.
+//!
+//! With the `tracing` feature, [`phase!`] opens a span with `bytes`,
+//! `entries`, and `mib_per_sec` fields; the span is entered until the
+//! returned [`Phase`] is finished or dropped, which records the throughput.
+//! Without the feature, [`Phase`] is empty and the calls compile to nothing.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+#[cfg(feature = "tracing")]
+use tracing::span::EnteredSpan;
+
+/// Open a phase span: `phase!(info_span, "parse", input.len(), extra = value)`.
+#[cfg(feature = "tracing")]
+macro_rules! phase {
+    ($level:ident, $name:literal, $bytes:expr $(, $field:ident = $value:expr)* $(,)?) => {{
+        let bytes = $bytes;
+        $crate::trace::Phase::enter(
+            ::tracing::$level!(
+                $name,
+                bytes,
+                entries = ::tracing::field::Empty,
+                mib_per_sec = ::tracing::field::Empty
+                $(, $field = $value)*
+            ),
+            bytes,
+        )
+    }};
+}
+
+/// Open a phase span: `phase!(info_span, "parse", input.len(), extra = value)`.
+#[cfg(not(feature = "tracing"))]
+macro_rules! phase {
+    ($level:ident, $name:literal, $bytes:expr $(, $field:ident = $value:expr)* $(,)?) => {
+        $crate::trace::Phase::disabled($bytes)
+    };
+}
+
+pub(crate) use phase;
+
+/// An entered phase span.
+pub struct Phase {
+    #[cfg(feature = "tracing")]
+    span: EnteredSpan,
+    #[cfg(feature = "tracing")]
+    started: Instant,
+    #[cfg(feature = "tracing")]
+    bytes: usize,
+}
+
+#[cfg(feature = "tracing")]
+impl Phase {
+    pub fn enter(span: tracing::Span, bytes: usize) -> Self {
+        Self {
+            span: span.entered(),
+            started: Instant::now(),
+            bytes,
+        }
+    }
+
+    /// Record the number of entries the phase produced.
+    pub fn entries(&self, entries: usize) {
+        self.span.record("entries", entries);
+    }
+
+    /// End the phase before the end of the scope.
+    pub fn finish(self) {
+        drop(self);
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[allow(clippy::unused_self)]
+impl Phase {
+    pub const fn disabled(_bytes: usize) -> Self {
+        Self {}
+    }
+
+    /// Record the number of entries the phase produced.
+    #[inline]
+    pub const fn entries(&self, _entries: usize) {}
+
+    /// End the phase before the end of the scope.
+    #[inline]
+    pub const fn finish(self) {}
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Phase {
+    fn drop(&mut self) {
+        let seconds = self.started.elapsed().as_secs_f64();
+        if seconds > 0.0 {
+            #[allow(clippy::cast_precision_loss)]
+            let mib = self.bytes as f64 / (1024.0 * 1024.0);
+            self.span.record("mib_per_sec", mib / seconds);
+        }
+    }
+}
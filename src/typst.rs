@@ -0,0 +1,184 @@
+//! Compatibility checks for Typst's `#bibliography` loader.
+//!
+//! Typst reads `.bib` files with the `biblatex` crate and converts them with
+//! hayagriva. The checks below flag input that loader rejects (errors), input
+//! it reads differently (warnings), and fields it drops (info).
+
+use crate::{parse_date_parts, DiagnosticCode, Entry, EntryType, Library, ValidationError, Value};
+
+/// Custom types the loader maps to a known type instead of `misc`.
+const ALIASED_TYPES: &[&str] = &["electronic", "www", "mvreference", "suppperiodical"];
+
+/// Fields Typst accepts but never renders.
+const IGNORED_FIELDS: &[&str] = &[
+    "abstract",
+    "annote",
+    "annotation",
+    "file",
+    "groups",
+    "keywords",
+    "mendeley-tags",
+    "owner",
+    "timestamp",
+];
+
+impl Entry<'_> {
+    /// Check the entry against what Typst's `#bibliography` loader accepts.
+    ///
+    /// Errors mark values the loader rejects, such as a `year` that is not
+    /// four digits, a malformed `date`, or an undefined `@string` variable.
+    /// Warnings mark entry types read as `misc` and keys that cannot be cited
+    /// with `@key` syntax. Info marks fields Typst ignores.
+    pub fn validate_typst(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let EntryType::Custom(name) = &self.ty {
+            if !ALIASED_TYPES.contains(&name.to_ascii_lowercase().as_str()) {
+                errors.push(
+                    ValidationError::warning(None, format!("Typst reads @{name} as @misc"))
+                        .with_code(DiagnosticCode::borrowed("typst-misc-type")),
+                );
+            }
+        }
+
+        if !is_typst_label(&self.key) {
+            errors.push(
+                ValidationError::warning(
+                    None,
+                    format!(
+                        "key `{}` cannot be cited as @key; use #cite(label(\"{}\"))",
+                        self.key, self.key
+                    ),
+                )
+                .with_code(DiagnosticCode::borrowed("typst-uncitable-key")),
+            );
+        }
+
+        for field in &self.fields {
+            let name = field.name.to_ascii_lowercase();
+            if let Some(variable) = unresolved_variable(&field.value) {
+                errors.push(
+                    ValidationError::error(
+                        Some(&name),
+                        format!("Typst rejects the undefined @string variable `{variable}`"),
+                    )
+                    .with_code(DiagnosticCode::borrowed("typst-undefined-variable")),
+                );
+                continue;
+            }
+            let value = field.value.to_plain_string();
+            match name.as_str() {
+                "year" if !is_four_digit_year(value.trim()) => {
+                    errors.push(
+                        ValidationError::error(Some("year"), "Typst requires a four-digit year")
+                            .with_code(DiagnosticCode::borrowed("typst-year")),
+                    );
+                }
+                "date" | "urldate" | "origdate" | "eventdate" if !is_edtf_date(value.trim()) => {
+                    errors.push(
+                        ValidationError::error(
+                            Some(&name),
+                            format!(
+                                "Typst requires {name} as YYYY, YYYY-MM, YYYY-MM-DD, or a range"
+                            ),
+                        )
+                        .with_code(DiagnosticCode::borrowed("typst-date")),
+                    );
+                }
+                _ if IGNORED_FIELDS.contains(&name.as_str()) => {
+                    errors.push(
+                        ValidationError::info(Some(&name), format!("Typst ignores {name}"))
+                            .with_code(DiagnosticCode::borrowed("typst-ignored-field")),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<'a> Library<'a> {
+    /// Check every entry against Typst's `#bibliography` loader.
+    ///
+    /// Returns the entries with findings, like [`Library::validate`]. See
+    /// [`Entry::validate_typst`] for the checks.
+    #[must_use]
+    pub fn validate_typst(&self) -> Vec<(usize, &Entry<'a>, Vec<ValidationError>)> {
+        self.entries()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                entry
+                    .validate_typst()
+                    .err()
+                    .map(|errors| (index, entry, errors))
+            })
+            .collect()
+    }
+}
+
+/// Month abbreviations are resolved by the loader; any other bare variable is
+/// an error.
+fn unresolved_variable<'v>(value: &'v Value<'_>) -> Option<&'v str> {
+    match value {
+        Value::Variable(name) if !is_month_macro(name) => Some(name),
+        Value::Concat(parts) => parts.iter().find_map(unresolved_variable),
+        _ => None,
+    }
+}
+
+fn is_month_macro(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "jan"
+            | "feb"
+            | "mar"
+            | "apr"
+            | "may"
+            | "jun"
+            | "jul"
+            | "aug"
+            | "sep"
+            | "oct"
+            | "nov"
+            | "dec"
+    )
+}
+
+/// Typst label syntax: alphanumerics, `_`, `-`, `:`, and `.`, not ending in
+/// punctuation that would end the sentence instead.
+fn is_typst_label(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | ':' | '.'))
+        && !key.ends_with(['.', ':'])
+}
+
+fn is_four_digit_year(value: &str) -> bool {
+    value.len() == 4 && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// `YYYY[-MM[-DD]]` with optional `?`/`~`/`%` qualifiers, or a `/` range
+/// whose ends may be open (empty or `..`).
+fn is_edtf_date(value: &str) -> bool {
+    let is_point = |point: &str| {
+        let point = point.trim_end_matches(['?', '~', '%']);
+        point.split('-').next().is_some_and(is_four_digit_year) && parse_date_parts(point).is_ok()
+    };
+    match value.split_once('/') {
+        Some((start, end)) => {
+            let open = |side: &str| side.is_empty() || side == "..";
+            (open(start) || is_point(start))
+                && (open(end) || is_point(end))
+                && !(open(start) && open(end))
+        }
+        None => is_point(value),
+    }
+}
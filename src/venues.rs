@@ -0,0 +1,120 @@
+//! Clustering of near-identical venue names.
+
+use crate::Entry;
+use ahash::AHashMap;
+
+/// Venue fields considered by [`cluster_venues`].
+pub const VENUE_FIELDS: &[&str] = &["journal", "journaltitle", "booktitle"];
+
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("proc", "proceedings"),
+    ("procs", "proceedings"),
+    ("conf", "conference"),
+    ("int", "international"),
+    ("intl", "international"),
+    ("trans", "transactions"),
+    ("j", "journal"),
+    ("jour", "journal"),
+    ("symp", "symposium"),
+    ("ann", "annual"),
+    ("assoc", "association"),
+    ("soc", "society"),
+    ("natl", "national"),
+    ("rev", "review"),
+    ("lett", "letters"),
+    ("sci", "science"),
+    ("res", "research"),
+    ("eng", "engineering"),
+    ("mach", "machine"),
+    ("syst", "systems"),
+    ("comm", "communications"),
+    ("commun", "communications"),
+];
+
+const STOPWORDS: &[&str] = &["the", "of", "on", "and", "in", "for", "at", "a", "an"];
+
+/// A group of venue spellings that normalize to the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VenueCluster {
+    /// Normalized key shared by every variant.
+    pub key: String,
+    /// Proposed spelling: the most common variant, preferring longer text on ties.
+    pub canonical: String,
+    /// Distinct spellings with their occurrence counts, most common first.
+    pub variants: Vec<(String, usize)>,
+    /// Indices of entries using any variant, in input order.
+    pub entries: Vec<usize>,
+}
+
+/// Normalize a venue name for clustering.
+///
+/// Case, braces, punctuation, and stop words are dropped, and common
+/// abbreviations such as `Proc.` or `Intl.` are expanded. Years and
+/// ordinals are kept, so `37th ... 2020` never merges with an undated
+/// spelling of the same venue.
+#[must_use]
+pub fn venue_key(venue: &str) -> String {
+    venue
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .filter(|token| !STOPWORDS.contains(&token.as_str()))
+        .map(|token| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| *short == token)
+                .map_or(token, |(_, long)| (*long).to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cluster the venue fields of `entries` and return groups with more than one spelling.
+///
+/// Each entry contributes its first literal field from [`VENUE_FIELDS`];
+/// macro references are skipped. Clusters are ordered by their first entry.
+#[must_use]
+pub fn cluster_venues<'e, 'a: 'e>(
+    entries: impl IntoIterator<Item = &'e Entry<'a>>,
+) -> Vec<VenueCluster> {
+    let mut clusters = Vec::<VenueCluster>::new();
+    let mut by_key = AHashMap::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let Some(venue) = entry.get_any_ignore_case(VENUE_FIELDS) else {
+            continue;
+        };
+        let venue = venue.split_whitespace().collect::<Vec<_>>().join(" ");
+        let key = venue_key(&venue);
+        if key.is_empty() {
+            continue;
+        }
+        let cluster = *by_key.entry(key.clone()).or_insert_with(|| {
+            clusters.push(VenueCluster {
+                key,
+                canonical: String::new(),
+                variants: Vec::new(),
+                entries: Vec::new(),
+            });
+            clusters.len() - 1
+        });
+        let cluster = &mut clusters[cluster];
+        cluster.entries.push(index);
+        if let Some(variant) = cluster.variants.iter_mut().find(|(text, _)| *text == venue) {
+            variant.1 += 1;
+        } else {
+            cluster.variants.push((venue, 1));
+        }
+    }
+
+    clusters.retain(|cluster| cluster.variants.len() > 1);
+    for cluster in &mut clusters {
+        cluster.variants.sort_by(|left, right| {
+            right
+                .1
+                .cmp(&left.1)
+                .then_with(|| right.0.len().cmp(&left.0.len()))
+        });
+        cluster.canonical = cluster.variants[0].0.clone();
+    }
+    clusters
+}
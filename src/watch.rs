@@ -0,0 +1,372 @@
+//! Live reloading of bibliography files.
+//!
+//! Enabled by the `watch` feature. A [`LibraryWatcher`] parses a set of
+//! files, watches them for edits, and re-parses only the file that changed.
+//! Each reload is compared with the previous contents of that file by
+//! citation key, and the added, removed, and modified entries are sent to
+//! every subscriber as a [`WatchEvent`], which is what live-preview tools
+//! need to update only what changed.
+//!
+//! ```no_run
+//! use bibtex_parser::watch::{LibraryWatcher, WatchEvent};
+//!
+//! let watcher = LibraryWatcher::new(&["refs.bib"])?;
+//! let events = watcher.subscribe();
+//! for event in events {
+//!     if let WatchEvent::Changed { path, changes } = event {
+//!         println!("{}: {} entries changed", path.display(), changes.len());
+//!     }
+//! }
+//! # Ok::<(), bibtex_parser::Error>(())
+//! ```
+//!
+//! Parent directories are watched rather than the files themselves, so
+//! editors that save by replacing the file are followed. Events are
+//! debounced: a file is re-parsed once it has been quiet for 50 ms, so a
+//! burst of writes from one save costs one parse. A deleted file keeps its
+//! entries until it is created again, so a save that deletes and rewrites
+//! the file is not reported as removing everything.
+
+use crate::{Entry, Error, Library, Parser, Result, SharedLibrary};
+use ahash::AHashMap;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Quiet period after the last event for a file before it is re-parsed.
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A change to one entry between two parses of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryChange {
+    /// An entry whose key was not in the file before.
+    Added(Entry<'static>),
+    /// An entry whose key is no longer in the file.
+    Removed(Entry<'static>),
+    /// An entry whose key is kept but whose type or fields changed.
+    Modified {
+        /// Entry before the edit.
+        before: Entry<'static>,
+        /// Entry after the edit.
+        after: Entry<'static>,
+    },
+}
+
+impl EntryChange {
+    /// Return the citation key of the changed entry.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Added(entry) | Self::Removed(entry) | Self::Modified { after: entry, .. } => {
+                entry.key()
+            }
+        }
+    }
+}
+
+/// A notification sent to [`LibraryWatcher`] subscribers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// A file was re-parsed and its entries changed.
+    Changed {
+        /// Watched file.
+        path: PathBuf,
+        /// Entry changes, removals first, then modifications and additions
+        /// in file order.
+        changes: Vec<EntryChange>,
+    },
+    /// A file could not be re-parsed; its previous entries are kept.
+    Failed {
+        /// Watched file.
+        path: PathBuf,
+        /// Error description.
+        message: String,
+    },
+}
+
+/// Watches bibliography files and reports entry changes to subscribers.
+///
+/// Watching stops when the watcher is dropped, which also closes every
+/// subscription channel.
+pub struct LibraryWatcher {
+    state: Arc<Mutex<State>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for LibraryWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LibraryWatcher")
+            .field("paths", &self.paths())
+            .finish_non_exhaustive()
+    }
+}
+
+struct State {
+    parser: Arc<Parser>,
+    files: Vec<WatchedFile>,
+    subscribers: Vec<Sender<WatchEvent>>,
+    published: Vec<SharedLibrary>,
+}
+
+struct WatchedFile {
+    path: PathBuf,
+    entries: Vec<Entry<'static>>,
+}
+
+impl LibraryWatcher {
+    /// Parse `paths` with a default [`Parser`] and start watching them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a file cannot be read or parsed, or
+    /// [`Error::Watch`] when the platform watcher cannot be started.
+    pub fn new<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        Self::with_parser(paths, Parser::new())
+    }
+
+    /// Parse `paths` with `parser` and start watching them.
+    ///
+    /// The same parser settings are used for every reload.
+    ///
+    /// # Errors
+    ///
+    /// See [`LibraryWatcher::new`].
+    pub fn with_parser<P: AsRef<Path>>(paths: &[P], parser: Parser) -> Result<Self> {
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let path = path.as_ref().canonicalize()?;
+            let entries = read_entries(&parser, &path)?.unwrap_or_default();
+            files.push(WatchedFile { path, entries });
+        }
+        let mut dirs: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.path.parent().map(Path::to_path_buf))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        let state = Arc::new(Mutex::new(State {
+            parser: Arc::new(parser),
+            files,
+            subscribers: Vec::new(),
+            published: Vec::new(),
+        }));
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                for path in event.paths {
+                    // The worker has stopped only when the watcher is gone.
+                    let _ = sender.send(path);
+                }
+            })
+            .map_err(|error| Error::Watch(error.to_string()))?;
+        let worker_state = Arc::clone(&state);
+        thread::spawn(move || reload_debounced(&worker_state, &receiver));
+        for dir in &dirs {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|error| Error::Watch(error.to_string()))?;
+        }
+
+        Ok(Self {
+            state,
+            _watcher: watcher,
+        })
+    }
+
+    /// Subscribe to change events.
+    ///
+    /// Each subscriber receives every event sent after it subscribed.
+    /// Dropping the receiver ends the subscription.
+    #[must_use]
+    pub fn subscribe(&self) -> Receiver<WatchEvent> {
+        let (sender, receiver) = mpsc::channel();
+        lock(&self.state).subscribers.push(sender);
+        receiver
+    }
+
+    /// Re-parse `path` now instead of waiting for a file event.
+    ///
+    /// Subscribers are notified as for a file event. Returns `None` when
+    /// `path` is not watched.
+    pub fn reload(&self, path: impl AsRef<Path>) -> Option<WatchEvent> {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        reload(&self.state, &path)
+    }
+
+    /// Return the watched files, canonicalized, in the order given.
+    #[must_use]
+    pub fn paths(&self) -> Vec<PathBuf> {
+        lock(&self.state)
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect()
+    }
+
+    /// Return the current entries of every watched file as one library.
+    #[must_use]
+    pub fn library(&self) -> Library<'static> {
+        lock(&self.state).library()
+    }
+
+    /// Keep `shared` holding the current entries of every watched file.
+    ///
+    /// `shared` is replaced now and after every reload that changes entries,
+    /// so readers of it always see a complete parse.
+    pub fn publish_to(&self, shared: &SharedLibrary) {
+        let mut state = lock(&self.state);
+        shared.store(state.library());
+        state.published.push(shared.clone());
+    }
+}
+
+impl State {
+    fn library(&self) -> Library<'static> {
+        let mut library = Library::new();
+        for entry in self.files.iter().flat_map(|file| &file.entries) {
+            library.add_entry(entry.clone());
+        }
+        library
+    }
+
+    /// Record a new parse of `path` and notify subscribers of changes.
+    fn update(&mut self, path: &Path, entries: Result<Vec<Entry<'static>>>) -> Option<WatchEvent> {
+        let file = self.files.iter_mut().find(|file| file.path == path)?;
+        let event = match entries {
+            Ok(entries) => {
+                let changes = diff_entries(&file.entries, &entries);
+                file.entries = entries;
+                if changes.is_empty() {
+                    return None;
+                }
+                WatchEvent::Changed {
+                    path: file.path.clone(),
+                    changes,
+                }
+            }
+            Err(error) => WatchEvent::Failed {
+                path: file.path.clone(),
+                message: error.to_string(),
+            },
+        };
+        if matches!(event, WatchEvent::Changed { .. }) && !self.published.is_empty() {
+            let library = self.library();
+            for shared in &self.published {
+                shared.store(library.clone());
+            }
+        }
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        Some(event)
+    }
+}
+
+/// Re-parse `path` if it is watched and notify subscribers of changes.
+///
+/// The file is read and parsed without holding the state lock. Returns
+/// `None` when `path` is not watched, is missing, or did not change.
+fn reload(state: &Mutex<State>, path: &Path) -> Option<WatchEvent> {
+    let parser = {
+        let state = lock(state);
+        if !state.files.iter().any(|file| file.path == path) {
+            return None;
+        }
+        Arc::clone(&state.parser)
+    };
+    let entries = read_entries(&parser, path).transpose()?;
+    lock(state).update(path, entries)
+}
+
+/// Reload each path once its events have been quiet for [`DEBOUNCE`].
+///
+/// Returns when the watcher, and with it the sending side, is dropped.
+fn reload_debounced(state: &Mutex<State>, paths: &Receiver<PathBuf>) {
+    while let Ok(path) = paths.recv() {
+        let mut pending = vec![path];
+        loop {
+            match paths.recv_timeout(DEBOUNCE) {
+                Ok(path) if pending.contains(&path) => {}
+                Ok(path) => pending.push(path),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        for path in &pending {
+            reload(state, path);
+        }
+    }
+}
+
+/// Parse the entries of `path`, returning `None` when the file is missing.
+fn read_entries(parser: &Parser, path: &Path) -> Result<Option<Vec<Entry<'static>>>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    Ok(Some(
+        parser
+            .parse(&content)?
+            .entries()
+            .iter()
+            .map(|entry| entry.clone().into_owned())
+            .collect(),
+    ))
+}
+
+/// Compare two parses of one file by citation key.
+///
+/// The n-th entry with a key is paired with the n-th entry with the same
+/// key in the other parse, so duplicate keys are compared in file order.
+fn diff_entries(before: &[Entry<'static>], after: &[Entry<'static>]) -> Vec<EntryChange> {
+    let mut before_by_key = AHashMap::<&str, Vec<&Entry<'static>>>::new();
+    for entry in before {
+        before_by_key.entry(entry.key()).or_default().push(entry);
+    }
+    let mut after_counts = AHashMap::<&str, usize>::new();
+    for entry in after {
+        *after_counts.entry(entry.key()).or_default() += 1;
+    }
+
+    let mut changes = Vec::new();
+    let mut seen = AHashMap::<&str, usize>::new();
+    for entry in before {
+        let rank = seen.entry(entry.key()).or_default();
+        if *rank >= after_counts.get(entry.key()).copied().unwrap_or_default() {
+            changes.push(EntryChange::Removed(entry.clone()));
+        }
+        *rank += 1;
+    }
+    seen.clear();
+    for entry in after {
+        let rank = seen.entry(entry.key()).or_default();
+        let previous = before_by_key
+            .get(entry.key())
+            .and_then(|entries| entries.get(*rank));
+        *rank += 1;
+        match previous {
+            Some(previous) if *previous != entry => changes.push(EntryChange::Modified {
+                before: (*previous).clone(),
+                after: entry.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(EntryChange::Added(entry.clone())),
+        }
+    }
+    changes
+}
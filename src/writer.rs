@@ -1,11 +1,30 @@
 //! BibTeX writer for serializing libraries
 
-use crate::{Block, Entry, Library, ParsedBlock, ParsedDocument, ParsedEntry, Result, Value};
+use crate::model::{collect_variable_names, value_to_bibtex_source};
+use crate::{
+    Block, Entry, Field, Library, LibraryView, ParsedBlock, ParsedDocument, ParsedEntry, Result,
+    SourceSpan, StringDefinition, Value, ValueDelimiter,
+};
 use std::borrow::Cow;
-use std::io::{self, Write};
+use std::fmt;
+use std::io::{self, BufWriter, Write};
 
 /// Configuration for writing BibTeX
+///
+/// Start from [`WriterConfig::default`] and set options with the builder
+/// methods or by assigning fields; new options may be added without a major
+/// release.
+///
+/// ```
+/// use bibtex_parser::{TrailingComma, WriterConfig};
+///
+/// let config = WriterConfig::default()
+///     .align_values(true)
+///     .trailing_comma(TrailingComma::Always);
+/// assert!(config.align_values);
+/// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct WriterConfig {
     /// Indentation string (default: "  ")
     pub indent: String,
@@ -23,6 +42,465 @@ pub struct WriterConfig {
     pub trailing_comma: TrailingComma,
     /// Separator written between document blocks.
     pub entry_separator: String,
+    /// Blank lines written between blocks, replacing `entry_separator` and
+    /// the single blank line between library blocks (default: none).
+    pub blank_lines_between_entries: Option<usize>,
+    /// Spacing between parsed document blocks (default: configured).
+    pub block_spacing: BlockSpacing,
+    /// Where library string definitions and preambles are written.
+    pub header_placement: HeaderPlacement,
+    /// Group sorted entries under `%%` section comments (default: none).
+    ///
+    /// Only applies when `sort_entries` is set; entries are then sorted by
+    /// section and by key within each section.
+    pub section_headers: Option<SectionGrouping>,
+    /// Handling of repeated field names within an entry.
+    pub duplicate_fields: DuplicateFieldPolicy,
+    /// Delimiter used for literal values (default: braces).
+    pub value_delimiter: DelimiterStyle,
+    /// Shorten or drop overly long fields such as `abstract` (default: none).
+    pub long_fields: Option<FieldTruncation>,
+    /// Per-entry-type field order templates (default: none).
+    ///
+    /// Listed fields are written first, in template order; the rest follow
+    /// in source order, or by name when `sort_fields` is set.
+    pub field_order: Option<FieldOrder>,
+    /// Align `=` signs at a fixed column (default: none).
+    ///
+    /// Takes precedence over `align_values`.
+    pub tabular: Option<TabularStyle>,
+}
+
+/// Return the source text under `span`.
+fn source_text(source: &str, span: SourceSpan) -> Option<&str> {
+    source.get(span.byte_start..span.byte_end)
+}
+
+/// Return `true` when `source[start..end]` holds only whitespace, the
+/// trailing `comment`, and one comma, or at most one when `comma` is false.
+fn is_separator_gap(
+    source: &str,
+    start: usize,
+    end: usize,
+    comment: Option<SourceSpan>,
+    comma: bool,
+) -> bool {
+    let Some(gap) = source.get(start..end) else {
+        return false;
+    };
+    let (before, after) = match comment {
+        Some(comment) if comment.byte_start >= start && comment.byte_end <= end => (
+            &gap[..comment.byte_start - start],
+            &gap[comment.byte_end - start..],
+        ),
+        _ => (gap, ""),
+    };
+    let layout = || before.chars().chain(after.chars());
+    let commas = layout().filter(|&ch| ch == ',').count();
+    layout().all(|ch| ch == ',' || ch.is_whitespace()) && (commas == 1 || (!comma && commas == 0))
+}
+
+/// Return the character width of the longest name.
+fn name_width<'n>(names: impl Iterator<Item = &'n str>) -> usize {
+    names.map(|name| name.chars().count()).max().unwrap_or(0)
+}
+
+/// Size limit for long free-text fields such as `abstract` and `note`.
+///
+/// Used by [`WriterConfig::long_fields`] and
+/// [`Library::truncate_long_fields`](crate::Library::truncate_long_fields).
+/// Lengths count characters, and only literal values are affected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldTruncation {
+    fields: Vec<String>,
+    max_chars: usize,
+    drop: bool,
+    ellipsis: String,
+}
+
+impl FieldTruncation {
+    /// Limit `abstract` and `note` to `max_chars` characters.
+    #[must_use]
+    pub fn new(max_chars: usize) -> Self {
+        Self {
+            fields: vec!["abstract".to_string(), "note".to_string()],
+            max_chars,
+            drop: false,
+            ellipsis: "...".to_string(),
+        }
+    }
+
+    /// Replace the limited field names, matched ignoring ASCII case.
+    #[must_use]
+    pub fn fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Drop over-long fields instead of truncating them.
+    #[must_use]
+    #[inline]
+    pub const fn drop_fields(mut self) -> Self {
+        self.drop = true;
+        self
+    }
+
+    /// Set the text appended to truncated values (default: `...`).
+    #[must_use]
+    pub fn ellipsis(mut self, ellipsis: impl Into<String>) -> Self {
+        self.ellipsis = ellipsis.into();
+        self
+    }
+
+    /// Return `true` when `name` is one of the limited fields.
+    #[must_use]
+    pub fn applies_to(&self, name: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.eq_ignore_ascii_case(name))
+    }
+
+    /// Return `true` when over-long fields are dropped rather than truncated.
+    #[must_use]
+    pub const fn drops_fields(&self) -> bool {
+        self.drop
+    }
+
+    /// Shorten `text` when it exceeds the limit.
+    ///
+    /// The cut falls on the last whitespace outside braces that leaves room
+    /// for the ellipsis, so words and brace groups stay intact. Text with no
+    /// such whitespace, such as a fully braced value, is cut at the last
+    /// whitespace inside a brace group, or else at the last character that
+    /// fits; groups left open by the cut are closed. Returns `None` when the
+    /// text already fits.
+    #[must_use]
+    pub fn truncate(&self, text: &str) -> Option<String> {
+        if text.chars().count() <= self.max_chars {
+            return None;
+        }
+        let budget = self.max_chars.saturating_sub(self.ellipsis.chars().count());
+        let mut depth = 0usize;
+        let mut outer = None;
+        let mut inner = None;
+        let mut boundary = (0, 0);
+        for (count, (index, ch)) in text.char_indices().enumerate() {
+            if count + depth > budget {
+                break;
+            }
+            boundary = (index, depth);
+            match ch {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                ch if ch.is_whitespace() && depth == 0 => outer = Some((index, 0)),
+                ch if ch.is_whitespace() => inner = Some((index, depth)),
+                _ => {}
+            }
+        }
+        let (cut, open) = outer.or(inner).unwrap_or(boundary);
+        let kept =
+            text[..cut].trim_end_matches(|ch: char| ch.is_whitespace() || ",;:.".contains(ch));
+        let kept = kept.strip_suffix('\\').unwrap_or(kept);
+        Some(format!("{kept}{}{}", "}".repeat(open), self.ellipsis))
+    }
+
+    /// Decide how a field value is written under this limit.
+    pub(crate) fn limit(&self, name: &str, value: &Value) -> FieldLimit {
+        let truncated = self
+            .applies_to(name)
+            .then(|| value.as_str().and_then(|text| self.truncate(text)))
+            .flatten();
+        match truncated {
+            None => FieldLimit::Keep,
+            Some(_) if self.drop => FieldLimit::Drop,
+            Some(text) => FieldLimit::Replace(text),
+        }
+    }
+}
+
+/// Outcome of applying a [`FieldTruncation`] to one field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldLimit {
+    Keep,
+    Drop,
+    Replace(String),
+}
+
+/// Column layout that lines up `=` signs and value starts.
+///
+/// Used by [`WriterConfig::tabular`]. The `=` sign of each field goes at a
+/// fixed character column counted from the start of the line. Field names
+/// too long for that column push it out for their whole entry, or for the
+/// whole file with [`Self::across_file`]. Raw-preserved entries keep their
+/// original layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabularStyle {
+    column: usize,
+    across_file: bool,
+}
+
+impl TabularStyle {
+    /// Put `=` signs at zero-based `column`.
+    #[must_use]
+    pub const fn new(column: usize) -> Self {
+        Self {
+            column,
+            across_file: false,
+        }
+    }
+
+    /// Push the column out to fit the longest field name in the written
+    /// library or document rather than in each entry.
+    #[must_use]
+    pub const fn across_file(mut self) -> Self {
+        self.across_file = true;
+        self
+    }
+
+    /// Return the requested `=` column.
+    #[must_use]
+    pub const fn column(self) -> usize {
+        self.column
+    }
+
+    /// Return `true` when the column is shared across the file.
+    #[must_use]
+    pub const fn is_across_file(self) -> bool {
+        self.across_file
+    }
+}
+
+/// Field order templates keyed by entry type.
+///
+/// Used by [`WriterConfig::field_order`]. Entry types without their own
+/// template use the default template. Names are matched ignoring ASCII case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldOrder {
+    default: Vec<String>,
+    by_type: Vec<(String, Vec<String>)>,
+}
+
+/// Conventional field orders used by [`FieldOrder::standard`].
+const STANDARD_FIELD_ORDER: &[(&str, &[&str])] = &[
+    (
+        "article",
+        &[
+            "author", "title", "journal", "volume", "number", "pages", "year", "month",
+        ],
+    ),
+    (
+        "inproceedings",
+        &[
+            "author",
+            "title",
+            "booktitle",
+            "editor",
+            "pages",
+            "publisher",
+            "address",
+            "year",
+            "month",
+        ],
+    ),
+    (
+        "incollection",
+        &[
+            "author",
+            "title",
+            "booktitle",
+            "editor",
+            "pages",
+            "publisher",
+            "address",
+            "year",
+        ],
+    ),
+    (
+        "book",
+        &[
+            "author",
+            "editor",
+            "title",
+            "edition",
+            "series",
+            "volume",
+            "publisher",
+            "address",
+            "year",
+        ],
+    ),
+    (
+        "phdthesis",
+        &["author", "title", "school", "type", "address", "year"],
+    ),
+    (
+        "mastersthesis",
+        &["author", "title", "school", "type", "address", "year"],
+    ),
+    (
+        "techreport",
+        &[
+            "author",
+            "title",
+            "institution",
+            "number",
+            "address",
+            "year",
+        ],
+    ),
+];
+
+impl FieldOrder {
+    /// Create an empty order that leaves fields as they are.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Conventional orders for common entry types, such as
+    /// `journal`, `volume`, `number`, `pages` for articles and `booktitle`,
+    /// `editor`, `pages` for proceedings papers.
+    ///
+    /// Other types list `author`, `editor`, `title`, and `year` first.
+    #[must_use]
+    pub fn standard() -> Self {
+        STANDARD_FIELD_ORDER.iter().fold(
+            Self::new().default_order(["author", "editor", "title", "year"]),
+            |order, (ty, fields)| order.entry_type(ty, fields.iter().copied()),
+        )
+    }
+
+    /// Set the template for entry types without their own template.
+    #[must_use]
+    pub fn default_order<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.default = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the template for `entry_type`, replacing any earlier one.
+    #[must_use]
+    pub fn entry_type<I, S>(mut self, entry_type: &str, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fields = fields.into_iter().map(Into::into).collect();
+        match self
+            .by_type
+            .iter_mut()
+            .find(|(ty, _)| ty.eq_ignore_ascii_case(entry_type))
+        {
+            Some((_, template)) => *template = fields,
+            None => self.by_type.push((entry_type.to_string(), fields)),
+        }
+        self
+    }
+
+    /// Return the template used for `entry_type`.
+    #[must_use]
+    pub fn template(&self, entry_type: &str) -> &[String] {
+        self.by_type
+            .iter()
+            .find(|(ty, _)| ty.eq_ignore_ascii_case(entry_type))
+            .map_or(&self.default, |(_, template)| template)
+    }
+
+    /// Stable-sort `fields` into template order for `entry_type`.
+    pub(crate) fn sort<T>(&self, entry_type: &str, fields: &mut [T], name: impl Fn(&T) -> &str) {
+        let template = self.template(entry_type);
+        fields.sort_by_key(|field| {
+            let name = name(field);
+            template
+                .iter()
+                .position(|listed| listed.eq_ignore_ascii_case(name))
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+/// Delimiter used when writing literal values.
+///
+/// Literals whose braces do not balance are always written in quotes, since
+/// braces cannot delimit them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DelimiterStyle {
+    /// Write literals as `{...}`.
+    #[default]
+    Braces,
+    /// Write literals as `"..."`, brace-protecting inner quotes.
+    Quotes,
+    /// Reuse the delimiter a parsed value had, falling back to braces.
+    PreserveOriginal,
+}
+
+impl DelimiterStyle {
+    fn quotes(self, original: Option<ValueDelimiter>) -> bool {
+        match self {
+            Self::Braces => false,
+            Self::Quotes => true,
+            Self::PreserveOriginal => original == Some(ValueDelimiter::Quotes),
+        }
+    }
+}
+
+/// Handling of repeated field names within an entry when writing.
+///
+/// Field names are compared ignoring ASCII case. BibTeX silently uses the
+/// first occurrence of a repeated field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateFieldPolicy {
+    /// Write every occurrence without checking.
+    #[default]
+    Allow,
+    /// Write every occurrence and record it in [`Writer::duplicate_fields`].
+    Warn,
+    /// Fail with an [`io::ErrorKind::InvalidData`] error before writing the entry.
+    Error,
+    /// Write only the first occurrence, as BibTeX would read it.
+    KeepFirst,
+}
+
+/// Grouping used for section header comments in sorted output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionGrouping {
+    /// One section per entry type, such as `%% Articles`.
+    EntryType,
+    /// One section per year, such as `%% 2024`.
+    Year,
+}
+
+impl SectionGrouping {
+    /// Return the section title for `entry`.
+    #[must_use]
+    pub fn title(self, entry: &Entry) -> String {
+        match self {
+            Self::EntryType => entry_type_section_title(entry.ty.canonical_name()),
+            Self::Year => entry.year().unwrap_or_else(|| "No year".to_string()),
+        }
+    }
+}
+
+/// Placement of `@string` and `@preamble` blocks when writing a library.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeaderPlacement {
+    /// Keep blocks at their original positions.
+    ///
+    /// Sorted output has no original positions and behaves like [`Self::Top`].
+    #[default]
+    Preserve,
+    /// Write all preambles and then all string definitions before other blocks.
+    Top,
+    /// Write preambles first and each string definition just before the first
+    /// block that uses it. Strings that nothing uses stay in the header.
+    BeforeFirstUse,
 }
 
 /// Raw-backed document writing behavior.
@@ -34,6 +512,18 @@ pub enum RawWriteMode {
     Normalize,
 }
 
+/// Spacing between parsed document blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockSpacing {
+    /// Use `blank_lines_between_entries` or `entry_separator`.
+    Configured,
+    /// Keep the blank lines found between blocks in the source.
+    ///
+    /// Blocks without a source location, such as added entries, fall back
+    /// to the configured spacing.
+    Preserve,
+}
+
 /// Trailing comma behavior for structured entry writing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrailingComma {
@@ -54,29 +544,176 @@ impl Default for WriterConfig {
             raw_write_mode: RawWriteMode::Preserve,
             trailing_comma: TrailingComma::Omit,
             entry_separator: "\n".to_string(),
+            blank_lines_between_entries: None,
+            block_spacing: BlockSpacing::Configured,
+            header_placement: HeaderPlacement::Preserve,
+            section_headers: None,
+            duplicate_fields: DuplicateFieldPolicy::Allow,
+            value_delimiter: DelimiterStyle::Braces,
+            long_fields: None,
+            field_order: None,
+            tabular: None,
         }
     }
 }
 
+impl WriterConfig {
+    /// Set the indentation string.
+    #[must_use]
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Align field values.
+    #[must_use]
+    pub const fn align_values(mut self, align: bool) -> Self {
+        self.align_values = align;
+        self
+    }
+
+    /// Set the maximum line length for wrapping.
+    #[must_use]
+    pub const fn max_line_length(mut self, length: usize) -> Self {
+        self.max_line_length = length;
+        self
+    }
+
+    /// Sort entries by key.
+    #[must_use]
+    pub const fn sort_entries(mut self, sort: bool) -> Self {
+        self.sort_entries = sort;
+        self
+    }
+
+    /// Sort fields within entries.
+    #[must_use]
+    pub const fn sort_fields(mut self, sort: bool) -> Self {
+        self.sort_fields = sort;
+        self
+    }
+
+    /// Set the raw-backed document writing behavior.
+    #[must_use]
+    pub const fn raw_write_mode(mut self, mode: RawWriteMode) -> Self {
+        self.raw_write_mode = mode;
+        self
+    }
+
+    /// Set the trailing comma behavior.
+    #[must_use]
+    pub const fn trailing_comma(mut self, comma: TrailingComma) -> Self {
+        self.trailing_comma = comma;
+        self
+    }
+
+    /// Set the separator written between document blocks.
+    #[must_use]
+    pub fn entry_separator(mut self, separator: impl Into<String>) -> Self {
+        self.entry_separator = separator.into();
+        self
+    }
+
+    /// Write `lines` blank lines between blocks.
+    #[must_use]
+    pub const fn blank_lines_between_entries(mut self, lines: usize) -> Self {
+        self.blank_lines_between_entries = Some(lines);
+        self
+    }
+
+    /// Set the spacing between parsed document blocks.
+    #[must_use]
+    pub const fn block_spacing(mut self, spacing: BlockSpacing) -> Self {
+        self.block_spacing = spacing;
+        self
+    }
+
+    /// Set where string definitions and preambles are written.
+    #[must_use]
+    pub const fn header_placement(mut self, placement: HeaderPlacement) -> Self {
+        self.header_placement = placement;
+        self
+    }
+
+    /// Group sorted entries under `%%` section comments.
+    #[must_use]
+    pub const fn section_headers(mut self, grouping: SectionGrouping) -> Self {
+        self.section_headers = Some(grouping);
+        self
+    }
+
+    /// Set the handling of repeated field names.
+    #[must_use]
+    pub const fn duplicate_fields(mut self, policy: DuplicateFieldPolicy) -> Self {
+        self.duplicate_fields = policy;
+        self
+    }
+
+    /// Set the delimiter used for literal values.
+    #[must_use]
+    pub const fn value_delimiter(mut self, style: DelimiterStyle) -> Self {
+        self.value_delimiter = style;
+        self
+    }
+
+    /// Shorten or drop overly long fields.
+    #[must_use]
+    pub fn long_fields(mut self, truncation: FieldTruncation) -> Self {
+        self.long_fields = Some(truncation);
+        self
+    }
+
+    /// Set the per-entry-type field order templates.
+    #[must_use]
+    pub fn field_order(mut self, order: FieldOrder) -> Self {
+        self.field_order = Some(order);
+        self
+    }
+
+    /// Align `=` signs at a fixed column.
+    #[must_use]
+    pub const fn tabular(mut self, style: TabularStyle) -> Self {
+        self.tabular = Some(style);
+        self
+    }
+}
+
 /// BibTeX writer
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)]
 pub struct Writer<W: Write> {
-    writer: W,
+    writer: LineTracker<W>,
     config: WriterConfig,
+    duplicate_fields: Vec<(String, String)>,
+    /// Longest field name of the library or document being written, for
+    /// [`TabularStyle::across_file`].
+    file_name_width: usize,
 }
 
 impl<W: Write> Writer<W> {
     /// Create a new writer with default configuration
     pub fn new(writer: W) -> Self {
-        Self {
-            writer,
-            config: WriterConfig::default(),
-        }
+        Self::with_config(writer, WriterConfig::default())
     }
 
     /// Create a new writer with custom configuration
     pub const fn with_config(writer: W, config: WriterConfig) -> Self {
-        Self { writer, config }
+        Self {
+            writer: LineTracker {
+                inner: writer,
+                line_start: true,
+            },
+            config,
+            duplicate_fields: Vec::new(),
+            file_name_width: 0,
+        }
+    }
+
+    /// Return `(key, field)` pairs for repeated fields found while writing
+    /// with [`DuplicateFieldPolicy::Warn`].
+    #[must_use]
+    pub fn duplicate_fields(&self) -> &[(String, String)] {
+        &self.duplicate_fields
     }
 
     /// Access the writer configuration mutably
@@ -88,31 +725,200 @@ impl<W: Write> Writer<W> {
     /// Consume the writer and return the underlying writer
     #[must_use]
     pub fn into_inner(self) -> W {
-        self.writer
+        self.writer.inner
     }
 
     /// Write a complete library.
     pub fn write_library(&mut self, library: &Library) -> io::Result<()> {
-        if self.config.sort_entries {
-            return self.write_library_sorted(library);
+        self.write_library_entries(library, |_| true)
+    }
+
+    /// Write the entries of a library view along with the library's other blocks.
+    pub fn write_view(&mut self, view: &LibraryView) -> io::Result<()> {
+        self.write_library_entries(view.library(), |index| view.contains_index(index))
+    }
+
+    fn write_library_entries(
+        &mut self,
+        library: &Library,
+        keep: impl Fn(usize) -> bool,
+    ) -> io::Result<()> {
+        self.file_name_width = name_width(
+            library
+                .entries()
+                .iter()
+                .flat_map(Entry::fields)
+                .map(|field| field.name.as_ref()),
+        );
+        let sections = self
+            .config
+            .section_headers
+            .filter(|_| self.config.sort_entries);
+        let blocks = if self.config.sort_entries {
+            sorted_library_blocks(library, keep, sections)
+        } else {
+            library.blocks_with_entries(keep)
+        };
+        let blocks = match self.config.header_placement {
+            HeaderPlacement::Preserve => blocks,
+            HeaderPlacement::Top => header_first_blocks(blocks),
+            HeaderPlacement::BeforeFirstUse => strings_before_first_use(blocks),
+        };
+
+        let mut section = None;
+        let mut first = true;
+        for (index, block) in blocks.iter().enumerate() {
+            let mut text = None;
+            let mut header = None;
+            if let Block::Comment(comment) = block {
+                // Sorted output keeps only leading comments; blank lines after
+                // them belong to the old layout.
+                text = Some(if self.config.sort_entries {
+                    comment.text().trim_end()
+                } else {
+                    comment.text()
+                });
+            }
+            if let (Some(grouping), Block::Entry(..) | Block::Comment(_)) = (sections, block) {
+                // Sorted comments lead an entry, so the entry's section header
+                // goes first. A header written by an earlier sorted write is
+                // dropped rather than repeated.
+                let entry = blocks[index..].iter().find_map(|block| match block {
+                    Block::Entry(entry, _) => Some(*entry),
+                    _ => None,
+                });
+                if let Some(entry) = entry {
+                    let title = grouping.title(entry);
+                    text = text.map(|text| strip_section_header(text, &title));
+                    if section.as_ref() != Some(&title) {
+                        header = Some(title);
+                    }
+                }
+            }
+            if text.is_some_and(|text| text.trim().is_empty()) {
+                continue;
+            }
+
+            if !first {
+                match self.config.blank_lines_between_entries {
+                    Some(blank_lines) => self.write_blank_lines(blank_lines)?,
+                    None => writeln!(self.writer)?,
+                }
+            }
+            first = false;
+            if let Some(title) = header {
+                writeln!(self.writer, "%% {title}")?;
+                section = Some(title);
+            }
+            match text {
+                Some(text) => self.write_comment(text)?,
+                None => self.write_block(*block)?,
+            }
         }
 
+        Ok(())
+    }
+
+    /// Write a library over the source it was parsed from, copying unchanged
+    /// blocks verbatim.
+    ///
+    /// A block is unchanged when `original_source` holds an equal block at
+    /// the block's source span. Unchanged blocks and the whitespace between
+    /// source-adjacent blocks are copied as they are; edited and added blocks
+    /// are serialized with this writer's configuration, and removed blocks
+    /// disappear. Without source spans every block counts as changed, so parse
+    /// the library with [`Parser::capture_source`](crate::Parser::capture_source).
+    ///
+    /// `parser` must be the parser that produced `library`: `original_source`
+    /// is parsed again with it, so values that depend on its settings, such
+    /// as a [string library](crate::Parser::with_string_library), compare
+    /// like for like.
+    pub fn write_changes(
+        &mut self,
+        library: &Library,
+        original_source: &str,
+        parser: &crate::Parser,
+    ) -> io::Result<()> {
+        let original = parser.parse(original_source).ok();
+        let original_blocks = original.as_ref().map_or_else(Vec::new, |original| {
+            original
+                .blocks()
+                .into_iter()
+                .filter_map(|block| Some((block_source(block)?, block)))
+                .collect::<Vec<_>>()
+        });
+        let original_at = |span: SourceSpan| {
+            original_blocks
+                .binary_search_by_key(&span.byte_start, |(source, _)| source.byte_start)
+                .ok()
+                .map(|index| original_blocks[index])
+                .filter(|(source, _)| source.byte_end == span.byte_end)
+                .map(|(_, block)| block)
+        };
+        let whitespace = |start: usize, end: usize| {
+            original_source
+                .get(start..end)
+                .filter(|gap| gap.trim().is_empty())
+        };
+
+        // Source span of the previous block, when it is still in place. The
+        // first block follows an empty span so leading whitespace is kept.
+        let mut previous = Some(SourceSpan::new(0, 0, 1, 1));
         for (index, block) in library.blocks().into_iter().enumerate() {
-            if index > 0 {
-                writeln!(self.writer)?;
+            let source = block_source(block);
+            let original = source.and_then(|span| Some((span, original_at(span)?)));
+            let gap = previous
+                .zip(original)
+                .and_then(|(previous, (next, _))| whitespace(previous.byte_end, next.byte_start));
+            match gap {
+                Some(gap) => self.write_source_gap(gap)?,
+                None if index > 0 => {
+                    self.write_blank_lines(self.config.blank_lines_between_entries.unwrap_or(1))?;
+                }
+                None => {}
             }
-            match block {
-                Block::Entry(entry, _) => self.write_entry(entry)?,
-                Block::String(definition) => {
-                    self.write_string(&definition.name, &definition.value)?;
+
+            match original {
+                Some((span, original)) if same_block(block, original) => {
+                    self.writer
+                        .write_all(&original_source.as_bytes()[span.byte_start..span.byte_end])?;
                 }
-                Block::Preamble(preamble) => self.write_preamble(&preamble.value)?,
-                Block::Comment(comment) => self.write_comment(comment.text())?,
-                Block::Failed(failed) => self.writer.write_all(failed.raw.as_bytes())?,
+                _ => self.write_block(block)?,
             }
+            previous = original.map(|(span, _)| span);
         }
 
-        Ok(())
+        let tail = previous.and_then(|span| whitespace(span.byte_end, original_source.len()));
+        match tail {
+            Some(tail) => self.write_source_gap(tail),
+            None => self.write_blank_lines(0),
+        }
+    }
+
+    /// Write whitespace copied from between two source blocks, dropping the
+    /// first line break when a serialized block already ended the line.
+    fn write_source_gap(&mut self, gap: &str) -> io::Result<()> {
+        let gap = if self.writer.line_start {
+            gap.strip_prefix("\r\n")
+                .or_else(|| gap.strip_prefix('\n'))
+                .unwrap_or(gap)
+        } else {
+            gap
+        };
+        self.writer.write_all(gap.as_bytes())
+    }
+
+    /// Write a library block with the configured formatting.
+    fn write_block(&mut self, block: Block) -> io::Result<()> {
+        match block {
+            Block::Entry(entry, _) => self.write_entry(entry),
+            Block::String(definition) => {
+                self.write_string(&definition.name, &definition.value, None)
+            }
+            Block::Preamble(preamble) => self.write_preamble(&preamble.value, None),
+            Block::Comment(comment) => self.write_comment(comment.text()),
+            Block::Failed(failed) => self.writer.write_all(failed.raw.as_bytes()),
+        }
     }
 
     /// Write a parsed document, reusing retained raw blocks when configured.
@@ -125,11 +931,24 @@ impl<W: Write> Writer<W> {
         document: &ParsedDocument,
         raw_source: Option<&str>,
     ) -> io::Result<()> {
+        self.file_name_width = name_width(
+            document
+                .entries()
+                .iter()
+                .flat_map(|entry| &entry.fields)
+                .map(|field| field.name.as_ref()),
+        );
+        let mut previous = None;
         for (index, block) in document.blocks().iter().copied().enumerate() {
+            let source = document.block_source(block);
             if index > 0 {
-                self.writer
-                    .write_all(self.config.entry_separator.as_bytes())?;
+                let preserved = previous
+                    .zip(source)
+                    .filter(|_| self.config.block_spacing == BlockSpacing::Preserve)
+                    .and_then(|(previous, next)| source_blank_lines(previous, next));
+                self.write_separator(preserved)?;
             }
+            previous = source;
 
             match block {
                 ParsedBlock::Entry(entry_index) => {
@@ -148,7 +967,7 @@ impl<W: Write> Writer<W> {
                             continue;
                         }
                     }
-                    self.write_string(&string.name, &string.value.value)?;
+                    self.write_string(&string.name, &string.value.value, string.value.delimiter)?;
                 }
                 ParsedBlock::Preamble(preamble_index) => {
                     let preamble = &document.preambles()[preamble_index];
@@ -162,7 +981,7 @@ impl<W: Write> Writer<W> {
                             continue;
                         }
                     }
-                    self.write_preamble(&preamble.value.value)?;
+                    self.write_preamble(&preamble.value.value, preamble.value.delimiter)?;
                 }
                 ParsedBlock::Comment(comment_index) => {
                     let comment = &document.comments()[comment_index];
@@ -214,8 +1033,7 @@ impl<W: Write> Writer<W> {
                 continue;
             }
             if written > 0 {
-                self.writer
-                    .write_all(self.config.entry_separator.as_bytes())?;
+                self.write_separator(None)?;
             }
             self.write_parsed_entry_with_raw_source(entry, raw_source)?;
             written += 1;
@@ -224,72 +1042,118 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
-    fn write_library_sorted(&mut self, library: &Library) -> io::Result<()> {
-        // Write preambles
-        for preamble in library.preambles() {
-            self.write_preamble(&preamble.value)?;
-            writeln!(self.writer)?;
-        }
-
-        // Write strings
-        let mut strings: Vec<_> = library.strings().iter().collect();
-        if self.config.sort_entries {
-            strings.sort_by(|a, b| a.name.cmp(&b.name));
-        }
-
-        for definition in strings {
-            self.write_string(&definition.name, &definition.value)?;
-            writeln!(self.writer)?;
-        }
-
-        // Write entries
-        let mut entries = library.entries().iter().collect::<Vec<_>>();
-        if self.config.sort_entries {
-            entries.sort_by(|a, b| a.key.cmp(&b.key));
-        }
-
-        for (i, entry) in entries.iter().enumerate() {
-            if i > 0 {
-                writeln!(self.writer)?;
-            }
-            self.write_entry(entry)?;
+    /// Start streaming blocks one at a time.
+    ///
+    /// Preambles and string definitions must be written before the first
+    /// entry, so they always form a header ahead of the entries.
+    pub const fn begin(self) -> StreamWriter<W> {
+        StreamWriter {
+            writer: self,
+            blocks: 0,
+            entries: 0,
         }
-
-        Ok(())
     }
 
     /// Write a single entry
     pub fn write_entry(&mut self, entry: &Entry) -> io::Result<()> {
-        writeln!(self.writer, "@{}{{{},", entry.ty, entry.key)?;
+        let duplicates = self.check_duplicate_fields(
+            &entry.key,
+            entry.fields().iter().map(|field| field.name.as_ref()),
+        )?;
+        self.write_checked_entry(entry, duplicates, &[], &[], None)
+    }
 
-        let mut fields = entry.fields().to_vec();
+    /// Write an entry whose duplicate fields were already checked.
+    ///
+    /// `delimiters` and `comments` hold original value delimiters and
+    /// trailing `%` comments aligned with the fields; `type_spelling`
+    /// overrides the canonical entry type name.
+    fn write_checked_entry(
+        &mut self,
+        entry: &Entry,
+        duplicates: bool,
+        delimiters: &[Option<ValueDelimiter>],
+        comments: &[Option<&str>],
+        type_spelling: Option<&str>,
+    ) -> io::Result<()> {
+        let ty = type_spelling.unwrap_or_else(|| entry.ty.canonical_name());
+        writeln!(self.writer, "@{ty}{{{},", entry.key)?;
+
+        let mut fields = entry
+            .fields()
+            .iter()
+            .zip(delimiters.iter().copied().chain(std::iter::repeat(None)))
+            .zip(comments.iter().copied().chain(std::iter::repeat(None)))
+            .map(|((field, delimiter), comment)| (field, delimiter, comment))
+            .collect::<Vec<_>>();
+        if duplicates && self.config.duplicate_fields == DuplicateFieldPolicy::KeepFirst {
+            let mut seen = Vec::<String>::with_capacity(fields.len());
+            fields.retain(|(field, _, _)| {
+                let name = field.name.to_ascii_lowercase();
+                let first = !seen.contains(&name);
+                seen.push(name);
+                first
+            });
+        }
+        let replacements: Vec<Field>;
+        if let Some(limit) = &self.config.long_fields {
+            let limits = fields
+                .iter()
+                .map(|(field, _, _)| limit.limit(&field.name, &field.value))
+                .collect::<Vec<_>>();
+            replacements = fields
+                .iter()
+                .zip(&limits)
+                .filter_map(|((field, _, _), outcome)| match outcome {
+                    FieldLimit::Replace(text) => Some(Field::new(
+                        field.name.as_ref(),
+                        Value::Literal(Cow::Owned(text.clone())),
+                    )),
+                    FieldLimit::Keep | FieldLimit::Drop => None,
+                })
+                .collect();
+            let mut replacements = replacements.iter();
+            fields = fields
+                .into_iter()
+                .zip(limits)
+                .filter_map(|((field, delimiter, comment), outcome)| match outcome {
+                    FieldLimit::Keep => Some((field, delimiter, comment)),
+                    FieldLimit::Drop => None,
+                    FieldLimit::Replace(_) => replacements
+                        .next()
+                        .map(|replacement| (replacement, delimiter, comment)),
+                })
+                .collect();
+        }
         if self.config.sort_fields {
-            fields.sort_by(|a, b| a.name.cmp(&b.name));
+            fields.sort_by(|(a, _, _), (b, _, _)| a.name.cmp(&b.name));
+        }
+        if let Some(order) = &self.config.field_order {
+            order.sort(entry.ty.canonical_name(), &mut fields, |(field, _, _)| {
+                &field.name
+            });
         }
 
         // Calculate alignment if needed
-        let max_name_len = if self.config.align_values {
-            fields.iter().map(|f| f.name.len()).max().unwrap_or(0)
-        } else {
-            0
-        };
+        let max_name_len =
+            self.padded_name_width(fields.iter().map(|(field, _, _)| field.name.as_ref()));
 
-        for (i, field) in fields.iter().enumerate() {
+        for (i, &(field, delimiter, comment)) in fields.iter().enumerate() {
             write!(self.writer, "{}", self.config.indent)?;
             write!(self.writer, "{}", field.name)?;
 
-            if self.config.align_values {
-                let padding = max_name_len - field.name.len();
-                write!(self.writer, "{}", " ".repeat(padding))?;
-            }
+            let padding = max_name_len.saturating_sub(field.name.chars().count());
+            write!(self.writer, "{}", " ".repeat(padding))?;
 
             write!(self.writer, " = ")?;
-            self.write_value(&field.value)?;
+            self.write_value(&field.value, delimiter)?;
 
             if i < fields.len() - 1 || self.config.trailing_comma == TrailingComma::Always {
-                writeln!(self.writer, ",")?;
-            } else {
-                writeln!(self.writer)?;
+                write!(self.writer, ",")?;
+            }
+            match comment {
+                Some(comment) => writeln!(self.writer, " {comment}")?,
+                None => writeln!(self.writer)?,
             }
         }
 
@@ -297,33 +1161,265 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Width that field names are padded to under the alignment options.
+    fn padded_name_width<'n>(&self, names: impl Iterator<Item = &'n str>) -> usize {
+        let entry_name_width = name_width(names);
+        match self.config.tabular {
+            Some(tabular) => {
+                // `=` follows the padded name and one space.
+                let indent = self.config.indent.chars().count();
+                let widest = if tabular.across_file {
+                    entry_name_width.max(self.file_name_width)
+                } else {
+                    entry_name_width
+                };
+                tabular.column.saturating_sub(indent + 1).max(widest)
+            }
+            None if self.config.align_values => entry_name_width,
+            None => 0,
+        }
+    }
+
+    /// Write `source` with only the entry layout that breaks this writer's
+    /// style rewritten.
+    ///
+    /// Entries are laid out again with the configured indent, alignment, and
+    /// trailing comma, but keep the source spelling of their header, field
+    /// names, and values, so lines that already conform come out byte for
+    /// byte. Comments, `@string` and `@preamble` blocks, and the text between
+    /// blocks are copied unchanged, as are entries that cannot be laid out
+    /// again without losing text, such as those with comment lines between
+    /// fields or several fields on a line with other text.
+    pub fn write_min_diff(&mut self, source: &str) -> io::Result<()> {
+        let Ok(document) = crate::Parser::new().tolerant().parse_document(source) else {
+            return self.writer.write_all(source.as_bytes());
+        };
+        self.file_name_width = name_width(
+            document
+                .entries()
+                .iter()
+                .flat_map(|entry| &entry.fields)
+                .map(|field| field.name.as_ref()),
+        );
+        let newline = if source.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        let mut copied = 0;
+        for entry in document.entries() {
+            let Some(span) = entry.source.filter(|span| span.byte_start >= copied) else {
+                continue;
+            };
+            let Some(text) = self.min_diff_entry(entry, source, newline) else {
+                continue;
+            };
+            self.writer
+                .write_all(&source.as_bytes()[copied..span.byte_start])?;
+            self.writer.write_all(text.as_bytes())?;
+            copied = span.byte_end;
+        }
+        self.writer.write_all(&source.as_bytes()[copied..])
+    }
+
+    /// Lay out a parsed entry in this writer's style from its source text,
+    /// or return `None` when that would drop text.
+    fn min_diff_entry(&self, entry: &ParsedEntry, source: &str, newline: &str) -> Option<String> {
+        if entry.status != crate::ParsedEntryStatus::Complete || entry.fields.is_empty() {
+            return None;
+        }
+        let span = entry.source?;
+        let key_end = entry.key_source?.byte_end;
+        let close = source.get(span.byte_start..span.byte_end)?.chars().last()?;
+        if !matches!(close, '}' | ')') {
+            return None;
+        }
+
+        let width = self.padded_name_width(entry.fields.iter().map(|field| field.name.as_ref()));
+        let mut text = String::from(source.get(span.byte_start..key_end)?);
+        text.push(',');
+        text.push_str(newline);
+        let mut gap_start = key_end;
+        let mut comment = None;
+        for (index, field) in entry.fields.iter().enumerate() {
+            // Field spans may include the separating comma, so gaps run
+            // from value to name.
+            let (name_span, value_span) = (field.name_source?, field.value_source?);
+            if !is_separator_gap(source, gap_start, name_span.byte_start, comment, true) {
+                return None;
+            }
+            let name = source_text(source, name_span)?;
+            let value = source_text(source, value_span)?;
+            text.push_str(&self.config.indent);
+            text.push_str(name);
+            text.push_str(&" ".repeat(width.saturating_sub(name.chars().count())));
+            text.push_str(" = ");
+            text.push_str(value);
+            if index + 1 < entry.fields.len() || self.config.trailing_comma == TrailingComma::Always
+            {
+                text.push(',');
+            }
+            comment = field.comment_source;
+            if let Some(comment) = comment {
+                text.push(' ');
+                text.push_str(source_text(source, comment)?);
+            }
+            text.push_str(newline);
+            gap_start = value_span.byte_end;
+        }
+        if !is_separator_gap(source, gap_start, span.byte_end - 1, comment, false) {
+            return None;
+        }
+        text.push(close);
+        Some(text)
+    }
+
     fn write_parsed_entry_with_raw_source(
         &mut self,
         entry: &ParsedEntry,
         raw_source: Option<&str>,
     ) -> io::Result<()> {
-        if self.config.raw_write_mode == RawWriteMode::Preserve {
+        let duplicates = self.check_duplicate_fields(
+            &entry.key,
+            entry.fields.iter().map(|field| field.name.as_ref()),
+        )?;
+        let keep_first =
+            duplicates && self.config.duplicate_fields == DuplicateFieldPolicy::KeepFirst;
+        let limited = self.config.long_fields.as_ref().is_some_and(|limit| {
+            entry
+                .fields
+                .iter()
+                .any(|field| limit.limit(&field.name, &field.value.value) != FieldLimit::Keep)
+        });
+        let reordered = self.config.field_order.as_ref().is_some_and(|order| {
+            let mut names = entry
+                .fields
+                .iter()
+                .map(|field| field.name.as_ref())
+                .collect::<Vec<_>>();
+            let original = names.clone();
+            order.sort(entry.ty.canonical_name(), &mut names, |name| name);
+            names != original
+        });
+        if self.config.raw_write_mode == RawWriteMode::Preserve
+            && !keep_first
+            && !limited
+            && !reordered
+        {
             if let Some(raw) = patched_entry_raw(entry, raw_source, &self.config) {
                 self.writer.write_all(raw.as_bytes())?;
                 return Ok(());
             }
         }
 
-        self.write_entry(&entry.clone().into_entry())
+        let delimiters = entry
+            .fields
+            .iter()
+            .map(|field| field.value.delimiter)
+            .collect::<Vec<_>>();
+        let type_spelling = entry.entry_type_spelling.as_deref().filter(|spelling| {
+            self.config.raw_write_mode == RawWriteMode::Preserve
+                && crate::EntryType::parse(spelling) == entry.ty
+        });
+        let comments = entry
+            .fields
+            .iter()
+            .map(|field| field.comment.as_deref())
+            .collect::<Vec<_>>();
+        self.write_checked_entry(
+            &entry.clone().into_entry(),
+            duplicates,
+            &delimiters,
+            &comments,
+            type_spelling,
+        )
+    }
+
+    /// Apply the duplicate-field policy, returning whether `names` repeat.
+    fn check_duplicate_fields<'n>(
+        &mut self,
+        key: &str,
+        names: impl Iterator<Item = &'n str>,
+    ) -> io::Result<bool> {
+        if self.config.duplicate_fields == DuplicateFieldPolicy::Allow {
+            return Ok(false);
+        }
+        let mut seen = Vec::<&str>::new();
+        let mut repeated = Vec::<&str>::new();
+        for name in names {
+            if seen.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+                if !repeated
+                    .iter()
+                    .any(|other| other.eq_ignore_ascii_case(name))
+                {
+                    repeated.push(name);
+                }
+            } else {
+                seen.push(name);
+            }
+        }
+
+        match self.config.duplicate_fields {
+            DuplicateFieldPolicy::Error if !repeated.is_empty() => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("entry `{key}` repeats field(s): {}", repeated.join(", ")),
+            )),
+            DuplicateFieldPolicy::Warn => {
+                self.duplicate_fields.extend(
+                    repeated
+                        .iter()
+                        .map(|name| (key.to_string(), (*name).to_string())),
+                );
+                Ok(!repeated.is_empty())
+            }
+            _ => Ok(!repeated.is_empty()),
+        }
     }
 
     /// Write a string definition
-    fn write_string(&mut self, name: &str, value: &Value) -> io::Result<()> {
+    fn write_string(
+        &mut self,
+        name: &str,
+        value: &Value,
+        delimiter: Option<ValueDelimiter>,
+    ) -> io::Result<()> {
         write!(self.writer, "@string{{{name} = ")?;
-        self.write_value(value)?;
+        self.write_value(value, delimiter)?;
         writeln!(self.writer, "}}")?;
         Ok(())
     }
 
+    /// Separate two blocks by `blank_lines`, the configured blank lines, or
+    /// the configured separator, in that order.
+    fn write_separator(&mut self, blank_lines: Option<usize>) -> io::Result<()> {
+        match blank_lines.or(self.config.blank_lines_between_entries) {
+            Some(blank_lines) => self.write_blank_lines(blank_lines),
+            None => self
+                .writer
+                .write_all(self.config.entry_separator.as_bytes()),
+        }
+    }
+
+    /// End the current line, then write `count` empty lines.
+    fn write_blank_lines(&mut self, count: usize) -> io::Result<()> {
+        if !self.writer.line_start {
+            self.writer.write_all(b"\n")?;
+        }
+        for _ in 0..count {
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
     /// Write a preamble
-    fn write_preamble(&mut self, value: &Value) -> io::Result<()> {
+    fn write_preamble(
+        &mut self,
+        value: &Value,
+        delimiter: Option<ValueDelimiter>,
+    ) -> io::Result<()> {
         write!(self.writer, "@preamble{{")?;
-        self.write_value(value)?;
+        self.write_value(value, delimiter)?;
         writeln!(self.writer, "}}")?;
         Ok(())
     }
@@ -342,13 +1438,353 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
-    /// Write a value
-    fn write_value(&mut self, value: &Value) -> io::Result<()> {
-        self.writer.write_all(value.to_bibtex_source().as_bytes())?;
+    /// Write a value using the configured delimiter style
+    fn write_value(&mut self, value: &Value, delimiter: Option<ValueDelimiter>) -> io::Result<()> {
+        let source = render_value(&self.config, value, delimiter);
+        self.writer.write_all(source.as_bytes())?;
         Ok(())
     }
 }
 
+/// Stateful writer for streaming blocks, created by [`Writer::begin`].
+#[derive(Debug)]
+pub struct StreamWriter<W: Write> {
+    writer: Writer<W>,
+    blocks: usize,
+    entries: usize,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Write a preamble into the header.
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error once an entry has been written.
+    pub fn write_preamble(&mut self, value: &Value) -> io::Result<()> {
+        self.ensure_header("preamble")?;
+        self.separate()?;
+        self.writer.write_preamble(value, None)
+    }
+
+    /// Write a string definition into the header.
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error once an entry has been written.
+    pub fn write_string(&mut self, name: &str, value: &Value) -> io::Result<()> {
+        self.ensure_header("string definition")?;
+        self.separate()?;
+        self.writer.write_string(name, value, None)
+    }
+
+    /// Write the next entry.
+    pub fn write_entry(&mut self, entry: &Entry) -> io::Result<()> {
+        self.separate()?;
+        self.writer.write_entry(entry)?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Return the number of entries written so far.
+    #[must_use]
+    pub const fn entries_written(&self) -> usize {
+        self.entries
+    }
+
+    /// Flush the output and return the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        let mut writer = self.writer.into_inner();
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn ensure_header(&self, block: &str) -> io::Result<()> {
+        if self.entries == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot write a {block} after entries"),
+            ))
+        }
+    }
+
+    fn separate(&mut self) -> io::Result<()> {
+        if self.blocks > 0 {
+            self.writer.write_separator(None)?;
+        }
+        self.blocks += 1;
+        Ok(())
+    }
+}
+
+impl<F: fmt::Write> Writer<FmtWriter<F>> {
+    /// Create a writer that appends to a [`fmt::Write`] target such as a `String`.
+    pub fn from_fmt(writer: F) -> Self {
+        Self::new(FmtWriter::new(writer))
+    }
+
+    /// Create a [`fmt::Write`]-backed writer with custom configuration.
+    pub const fn from_fmt_with_config(writer: F, config: WriterConfig) -> Self {
+        Self::with_config(FmtWriter::new(writer), config)
+    }
+}
+
+/// Adapter that lets [`Writer`] target a [`fmt::Write`] sink.
+///
+/// The writer only emits complete UTF-8 strings, so bytes pass straight
+/// through to [`fmt::Write::write_str`].
+#[derive(Debug)]
+pub struct FmtWriter<F> {
+    inner: F,
+}
+
+impl<F> FmtWriter<F> {
+    /// Wrap a [`fmt::Write`] target.
+    pub const fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    /// Return the wrapped target.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F: fmt::Write> Write for FmtWriter<F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = std::str::from_utf8(buf)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        self.inner.write_str(text).map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Output sink that remembers whether it ends at the start of a line.
+#[derive(Debug)]
+struct LineTracker<W> {
+    inner: W,
+    line_start: bool,
+}
+
+impl<W: Write> Write for LineTracker<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(&last) = buf[..written].last() {
+            self.line_start = last == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Blank lines between two blocks in the same source.
+fn source_blank_lines(previous: SourceSpan, next: SourceSpan) -> Option<usize> {
+    if previous.source != next.source {
+        return None;
+    }
+    if next.byte_start < previous.byte_end {
+        return None;
+    }
+    let line_breaks = next.line.checked_sub(previous.end_line)?;
+    // A block that ends with its own line break ends at column 1.
+    let ends_line = previous.end_column == 1 && previous.byte_end > previous.byte_start;
+    Some(if ends_line {
+        line_breaks
+    } else {
+        line_breaks.saturating_sub(1)
+    })
+}
+
+const fn block_source(block: Block) -> Option<SourceSpan> {
+    match block {
+        Block::Entry(_, source) => source,
+        Block::String(definition) => definition.source,
+        Block::Preamble(preamble) => preamble.source,
+        Block::Comment(comment) => comment.source,
+        Block::Failed(failed) => failed.source,
+    }
+}
+
+/// Compare block contents, ignoring source locations.
+fn same_block(current: Block, original: Block) -> bool {
+    match (current, original) {
+        (Block::Entry(current, _), Block::Entry(original, _)) => current == original,
+        (Block::String(current), Block::String(original)) => {
+            current.name == original.name && current.value == original.value
+        }
+        (Block::Preamble(current), Block::Preamble(original)) => current.value == original.value,
+        (Block::Comment(current), Block::Comment(original)) => current.text == original.text,
+        (Block::Failed(current), Block::Failed(original)) => current.raw == original.raw,
+        _ => false,
+    }
+}
+
+/// The file header, preambles, strings sorted by name, and entries sorted by key.
+fn sorted_library_blocks<'lib, 'a>(
+    library: &'lib Library<'a>,
+    keep: impl Fn(usize) -> bool,
+    sections: Option<SectionGrouping>,
+) -> Vec<Block<'lib, 'a>> {
+    let mut strings = library.strings().iter().collect::<Vec<_>>();
+    strings.sort_by(|left, right| left.name.cmp(&right.name));
+    let mut entries = library
+        .entries()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| keep(*index))
+        .collect::<Vec<_>>();
+    if let Some(grouping) = sections {
+        entries.sort_by_cached_key(|(_, entry)| (grouping.title(entry), entry.key.clone()));
+    } else {
+        entries.sort_by(|(_, left), (_, right)| left.key.cmp(&right.key));
+    }
+    let comment = |index: usize| Block::Comment(&library.comments()[index]);
+
+    library
+        .header_comment_indices()
+        .into_iter()
+        .map(comment)
+        .chain(library.preambles().iter().map(Block::Preamble))
+        .chain(strings.into_iter().map(Block::String))
+        .chain(entries.into_iter().flat_map(|(index, entry)| {
+            library
+                .leading_comment_indices(index)
+                .iter()
+                .map(|&index| comment(index))
+                .chain(std::iter::once(Block::Entry(entry, None)))
+        }))
+        .collect()
+}
+
+/// Remove a leading `%% title` line from a comment.
+fn strip_section_header<'t>(text: &'t str, title: &str) -> &'t str {
+    text.trim_start()
+        .strip_prefix("%% ")
+        .and_then(|rest| rest.strip_prefix(title))
+        .filter(|rest| rest.is_empty() || rest.starts_with(['\n', '\r']))
+        .map_or(text, str::trim_start)
+}
+
+fn entry_type_section_title(ty: &str) -> String {
+    let title = match ty.to_ascii_lowercase().as_str() {
+        "article" => "Articles",
+        "book" | "mvbook" => "Books",
+        "booklet" => "Booklets",
+        "inbook" | "bookinbook" | "incollection" => "Book Chapters",
+        "collection" | "mvcollection" => "Collections",
+        "inproceedings" | "conference" => "Conference Papers",
+        "proceedings" | "mvproceedings" => "Proceedings",
+        "manual" => "Manuals",
+        "mastersthesis" => "Master's Theses",
+        "phdthesis" => "PhD Theses",
+        "thesis" => "Theses",
+        "techreport" | "report" => "Reports",
+        "online" | "electronic" | "www" => "Online Resources",
+        "patent" => "Patents",
+        "software" => "Software",
+        "dataset" => "Datasets",
+        "unpublished" => "Unpublished",
+        "misc" => "Miscellaneous",
+        _ => {
+            let mut chars = ty.chars();
+            return chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            });
+        }
+    };
+    title.to_string()
+}
+
+fn header_first_blocks<'lib, 'a>(blocks: Vec<Block<'lib, 'a>>) -> Vec<Block<'lib, 'a>> {
+    let (mut header, rest): (Vec<_>, Vec<_>) = blocks
+        .into_iter()
+        .partition(|block| matches!(block, Block::Preamble(_) | Block::String(_)));
+    header.sort_by_key(|block| matches!(block, Block::String(_)));
+    header.extend(rest);
+    header
+}
+
+fn strings_before_first_use<'lib, 'a>(blocks: Vec<Block<'lib, 'a>>) -> Vec<Block<'lib, 'a>> {
+    let mut strings = Vec::new();
+    let mut preambles = Vec::new();
+    let mut rest = Vec::new();
+    for block in blocks {
+        match block {
+            Block::String(definition) => strings.push(definition),
+            Block::Preamble(_) => preambles.push(block),
+            _ => rest.push(block),
+        }
+    }
+
+    let mut placed = vec![false; strings.len()];
+    let mut header = Vec::with_capacity(preambles.len());
+    for block in preambles {
+        if let Block::Preamble(preamble) = block {
+            let mut names = Vec::new();
+            collect_variable_names(&preamble.value, &mut names);
+            for name in names {
+                place_string(name, &strings, &mut placed, &mut header);
+            }
+        }
+        header.push(block);
+    }
+
+    let mut body = Vec::with_capacity(rest.len() + strings.len());
+    for block in rest {
+        let mut names = Vec::new();
+        if let Block::Entry(entry, _) = block {
+            for field in entry.fields() {
+                collect_variable_names(&field.value, &mut names);
+            }
+        }
+        for name in names {
+            place_string(name, &strings, &mut placed, &mut body);
+        }
+        body.push(block);
+    }
+
+    for (definition, placed) in strings.iter().zip(placed) {
+        if !placed {
+            header.push(Block::String(definition));
+        }
+    }
+    header.extend(body);
+    header
+}
+
+/// Place the latest definition of `name` after the strings it depends on.
+fn place_string<'lib, 'a>(
+    name: &str,
+    strings: &[&'lib StringDefinition<'a>],
+    placed: &mut [bool],
+    output: &mut Vec<Block<'lib, 'a>>,
+) {
+    let Some(index) = strings
+        .iter()
+        .rposition(|definition| definition.name.eq_ignore_ascii_case(name))
+    else {
+        return;
+    };
+    if placed[index] {
+        return;
+    }
+    placed[index] = true;
+    let mut dependencies = Vec::new();
+    collect_variable_names(&strings[index].value, &mut dependencies);
+    for dependency in dependencies {
+        place_string(dependency, strings, placed, output);
+    }
+    output.push(Block::String(strings[index]));
+}
+
+fn render_value(config: &WriterConfig, value: &Value, delimiter: Option<ValueDelimiter>) -> String {
+    value_to_bibtex_source(value, config.value_delimiter.quotes(delimiter))
+}
+
 fn raw_text_with_source<'a>(
     raw: Option<&'a str>,
     raw_source: Option<&'a str>,
@@ -385,7 +1821,7 @@ fn patched_entry_raw<'entry>(
         source.byte_start,
         entry.key_source,
         &entry.key,
-        |raw_key| raw_key == entry.key,
+        |raw_key| raw_key == entry.key || entry.original_key.as_deref() == Some(raw_key),
     )?;
 
     for removed in entry.removed_field_sources() {
@@ -420,12 +1856,24 @@ fn patched_entry_raw<'entry>(
             if source_slice(raw_source, value_source).is_none() {
                 let start = value_source.byte_start.checked_sub(source.byte_start)?;
                 let end = value_source.byte_end.checked_sub(source.byte_start)?;
-                replacements.push((start, end, field.value.value.to_bibtex_source()));
+                replacements.push((
+                    start,
+                    end,
+                    render_value(config, &field.value.value, field.value.delimiter),
+                ));
             }
         }
     }
 
-    if !entry.removed_field_sources().is_empty() && !added_fields.is_empty() {
+    // Added fields go after the last source field, where its trailing
+    // comment would swallow them.
+    let last_commented = entry
+        .fields
+        .iter()
+        .rev()
+        .find(|field| field.source.is_some())
+        .is_some_and(|field| field.comment_source.is_some());
+    if !added_fields.is_empty() && (!entry.removed_field_sources().is_empty() || last_commented) {
         return None;
     }
 
@@ -492,7 +1940,11 @@ fn render_added_fields(
         output.push_str(&config.indent);
         output.push_str(&field.name);
         output.push_str(" = ");
-        output.push_str(&field.value.value.to_bibtex_source());
+        output.push_str(&render_value(
+            config,
+            &field.value.value,
+            field.value.delimiter,
+        ));
         if index < fields.len() - 1 || config.trailing_comma == TrailingComma::Always {
             output.push(',');
         }
@@ -523,36 +1975,67 @@ fn push_token_replacement(
 /// Convenience function to write a library to a string.
 #[must_use = "Check the result to detect serialization errors"]
 pub fn to_string(library: &Library) -> Result<String> {
-    let mut buf = Vec::new();
-    let mut writer = Writer::new(&mut buf);
-    writer.write_library(library)?;
-    Ok(String::from_utf8(buf).expect("valid UTF-8"))
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_library(library)?;
+    Ok(output)
+}
+
+/// Convenience function to reformat BibTeX source with
+/// [`Writer::write_min_diff`], leaving conforming text unchanged.
+#[must_use = "Check the result to detect serialization errors"]
+pub fn format_min_diff(source: &str, config: WriterConfig) -> Result<String> {
+    let mut output = String::new();
+    Writer::from_fmt_with_config(&mut output, config).write_min_diff(source)?;
+    Ok(output)
+}
+
+/// Convenience function to write a single entry to a string.
+#[must_use = "Check the result to detect serialization errors"]
+pub fn to_string_entry(entry: &Entry) -> Result<String> {
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_entry(entry)?;
+    Ok(output)
+}
+
+/// Convenience function to write a single `@string` definition to a string.
+#[must_use = "Check the result to detect serialization errors"]
+pub fn to_string_string_def(name: &str, value: &Value) -> Result<String> {
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_string(name, value, None)?;
+    Ok(output)
+}
+
+/// Convenience function to write a single `@preamble` to a string.
+#[must_use = "Check the result to detect serialization errors"]
+pub fn to_string_preamble(value: &Value) -> Result<String> {
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_preamble(value, None)?;
+    Ok(output)
 }
 
 /// Convenience function to write a parsed document to a string.
 #[must_use = "Check the result to detect serialization errors"]
 pub fn document_to_string(document: &ParsedDocument) -> Result<String> {
-    let mut buf = Vec::new();
-    let mut writer = Writer::new(&mut buf);
-    writer.write_document(document)?;
-    Ok(String::from_utf8(buf).expect("valid UTF-8"))
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_document(document)?;
+    Ok(output)
 }
 
 /// Convenience function to write selected parsed-document entries to a string.
 #[must_use = "Check the result to detect serialization errors"]
 pub fn selected_entries_to_string(document: &ParsedDocument, keys: &[&str]) -> Result<String> {
-    let mut buf = Vec::new();
-    let mut writer = Writer::new(&mut buf);
-    writer.write_selected_entries(document, keys)?;
-    Ok(String::from_utf8(buf).expect("valid UTF-8"))
+    let mut output = String::new();
+    Writer::from_fmt(&mut output).write_selected_entries(document, keys)?;
+    Ok(output)
 }
 
 /// Convenience function to write a library to a file.
 #[must_use = "Check the result to detect IO or serialization errors"]
 pub fn to_file(library: &Library, path: impl AsRef<std::path::Path>) -> Result<()> {
     let file = std::fs::File::create(path)?;
-    let mut writer = Writer::new(file);
+    let mut writer = Writer::new(BufWriter::new(file));
     writer.write_library(library)?;
+    writer.into_inner().flush()?;
     Ok(())
 }
 
@@ -584,4 +2067,22 @@ mod tests {
         assert!(result.contains("title = {Test Article}"));
         assert!(result.contains("year = 2023"));
     }
+
+    #[test]
+    fn test_write_to_fmt_target() {
+        let entry = Entry {
+            ty: EntryType::Misc,
+            key: Cow::Borrowed("note"),
+            fields: vec![Field::new(
+                "title",
+                Value::Literal(Cow::Borrowed("Ünïcode")),
+            )],
+        };
+
+        let mut writer = Writer::from_fmt(String::from("% header\n"));
+        writer.write_entry(&entry).unwrap();
+        let output = writer.into_inner().into_inner();
+
+        assert_eq!(output, "% header\n@misc{note,\n  title = {Ünïcode}\n}\n");
+    }
 }
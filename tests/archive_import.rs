@@ -0,0 +1,92 @@
+#![cfg(feature = "archive")]
+
+use bibtex_parser::archive::{parse_archive, ArchiveConflict};
+use bibtex_parser::{Error, MergePolicy, Parser};
+use std::fs;
+use std::io::Write;
+
+const GROUP: &str = "@article{shared, title = {Shared}, year = {2020}}\n@misc{group, title = {G}}";
+const MINE: &str = "@article{shared, title = {Shared}, year = {2021}, doi = {10.1/x}}";
+
+fn zip_bytes(members: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in members {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn zip_members_merge_in_order_with_provenance() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.zip");
+    fs::write(
+        &path,
+        zip_bytes(&[
+            ("refs/group.bib", GROUP),
+            ("notes.txt", "not a bibliography"),
+            ("__MACOSX/refs/._group.bib", "junk"),
+            ("refs/Mine.BIB", MINE),
+        ]),
+    )
+    .unwrap();
+
+    let import = parse_archive(&path).unwrap();
+    assert_eq!(import.members, ["refs/group.bib", "refs/Mine.BIB"]);
+    assert_eq!(import.library.entries().len(), 2);
+    assert_eq!(import.member_of(1), Some("refs/group.bib"));
+    assert_eq!(
+        import.conflicts,
+        [ArchiveConflict {
+            member: 1,
+            key: "shared".to_string(),
+            existing: 0,
+            fields: vec!["year".to_string()],
+        }]
+    );
+
+    let import = Parser::new()
+        .parse_archive(&path, MergePolicy::PreferIncoming)
+        .unwrap();
+    assert!(import.conflicts.is_empty());
+    let shared = &import.library.entries()[0];
+    assert_eq!(shared.get("year"), Some("2021"));
+    assert_eq!(shared.get("doi"), Some("10.1/x"));
+}
+
+#[test]
+fn gzipped_tarballs_are_read_and_member_errors_are_named() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.tar.gz");
+    let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    ));
+    for (name, content) in [
+        ("a/group.bib", GROUP),
+        ("b/broken.bib", "@misc{x, title = "),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content.as_bytes())
+            .unwrap();
+    }
+    fs::write(&path, tar.into_inner().unwrap().finish().unwrap()).unwrap();
+
+    let Err(Error::Archive(message)) = parse_archive(&path) else {
+        panic!("expected the broken member to fail");
+    };
+    assert!(message.starts_with("b/broken.bib: "), "{message}");
+
+    let import = Parser::new()
+        .tolerant()
+        .parse_archive(&path, MergePolicy::KeepExisting)
+        .unwrap();
+    assert_eq!(import.members.len(), 2);
+    assert_eq!(import.library.entries()[1].key(), "group");
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "biblatex")]
+
+use biblatex::{Bibliography, ChunksExt, Person};
+use bibtex_parser::{Entry, Error, Library};
+
+const INPUT: &str = r#"
+@string{acm = "ACM Press"}
+@inproceedings{doe2024,
+    author = {Doe, Jane and Roe, Rick},
+    title = {Scaling {NASA} Pipelines with $O(n)$ Memory},
+    publisher = acm,
+    month = jan,
+    year = 2024,
+    url = {https://example.org/a_b%20c}
+}
+@book{ref, title = {A \& B}}
+"#;
+
+#[test]
+fn library_converts_to_a_biblatex_bibliography() {
+    let library = Library::parse(INPUT).unwrap();
+    let bibliography = Bibliography::try_from(&library).unwrap();
+
+    assert_eq!(bibliography.len(), 2);
+    let entry = bibliography.get("doe2024").unwrap();
+    assert_eq!(entry.entry_type, biblatex::EntryType::InProceedings);
+    let authors: Vec<Person> = entry.get_as("author").unwrap();
+    assert_eq!(authors[1].name, "Roe");
+    assert_eq!(
+        entry.get("publisher").unwrap().format_verbatim(),
+        "ACM Press"
+    );
+    assert_eq!(
+        entry.get("url").unwrap().format_verbatim(),
+        "https://example.org/a_b%20c"
+    );
+    assert_eq!(
+        bibliography
+            .get("ref")
+            .unwrap()
+            .get("title")
+            .unwrap()
+            .format_verbatim(),
+        "A & B"
+    );
+}
+
+#[test]
+fn bibliography_converts_back_to_equivalent_bibtex() {
+    let library = Library::parse(INPUT).unwrap();
+    let bibliography = Bibliography::try_from(&library).unwrap();
+    let back = Library::from(&bibliography);
+
+    let entry = back.find_by_key("doe2024").unwrap();
+    assert_eq!(entry.ty.to_string(), "inproceedings");
+    assert_eq!(
+        entry.get("title"),
+        Some("Scaling {NASA} Pipelines with $O(n)$ Memory")
+    );
+    assert_eq!(entry.get("url"), Some("https://example.org/a_b%20c"));
+    assert_eq!(
+        back.find_by_key("ref").unwrap().get("title"),
+        Some(r"A \& B")
+    );
+
+    let again = Bibliography::try_from(&back).unwrap();
+    assert_eq!(
+        again.to_biblatex_string(),
+        bibliography.to_biblatex_string()
+    );
+}
+
+#[test]
+fn single_entries_convert_both_ways() {
+    let library = Library::parse("@misc{note, note = {50\\% off}}").unwrap();
+    let converted = biblatex::Entry::try_from(&library.entries()[0]).unwrap();
+    assert_eq!(converted.key, "note");
+
+    let entry = Entry::from(&converted);
+    assert_eq!(entry.key(), "note");
+    assert_eq!(entry.get("note"), Some(r"50\% off"));
+}
+
+#[test]
+fn unresolved_variables_are_conversion_errors() {
+    let library = Library::parse("@misc{a, publisher = nowhere}").unwrap();
+    let error = Bibliography::try_from(&library).unwrap_err();
+    assert!(matches!(error, Error::Conversion(_)), "{error}");
+}
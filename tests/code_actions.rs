@@ -0,0 +1,68 @@
+use bibtex_parser::{ExpansionPolicy, Parser, ValidationLevel};
+
+const INPUT: &str = "@article{paper,\n  author = {Ada Lovelace},\n  title = {Notes},\n  journal = {J},\n  note = {},\n  year = 2020,\n  pages = {12\u{2013}34},\n  month = {Sept.},\n  url = {example.org/paper}\n}\n@book{thin, author = {A}, title = {T}, year = 1999}\n";
+
+#[test]
+fn validation_findings_become_applicable_code_actions() {
+    let document = Parser::new().parse_document(INPUT).unwrap();
+    let actions = document.code_actions(ValidationLevel::Strict);
+    let titles = actions
+        .iter()
+        .map(|action| action.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        [
+            "Write pages as `12--34`",
+            "Remove empty field `note`",
+            "Prefix URL with `https://`",
+            "Use month macro `sep`",
+            "Add missing field `publisher`",
+        ]
+    );
+
+    let mut preferred = actions
+        .iter()
+        .filter(|action| action.is_preferred)
+        .collect::<Vec<_>>();
+    preferred.sort_by_key(|action| std::cmp::Reverse(action.edits[0].span.byte_start));
+    let fixed = preferred
+        .into_iter()
+        .fold(INPUT.to_string(), |text, action| action.apply(&text));
+    assert!(fixed.contains("pages = {12--34}"));
+    assert!(fixed.contains("month = sep,"));
+    assert!(!fixed.contains("note"));
+    assert!(fixed.contains("journal = {J},\n  year = 2020"));
+
+    let url = actions.iter().find(|action| !action.is_preferred).unwrap();
+    assert_eq!(url.diagnostic.field.as_deref(), Some("url"));
+    assert!(url
+        .apply(INPUT)
+        .contains("url = {https://example.org/paper}"));
+
+    let missing = actions.last().unwrap();
+    assert_eq!(missing.entry, 1);
+    assert!(missing
+        .apply(INPUT)
+        .contains("year = 1999,\n  publisher = {}}"));
+}
+
+#[test]
+fn numbers_and_variables_are_not_offered_as_empty_fields() {
+    let input = "@string{later = 3000}\n\
+                 @article{a, author = {A}, title = {T}, journal = {J}, year = 3000}\n\
+                 @article{b, author = {B}, title = {T}, journal = {J}, year = later}\n";
+    for parser in [
+        Parser::new(),
+        Parser::new().expansion_policy(ExpansionPolicy::preserve_all()),
+    ] {
+        let document = parser.parse_document(input).unwrap();
+        let actions = document.code_actions(ValidationLevel::Strict);
+        assert!(
+            actions
+                .iter()
+                .all(|action| !action.title.starts_with("Remove empty field")),
+            "{actions:?}"
+        );
+    }
+}
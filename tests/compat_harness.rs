@@ -0,0 +1,176 @@
+//! Compatibility harness against classic BibTeX tooling.
+//!
+//! Enabled by the development-only `compat_harness` feature:
+//!
+//! ```text
+//! cargo test --features compat_harness --test compat_harness
+//! ```
+//!
+//! Each fixture under `tests/fixtures/compat` is expanded by this crate and
+//! the results are pinned in `EXPECTED`. When `bibtool` or `biber` is
+//! installed (or named by the `BIBTOOL` or `BIBER` environment variable),
+//! the same fixture is resolved by that tool and every lookup is compared.
+//! Known differences are listed in `DIVERGENCES`; an unlisted difference,
+//! or a listed one that no longer occurs, fails the test.
+#![cfg(feature = "compat_harness")]
+
+use bibtex_parser::Library;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const FIXTURE: &str = "tests/fixtures/compat/semantics.bib";
+
+/// `(key, field, value)` after string expansion, crossref inheritance, and
+/// xdata resolution. `None` means the field must be absent.
+const EXPECTED: &[(&str, &str, Option<&str>)] = &[
+    ("paper", "title", Some("A Paper")),
+    ("paper", "pages", Some("1--10")),
+    ("paper", "editor", Some("Eve Editor")),
+    ("paper", "publisher", Some("ACM Press")),
+    ("paper", "year", Some("2024")),
+    ("paper", "booktitle", None),
+    ("concat", "title", Some("Part One")),
+    ("concat", "note", Some("ACM Press, Part Two")),
+    ("book", "publisher", Some("Springer")),
+    ("book", "location", Some("Berlin")),
+];
+
+/// `(tool, key, field, reason)` for lookups where a reference tool differs.
+const DIVERGENCES: &[(&str, &str, &str, &str)] = &[
+    (
+        "bibtool",
+        "book",
+        "publisher",
+        "bibtool does not know biblatex @xdata",
+    ),
+    (
+        "bibtool",
+        "book",
+        "location",
+        "bibtool does not know biblatex @xdata",
+    ),
+    (
+        "biber",
+        "paper",
+        "booktitle",
+        "biber maps the parent title to booktitle; BibTeX copies fields by name",
+    ),
+];
+
+struct Reference {
+    name: &'static str,
+    env: &'static str,
+    run: fn(&Path, &Path) -> Option<String>,
+}
+
+const REFERENCES: &[Reference] = &[
+    Reference {
+        name: "bibtool",
+        env: "BIBTOOL",
+        run: run_bibtool,
+    },
+    Reference {
+        name: "biber",
+        env: "BIBER",
+        run: run_biber,
+    },
+];
+
+fn resolve(library: &mut Library<'_>) {
+    library.inherit_crossref_fields();
+    library.resolve_xdata().unwrap();
+}
+
+fn lookup(library: &Library<'_>, key: &str, field: &str) -> Option<String> {
+    library
+        .find_by_key(key)
+        .and_then(|entry| entry.get_as_string_ignore_case(field))
+}
+
+#[test]
+fn crate_semantics_match_pinned_expectations() {
+    let mut library = Library::parse_file(FIXTURE).unwrap();
+    resolve(&mut library);
+    for &(key, field, expected) in EXPECTED {
+        assert_eq!(
+            lookup(&library, key, field).as_deref(),
+            expected,
+            "{key}.{field}"
+        );
+    }
+}
+
+#[test]
+fn reference_tools_agree_except_for_documented_divergences() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURE);
+    for reference in REFERENCES {
+        let program = std::env::var_os(reference.env)
+            .map_or_else(|| PathBuf::from(reference.name), PathBuf::from);
+        if Command::new(&program).arg("--version").output().is_err() {
+            eprintln!("skipping {}: not installed", reference.name);
+            continue;
+        }
+        let output = (reference.run)(&program, &fixture)
+            .unwrap_or_else(|| panic!("{} failed on {FIXTURE}", reference.name));
+        let library = Library::parse(&output)
+            .unwrap_or_else(|error| panic!("{} output does not parse: {error}", reference.name));
+
+        let mut report = Vec::new();
+        for &(key, field, expected) in EXPECTED {
+            let actual = lookup(&library, key, field);
+            let listed = DIVERGENCES
+                .iter()
+                .any(|&(tool, k, f, _)| tool == reference.name && k == key && f == field);
+            match (actual.as_deref() == expected, listed) {
+                (false, false) => report.push(format!(
+                    "{key}.{field}: crate {expected:?}, {} {actual:?}",
+                    reference.name
+                )),
+                (true, true) => {
+                    report.push(format!("{key}.{field}: listed divergence no longer occurs"))
+                }
+                _ => {}
+            }
+        }
+        assert!(
+            report.is_empty(),
+            "{} differs from pinned semantics:\n{}",
+            reference.name,
+            report.join("\n")
+        );
+    }
+}
+
+fn run_bibtool(program: &Path, fixture: &Path) -> Option<String> {
+    let output = Command::new(program)
+        .args(["-q", "--", "expand.macros=on", "--", "expand.crossref=on"])
+        .arg(fixture)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn run_biber(program: &Path, fixture: &Path) -> Option<String> {
+    let scratch = tempfile::tempdir().ok()?;
+    let target = scratch.path().join("resolved.bib");
+    let status = Command::new(program)
+        .args([
+            "--tool",
+            "--quiet",
+            "--output-resolve",
+            "--output-fieldcase=lower",
+        ])
+        .arg("--output-file")
+        .arg(&target)
+        .arg(fixture)
+        .current_dir(scratch.path())
+        .status()
+        .ok()?;
+    status
+        .success()
+        .then(|| std::fs::read_to_string(&target).ok())
+        .flatten()
+}
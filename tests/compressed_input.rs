@@ -0,0 +1,38 @@
+#![cfg(feature = "compress")]
+
+use bibtex_parser::{Library, Parser};
+use std::fs;
+use std::io::Write;
+
+#[test]
+fn compressed_files_are_decompressed_by_their_magic_bytes() {
+    let dir = tempfile::tempdir().unwrap();
+    let gz = dir.path().join("dump.bib.gz");
+    let zst = dir.path().join("dump.bib.zst");
+    let plain = dir.path().join("refs.bib");
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all("@article{gz, title = {Gzip}}\n".as_bytes())
+        .unwrap();
+    fs::write(&gz, encoder.finish().unwrap()).unwrap();
+    let compressed =
+        zstd::stream::encode_all("@book{zst, title = {Zstd}}\n".as_bytes(), 0).unwrap();
+    fs::write(&zst, compressed).unwrap();
+    fs::write(&plain, "@misc{plain, title = {Plain}}\n").unwrap();
+
+    let library = Library::parse_file(&gz).unwrap();
+    assert_eq!(library.entries()[0].get("title"), Some("Gzip"));
+    assert_eq!(
+        bibtex_parser::parse_file(&zst).unwrap().entries()[0].key(),
+        "zst"
+    );
+
+    let library = Parser::new().parse_files(&[&gz, &zst, &plain]).unwrap();
+    let mut keys: Vec<_> = library.entries().iter().map(|entry| entry.key()).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["gz", "plain", "zst"]);
+
+    fs::write(&gz, [0x1f, 0x8b, 0x00]).unwrap();
+    assert!(Library::parse_file(&gz).is_err());
+}
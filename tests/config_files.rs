@@ -0,0 +1,124 @@
+use bibtex_parser::{
+    Config, DelimiterStyle, DiagnosticCode, DiagnosticSeverity, Error, ParserProfile,
+    TrailingComma, ValidationLevel,
+};
+use std::fs;
+
+#[test]
+fn nearest_config_file_is_discovered_up_the_tree() {
+    let root = tempfile::tempdir().unwrap();
+    let nested = root.path().join("refs/chapters");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(
+        root.path().join("bibtex.toml"),
+        "[parser]\nprofile = \"legacy\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.path().join("refs/.bibfmt.toml"),
+        r#"
+# Shared by the formatter and the linter.
+[parser]
+profile = "zotero"
+
+[format]
+indent = "    "
+trailing_comma = true
+value_delimiter = "quotes"
+tabular_column = 16
+field_order = "standard"
+
+[field_order]
+misc = ["title", "year"]
+
+[lint]
+level = "strict"
+baseline = "lint-baseline.txt"
+
+[lint.rules]
+empty-field = "off"
+invalid-doi = "error"
+"#,
+    )
+    .unwrap();
+
+    let config = Config::discover(&nested).unwrap().unwrap();
+    assert_eq!(
+        config.path.as_deref(),
+        Some(root.path().join("refs/.bibfmt.toml").as_path())
+    );
+    assert_eq!(config.profile, Some(ParserProfile::Zotero));
+    assert_eq!(config.writer.indent, "    ");
+    assert_eq!(config.writer.trailing_comma, TrailingComma::Always);
+    assert_eq!(config.writer.value_delimiter, DelimiterStyle::Quotes);
+    assert_eq!(config.writer.tabular.map(|style| style.column()), Some(16));
+    let order = config.writer.field_order.as_ref().unwrap();
+    assert_eq!(order.template("misc"), ["title", "year"]);
+    assert_eq!(order.template("article")[2], "journal");
+    assert_eq!(config.lint.level(), ValidationLevel::Strict);
+    assert_eq!(
+        config
+            .lint
+            .resolve(&DiagnosticCode::EMPTY_FIELD, DiagnosticSeverity::Warning),
+        None
+    );
+    assert_eq!(
+        config
+            .lint
+            .resolve(&DiagnosticCode::INVALID_DOI, DiagnosticSeverity::Warning),
+        Some(DiagnosticSeverity::Error)
+    );
+    assert_eq!(
+        config.baseline,
+        Some(root.path().join("refs/lint-baseline.txt"))
+    );
+
+    let library = config.parser().parse("@misc{a, pages = -5}").unwrap();
+    assert_eq!(library.entries()[0].get("pages"), Some("-5"));
+
+    let outer = Config::discover(root.path()).unwrap().unwrap();
+    assert_eq!(outer.profile, Some(ParserProfile::Legacy));
+}
+
+#[test]
+fn relative_directories_are_discovered_from_the_working_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let nested = root.path().join("refs");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(
+        root.path().join("bibtex.toml"),
+        "[parser]\nprofile = \"legacy\"\n",
+    )
+    .unwrap();
+
+    let previous = std::env::current_dir().unwrap();
+    std::env::set_current_dir(&nested).unwrap();
+    let config = Config::discover(".");
+    std::env::set_current_dir(previous).unwrap();
+
+    let config = config.unwrap().unwrap();
+    assert_eq!(config.profile, Some(ParserProfile::Legacy));
+}
+
+#[test]
+fn config_errors_name_the_file_and_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bibtex.toml");
+    fs::write(&path, "[format]\nindnet = \"  \"\n").unwrap();
+    let Err(Error::Config(message)) = Config::load(&path) else {
+        panic!("expected a configuration error");
+    };
+    assert!(
+        message.starts_with(&path.display().to_string()),
+        "{message}"
+    );
+    assert!(
+        message.ends_with("line 2: unknown key `indnet` in [format]"),
+        "{message}"
+    );
+
+    assert!(matches!(
+        Config::parse("[lint]\nlevel = \"pedantic\""),
+        Err(Error::Config(_))
+    ));
+}
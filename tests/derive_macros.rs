@@ -0,0 +1,124 @@
+#![cfg(feature = "derive")]
+
+use bibtex_parser::{FieldErrorKind, FromEntry, Library, PersonName, ToEntry};
+
+fn parse_pages(value: Option<&str>) -> Result<(u32, u32), FieldErrorKind> {
+    let value = value.ok_or(FieldErrorKind::Missing)?;
+    let invalid = || FieldErrorKind::Invalid {
+        value: value.to_string(),
+        expected: "a page range".to_string(),
+    };
+    let (start, end) = value.split_once("--").ok_or_else(invalid)?;
+    Ok((
+        start.trim().parse().map_err(|_| invalid())?,
+        end.trim().parse().map_err(|_| invalid())?,
+    ))
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn write_pages(pages: &(u32, u32)) -> Option<String> {
+    Some(format!("{}--{}", pages.0, pages.1))
+}
+
+#[derive(Debug, PartialEq, FromEntry, ToEntry)]
+#[bibtex(entry_type = "article")]
+struct Paper {
+    #[bibtex(key)]
+    key: String,
+    title: String,
+    #[bibtex(rename = "author")]
+    authors: Vec<PersonName>,
+    year: i32,
+    doi: Option<String>,
+    #[bibtex(parse_with = "parse_pages", write_with = "write_pages")]
+    pages: (u32, u32),
+    #[bibtex(skip)]
+    starred: bool,
+}
+
+const INPUT: &str = "@article{doe2024,
+    title = {A Paper},
+    author = {Doe, Jane and Roe, Rick},
+    year = 2024,
+    pages = {3--14}
+}";
+
+#[test]
+fn derived_from_entry_reads_renamed_optional_and_custom_fields() {
+    let library = Library::parse(INPUT).unwrap();
+    let paper: Paper = library.entries()[0].extract().unwrap();
+
+    assert_eq!(paper.key, "doe2024");
+    assert_eq!(paper.title, "A Paper");
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.authors[1].last, "Roe");
+    assert_eq!(paper.year, 2024);
+    assert_eq!(paper.doi, None);
+    assert_eq!(paper.pages, (3, 14));
+    assert!(!paper.starred);
+}
+
+#[test]
+fn derived_from_entry_reports_every_failed_field() {
+    let library = Library::parse("@article{bad, year = {soon}, pages = {7}}").unwrap();
+    let error = library.entries()[0].extract::<Paper>().unwrap_err();
+
+    let fields: Vec<_> = error.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(error.key, "bad");
+    assert_eq!(fields, ["title", "author", "year", "pages"]);
+}
+
+#[test]
+fn derived_to_entry_round_trips() {
+    let library = Library::parse(INPUT).unwrap();
+    let mut paper: Paper = library.entries()[0].extract().unwrap();
+    paper.doi = Some("10.1000/xyz".to_string());
+    paper.starred = true;
+
+    let entry = paper.to_entry();
+    assert_eq!(entry.ty.to_string(), "article");
+    assert_eq!(entry.key(), "doe2024");
+    assert_eq!(entry.get("author"), Some("Doe, Jane and Roe, Rick"));
+    assert_eq!(entry.get("pages"), Some("3--14"));
+    assert_eq!(entry.get("starred"), None);
+
+    let back: Paper = entry.extract().unwrap();
+    assert_eq!(back.doi.as_deref(), Some("10.1000/xyz"));
+    assert_eq!(
+        Paper {
+            starred: true,
+            ..back
+        },
+        paper
+    );
+}
+
+#[derive(FromEntry, ToEntry)]
+struct Note {
+    #[bibtex(key)]
+    id: String,
+    #[bibtex(rename = "note")]
+    text: Option<String>,
+}
+
+#[test]
+fn derived_to_entry_defaults_to_misc_and_omits_missing_fields() {
+    let entry = Note {
+        id: "n1".to_string(),
+        text: None,
+    }
+    .to_entry();
+    assert_eq!(entry.ty.to_string(), "misc");
+    assert_eq!(entry.key(), "n1");
+    assert!(entry.fields().is_empty());
+
+    let note: Note = Note {
+        id: "n1".to_string(),
+        text: Some("hi".to_string()),
+    }
+    .to_entry()
+    .extract()
+    .unwrap();
+    assert_eq!(note.id, "n1");
+    assert_eq!(note.text.as_deref(), Some("hi"));
+}
@@ -1,4 +1,8 @@
-use bibtex_parser::{DiagnosticCode, DiagnosticSeverity, DiagnosticTarget, ParseStatus, Parser};
+use bibtex_parser::{
+    CommentPolicy, DiagnosticCode, DiagnosticSeverity, DiagnosticTarget, DuplicateKeyPolicy, Entry,
+    KeyCharset, ParseEvent, ParseFlow, ParseStatus, Parser, ParserProfile,
+    UnresolvedVariablePolicy, Value,
+};
 
 fn first_diagnostic(input: &str) -> (DiagnosticCode, DiagnosticTarget) {
     let document = Parser::new().tolerant().parse_document(input).unwrap();
@@ -150,3 +154,251 @@ fn snippets_cover_beginning_middle_multiline_unicode_and_eof() {
         .unwrap()
         .contains("@article{bad"));
 }
+
+#[test]
+fn lossy_bare_numbers_are_kept_as_literals_with_warnings() {
+    let input = "@article{paper, volume = 07, year = 2020., number = 123456789012345678901234567890, pages = 12}";
+    let document = Parser::new().parse_document(input).unwrap();
+
+    assert_eq!(document.status(), ParseStatus::Ok);
+    let entry = &document.entries()[0];
+    assert_eq!(entry.fields[0].value.plain_text(), "07");
+    assert_eq!(entry.fields[1].value.plain_text(), "2020.");
+    assert_eq!(
+        entry.fields[2].value.plain_text(),
+        "123456789012345678901234567890"
+    );
+
+    let diagnostics = document.diagnostics();
+    assert_eq!(diagnostics.len(), 3);
+    assert!(diagnostics.iter().all(|diagnostic| {
+        diagnostic.code == DiagnosticCode::NUMERIC_LITERAL_FALLBACK
+            && diagnostic.severity == DiagnosticSeverity::Warning
+    }));
+    assert_eq!(
+        diagnostics[0].target,
+        DiagnosticTarget::Value { entry: 0, field: 0 }
+    );
+    assert_eq!(diagnostics[2].source.unwrap().column, 53);
+
+    let library = bibtex_parser::Library::parse(input).unwrap();
+    assert!(library.to_bibtex().unwrap().contains("volume = {07}"));
+    let mut streamed = Vec::new();
+    Parser::new()
+        .parse_events(input, |event| {
+            if let ParseEvent::Diagnostic(diagnostic) = event {
+                streamed.push(diagnostic.target);
+            }
+            Ok(ParseFlow::Continue)
+        })
+        .unwrap();
+    assert_eq!(streamed.len(), 3);
+    assert_eq!(streamed[1], DiagnosticTarget::Value { entry: 0, field: 1 });
+}
+
+#[test]
+fn digit_led_words_are_not_reported_as_lossy_numbers() {
+    let input = "@article{paper, edition = 2nd, number = 2020a, volume = {07}, pages = 12}";
+    let document = Parser::new().parse_document(input).unwrap();
+    assert!(document.diagnostics().is_empty());
+}
+
+#[test]
+fn signed_numbers_can_be_kept_as_literal_text() {
+    let input = "@article{paper, pages = -5, volume = -03, year = -350, note = \"p. \" # -7 # {}}";
+
+    let library = Parser::new().parse(input).unwrap();
+    assert_eq!(
+        library.entries()[0].field("pages").unwrap().value,
+        Value::Number(-5)
+    );
+
+    for library in [
+        Parser::new()
+            .signed_numbers_as_literals()
+            .parse(input)
+            .unwrap(),
+        Parser::new()
+            .signed_numbers_as_literals()
+            .tolerant()
+            .capture_source()
+            .parse(input)
+            .unwrap(),
+    ] {
+        let entry = &library.entries()[0];
+        assert_eq!(entry.get("pages"), Some("-5"));
+        assert_eq!(entry.get("volume"), Some("-03"));
+        assert_eq!(entry.field("year").unwrap().value, Value::Number(-350));
+        assert_eq!(entry.get_as_string("note"), Some("p. -7".to_string()));
+    }
+
+    let document = Parser::new()
+        .signed_numbers_as_literals()
+        .parse_document(input)
+        .unwrap();
+    assert_eq!(document.entries()[0].fields[0].value.plain_text(), "-5");
+    assert!(document.diagnostics().is_empty());
+
+    let mut pages = Vec::new();
+    Parser::new()
+        .signed_numbers_as_literals()
+        .parse_events(input, |event| {
+            if let bibtex_parser::ParseEvent::Entry(entry) = event {
+                pages.push(entry.fields[0].value.value.clone().into_owned());
+            }
+            Ok(bibtex_parser::ParseFlow::Continue)
+        })
+        .unwrap();
+    assert_eq!(pages, [Value::Literal("-5".into())]);
+}
+
+#[test]
+fn parser_profiles_bundle_recovery_and_variable_policies() {
+    let input = "@string{ieee = \"IEEE\"}\n\
+                 @article{a, journal = ieee, publisher = acm, month = jan, pages = -5}\n\
+                 @article{broken, title = \n";
+
+    assert!(Parser::new()
+        .profile(ParserProfile::Strict)
+        .parse(input)
+        .is_err());
+
+    let tolerant = Parser::new()
+        .profile(ParserProfile::Tolerant)
+        .parse(input)
+        .unwrap();
+    let entry = &tolerant.entries()[0];
+    assert_eq!(entry.get("journal"), Some("IEEE"));
+    assert_eq!(
+        entry.field("publisher").unwrap().value,
+        Value::Variable("acm".into())
+    );
+    assert_eq!(entry.field("pages").unwrap().value, Value::Number(-5));
+    assert_eq!(tolerant.failed_blocks().len(), 1);
+
+    let zotero = Parser::new()
+        .profile(ParserProfile::Zotero)
+        .parse(input)
+        .unwrap();
+    assert_eq!(zotero.entries()[0].get("pages"), Some("-5"));
+
+    let jabref = Parser::new()
+        .profile(ParserProfile::JabRef)
+        .parse(input)
+        .unwrap();
+    let entry = &jabref.entries()[0];
+    assert_eq!(
+        entry.field("journal").unwrap().value,
+        Value::Variable("ieee".into())
+    );
+    assert_eq!(entry.get("month"), Some("January"));
+
+    let legacy = Parser::new()
+        .profile(ParserProfile::Legacy)
+        .parse(input)
+        .unwrap();
+    assert_eq!(legacy.entries()[0].get("publisher"), Some(""));
+
+    let overridden = Parser::new()
+        .profile(ParserProfile::Legacy)
+        .undefined_variables(UnresolvedVariablePolicy::Preserve)
+        .parse(input)
+        .unwrap();
+    assert_eq!(
+        overridden.entries()[0].field("publisher").unwrap().value,
+        Value::Variable("acm".into())
+    );
+}
+
+#[test]
+fn parser_profiles_bundle_comment_duplicate_and_key_rules() {
+    let input = "% exported 2020\n\
+                 @comment{jabref-meta: databaseType:bibtex;}\n\
+                 @misc{Smith, title = {First}}\n\
+                 @misc{smith, title = {Second}}\n\
+                 @misc{M\u{fc}ller/2020+, title = {Third}}\n";
+
+    let error = Parser::new()
+        .profile(ParserProfile::Strict)
+        .key_charset(KeyCharset::BibTeX)
+        .parse(input)
+        .unwrap_err();
+    assert!(error.to_string().contains("line 4"), "{error}");
+    assert!(Parser::new()
+        .profile(ParserProfile::Strict)
+        .parse("@misc{M\u{fc}ller/2020+, title = {A}}")
+        .is_err());
+
+    let jabref = Parser::new()
+        .profile(ParserProfile::JabRef)
+        .parse(input)
+        .unwrap();
+    assert_eq!(jabref.entries().len(), 2);
+    assert_eq!(jabref.failed_blocks().len(), 1);
+    assert_eq!(jabref.comments().len(), 2);
+
+    let legacy = Parser::new()
+        .profile(ParserProfile::Legacy)
+        .parse(input)
+        .unwrap();
+    let keys = legacy.entries().iter().map(Entry::key).collect::<Vec<_>>();
+    assert_eq!(keys, ["Smith", "M\u{fc}ller/2020+"]);
+    assert_eq!(legacy.entries()[0].get("title"), Some("First"));
+    assert!(legacy.comments().is_empty());
+
+    let document = Parser::new()
+        .tolerant()
+        .duplicate_keys(DuplicateKeyPolicy::Error)
+        .key_charset(KeyCharset::BibTeX)
+        .comments(CommentPolicy::Discard)
+        .parse_document(input)
+        .unwrap();
+    let entries = document.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].key_source.unwrap().line, 5);
+    assert_eq!(document.failed_blocks().len(), 1);
+    assert!(document.comments().is_empty());
+
+    let mut events = Vec::new();
+    Parser::new()
+        .profile(ParserProfile::Legacy)
+        .parse_events(input, |event| {
+            events.push(match event {
+                ParseEvent::Entry(entry) => entry.key.to_string(),
+                other => format!("{other:?}"),
+            });
+            Ok(ParseFlow::Continue)
+        })
+        .unwrap();
+    assert_eq!(events, ["Smith", "M\u{fc}ller/2020+"]);
+}
+
+#[test]
+fn multibyte_text_right_after_at_sign_never_panics() {
+    let inputs = [
+        "@é",
+        "@𝄞{a, title = {x}}",
+        "@strinä{a = \"b\"}",
+        "@comme€{x}",
+        "x 𝄞@𝄞, y",
+        "@book{ok, title = {B}}\n@ſtring{𝄞}",
+    ];
+    for input in inputs {
+        assert!(Parser::new().parse(input).is_err(), "{input}");
+        let document = Parser::new()
+            .tolerant()
+            .preserve_raw()
+            .parse_document(input)
+            .unwrap();
+        for diagnostic in document.diagnostics() {
+            let span = diagnostic.source.unwrap();
+            assert!(input.get(span.byte_start..span.byte_end).is_some());
+            assert!(diagnostic.snippet.is_some());
+        }
+        assert!(bibtex_parser::tokenize(input).count() > 0);
+    }
+
+    let map = bibtex_parser::SourceMap::anonymous("aé\n𝄞");
+    assert_eq!(map.snippet(map.span(3, 3), 10).as_deref(), Some("aé"));
+    assert_eq!(map.snippet(map.span(5, 5), 10).as_deref(), Some("𝄞"));
+}
@@ -1,7 +1,12 @@
 use bibtex_parser::{
-    document_to_string, selected_entries_to_string, EntryType, Library, Parser, Value,
+    bbl_keys, cited_keys, document_to_string, rewrite_citations, selected_entries_to_string,
+    AddOutcome, EntryType, FieldOrigin, KeyRenameMap, Library, MergePolicy, Parser, SortOptions,
+    Value,
 };
+#[cfg(feature = "key_canonicalization")]
+use bibtex_parser::{Entry, KeyCanonicalization, ParseEvent, ParseFlow};
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 #[test]
 fn key_type_field_and_value_edits_preserve_unrelated_raw_text() {
@@ -110,3 +115,442 @@ fn selected_entries_are_serialized_in_source_order() {
 @misc{c, title = "C"}"#
     );
 }
+
+#[test]
+fn add_or_update_entry_matches_on_key_or_doi_and_applies_merge_policy() {
+    let mut library =
+        Library::parse("@article{a, title = {Old}, doi = {10.1000/X}}\n@book{b, title = {B}}")
+            .unwrap();
+
+    let mut incoming = Library::parse(
+        "@article{a, Title = {Old}, year = 2024}\n\
+         @misc{other, title = {New}, doi = {https://doi.org/10.1000/x}}\n\
+         @misc{c, title = {C}}\n\
+         @book{b, title = {B}}",
+    )
+    .unwrap()
+    .entries()
+    .to_vec()
+    .into_iter();
+
+    assert_eq!(
+        library.add_or_update_entry(incoming.next().unwrap(), MergePolicy::default()),
+        AddOutcome::Updated(0)
+    );
+    assert_eq!(
+        library.entries()[0].get_as_string("year").as_deref(),
+        Some("2024")
+    );
+
+    let by_doi = incoming.next().unwrap();
+    assert_eq!(
+        library.add_or_update_entry(by_doi.clone(), MergePolicy::RejectConflicts),
+        AddOutcome::Conflicted {
+            index: 0,
+            fields: vec!["title".to_string()],
+        }
+    );
+    assert_eq!(
+        library.add_or_update_entry(by_doi.clone(), MergePolicy::KeepExisting),
+        AddOutcome::Unchanged(0)
+    );
+    assert_eq!(
+        library.add_or_update_entry(by_doi, MergePolicy::PreferIncoming),
+        AddOutcome::Updated(0)
+    );
+    assert_eq!(library.entries()[0].key(), "a");
+    assert_eq!(library.entries()[0].title().as_deref(), Some("New"));
+
+    assert_eq!(
+        library.add_or_update_entry(incoming.next().unwrap(), MergePolicy::default()),
+        AddOutcome::Inserted(2)
+    );
+    assert_eq!(
+        library.add_or_update_entry(incoming.next().unwrap(), MergePolicy::default()),
+        AddOutcome::Unchanged(1)
+    );
+    assert_eq!(library.entries().len(), 3);
+    assert!(Library::parse(&library.to_bibtex().unwrap()).is_ok());
+}
+
+#[test]
+fn add_or_update_entry_ignores_key_case_and_repeated_incoming_fields() {
+    let mut library = Library::parse("@article{Smith2024, title = {T}}").unwrap();
+    let incoming = Library::parse("@article{smith2024, year = 2024, YEAR = 2025, note = {N}}")
+        .unwrap()
+        .entries()[0]
+        .clone();
+
+    assert_eq!(
+        library.add_or_update_entry(incoming.clone(), MergePolicy::PreferIncoming),
+        AddOutcome::Updated(0)
+    );
+    assert_eq!(library.entries().len(), 1);
+    let entry = &library.entries()[0];
+    assert_eq!(entry.key(), "Smith2024");
+    assert_eq!(
+        entry
+            .fields()
+            .iter()
+            .filter(|field| field.name.eq_ignore_ascii_case("year"))
+            .count(),
+        1
+    );
+    assert_eq!(entry.get_as_string("year").as_deref(), Some("2024"));
+    assert_eq!(
+        library.add_or_update_entry(incoming, MergePolicy::RejectConflicts),
+        AddOutcome::Unchanged(0)
+    );
+}
+
+#[test]
+fn regenerated_keys_update_references_and_return_rename_map() {
+    let mut library = Library::parse(
+        "@proceedings{proc, title = {Proc}}\n\
+         @inproceedings{talk, crossref = {proc}, related = {proc, other}, ids = {talk-old}}\n\
+         @misc{other, xdata = {proc}}",
+    )
+    .unwrap();
+
+    let renames = library.regenerate_keys(|entry| match entry.key() {
+        "proc" => Some("conf2020".to_string()),
+        "other" => Some("other".to_string()),
+        _ => None,
+    });
+
+    let expected: KeyRenameMap = [("proc", "conf2020")].into_iter().collect();
+    assert_eq!(renames, expected);
+    assert!(library.contains_key("conf2020"));
+    let talk = library.find_by_key("talk").unwrap();
+    assert_eq!(talk.get("crossref"), Some("conf2020"));
+    assert_eq!(talk.get("related"), Some("conf2020, other"));
+    assert_eq!(talk.get("ids"), Some("talk-old"));
+    assert_eq!(
+        library.find_by_key("other").unwrap().get("xdata"),
+        Some("conf2020")
+    );
+}
+
+#[test]
+fn rewrite_citations_changes_only_cite_command_keys() {
+    let renames: KeyRenameMap = [("old", "new2020"), ("b", "beta")].into_iter().collect();
+    let latex = "See \\citep[p.~3]{old, a} and \\textcite*{b}.\n\
+                 \\cites(pre)(post)[see][]{old}{b} \\\\cite{old} \\ref{old} old\n\
+                 \\nocite{ a ,old } % \\cite{old\n";
+
+    assert_eq!(
+        rewrite_citations(latex, &renames),
+        "See \\citep[p.~3]{new2020, a} and \\textcite*{beta}.\n\
+         \\cites(pre)(post)[see][]{new2020}{beta} \\\\cite{old} \\ref{old} old\n\
+         \\nocite{ a ,new2020 } % \\cite{old\n"
+    );
+    assert_eq!(rewrite_citations(latex, &KeyRenameMap::new()), latex);
+
+    let mut renames: KeyRenameMap = (0..1000)
+        .map(|n| (format!("k{n}"), format!("r{n}")))
+        .collect();
+    renames.insert("k5", "again");
+    assert_eq!(renames.len(), 1000);
+    assert_eq!(renames.get("k5"), Some("again"));
+    assert_eq!(renames.get("k999"), Some("r999"));
+    assert_eq!(renames.iter().nth(5), Some(("k5", "again")));
+}
+
+#[test]
+fn bbl_check_reports_stale_and_missing_references() {
+    let library = Library::parse("@misc{a, year = 2024}\n@misc{b, year = 2024}").unwrap();
+    let bibtex = "\\bibitem[A(2024)]{a} A.\n\\bibitem[{Old et~al.}(2020)]{old} Old.\n";
+    let biber =
+        "\\entry{a}{misc}{}\n  \\field{year}{2024}\n\\endentry\n\\entry{b}{misc}{}\n\\endentry\n";
+    let latex = "\\cite{a} \\citep[see][]{b, a} \\nocite{*} \\ref{c}";
+
+    assert_eq!(bbl_keys(bibtex), ["a", "old"]);
+    assert_eq!(bbl_keys(biber), ["a", "b"]);
+    assert_eq!(cited_keys(latex), ["a", "b"]);
+
+    let check = library.check_bbl(bibtex, latex);
+    assert_eq!(check.missing_from_library, ["old"]);
+    assert_eq!(check.missing_from_bbl, ["b"]);
+    assert!(library.check_bbl(biber, latex).is_clean());
+
+    let check = library.check_bbl("\\bibitem{A} A.\n\\bibitem{a} A.\n", "\\cite{B}");
+    assert_eq!(check.missing_from_library, ["A"]);
+    assert_eq!(check.missing_from_bbl, ["B"]);
+}
+
+#[test]
+fn entry_ids_survive_sorting_key_renames_and_removal() {
+    let mut library =
+        Library::parse("@misc{c, title = {C}}\n@misc{a, title = {A}}\n@misc{b, title = {B}}")
+            .unwrap();
+    let ids = library.entry_ids().to_vec();
+    let (c, a, b) = (ids[0], ids[1], ids[2]);
+    assert!(c < a && a < b);
+
+    library.sort(SortOptions {
+        entries_by_key: true,
+        fields_by_name: false,
+        entries_by_year: false,
+    });
+    assert_eq!(library.entry_ids(), &[a, b, c]);
+    assert_eq!(library.entry_by_id(c).unwrap().key(), "c");
+
+    library.regenerate_keys(|entry| Some(format!("{}2020", entry.key())));
+    assert_eq!(library.entry_by_id(a).unwrap().key(), "a2020");
+
+    assert_eq!(library.remove_entry(b).unwrap().key(), "b2020");
+    assert_eq!(library.entry_by_id(b), None);
+    assert_eq!(library.entry_index(c), Some(1));
+
+    library.add_entry(library.entries()[0].clone());
+    let added = library.entry_id(2).unwrap();
+    assert!(added > b);
+
+    let keys = library
+        .entries_in_source_order()
+        .into_iter()
+        .map(|entry| entry.key().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["c2020", "a2020", "a2020"]);
+    assert!(Library::parse(&library.to_bibtex().unwrap()).is_ok());
+}
+
+#[test]
+fn field_origins_follow_their_entry_through_removal_and_sorting() {
+    let input = "@string{pub = {ACM}}\n\
+                 @string{press = { Press}}\n\
+                 @misc{c, publisher = pub}\n\
+                 @misc{a, title = {A}}\n\
+                 @misc{b, publisher = pub # press}";
+    let mut library = Parser::new().track_field_origins().parse(input).unwrap();
+    let ids = library.entry_ids().to_vec();
+    let (c, a, b) = (ids[0], ids[1], ids[2]);
+
+    library.remove_entry(a).unwrap();
+    library.sort(SortOptions {
+        entries_by_key: true,
+        fields_by_name: false,
+        entries_by_year: false,
+    });
+    assert_eq!(library.entry_ids(), &[b, c]);
+    assert_eq!(library.entry_index(c), Some(1));
+    assert_eq!(
+        library.field_origin(c, "publisher"),
+        Some(&FieldOrigin::FromString("pub".to_string()))
+    );
+    assert_eq!(
+        library.field_origin(b, "publisher"),
+        Some(&FieldOrigin::FromStrings(vec![
+            "pub".to_string(),
+            "press".to_string()
+        ]))
+    );
+
+    let mut merged = Library::parse("@misc{z, title = {Z}}").unwrap();
+    merged.merge(library);
+    let c = merged.entry_id(2).unwrap();
+    assert_eq!(merged.entry_by_id(c).unwrap().key(), "c");
+    assert_eq!(
+        merged.field_origin(c, "publisher"),
+        Some(&FieldOrigin::FromString("pub".to_string()))
+    );
+}
+
+#[test]
+#[cfg(feature = "key_canonicalization")]
+fn canonicalized_keys_match_across_unicode_normalizations() {
+    let mut library = Library::new();
+    library.add_entry(Entry::new(EntryType::Article, "Caf\u{e9}2024"));
+    library.add_entry(Entry::new(EntryType::Article, " Cafe\u{301}2024"));
+    let canonicalization = KeyCanonicalization::new().lowercase();
+    assert_eq!(library.canonicalize_keys(canonicalization), 2);
+    let keys = library
+        .entries()
+        .iter()
+        .map(|entry| entry.key.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["caf\u{e9}2024", "caf\u{e9}2024"]);
+    assert_eq!(
+        library.original_key(library.entry_id(1).unwrap()),
+        Some(" Cafe\u{301}2024")
+    );
+    assert_eq!(
+        KeyCanonicalization::new().canonicalize("plain"),
+        Cow::Borrowed("plain")
+    );
+
+    let input = "@article{Smith2024, title = {A}}";
+    let parser = Parser::new().canonicalize_keys(canonicalization);
+    let mut merged = parser.parse(input).unwrap();
+    merged.merge(parser.parse("@book{smith2024, title = {B}}").unwrap());
+    assert_eq!(
+        merged.find_by_key("smith2024").unwrap().get("title"),
+        Some("A")
+    );
+    assert_eq!(
+        merged.original_key(merged.entry_id(0).unwrap()),
+        Some("Smith2024")
+    );
+    assert_eq!(merged.original_key(merged.entry_id(1).unwrap()), None);
+
+    let document = parser.preserve_raw().parse_document(input).unwrap();
+    let entry = &document.entries()[0];
+    assert_eq!(entry.key, "smith2024");
+    assert_eq!(entry.original_key.as_deref(), Some("Smith2024"));
+    assert_eq!(document_to_string(&document).unwrap(), input);
+}
+
+#[test]
+#[cfg(feature = "key_canonicalization")]
+fn canonicalized_keys_keep_crossrefs_attached() {
+    let input = "@proceedings{Conf, title = {Proceedings}}\n\
+                 @inproceedings{Paper, crossref = {Conf}, related = {Other, Conf}}\n";
+    let parser = Parser::new().canonicalize_keys(KeyCanonicalization::new().lowercase());
+
+    let library = parser.parse(input).unwrap();
+    let paper = library.find_by_key("paper").unwrap();
+    assert_eq!(paper.get("crossref"), Some("conf"));
+    assert_eq!(paper.get("related"), Some("Other, conf"));
+
+    let document = parser.parse_document(input).unwrap();
+    let paper = &document.entries()[1];
+    assert_eq!(
+        paper
+            .field_ignore_case("crossref")
+            .unwrap()
+            .value
+            .plain_text(),
+        "conf"
+    );
+
+    let mut streamed = Vec::new();
+    parser
+        .parse_events(input, |event| {
+            if let ParseEvent::Entry(entry) = event {
+                streamed.push(entry.into_entry().into_owned());
+            }
+            Ok(ParseFlow::Continue)
+        })
+        .unwrap();
+    assert_eq!(streamed[1].get("crossref"), Some("conf"));
+}
+
+#[test]
+#[cfg(feature = "key_canonicalization")]
+fn keys_can_be_looked_up_under_unicode_normalization() {
+    let mut library = Library::new();
+    library.add_entry(Entry::new(EntryType::Article, "M\u{fc}ller2024"));
+    let decomposed = "Mu\u{308}ller2024";
+
+    assert!(library.find_by_key(decomposed).is_none());
+    let nfc = KeyCanonicalization::new();
+    assert_eq!(
+        library.find_by_key_canonical(decomposed, nfc).unwrap().key,
+        "M\u{fc}ller2024"
+    );
+    assert!(library
+        .find_by_key_canonical("mu\u{308}ller2024", nfc)
+        .is_none());
+    assert!(library
+        .find_by_key_canonical("mu\u{308}ller2024 ", nfc.lowercase())
+        .is_some());
+    assert!(nfc.lowercase().matches("M\u{dc}LLER", "mu\u{308}ller"));
+}
+
+#[test]
+fn sorting_moves_leading_comments_with_their_entry() {
+    let mut library = Library::parse(
+        "@string{j = {J}}\n% Check pages.\n@article{zeta, journal = j}\n@comment{about alpha}\n@article{alpha, year = 2020}\n@misc{beta, note = {N}}",
+    )
+    .unwrap();
+    let texts = |library: &Library, index| {
+        library
+            .leading_comments(index)
+            .iter()
+            .map(|comment| comment.text().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&library, 0), ["% Check pages.\n"]);
+    assert_eq!(texts(&library, 1), ["about alpha"]);
+    assert!(texts(&library, 2).is_empty());
+
+    library.sort(SortOptions {
+        entries_by_key: true,
+        fields_by_name: false,
+        entries_by_year: false,
+    });
+    assert_eq!(texts(&library, 0), ["about alpha"]);
+    assert_eq!(texts(&library, 2), ["% Check pages.\n"]);
+
+    let output = library.to_bibtex().unwrap();
+    let alpha = output.find("@article{alpha").unwrap();
+    let zeta = output.find("@article{zeta").unwrap();
+    assert!(output.find("about alpha").unwrap() < alpha);
+    assert!((alpha..zeta).contains(&output.find("Check pages.").unwrap()));
+}
+
+#[test]
+fn sorting_keeps_the_file_header_at_the_top() {
+    let input = "% My bibliography\n\n@comment{Check pages.}\n@misc{zeta, year = 2020}\n@misc{alpha, year = 2021}";
+    let mut library = Library::parse(input).unwrap();
+    let texts = |library: &Library, index| {
+        library
+            .leading_comments(index)
+            .iter()
+            .map(|comment| comment.text().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&library, 0), ["Check pages."]);
+
+    library.sort(SortOptions {
+        entries_by_key: true,
+        fields_by_name: false,
+        entries_by_year: false,
+    });
+    assert_eq!(texts(&library, 1), ["Check pages."]);
+    let output = library.to_bibtex().unwrap();
+    assert!(output.starts_with("% My bibliography"));
+    let alpha = output.find("@misc{alpha").unwrap();
+    assert!((alpha..).contains(&output.find("Check pages.").unwrap()));
+
+    let mut sorted = Vec::new();
+    let config = bibtex_parser::WriterConfig::default().sort_entries(true);
+    bibtex_parser::Writer::with_config(&mut sorted, config)
+        .write_library(&Library::parse(input).unwrap())
+        .unwrap();
+    let sorted = String::from_utf8(sorted).unwrap();
+    assert!(sorted.starts_with("% My bibliography"));
+    assert!(sorted.find("Check pages.").unwrap() > sorted.find("@misc{alpha").unwrap());
+}
+
+#[test]
+fn library_splits_into_cited_and_uncited_entries() {
+    let input = "@string{j = {Journal}}\n@preamble{\"pre\"}\n\
+                 @article{a, journal = j}\n% about b\n@article{b, journal = j}\n@misc{c, note = {n}}\n";
+    let library = Parser::new().capture_source().parse(input).unwrap();
+    let ids: Vec<_> = library.entry_ids().to_vec();
+    let cited: HashSet<&str> = cited_keys(r"\cite{c, a}").into_iter().collect();
+
+    let (cited, uncited) = library.split_by_key_set(&cited);
+    let keys = |library: &Library<'_>| -> Vec<String> {
+        library
+            .iter()
+            .map(|entry| entry.key().to_string())
+            .collect()
+    };
+    assert_eq!(keys(&cited), ["a", "c"]);
+    assert_eq!(keys(&uncited), ["b"]);
+    assert_eq!(cited.entry_ids(), [ids[0], ids[2]]);
+    assert_eq!(uncited.entry_id(0), Some(ids[1]));
+    assert_eq!(uncited.strings().len(), 1);
+    assert_eq!(uncited.preambles().len(), 1);
+    assert_eq!(
+        uncited.entries()[0].get("journal"),
+        cited.entries()[0].get("journal")
+    );
+
+    let written = cited.to_bibtex().unwrap();
+    assert!(written.contains("@article{a,") && written.contains("@misc{c"));
+    assert!(!written.contains("@article{b,"));
+    assert_eq!(Library::parse(&written).unwrap().entries().len(), 2);
+}
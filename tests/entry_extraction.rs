@@ -0,0 +1,84 @@
+use bibtex_parser::{
+    Entry, ExtractError, FieldErrorKind, FieldReader, FromEntry, Library, PersonName,
+};
+
+#[derive(Debug)]
+struct Paper {
+    title: String,
+    year: i32,
+    authors: Vec<PersonName>,
+    keywords: Option<Vec<String>>,
+    pages: Option<u32>,
+}
+
+impl FromEntry for Paper {
+    fn from_entry(entry: &Entry<'_>) -> Result<Self, ExtractError> {
+        let mut fields = FieldReader::new(entry);
+        let title = fields.read("title");
+        let year = fields.read("year");
+        let authors = fields.read("author");
+        let keywords = fields.read("keywords");
+        let pages = fields.read_with("pages", |value| {
+            value
+                .map(|value| {
+                    let first = value.split('-').next().unwrap_or(value).trim();
+                    first.parse().map_err(|_| FieldErrorKind::Invalid {
+                        value: value.to_string(),
+                        expected: "a page range".to_string(),
+                    })
+                })
+                .transpose()
+        });
+        fields.finish(|| {
+            Some(Self {
+                title: title?,
+                year: year?,
+                authors: authors?,
+                keywords: keywords?,
+                pages: pages?,
+            })
+        })
+    }
+}
+
+#[test]
+fn entries_map_onto_typed_structs() {
+    let library = Library::parse(
+        r#"@string{t = "Typed"}
+@article{ok, TITLE = t # " Fields", year = 2024, author = "Doe, Jane and John Roe",
+  keywords = "bib; rust, parsing", pages = "12--20"}"#,
+    )
+    .unwrap();
+
+    let paper = library.entries()[0].extract::<Paper>().unwrap();
+    assert_eq!(paper.title, "Typed Fields");
+    assert_eq!(paper.year, 2024);
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.authors[1].last, "Roe");
+    assert_eq!(
+        paper.keywords.as_deref(),
+        Some(&["bib".to_string(), "rust".to_string(), "parsing".to_string()][..])
+    );
+    assert_eq!(paper.pages, Some(12));
+}
+
+#[test]
+fn extraction_reports_every_failed_field() {
+    let library = Library::parse("@article{bad, year = {circa 2024}, pages = {xii}}").unwrap();
+
+    let error = library.entries()[0].extract::<Paper>().unwrap_err();
+    assert_eq!(error.key, "bad");
+    let failed = error
+        .errors
+        .iter()
+        .map(|error| error.field.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(failed, ["title", "year", "author", "pages"]);
+    assert_eq!(error.errors[0].kind, FieldErrorKind::Missing);
+    assert_eq!(
+        error.to_string(),
+        "cannot extract entry `bad`: missing field `title`; field `year`: expected an integer, \
+         found \"circa 2024\"; missing field `author`; field `pages`: expected a page range, \
+         found \"xii\""
+    );
+}
@@ -0,0 +1,33 @@
+use bibtex_parser::Library;
+
+#[test]
+fn set_entries_expose_members_and_report_missing_ones() {
+    let input = r#"
+@set{bundle, entryset = {first, second, ghost}}
+@article{first, title = "First", author = "A", journal = "J", year = 2020, doi = "10.1/a"}
+@article{second, title = "Second", author = "B", journal = "J", year = 2021, doi = "10.1/b"}
+"#;
+    let library = Library::parse(input).unwrap();
+
+    let sets = library.sets();
+    assert_eq!(sets.len(), 1);
+    assert_eq!(sets[0].entry.key(), "bundle");
+    assert_eq!(sets[0].members, vec!["first", "second", "ghost"]);
+    let members = sets[0]
+        .member_entries(&library)
+        .into_iter()
+        .map(|entry| entry.key())
+        .collect::<Vec<_>>();
+    assert_eq!(members, vec!["first", "second"]);
+
+    let report = library.report();
+    assert_eq!(report.total_issues(), 1);
+    assert_eq!(report.missing_set_members[0].2, "ghost");
+
+    let mut renamed = library.clone();
+    renamed.regenerate_keys(|entry| (entry.key() == "first").then(|| "one".to_string()));
+    assert_eq!(
+        renamed.find_by_key("bundle").unwrap().set_members(),
+        vec!["one", "second", "ghost"]
+    );
+}
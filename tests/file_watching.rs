@@ -0,0 +1,135 @@
+#![cfg(feature = "watch")]
+
+use bibtex_parser::watch::{EntryChange, LibraryWatcher, WatchEvent};
+use bibtex_parser::SharedLibrary;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// Replace `path` in one step, as editors do when saving.
+fn save(path: &Path, content: &str) {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, content).unwrap();
+    fs::rename(&temp, path).unwrap();
+}
+
+fn next_event(events: &Receiver<WatchEvent>) -> WatchEvent {
+    events.recv_timeout(Duration::from_secs(10)).unwrap()
+}
+
+#[test]
+fn edits_are_reported_as_entry_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("refs.bib");
+    fs::write(
+        &path,
+        "@article{kept, title = {Old}}\n@book{gone, title = {Book}}\n",
+    )
+    .unwrap();
+    let watcher = LibraryWatcher::new(&[&path]).unwrap();
+    let events = watcher.subscribe();
+    let shared = SharedLibrary::default();
+    watcher.publish_to(&shared);
+    assert_eq!(watcher.paths(), [path.canonicalize().unwrap()]);
+    assert_eq!(shared.load().entries().len(), 2);
+    assert_eq!(watcher.library().entries().len(), 2);
+
+    save(
+        &path,
+        "@article{kept, title = {New}}\n@misc{fresh, title = {Misc}}\n",
+    );
+    let WatchEvent::Changed {
+        path: changed,
+        changes,
+    } = next_event(&events)
+    else {
+        panic!("expected entry changes");
+    };
+    assert_eq!(changed, path.canonicalize().unwrap());
+    let summary: Vec<(&str, &str)> = changes
+        .iter()
+        .map(|change| {
+            let kind = match change {
+                EntryChange::Added(_) => "added",
+                EntryChange::Removed(_) => "removed",
+                EntryChange::Modified { .. } => "modified",
+            };
+            (kind, change.key())
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("removed", "gone"),
+            ("modified", "kept"),
+            ("added", "fresh")
+        ]
+    );
+    assert_eq!(
+        watcher.library().find_by_key("kept").unwrap().get("title"),
+        Some("New")
+    );
+    assert!(shared.load().find_by_key("fresh").is_some());
+    assert!(watcher.reload(&path).is_none());
+    assert!(watcher.reload(dir.path().join("other.bib")).is_none());
+}
+
+#[test]
+fn unparsable_saves_keep_the_previous_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("live.bib");
+    fs::write(&path, "@misc{a, title = {A}}\n").unwrap();
+    let watcher = LibraryWatcher::new(&[&path]).unwrap();
+    let events = watcher.subscribe();
+
+    save(&path, "@misc{a, title = {A}\n");
+    assert!(matches!(next_event(&events), WatchEvent::Failed { .. }));
+    assert_eq!(watcher.library().entries().len(), 1);
+
+    save(&path, "@misc{a, title = {A}}\n@misc{b, title = {B}}\n");
+    let event = loop {
+        match next_event(&events) {
+            WatchEvent::Failed { .. } => continue,
+            event => break event,
+        }
+    };
+    let WatchEvent::Changed { changes, .. } = event else {
+        unreachable!()
+    };
+    assert!(matches!(&changes[..], [EntryChange::Added(entry)] if entry.key() == "b"));
+}
+
+#[test]
+fn duplicate_keys_pair_in_order_and_deleted_files_keep_their_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dups.bib");
+    fs::write(
+        &path,
+        "@misc{same, title = {First}}\n@misc{same, title = {Second}}\n",
+    )
+    .unwrap();
+    let path = path.canonicalize().unwrap();
+    let watcher = LibraryWatcher::new(&[&path]).unwrap();
+    let events = watcher.subscribe();
+
+    save(
+        &path,
+        "@misc{same, title = {First}}\n@misc{same, title = {Changed}}\n",
+    );
+    watcher.reload(&path);
+    let WatchEvent::Changed { changes, .. } = next_event(&events) else {
+        panic!("expected entry changes");
+    };
+    let [EntryChange::Modified { before, after }] = &changes[..] else {
+        panic!("expected one modification, got {changes:?}");
+    };
+    assert_eq!(
+        (before.get("title"), after.get("title")),
+        (Some("Second"), Some("Changed"))
+    );
+
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.reload(&path).is_none());
+    assert_eq!(watcher.library().entries().len(), 2);
+}
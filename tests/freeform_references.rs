@@ -0,0 +1,83 @@
+#![cfg(feature = "freeform")]
+
+use bibtex_parser::freeform::parse_reference;
+use bibtex_parser::EntryType;
+
+#[test]
+fn journal_reference_with_surname_first_authors_and_doi() {
+    let guess = parse_reference(
+        "[3] Einstein, A., Podolsky, B., & Rosen, N. (1935). Can quantum-mechanical \
+         description of physical reality be considered complete? Physical Review, \
+         47(10), 777–780. https://doi.org/10.1103/PhysRev.47.777",
+    );
+    let entry = &guess.entry;
+
+    assert_eq!(entry.ty, EntryType::Article);
+    assert_eq!(entry.key(), "einstein1935");
+    assert_eq!(
+        entry.get("author"),
+        Some("Einstein, A. and Podolsky, B. and Rosen, N.")
+    );
+    assert_eq!(
+        entry.get("title"),
+        Some("Can quantum-mechanical description of physical reality be considered complete?")
+    );
+    assert_eq!(entry.get("journal"), Some("Physical Review"));
+    assert_eq!(entry.get("year"), Some("1935"));
+    assert_eq!(entry.get("volume"), Some("47"));
+    assert_eq!(entry.get("number"), Some("10"));
+    assert_eq!(entry.get("pages"), Some("777--780"));
+    assert_eq!(entry.get("doi"), Some("10.1103/physrev.47.777"));
+    assert!(guess.confidence("doi").unwrap() > guess.confidence("journal").unwrap());
+}
+
+#[test]
+fn quoted_title_and_collection_venue_become_inproceedings() {
+    let guess = parse_reference(
+        "J. Smith and K. Lee. \"Fast parsing of bibliographies,\" in Proceedings of the \
+         ACM Conference on Document Engineering, pp. 12-20, 2019.",
+    );
+    let entry = &guess.entry;
+
+    assert_eq!(entry.ty, EntryType::InProceedings);
+    assert_eq!(entry.get("author"), Some("J. Smith and K. Lee"));
+    assert_eq!(entry.get("title"), Some("Fast parsing of bibliographies"));
+    assert_eq!(
+        entry.get("booktitle"),
+        Some("Proceedings of the ACM Conference on Document Engineering")
+    );
+    assert_eq!(entry.get("pages"), Some("12--20"));
+    assert!(guess.confidence("title").unwrap() >= 0.9);
+
+    let guess = parse_reference(
+        "Vaswani, A. et al. Attention is all you need. In Advances in Neural \
+         Information Processing Systems, vol. 30, 2017.",
+    );
+    assert_eq!(guess.entry.ty, EntryType::InProceedings);
+    assert_eq!(guess.entry.get("author"), Some("Vaswani, A. and others"));
+    assert_eq!(guess.entry.get("volume"), Some("30"));
+}
+
+#[test]
+fn books_theses_and_bare_text_get_low_type_confidence() {
+    let book =
+        parse_reference("Knuth, D. E. The Art of Computer Programming. Addison-Wesley, 1968.");
+    assert_eq!(book.entry.ty, EntryType::Book);
+    assert_eq!(book.entry.get("author"), Some("Knuth, D. E."));
+    assert_eq!(book.entry.get("publisher"), Some("Addison-Wesley"));
+    assert!(book.type_confidence < 0.5);
+
+    let thesis =
+        parse_reference("Doe, Jane. Learning to Cite. PhD thesis, MIT, 2020. arXiv:2001.01234");
+    assert_eq!(thesis.entry.ty, EntryType::PhdThesis);
+    assert_eq!(thesis.entry.get("author"), Some("Doe, Jane"));
+    assert_eq!(thesis.entry.get("school"), Some("MIT"));
+    assert_eq!(thesis.entry.get("eprint"), Some("2001.01234"));
+
+    let bare = parse_reference("Untitled notes");
+    assert_eq!(bare.entry.ty, EntryType::Misc);
+    assert_eq!(bare.entry.key(), "ref");
+    assert_eq!(bare.entry.get("title"), Some("Untitled notes"));
+    assert!(bare.confidence("title").unwrap() < 0.5);
+    assert_eq!(bare.confidence("author"), None);
+}
@@ -0,0 +1,125 @@
+use bibtex_parser::{Error, Library, ParseBudget, ParsedEntry, Parser, ParserState};
+use std::io::BufReader;
+
+const INPUT: &str = "@string{j = {Journal}}\n\
+                     % Group bibliography\n\
+                     @article{first, author = {Ada Lovelace}, journal = j}\n\
+                     @book{second,\n  title = {Über alles},\n  year = 1999\n}\n  \
+                     @misc{third, note = {trailing}}\n";
+
+fn entries_in_chunks(parser: &Parser, size: usize) -> Vec<ParsedEntry<'static>> {
+    let mut state = ParserState::new(parser.clone());
+    let mut entries = Vec::new();
+    let mut rest = INPUT;
+    while !rest.is_empty() {
+        let mut end = size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let document = state.feed(&rest[..end]).unwrap();
+        entries.extend(document.entries().iter().cloned());
+        rest = &rest[end..];
+    }
+    assert!(state.consumed() + state.pending().len() == INPUT.len());
+    entries.extend(state.finish().unwrap().entries().iter().cloned());
+    entries
+}
+
+#[test]
+fn chunked_input_parses_like_the_whole_input() {
+    let parser = Parser::new().preserve_raw();
+    let whole = parser.parse_document(INPUT).unwrap();
+    for size in [1, 2, 7, 64, INPUT.len()] {
+        let entries = entries_in_chunks(&parser, size);
+        assert_eq!(entries, whole.entries(), "chunk size {size}");
+    }
+}
+
+#[test]
+fn state_keeps_unfinished_blocks_and_budgeted_leftovers_pending() {
+    let mut state = ParserState::new(Parser::new());
+    let document = state.feed("@article{first, journal = {J}}\n@art").unwrap();
+    assert_eq!(document.entries()[0].key(), "first");
+    assert_eq!(state.pending(), "@art");
+    assert_eq!(state.consumed(), "@article{first, journal = {J}}\n".len());
+
+    let mut saved = state.clone();
+    for (state, key) in [(&mut state, "one"), (&mut saved, "two")] {
+        let chunk = format!("icle{{{key}, year = 2000}}\n");
+        assert!(state.feed(&chunk).unwrap().entries().is_empty());
+    }
+    assert_eq!(state.finish().unwrap().entries()[0].key(), "one");
+    assert_eq!(saved.finish().unwrap().entries()[0].key(), "two");
+
+    let mut state = ParserState::new(Parser::new().budget(ParseBudget::new().entries(1)));
+    let document = state.feed(INPUT).unwrap();
+    assert_eq!(document.entries().len(), 1);
+    assert!(state.pending().starts_with("@book{second"));
+    let document = state.feed("").unwrap();
+    assert_eq!(document.entries()[0].key(), "second");
+    assert_eq!(document.entries()[0].source.unwrap().line, 4);
+}
+
+#[test]
+fn line_start_at_signs_inside_values_do_not_split_blocks() {
+    let input = "@article{a,\n  abstract = {We study\n@ signs in text.},\n  \
+                 note = \"quoted\n@ too\"\n}\n@misc{b, title = {B}}\n";
+    let parser = Parser::new().tolerant();
+    let whole = parser.parse_document(input).unwrap();
+    assert!(whole.diagnostics().is_empty());
+    for size in [1, 5, 16] {
+        let mut state = ParserState::new(parser.clone());
+        let mut entries = Vec::new();
+        for chunk in input.as_bytes().chunks(size) {
+            let document = state.feed(std::str::from_utf8(chunk).unwrap()).unwrap();
+            assert!(document.diagnostics().is_empty(), "chunk size {size}");
+            entries.extend(document.entries().iter().cloned());
+        }
+        entries.extend(state.finish().unwrap().entries().iter().cloned());
+        assert_eq!(entries, whole.entries(), "chunk size {size}");
+    }
+}
+
+#[test]
+fn readers_parse_like_the_whole_input() {
+    let parser = Parser::new().capture_source();
+    let whole = parser.parse(INPUT).unwrap();
+    for capacity in [1, 3, 16, 4096] {
+        let reader = BufReader::with_capacity(capacity, INPUT.as_bytes());
+        let library = parser.parse_reader(reader).unwrap();
+        assert_eq!(library.entries(), whole.entries(), "capacity {capacity}");
+        assert_eq!(library.strings(), whole.strings());
+        assert_eq!(library.comments(), whole.comments());
+        assert!(library.comments()[0].source.is_some());
+    }
+
+    let broken = "@misc{a, title = {A}}\n@misc{b, title = {B}}\n@misc{c, title = }\n";
+    let Err(Error::ParseError { line, .. }) =
+        Library::from_reader(BufReader::with_capacity(4, broken.as_bytes()))
+    else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(line, 3);
+    assert!(Library::from_reader(&[b'@', 0xff][..]).is_err());
+
+    let input = "@article{a, abstract = {Line one\n@ line two}}\n@misc{b, title = {B}}\n";
+    let library = Library::from_reader(BufReader::with_capacity(2, input.as_bytes())).unwrap();
+    assert_eq!(library.entries().len(), 2);
+    assert_eq!(
+        library.entries()[0].get("abstract"),
+        Some("Line one\n@ line two")
+    );
+}
+
+#[test]
+fn readers_expand_strings_defined_before_use() {
+    let input =
+        "@string{j = {Old}}\n@misc{a, journal = j}\n@string{j = {New}}\n@misc{b, journal = j}\n";
+    let library = Library::from_reader(BufReader::with_capacity(1, input.as_bytes())).unwrap();
+    let journals: Vec<_> = library
+        .entries()
+        .iter()
+        .map(|entry| entry.get("journal"))
+        .collect();
+    assert_eq!(journals, [Some("Old"), Some("New")]);
+}
@@ -1,6 +1,6 @@
 use bibtex_parser::{
-    normalize_doi, parse_bibtex, parse_names, EntryType, Library, ParsedItem, ValidationError,
-    ValidationLevel, ValidationSeverity, Value,
+    normalize_doi, parse_bibtex, parse_names, EntryType, Field, Library, NormalizedValue,
+    ParsedItem, SortOptions, ValidationError, ValidationLevel, ValidationSeverity, Value,
 };
 use pretty_assertions::assert_eq;
 use std::borrow::Cow;
@@ -2271,6 +2271,24 @@ fn test_validation_zero_cost_when_not_used() {
     assert_eq!(library.entries().len(), 1000);
 }
 
+#[test]
+fn quoted_values_keep_quotes_inside_brace_groups() {
+    let input = r#"@misc{q,
+        title = "A {"} B {"nested {"} quote"} end",
+        note = "G\"odel",
+        howpublished = "open { brace"
+    }"#;
+    let library = Library::parse(input).unwrap();
+    let entry = &library.entries()[0];
+
+    assert_eq!(
+        entry.get("title"),
+        Some(r#"A {"} B {"nested {"} quote"} end"#)
+    );
+    assert_eq!(entry.get("note"), Some(r#"G\"odel"#));
+    assert_eq!(entry.get("howpublished"), Some("open { brace"));
+}
+
 // LATEX TO UNICODE CONVERSION TESTS
 
 #[cfg(feature = "latex_to_unicode")]
@@ -2906,3 +2924,471 @@ fn test_writer_preserves_block_order_by_default() {
     assert!(article < preamble);
     assert!(preamble < book);
 }
+
+#[test]
+fn test_canonical_string_ignores_layout_order_and_macros() {
+    let first = Library::parse(
+        r#"@string{ieee = "IEEE"}
+        % a comment
+        @Article{b, Year = 2020, Journal = ieee # " Trans.", TITLE = "A   long
+            title"}
+        @preamble{"\newcommand{\x}{x}"}
+        @BOOK{a, title = {Book}}"#,
+    )
+    .unwrap();
+    let second = Library::parse(
+        r#"@preamble{"\newcommand{\x}{x}"}
+        @book{a,title={Book}}
+        @article{b, title = {A long title}, journal = {IEEE Trans.}, year = 2020}"#,
+    )
+    .unwrap();
+
+    let canonical = first.canonical_string().unwrap();
+    assert_eq!(canonical, second.canonical_string().unwrap());
+    assert_eq!(
+        canonical,
+        "@preamble{{\\newcommand{\\x}{x}}}\n\n\
+         @book{a,\n  title = {Book}\n}\n\n\
+         @article{b,\n  journal = {IEEE Trans.},\n  title = {A long title},\n  year = 2020\n}\n"
+    );
+}
+
+#[test]
+fn test_model_types_work_in_std_collections() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let types: BTreeSet<_> = [
+        EntryType::Custom(Cow::Borrowed("zine")),
+        EntryType::Book,
+        EntryType::Article,
+        EntryType::Book,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        types.into_iter().collect::<Vec<_>>(),
+        vec![
+            EntryType::Article,
+            EntryType::Book,
+            EntryType::Custom(Cow::Borrowed("zine"))
+        ]
+    );
+
+    let fields: HashSet<_> = [
+        Field::new("year", Value::Number(2026)),
+        Field::new("year", Value::Number(2026)),
+        Field::new("year", Value::Literal(Cow::Borrowed("2026"))),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(fields.len(), 2);
+
+    let values: HashSet<_> = [
+        Value::Number(2026),
+        Value::Literal(Cow::Borrowed("2026")),
+        Value::Concat(
+            vec![
+                Value::Literal(Cow::Borrowed("20")),
+                Value::Literal(Cow::Borrowed("26")),
+            ]
+            .into_boxed_slice(),
+        ),
+        Value::Variable(Cow::Borrowed("year")),
+        Value::Variable(Cow::Borrowed("YEAR")),
+    ]
+    .into_iter()
+    .map(NormalizedValue::new)
+    .collect();
+    assert_eq!(values.len(), 2);
+    assert_eq!(
+        NormalizedValue::new(Value::Literal(Cow::Borrowed("Deep   Learning"))),
+        NormalizedValue::new(Value::Literal(Cow::Borrowed(" Deep Learning")))
+    );
+}
+
+#[test]
+fn test_library_composes_with_iterator_pipelines() {
+    let input = "@string{ieee = {IEEE}}\n@article{a, journal = ieee}\n@book{b, title = {B}}";
+    let mut library: Library = parse_bibtex(input).unwrap().into_iter().collect();
+    assert_eq!(library.entries().len(), 2);
+    assert_eq!(library.strings().len(), 1);
+
+    let keys: Vec<_> = (&library).into_iter().map(|entry| entry.key()).collect();
+    assert_eq!(keys, vec!["a", "b"]);
+
+    let extra = Library::parse("@misc{c, note = {C}}").unwrap();
+    library.extend(extra);
+    assert_eq!(library.entry_ids().len(), 3);
+
+    let books: Vec<_> = library
+        .into_iter()
+        .filter(|entry| *entry.entry_type() == EntryType::Book)
+        .collect();
+    assert_eq!(books.len(), 1);
+}
+
+#[test]
+fn test_library_view_filters_without_cloning() {
+    let input = r"
+        @string{ieee = {IEEE}}
+        @article{old, journal = ieee, year = 2019}
+        @article{new, journal = ieee, year = 2021}
+        @book{guide, title = {Guide}, year = 2022}
+    ";
+    let library = Library::parse(input).unwrap();
+    let recent = library.view(|entry| {
+        entry
+            .year()
+            .and_then(|year| year.parse::<u32>().ok())
+            .is_some_and(|year| year >= 2020)
+    });
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent.indices(), &[1, 2]);
+    assert!(std::ptr::eq(
+        recent.find_by_key("new").unwrap(),
+        library.find_by_key("new").unwrap()
+    ));
+
+    let articles = recent.view(|entry| *entry.entry_type() == EntryType::Article);
+    assert_eq!(
+        articles.iter().map(|entry| entry.key()).collect::<Vec<_>>(),
+        vec!["new"]
+    );
+    assert_eq!(recent.find_by_type("book").len(), 1);
+    assert_eq!(recent.find_by_field("journal", "IEEE").len(), 1);
+
+    let output = articles.to_bibtex().unwrap();
+    assert!(output.contains("@string{ieee"));
+    assert!(output.contains("@article{new"));
+    assert!(!output.contains("@article{old"));
+    assert!(!output.contains("@book{guide"));
+}
+
+#[test]
+fn test_expansion_depth_limit_stops_deep_string_chains() {
+    use bibtex_parser::{Error, Parser, DEFAULT_MAX_EXPANSION_DEPTH};
+    use std::fmt::Write as _;
+
+    let depth = 5_000;
+    let mut input = String::from("@string{s0 = {end}}\n");
+    for index in 1..=depth {
+        writeln!(input, "@string{{s{index} = s{}}}", index - 1).unwrap();
+    }
+    writeln!(input, "@misc{{deep, note = s{depth}}}").unwrap();
+
+    match Library::parse(&input) {
+        Err(Error::ExpansionDepthExceeded { chain, limit }) => {
+            assert_eq!(limit, DEFAULT_MAX_EXPANSION_DEPTH);
+            assert!(chain.starts_with(&format!("s{depth} -> ")));
+        }
+        other => panic!("expected depth error, got {other:?}"),
+    }
+
+    let library = Parser::new()
+        .max_expansion_depth(depth + 1)
+        .parse(&input)
+        .unwrap();
+    assert_eq!(library.entries()[0].get("note"), Some("end"));
+    assert_eq!(library.max_expansion_depth(), depth + 1);
+
+    assert!(matches!(
+        Parser::new()
+            .max_expansion_depth(depth - 1)
+            .expand_values()
+            .parse_document(&input),
+        Err(Error::ExpansionDepthExceeded { .. })
+    ));
+}
+
+#[test]
+fn test_expansion_length_limit_stops_doubling_strings() {
+    use bibtex_parser::{Error, Parser, DEFAULT_MAX_EXPANSION_LENGTH};
+    use std::fmt::Write as _;
+
+    let mut input = String::from("@string{a0 = {xxxxxxxxxxxxxxxx}}\n");
+    for index in 1..=30 {
+        writeln!(input, "@string{{a{index} = a{0} # a{0}}}", index - 1).unwrap();
+    }
+    writeln!(input, "@misc{{bomb, title = a30}}").unwrap();
+    assert!(input.len() < 1024);
+
+    match Library::parse(&input) {
+        Err(Error::ExpansionLengthExceeded { limit }) => {
+            assert_eq!(limit, DEFAULT_MAX_EXPANSION_LENGTH);
+        }
+        other => panic!("expected length error, got {other:?}"),
+    }
+    assert!(matches!(
+        Parser::new()
+            .expand_values()
+            .max_expansion_length(1 << 20)
+            .parse_document(&input),
+        Err(Error::ExpansionLengthExceeded { limit: 1_048_576 })
+    ));
+
+    let fits = input.replace("title = a30", "title = a10");
+    let library = Parser::new()
+        .max_expansion_length(16 << 10)
+        .parse(&fits)
+        .unwrap();
+    assert_eq!(library.entries()[0].get("title").unwrap().len(), 16 << 10);
+    assert_eq!(library.max_expansion_length(), 16 << 10);
+    let value = Value::Variable(Cow::Borrowed("a20"));
+    assert_eq!(library.expand_value_partial(&value), value);
+
+    let document = Parser::new()
+        .expand_values()
+        .max_expansion_length(16 << 10)
+        .parse_document(&fits.replace("title = a10", "title = a11"))
+        .unwrap_err();
+    assert!(matches!(document, Error::ExpansionLengthExceeded { .. }));
+}
+
+#[test]
+fn test_partial_expansion_follows_deep_chains_without_recursion() {
+    use bibtex_parser::Parser;
+    use std::fmt::Write as _;
+
+    let depth = 200_000;
+    let mut input = String::from("@string{s0 = {end}}\n");
+    for index in 1..=depth {
+        writeln!(input, "@string{{s{index} = s{} # {{}}}}", index - 1).unwrap();
+    }
+    let library = Parser::new()
+        .max_expansion_depth(10_000_000)
+        .parse(&input)
+        .unwrap();
+    let value = Value::Variable(Cow::Owned(format!("s{depth}")));
+    assert_eq!(
+        library.expand_value_partial(&value),
+        Value::Literal(Cow::Borrowed("end"))
+    );
+
+    let shallow = Parser::new().max_expansion_depth(3).parse(&input).unwrap();
+    assert_eq!(
+        shallow.expand_value_partial(&value),
+        Value::Concat(
+            vec![
+                Value::Variable(Cow::Owned(format!("s{}", depth - 3))),
+                Value::Literal(Cow::Borrowed("")),
+            ]
+            .into_boxed_slice()
+        )
+    );
+}
+
+#[test]
+fn test_reference_expansion_reports_cycles() {
+    let mut library = Library::new();
+    library.add_string("a", Value::Variable(Cow::Borrowed("b")));
+    library.add_string("b", Value::Variable(Cow::Borrowed("a")));
+    let value = Value::Variable(Cow::Borrowed("a"));
+
+    assert!(matches!(
+        library.expand_value_ref(&value),
+        Err(bibtex_parser::Error::CircularReference(chain)) if chain == "a -> b -> a"
+    ));
+    assert!(library.get_expanded_string(&value).is_err());
+}
+
+#[test]
+fn test_timeline_counts_entries_per_year_and_type() {
+    let library = Library::parse(
+        r"
+        @article{a, year = 2020}
+        @article{b, date = {2020-05-01}}
+        @book{c, year = 2020}
+        @inproceedings{d, year = 2018}
+        @article{e, year = {in press}}
+        @misc{f, title = {Undated}}
+        @xdata{shared, publisher = {P}}
+        @set{s, entryset = {a,b}}
+        ",
+    )
+    .unwrap();
+
+    let timeline = library.timeline();
+    assert_eq!(timeline.span(), Some((2018, 2020)));
+    assert_eq!(
+        timeline
+            .years
+            .iter()
+            .map(|counts| (counts.year, counts.total))
+            .collect::<Vec<_>>(),
+        [(2018, 1), (2020, 3)]
+    );
+    let year = timeline.year(2020).unwrap();
+    assert_eq!(year.by_type["article"], 2);
+    assert_eq!(year.by_type["book"], 1);
+    assert!(timeline.year(2019).is_none());
+    assert_eq!((timeline.pending, timeline.undated), (1, 1));
+}
+
+#[test]
+fn test_coauthor_graph_weights_shared_entries() {
+    let library = Library::parse(
+        r"
+        @article{a, author = {Donald E. Knuth and Leslie Lamport}}
+        @article{b, author = {Knuth, D. E. and Lamport, Leslie and Andrew Yao}}
+        @article{c, author = {Andrew Yao and Andrew Yao and others}}
+        @misc{d, title = {No authors}}
+        ",
+    )
+    .unwrap();
+
+    let graph = library.coauthor_graph();
+    let names = graph
+        .authors()
+        .iter()
+        .map(|author| (author.identity.as_str(), author.entries))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [("knuth, d. e.", 2), ("lamport, l.", 2), ("yao, a.", 2)]
+    );
+    assert_eq!(graph.authors()[0].name, "Donald E. Knuth");
+
+    let knuth = graph.index_of("knuth, d. e.").unwrap();
+    let lamport = graph.index_of("lamport, l.").unwrap();
+    let yao = graph.index_of("yao, a.").unwrap();
+    assert_eq!(graph.weight(knuth, lamport), 2);
+    assert_eq!(graph.weight(lamport, knuth), 2);
+    assert_eq!(graph.weight(knuth, yao), 1);
+    assert_eq!(graph.neighbors(yao).collect::<Vec<_>>(), [(0, 1), (1, 1)]);
+    assert_eq!(graph.edge_count(), 3);
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        [(0, 1, 2), (0, 2, 1), (1, 2, 1)]
+    );
+}
+
+#[test]
+fn test_venue_clusters_propose_canonical_spellings() {
+    let mut library = Library::parse(
+        r"
+        @string{jacm = {Journal of the ACM}}
+        @article{a, journal = {Proc. of the Intl. Conf. on Machine Learning}}
+        @inproceedings{b, booktitle = {Proceedings of the International Conference on Machine Learning}}
+        @inproceedings{c, booktitle = {Proceedings of the 37th International Conference on Machine Learning, 2020}}
+        @inproceedings{d, booktitle = {Proceedings of the International Conference on Machine Learning}}
+        @article{e, journal = {J. ACM}}
+        @article{f, journal = jacm}
+        @article{g, journal = {Nature}}
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        bibtex_parser::venue_key("Proc. Intl. Conf. Machine Learning (ICML 2020)"),
+        "proceedings international conference machine learning icml 2020"
+    );
+    assert_ne!(
+        bibtex_parser::venue_key("Proc. 37th Intl. Conf. Machine Learning"),
+        bibtex_parser::venue_key("Proc. 38th Intl. Conf. Machine Learning")
+    );
+
+    let clusters = library.venue_clusters();
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[1].canonical, "Journal of the ACM");
+    assert_eq!(clusters[1].entries, [4, 5]);
+    let icml = &clusters[0];
+    assert_eq!(
+        icml.canonical,
+        "Proceedings of the International Conference on Machine Learning"
+    );
+    assert_eq!(icml.entries, [0, 1, 3]);
+    assert_eq!(icml.variants.len(), 2);
+    assert_eq!(icml.variants[0].1, 2);
+
+    assert_eq!(library.apply_venue_clusters(&clusters), 2);
+    assert_eq!(
+        library.entries()[0].get("journal"),
+        Some("Proceedings of the International Conference on Machine Learning")
+    );
+    assert_eq!(
+        library.entries()[2].get("booktitle"),
+        Some("Proceedings of the 37th International Conference on Machine Learning, 2020")
+    );
+    assert!(library.venue_clusters().is_empty());
+}
+
+#[test]
+fn pages_and_samples_borrow_entries_without_reordering() {
+    let input: String = (0..50)
+        .map(|index| {
+            format!(
+                "@misc{{k{:02}, year = {}}}\n",
+                (index * 37) % 50,
+                2000 + index % 7
+            )
+        })
+        .collect();
+    let library = Library::parse(&input).unwrap();
+    let keys = |view: &bibtex_parser::LibraryView<'_, '_>| -> Vec<String> {
+        view.iter().map(|entry| entry.key().to_string()).collect()
+    };
+
+    let plain = library.page(45, 10, SortOptions::default());
+    assert_eq!(plain.indices(), [45, 46, 47, 48, 49]);
+    assert!(library.page(60, 10, SortOptions::default()).is_empty());
+
+    let options = SortOptions {
+        entries_by_year: true,
+        entries_by_key: true,
+        ..SortOptions::default()
+    };
+    let mut sorted = library.clone();
+    sorted.sort(options);
+    let all: Vec<String> = sorted.iter().map(|entry| entry.key().to_string()).collect();
+    for (offset, limit) in [(0, 7), (13, 9), (44, 20), (0, 50)] {
+        let end = (offset + limit).min(all.len());
+        assert_eq!(
+            keys(&library.page(offset, limit, options)),
+            all[offset..end]
+        );
+    }
+    assert_eq!(library.entries()[0].key(), "k00");
+    assert_eq!(library.entries()[1].key(), "k37");
+
+    let sample = library.sample(10, 7);
+    assert_eq!(sample.len(), 10);
+    assert_eq!(sample.indices(), library.sample(10, 7).indices());
+    assert_ne!(sample.indices(), library.sample(10, 8).indices());
+    assert!(sample.indices().windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(library.sample(80, 1).len(), 50);
+}
+
+#[test]
+fn iter_sorted_filters_and_sorts_without_reordering() {
+    let input = "@article{c, year = {2001}}\n\
+                 @book{a, year = {1999}}\n\
+                 @article{b, year = {1999}}\n\
+                 @article{d, year = {2001}}\n";
+    let library = Library::parse(input).unwrap();
+    let by_year = SortOptions {
+        entries_by_year: true,
+        ..SortOptions::default()
+    };
+    let keys: Vec<&str> = library
+        .iter_sorted(by_year)
+        .map(|entry| entry.key())
+        .collect();
+    assert_eq!(keys, ["a", "b", "c", "d"]);
+
+    let articles = library.view(|entry| entry.ty.canonical_name() == "article");
+    let by_key = SortOptions {
+        entries_by_key: true,
+        ..SortOptions::default()
+    };
+    let sorted = articles.iter_sorted(by_key);
+    assert_eq!(sorted.len(), 3);
+    let keys: Vec<&str> = sorted.rev().map(|entry| entry.key()).collect();
+    assert_eq!(keys, ["d", "c", "b"]);
+
+    let unsorted: Vec<&str> = articles
+        .iter_sorted(SortOptions::default())
+        .map(|entry| entry.key())
+        .collect();
+    assert_eq!(unsorted, ["c", "b", "d"]);
+    assert_eq!(library.entries()[0].key(), "c");
+}
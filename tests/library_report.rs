@@ -0,0 +1,127 @@
+use bibtex_parser::{Library, Parser};
+
+const REPORT_INPUT: &str = r#"
+@string{acm = "ACM"}
+@string{ieee = "IEEE"}
+@string{stale = "Never used"}
+
+@article{dup, author = "A. Author", title = "One", journal = acm, year = 2020, doi = "10.1/x"}
+@article{dup, author = "B. Author", title = "Two", journal = ieee, year = 2021, doi = "https://doi.org/10.1/X"}
+@article{partial, title = "No author", year = "20xx", url = "https://example.com"}
+@inproceedings{child, author = "C. Author", title = "Child", booktitle = "Proc", year = 3024, crossref = "missing-parent"}
+"#;
+
+#[test]
+fn report_collects_library_health_issues() {
+    let library = Library::parse(REPORT_INPUT).unwrap();
+    let report = library.report();
+
+    assert_eq!(report.total_entries, 4);
+    assert_eq!(report.duplicate_keys, ["dup"]);
+    assert_eq!(report.duplicate_dois.len(), 1);
+    assert_eq!(report.duplicate_dois[0].0, "10.1/x");
+
+    let (_, entry, missing) = &report.missing_required_fields[0];
+    assert_eq!(entry.key(), "partial");
+    assert_eq!(missing, &["author", "journal"]);
+
+    let missing_links = report
+        .missing_links
+        .iter()
+        .map(|(_, entry)| entry.key())
+        .collect::<Vec<_>>();
+    assert_eq!(missing_links, ["child"]);
+
+    let years = report
+        .suspicious_years
+        .iter()
+        .map(|(_, entry, year)| (entry.key(), year.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(years, [("partial", "20xx"), ("child", "3024")]);
+
+    assert_eq!(report.orphaned_crossrefs[0].2, "missing-parent");
+    assert_eq!(report.unused_strings.len(), 1);
+    assert_eq!(report.unused_strings[0].name, "stale");
+    assert!(!report.is_clean());
+    assert_eq!(report.total_issues(), 8);
+}
+
+#[test]
+fn report_tracks_string_usage_across_parse_paths() {
+    let late = "@article{a, journal = j # suffix}\n@string{j = \"J\"}\n@string{suffix = base}\n@string{base = \"B\"}\n@string{unused = \"U\"}";
+    for library in [
+        Library::parse(late).unwrap(),
+        Parser::new().capture_source().parse(late).unwrap(),
+        Parser::new().tolerant().parse(late).unwrap(),
+    ] {
+        let unused = library
+            .report()
+            .unused_strings
+            .iter()
+            .map(|definition| definition.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(unused, ["unused"]);
+    }
+
+    let mut library = Library::new();
+    library.add_string("used", bibtex_parser::Value::Variable("nested".into()));
+    library.add_string("nested", bibtex_parser::Value::from_plain_string("x"));
+    library.add_preamble(bibtex_parser::Value::Variable("used".into()));
+    assert!(library.report().unused_strings.is_empty());
+}
+
+#[test]
+fn report_renders_markdown_sections_only_for_found_issues() {
+    let library = Library::parse(REPORT_INPUT).unwrap();
+    let markdown = library.report().to_markdown();
+
+    assert!(markdown.starts_with("# Library report\n"));
+    assert!(markdown.contains("4 entries, 8 issues."));
+    assert!(markdown.contains("## Duplicate keys (1)\n\n- `dup`"));
+    assert!(markdown.contains("- `partial`: author, journal"));
+    assert!(markdown.contains("- `child` -> `missing-parent`"));
+    assert!(markdown.contains("## Unused strings (1)\n\n- `stale`"));
+
+    let clean =
+        Library::parse(r#"@misc{ok, title = "Fine", year = 2024, url = "https://example.com"}"#)
+            .unwrap();
+    let report = clean.report();
+    assert!(report.is_clean());
+    assert!(!report.to_markdown().contains("##"));
+}
+
+#[test]
+fn unused_and_undefined_strings_are_reported_without_expansion() {
+    let input = r#"
+@string{used = "Used"}
+@string{chain = used # " chain"}
+@string{spare = "Spare"}
+@article{a, journal = chain, month = jan, note = typo}
+@preamble{"\newcommand" # missing_macro}
+"#;
+    let document = Parser::new().parse_document(input).unwrap();
+
+    let unused = document
+        .unused_strings()
+        .iter()
+        .map(|definition| definition.name.as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(unused, ["spare"]);
+    assert_eq!(document.undefined_variables(), ["missing_macro", "typo"]);
+    assert_eq!(
+        document.library().undefined_variables(),
+        ["missing_macro", "typo"]
+    );
+
+    let library = Library::parse(
+        "@string{used = \"Used\"}\n@string{spare = \"Spare\"}\n@misc{a, note = used, month = jan}",
+    )
+    .unwrap();
+    let unused = library
+        .unused_strings()
+        .iter()
+        .map(|definition| definition.name.as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(unused, ["spare"]);
+    assert!(library.undefined_variables().is_empty());
+}
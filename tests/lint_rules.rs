@@ -0,0 +1,169 @@
+use bibtex_parser::{
+    Baseline, DiagnosticCode, DiagnosticSeverity, DiagnosticTarget, Diagnostics, Error,
+    LintOptions, Parser, SarifReport, ValidationLevel,
+};
+
+const SOURCE: &str = r"@article{kept,
+  author = {Ada Lovelace},
+  title = {Notes},
+  journal = {Memoirs},
+  year = {1843},
+  doi = {not a doi},
+  note = {},
+}
+
+% bibtex-lint: ignore invalid-doi
+@article{quiet,
+  author = {Ada Lovelace},
+  title = {Notes},
+  journal = {Memoirs},
+  year = {1843},
+  doi = {not a doi},
+}
+
+@article{silenced,
+  author = {Ada Lovelace},
+  title = {Notes},
+  journal = {Memoirs},
+  year = {1843},
+  doi = {not a doi}, % bibtex-lint: ignore
+}
+";
+
+fn codes(diagnostics: &[bibtex_parser::Diagnostic]) -> Vec<&str> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code.as_str())
+        .collect()
+}
+
+#[test]
+fn lint_locates_findings_and_honors_inline_suppression() {
+    let document = Parser::new().parse_document(SOURCE).unwrap();
+    let diagnostics = document.lint(&LintOptions::new(ValidationLevel::Strict));
+
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.message.starts_with("kept: ")));
+    let doi = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.code == DiagnosticCode::INVALID_DOI)
+        .unwrap();
+    assert_eq!(doi.target, DiagnosticTarget::Field { entry: 0, field: 4 });
+    let span = doi.source.unwrap();
+    assert_eq!(&SOURCE[span.byte_start..span.byte_end], "doi");
+    assert!(codes(&diagnostics).contains(&"empty-field"));
+}
+
+#[test]
+fn bare_suppression_silences_everything_in_either_order() {
+    let source = r"% bibtex-lint: ignore invalid-doi
+@article{bare_after,
+  author = {Ada Lovelace},
+  title = {Notes},
+  journal = {Memoirs},
+  year = {1843},
+  doi = {not a doi},
+  note = {}, % bibtex-lint: ignore
+}
+
+% bibtex-lint: ignore
+@article{bare_before,
+  author = {Ada Lovelace},
+  title = {Notes},
+  journal = {Memoirs},
+  year = {1843},
+  doi = {not a doi},
+  note = {}, % bibtex-lint: ignore invalid-doi
+}
+";
+    let document = Parser::new().parse_document(source).unwrap();
+    let diagnostics = document.lint(&LintOptions::new(ValidationLevel::Strict));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn lint_rules_suppress_and_override_severity() {
+    let document = Parser::new().parse_document(SOURCE).unwrap();
+    let options = LintOptions::new(ValidationLevel::Strict)
+        .suppress("empty-field")
+        .severity("invalid-doi", DiagnosticSeverity::Info);
+    let diagnostics = document.lint(&options);
+
+    assert!(!codes(&diagnostics).contains(&"empty-field"));
+    assert!(diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code == DiagnosticCode::INVALID_DOI)
+        .all(|diagnostic| diagnostic.severity == DiagnosticSeverity::Info));
+}
+
+#[test]
+fn baseline_reports_only_new_findings() {
+    let options = LintOptions::new(ValidationLevel::Strict);
+    let before = Parser::new().parse_document(SOURCE).unwrap();
+    let recorded = before.lint(&options);
+    let baseline =
+        Baseline::parse(&Baseline::from_diagnostics(&before, &recorded).to_string()).unwrap();
+    assert_eq!(baseline.len(), recorded.len());
+    assert!(baseline.new_diagnostics(&before, &recorded).is_empty());
+
+    let edited = format!("@misc{{added, year = {{soon}}}}\n\n{SOURCE}");
+    let after = Parser::new().parse_document(&edited).unwrap();
+    let diagnostics = after.lint(&options);
+    let new = baseline.new_diagnostics(&after, &diagnostics);
+    assert!(!new.is_empty());
+    assert!(new
+        .iter()
+        .all(|diagnostic| diagnostic.message.starts_with("added: ")));
+
+    assert!(matches!(
+        Baseline::parse("kept\tinvalid-doi\n"),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn sarif_report_locates_results_in_the_artifact() {
+    let document = Parser::new().parse_document(SOURCE).unwrap();
+    let diagnostics = document.lint(
+        &LintOptions::new(ValidationLevel::Strict)
+            .severity("empty-field", DiagnosticSeverity::Info),
+    );
+    let report = SarifReport::new().file("refs/library.bib", &diagnostics);
+    assert_eq!(report.len(), diagnostics.len());
+
+    let sarif = report.to_string();
+    assert!(sarif.contains("\"version\": \"2.1.0\""), "{sarif}");
+    assert!(sarif.contains("\"name\": \"bibtex-parser\""), "{sarif}");
+    assert!(sarif.contains("\"uri\": \"refs/library.bib\""), "{sarif}");
+    assert!(sarif.contains("\"ruleId\": \"invalid-doi\""), "{sarif}");
+    assert!(sarif.contains("\"level\": \"note\""), "{sarif}");
+    assert!(sarif.contains("\"startLine\": 6"), "{sarif}");
+    assert!(SarifReport::new().file("empty.bib", &[]).is_empty());
+}
+
+#[test]
+fn diagnostics_json_carries_codes_spans_and_fixes() {
+    let source = format!("{SOURCE}\n@misc{{broken, title = {{x}}");
+    let document = Parser::new().tolerant().parse_document(&source).unwrap();
+    let diagnostics = Diagnostics::collect(&document, &LintOptions::new(ValidationLevel::Strict));
+    assert!(diagnostics.len() > document.diagnostics().len());
+
+    let empty = diagnostics
+        .iter()
+        .position(|diagnostic| diagnostic.code == DiagnosticCode::EMPTY_FIELD)
+        .unwrap();
+    assert_eq!(diagnostics.fixes(empty).len(), 1);
+    assert!(diagnostics.fixes(empty)[0].is_preferred);
+
+    let json = diagnostics.to_json();
+    assert!(json.contains("\"version\": 1"), "{json}");
+    assert!(json.contains("\"code\": \"empty-field\""), "{json}");
+    assert!(json.contains("\"kind\": \"field\""), "{json}");
+    assert!(
+        json.contains("\"title\": \"Remove empty field `note`\""),
+        "{json}"
+    );
+    assert!(json.contains("\"new_text\": \"\""), "{json}");
+    assert!(json.contains("\"line\": 7"), "{json}");
+}
@@ -1,6 +1,8 @@
 use bibtex_parser::{
-    document_to_string, EntryType, Field, Library, Parser, RawWriteMode, TrailingComma, Value,
-    Writer, WriterConfig,
+    document_to_string, format_min_diff, to_string_entry, to_string_preamble, to_string_string_def,
+    BlockSpacing, DelimiterStyle, DuplicateFieldPolicy, Entry, EntryType, ExpansionPolicy, Field,
+    FieldOrder, FieldTruncation, HeaderPlacement, Library, ParsedDocument, ParsedEntry, Parser,
+    RawWriteMode, SectionGrouping, TabularStyle, TrailingComma, Value, Writer, WriterConfig,
 };
 use std::borrow::Cow;
 
@@ -70,11 +72,9 @@ fn normalized_document_output_is_explicitly_configured() {
     let input = "@article{paper,title=\"Fast\",year=2026}";
     let document = Parser::new().preserve_raw().parse_document(input).unwrap();
     let mut output = Vec::new();
-    let config = WriterConfig {
-        raw_write_mode: RawWriteMode::Normalize,
-        trailing_comma: TrailingComma::Always,
-        ..WriterConfig::default()
-    };
+    let config = WriterConfig::default()
+        .raw_write_mode(RawWriteMode::Normalize)
+        .trailing_comma(TrailingComma::Always);
 
     Writer::with_config(&mut output, config)
         .write_document(&document)
@@ -115,3 +115,558 @@ fn library_writer_still_writes_programmatic_entries() {
     let output = bibtex_parser::to_string(&library).unwrap();
     assert!(output.contains("@article{paper,"));
 }
+
+#[test]
+fn streaming_writer_separates_blocks_and_keeps_header_first() {
+    let library = Library::parse("@article{a, title = {A}}\n@book{b, title = {B}}").unwrap();
+    let mut stream = Writer::new(Vec::new()).begin();
+
+    stream
+        .write_string("ieee", &Value::from_plain_string("IEEE"))
+        .unwrap();
+    stream
+        .write_preamble(&Value::from_plain_string("\\relax"))
+        .unwrap();
+    for entry in library.entries() {
+        stream.write_entry(entry).unwrap();
+    }
+    let error = stream
+        .write_string("late", &Value::from_plain_string("x"))
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(stream.entries_written(), 2);
+
+    let output = String::from_utf8(stream.finish().unwrap()).unwrap();
+    assert_eq!(
+        output,
+        "@string{ieee = {IEEE}}\n\n@preamble{{\\relax}}\n\n\
+         @article{a,\n  title = {A}\n}\n\n@book{b,\n  title = {B}\n}\n"
+    );
+    assert_eq!(Library::parse(&output).unwrap().entries().len(), 2);
+}
+
+#[test]
+fn header_placement_moves_strings_and_preambles() {
+    let input = "@comment{Section A}\n\
+                 @article{a, journal = jn}\n\
+                 @string{unused = {U}}\n\
+                 @string{pub = {Pub}}\n\
+                 @string{jn = pub # { Journal}}\n\
+                 @preamble{{\\relax}}\n\
+                 @book{b, publisher = pub}";
+    let library = Parser::new()
+        .expansion_policy(ExpansionPolicy::preserve_all())
+        .parse(input)
+        .unwrap();
+    let write = |header_placement| {
+        let mut output = Vec::new();
+        let config = WriterConfig::default().header_placement(header_placement);
+        Writer::with_config(&mut output, config)
+            .write_library(&library)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(Library::parse(&output).unwrap().entries().len(), 2);
+        output
+            .lines()
+            .filter(|line| line.starts_with('@'))
+            .map(|line| line.split(['{', ',']).take(2).collect::<Vec<_>>().join(":"))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        write(HeaderPlacement::Preserve),
+        [
+            "@comment:Section A}",
+            "@article:a",
+            "@string:unused = ",
+            "@string:pub = ",
+            "@string:jn = pub # ",
+            "@preamble:",
+            "@book:b"
+        ]
+    );
+    assert_eq!(
+        write(HeaderPlacement::Top),
+        [
+            "@preamble:",
+            "@string:unused = ",
+            "@string:pub = ",
+            "@string:jn = pub # ",
+            "@comment:Section A}",
+            "@article:a",
+            "@book:b"
+        ]
+    );
+    assert_eq!(
+        write(HeaderPlacement::BeforeFirstUse),
+        [
+            "@preamble:",
+            "@string:unused = ",
+            "@comment:Section A}",
+            "@string:pub = ",
+            "@string:jn = pub # ",
+            "@article:a",
+            "@book:b"
+        ]
+    );
+}
+
+#[test]
+fn sorted_output_can_group_entries_under_section_comments() {
+    let library = Library::parse(
+        "@book{z, year = 2024}\n@article{b, year = 2023}\n@misc{m, note = {N}}\n@article{a, year = 2024}",
+    )
+    .unwrap();
+    let write = |section_headers| {
+        let mut output = Vec::new();
+        let config = WriterConfig::default()
+            .sort_entries(true)
+            .section_headers(section_headers);
+        Writer::with_config(&mut output, config)
+            .write_library(&library)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let by_type = write(SectionGrouping::EntryType);
+    assert_eq!(
+        by_type,
+        "%% Articles\n@article{a,\n  year = 2024\n}\n\n@article{b,\n  year = 2023\n}\n\n\
+         %% Books\n@book{z,\n  year = 2024\n}\n\n\
+         %% Miscellaneous\n@misc{m,\n  note = {N}\n}\n"
+    );
+    assert_eq!(Library::parse(&by_type).unwrap().entries().len(), 4);
+
+    let headers = write(SectionGrouping::Year)
+        .lines()
+        .filter(|line| line.starts_with("%%"))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(headers, ["%% 2023", "%% 2024", "%% No year"]);
+}
+
+#[test]
+fn sorted_sections_keep_leading_comments_after_the_header() {
+    let input = "% Needs a DOI.\n@book{z, year = 2024}\n@article{a, year = 2024}";
+    let config = WriterConfig::default()
+        .sort_entries(true)
+        .section_headers(SectionGrouping::EntryType);
+    let write = |library: &Library| {
+        let mut output = Vec::new();
+        Writer::with_config(&mut output, config.clone())
+            .write_library(library)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let sorted = write(&Library::parse(input).unwrap());
+    assert_eq!(
+        sorted,
+        "%% Articles\n@article{a,\n  year = 2024\n}\n\n\
+         %% Books\n% Needs a DOI.\n\n@book{z,\n  year = 2024\n}\n"
+    );
+    assert_eq!(write(&Library::parse(&sorted).unwrap()), sorted);
+}
+
+#[test]
+fn duplicate_field_policy_warns_errors_or_keeps_first() {
+    let input = "@article{dup,\n  title = {First},\n  TITLE = {Second},\n  year = 2024\n}";
+    let library = Library::parse(input).unwrap();
+    let document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    let config = |duplicate_fields| WriterConfig::default().duplicate_fields(duplicate_fields);
+
+    let mut writer = Writer::with_config(Vec::new(), config(DuplicateFieldPolicy::Warn));
+    writer.write_library(&library).unwrap();
+    assert_eq!(
+        writer.duplicate_fields(),
+        &[("dup".to_string(), "TITLE".to_string())]
+    );
+    assert!(String::from_utf8(writer.into_inner())
+        .unwrap()
+        .contains("Second"));
+
+    let error = Writer::with_config(Vec::new(), config(DuplicateFieldPolicy::Error))
+        .write_document(&document)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("TITLE"));
+
+    for keep_first in [
+        {
+            let mut writer =
+                Writer::with_config(Vec::new(), config(DuplicateFieldPolicy::KeepFirst));
+            writer.write_library(&library).unwrap();
+            writer.into_inner()
+        },
+        {
+            let mut writer =
+                Writer::with_config(Vec::new(), config(DuplicateFieldPolicy::KeepFirst));
+            writer.write_document(&document).unwrap();
+            writer.into_inner()
+        },
+    ] {
+        assert_eq!(
+            String::from_utf8(keep_first).unwrap(),
+            "@article{dup,\n  title = {First},\n  year = 2024\n}\n"
+        );
+    }
+}
+
+#[test]
+fn value_delimiter_controls_literal_quoting() {
+    let input = "@article{a,\n  title = \"Quoted\",\n  note = {Braced}\n}";
+    let document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    let write_document = |value_delimiter| {
+        let config = WriterConfig::default()
+            .raw_write_mode(RawWriteMode::Normalize)
+            .value_delimiter(value_delimiter);
+        let mut output = Vec::new();
+        Writer::with_config(&mut output, config)
+            .write_document(&document)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert_eq!(
+        write_document(DelimiterStyle::Braces),
+        "@article{a,\n  title = {Quoted},\n  note = {Braced}\n}\n"
+    );
+    assert_eq!(
+        write_document(DelimiterStyle::PreserveOriginal),
+        "@article{a,\n  title = \"Quoted\",\n  note = {Braced}\n}\n"
+    );
+    assert_eq!(
+        write_document(DelimiterStyle::Quotes),
+        "@article{a,\n  title = \"Quoted\",\n  note = \"Braced\"\n}\n"
+    );
+
+    let mut entry = Library::parse("@misc{q, title = {A \"B\" {\"C\"} G\\\"odel \\\"{o}}}")
+        .unwrap()
+        .entries()[0]
+        .clone();
+    entry.set("pages", Value::Number(7));
+    let config = WriterConfig::default().value_delimiter(DelimiterStyle::Quotes);
+    let mut output = Vec::new();
+    Writer::with_config(&mut output, config)
+        .write_entry(&entry)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(
+        output,
+        "@misc{q,\n  title = \"A {\"}B{\"} {\"C\"} G{\\\"o}del {\\\"{o}}\",\n  pages = 7\n}\n"
+    );
+    assert_eq!(
+        Library::parse(&output).unwrap().entries()[0].get("title"),
+        Some("A {\"}B{\"} {\"C\"} G{\\\"o}del {\\\"{o}}")
+    );
+}
+
+#[test]
+fn single_item_helpers_serialize_without_a_library() {
+    let library = Library::parse("@article{a, title = {A}, year = 2026}").unwrap();
+
+    assert_eq!(
+        to_string_entry(&library.entries()[0]).unwrap(),
+        "@article{a,\n  title = {A},\n  year = 2026\n}\n"
+    );
+    assert_eq!(
+        to_string_string_def("ieee", &Value::from_plain_string("IEEE")).unwrap(),
+        "@string{ieee = {IEEE}}\n"
+    );
+    assert_eq!(
+        to_string_preamble(&Value::from_plain_string("\\relax")).unwrap(),
+        "@preamble{{\\relax}}\n"
+    );
+}
+
+#[test]
+fn long_abstracts_and_notes_are_truncated_at_word_boundaries() {
+    let input = "@article{a,\n  title = {Short},\n  abstract = {Trees are {Very Large Plants} that grow, slowly, over years},\n  note = {Fine}\n}\n";
+    let limit = FieldTruncation::new(40).ellipsis("\\ldots{}");
+    assert_eq!(
+        limit.truncate("Trees are {Very Large Plants} that grow, slowly"),
+        Some("Trees are {Very Large Plants}\\ldots{}".to_string())
+    );
+    assert_eq!(limit.truncate("Fine"), None);
+
+    let document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    let mut output = Vec::new();
+    Writer::with_config(
+        &mut output,
+        WriterConfig::default().long_fields(FieldTruncation::new(20)),
+    )
+    .write_document(&document)
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("abstract = {Trees are...}"), "{output}");
+    assert!(output.contains("note = {Fine}"));
+
+    let mut library = Library::parse(input).unwrap();
+    let limit = FieldTruncation::new(20).fields(["Abstract"]).drop_fields();
+    assert_eq!(library.truncate_long_fields(&limit), 1);
+    assert!(!library.entries()[0].has_field("abstract"));
+    assert_eq!(library.entries()[0].get("note"), Some("Fine"));
+}
+
+#[test]
+fn long_fields_without_outer_whitespace_are_still_truncated() {
+    let limit = FieldTruncation::new(20);
+    assert_eq!(
+        limit.truncate("{A fully braced abstract that runs long}"),
+        Some("{A fully braced}...".to_string())
+    );
+
+    let limit = FieldTruncation::new(10);
+    assert_eq!(
+        limit.truncate("Supercalifragilistic"),
+        Some("Superca...".to_string())
+    );
+    assert_eq!(
+        limit.truncate("{Supercalifragilistic}"),
+        Some("{Super}...".to_string())
+    );
+    assert_eq!(
+        limit.truncate("Superc\\\"alifragilistic"),
+        Some("Superc...".to_string())
+    );
+}
+
+#[test]
+fn entry_type_and_field_name_casing_survive_structured_writes() {
+    let input = "@InProceedings{paper,\n  Title = {X},\n  title = {Y}\n}";
+    let document = Parser::new()
+        .capture_source()
+        .parse_document(input)
+        .unwrap();
+    let entry = &document.entries()[0];
+    assert_eq!(entry.ty, EntryType::InProceedings);
+    assert_eq!(entry.entry_type_spelling.as_deref(), Some("InProceedings"));
+    assert_eq!(entry.fields[0].name, "Title");
+
+    let keep_first = WriterConfig::default().duplicate_fields(DuplicateFieldPolicy::KeepFirst);
+    let mut writer = Writer::with_config(Vec::new(), keep_first.clone());
+    writer.write_document(&document).unwrap();
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        "@InProceedings{paper,\n  Title = {X}\n}\n"
+    );
+
+    let normalize = keep_first.raw_write_mode(RawWriteMode::Normalize);
+    let mut writer = Writer::with_config(Vec::new(), normalize);
+    writer.write_document(&document).unwrap();
+    assert!(String::from_utf8(writer.into_inner())
+        .unwrap()
+        .starts_with("@inproceedings{paper,"));
+
+    let lower = Parser::new()
+        .capture_source()
+        .parse_document("@article{a, title = {T}}")
+        .unwrap();
+    assert_eq!(lower.entries()[0].entry_type_spelling, None);
+}
+
+#[test]
+fn trailing_field_comments_survive_normalized_writes_and_field_removal() {
+    let input = "@article{paper,\n  title = {Fast}, % check casing\n  file = {drop.pdf}, % local copy\n  year = 2026 % final\n}";
+    let mut document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    assert_eq!(document.entries_mut()[0].remove_field("file"), 1);
+
+    let patched = document_to_string(&document).unwrap();
+    assert!(patched.contains("title = {Fast}, % check casing\n"));
+    assert!(!patched.contains("local copy"));
+    assert!(patched.ends_with("year = 2026 % final\n}"));
+
+    let mut output = Vec::new();
+    let config = WriterConfig::default().raw_write_mode(RawWriteMode::Normalize);
+    Writer::with_config(&mut output, config)
+        .write_document(&document)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("  title = {Fast}, % check casing\n"));
+    assert!(output.contains("  year = 2026 % final\n}"));
+
+    let reparsed = Parser::new().parse_document(&output).unwrap();
+    assert_eq!(
+        reparsed.entries()[0].fields[1].comment.as_deref(),
+        Some("% final")
+    );
+}
+
+#[test]
+fn blank_lines_between_entries_replace_the_separator() {
+    let library = Library::parse("@misc{a, year = 2024}\n@misc{b, year = 2025}").unwrap();
+    let config = WriterConfig::default().blank_lines_between_entries(2);
+    let mut output = Vec::new();
+    Writer::with_config(&mut output, config.clone())
+        .write_library(&library)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "@misc{a,\n  year = 2024\n}\n\n\n@misc{b,\n  year = 2025\n}\n"
+    );
+
+    let document = Parser::new()
+        .preserve_raw()
+        .parse_document("@misc{a, year = 2024}\n@misc{b, year = 2025}")
+        .unwrap();
+    let mut output = Vec::new();
+    Writer::with_config(&mut output, config)
+        .write_document(&document)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "@misc{a, year = 2024}\n\n\n@misc{b, year = 2025}"
+    );
+}
+
+#[test]
+fn preserved_blank_lines_keep_source_spacing_when_reformatting() {
+    let input = "@misc{a,year=2024}\n\n\n% Section two\n@misc{b,year=2025}\n@misc{c,year=2026}\n";
+    let mut document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    let config = WriterConfig::default().block_spacing(BlockSpacing::Preserve);
+    let write = |document: &ParsedDocument, config: WriterConfig| {
+        let mut output = Vec::new();
+        Writer::with_config(&mut output, config)
+            .write_document(document)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert_eq!(write(&document, config.clone()), input.trim_end());
+
+    let normalized = config.clone().raw_write_mode(RawWriteMode::Normalize);
+    assert_eq!(
+        write(&document, normalized),
+        "@misc{a,\n  year = 2024\n}\n\n\n% Section two\n@misc{b,\n  year = 2025\n}\n\
+         @misc{c,\n  year = 2026\n}\n"
+    );
+
+    let mut added = ParsedEntry::from_entry(Entry::new(EntryType::Misc, "d"), None);
+    added.add_field("year", Value::Number(2027));
+    document.push_entry(added);
+    let output = write(&document, config);
+    assert!(output.ends_with("@misc{c,year=2026}\n@misc{d,\n  year = 2027\n}\n"));
+}
+
+#[test]
+fn write_changes_copies_unchanged_blocks_and_serializes_edits() {
+    let input = "% Shared venues\n@string{vldb = \"VLDB\"}\n\n@inproceedings{kept,\n    booktitle = vldb,  year=2020}\n\n\n@misc{edited,note={Old}}\n@misc{dropped, note = {Gone}}\n\n@misc{last,   year = 2024}\n";
+    let parser = Parser::new().capture_source();
+    let mut library = parser.parse(input).unwrap();
+    assert_eq!(library.write_changes(input, &parser), input);
+
+    library.entries_mut()[1].set("note", Value::from_plain_string("New"));
+    let dropped = library.entry_id(2).unwrap();
+    library.remove_entry(dropped).unwrap();
+    library.add_entry(Entry::new(EntryType::Misc, "added"));
+
+    let output = library.write_changes(input, &parser);
+    assert_eq!(
+        output,
+        "% Shared venues\n@string{vldb = \"VLDB\"}\n\n@inproceedings{kept,\n    booktitle = vldb,  year=2020}\n\n\n\
+         @misc{edited,\n  note = {New}\n}\n\n@misc{last,   year = 2024}\n\n@misc{added,\n}\n"
+    );
+    assert_eq!(Library::parse(&output).unwrap().entries().len(), 4);
+
+    let without_spans = Library::parse(input).unwrap();
+    assert_eq!(
+        without_spans.write_changes(input, &Parser::new()),
+        without_spans.to_bibtex().unwrap()
+    );
+}
+
+#[test]
+fn write_changes_compares_blocks_parsed_with_the_same_settings() {
+    let input = "@misc{kept,\n    journal = ext,  note={Old}}\n";
+    let parser = Parser::new()
+        .capture_source()
+        .with_string_library("@string{ext = \"External\"}")
+        .unwrap();
+    let library = parser.parse(input).unwrap();
+    assert_eq!(library.write_changes(input, &parser), input);
+}
+
+#[test]
+fn field_order_templates_apply_per_entry_type() {
+    let input = "@article{a,\n  pages = {1--2},\n  note = {Kept},\n  journal = {J},\n  Author = {Doe},\n  number = {3},\n  volume = {4}\n}\n\n@inproceedings{b,\n  pages = {5},\n  editor = {Roe},\n  booktitle = {Proc}\n}\n\n@misc{c,\n  year = {2024},\n  title = {T}\n}\n";
+    let order = FieldOrder::standard().entry_type("misc", ["year", "title"]);
+    assert_eq!(order.template("ARTICLE")[2], "journal");
+
+    let document = Parser::new().preserve_raw().parse_document(input).unwrap();
+    let mut output = Vec::new();
+    Writer::with_config(&mut output, WriterConfig::default().field_order(order))
+        .write_document(&document)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.contains("@article{a,\n  Author = {Doe},\n  journal = {J},\n  volume = {4},\n  number = {3},\n  pages = {1--2},\n  note = {Kept}\n}"),
+        "{output}"
+    );
+    assert!(
+        output.contains(
+            "@inproceedings{b,\n  booktitle = {Proc},\n  editor = {Roe},\n  pages = {5}\n}"
+        ),
+        "{output}"
+    );
+    assert!(
+        output.contains("@misc{c,\n  year = {2024},\n  title = {T}\n}"),
+        "{output}"
+    );
+}
+
+#[test]
+fn tabular_style_aligns_equals_signs_at_a_column() {
+    let library = Library::parse(
+        "@article{a, title = {T}, year = {2024}}\n@misc{b, howpublished = {Web}, note = {N}}\n",
+    )
+    .unwrap();
+    let write = |tabular: TabularStyle| {
+        let mut output = Vec::new();
+        Writer::with_config(&mut output, WriterConfig::default().tabular(tabular))
+            .write_library(&library)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let output = write(TabularStyle::new(10));
+    assert!(
+        output.contains("@article{a,\n  title   = {T},\n  year    = {2024}\n}"),
+        "{output}"
+    );
+    assert!(
+        output.contains("  howpublished = {Web},\n  note         = {N}\n"),
+        "{output}"
+    );
+
+    let output = write(TabularStyle::new(10).across_file());
+    assert!(
+        output.contains("  title        = {T},\n  year         = {2024}\n"),
+        "{output}"
+    );
+}
+
+#[test]
+fn min_diff_formatting_only_touches_nonconforming_lines() {
+    let input = "% header kept\n@string{j = \"Journal\"}\n\n@article{ok,\n  title = {Fine},\n  journal = j\n}\n\n@Article{bad,\n    title={Needs   {Work}} , % keep me\n  year = 2024,}\n\n@misc{odd,\n  % own-line comment\n    note = {Untouched}\n}\n";
+    let output = format_min_diff(input, WriterConfig::default()).unwrap();
+    assert_eq!(
+        output,
+        "% header kept\n@string{j = \"Journal\"}\n\n@article{ok,\n  title = {Fine},\n  journal = j\n}\n\n@Article{bad,\n  title = {Needs   {Work}}, % keep me\n  year = 2024\n}\n\n@misc{odd,\n  % own-line comment\n    note = {Untouched}\n}\n"
+    );
+    assert_eq!(
+        format_min_diff(&output, WriterConfig::default()).unwrap(),
+        output
+    );
+
+    let aligned = format_min_diff(
+        "@misc{a,\n  title = {T},\n  howpublished = {Web}\n}",
+        WriterConfig::default()
+            .align_values(true)
+            .trailing_comma(TrailingComma::Always),
+    )
+    .unwrap();
+    assert_eq!(
+        aligned,
+        "@misc{a,\n  title        = {T},\n  howpublished = {Web},\n}"
+    );
+}
@@ -137,4 +137,29 @@ mod tests {
         // Concat should still work correctly with boxed Vec
         assert_eq!(entry.get("title").unwrap(), "Hello, World");
     }
+
+    #[test]
+    fn test_interned_libraries_share_repeated_literals() {
+        let input = r#"
+            @string{pub = "ACM Press"}
+            @inproceedings{a, publisher = pub, booktitle = "Proc. SIGMOD"}
+            @inproceedings{b, publisher = "ACM Press", booktitle = "Proc. SIGMOD"}
+        "#;
+        let library = Library::parser().parse(input).unwrap();
+        let mut interner = ValueInterner::new();
+        interner.collect(&library);
+        assert_eq!(interner.len(), 2);
+
+        let library = interner.intern(library);
+        let [a, b] = library.entries() else {
+            panic!("expected two entries");
+        };
+        for field in ["publisher", "booktitle"] {
+            assert_eq!(a.get(field), b.get(field));
+            assert_eq!(
+                a.get(field).unwrap().as_ptr(),
+                b.get(field).unwrap().as_ptr()
+            );
+        }
+    }
 }
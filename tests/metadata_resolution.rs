@@ -0,0 +1,427 @@
+use bibtex_parser::metadata::{EnrichmentPipeline, Identifier, MetadataCache, MetadataProvider};
+use bibtex_parser::{Entry, EntryType, Error, Library, Result};
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Fake {
+    name: &'static str,
+    books: bool,
+    calls: RefCell<Vec<String>>,
+}
+
+impl Fake {
+    fn new(name: &'static str, books: bool) -> Self {
+        Self {
+            name,
+            books,
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl MetadataProvider for Fake {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn supports(&self, identifier: &Identifier) -> bool {
+        matches!(identifier, Identifier::Isbn(_)) == self.books
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        self.calls
+            .borrow_mut()
+            .push(identifier.as_str().to_string());
+        match identifier.as_str() {
+            "10.1000/down" => Err(Error::MetadataLookup("service unavailable".to_string())),
+            "10.1000/unknown" => Ok(None),
+            _ => {
+                let ty = if self.books {
+                    EntryType::Book
+                } else {
+                    EntryType::Article
+                };
+                let mut entry = Entry::new(ty, "remote");
+                entry.set_literal("title", "Remote Title");
+                entry.set_literal("year", "1999");
+                entry.set_literal("note", "from provider");
+                Ok(Some(entry))
+            }
+        }
+    }
+}
+
+#[test]
+fn identifier_stubs_are_filled_from_the_first_provider_that_answers() {
+    let mut library = Library::parse(
+        "@misc{doi, doi = {https://doi.org/10.1000/ABC}, note = {mine}}\n\
+         @book{isbn, isbn = {978-0-201-89683-1}}\n\
+         @misc{complete, title = {Kept}, doi = {10.1000/abc}}\n\
+         @misc{down, doi = {10.1000/down}}\n\
+         @misc{unknown, doi = {10.1000/unknown}}\n\
+         @misc{bare, year = 2024}",
+    )
+    .unwrap();
+    let articles = Fake::new("articles", false);
+    let books = Fake::new("books", true);
+
+    let resolution = library.resolve_missing_metadata(&[&articles, &books]);
+
+    let doi = &library.entries()[0];
+    assert_eq!((doi.key(), doi.ty.clone()), ("doi", EntryType::Article));
+    assert_eq!(doi.get("title"), Some("Remote Title"));
+    assert_eq!(doi.get("note"), Some("mine"));
+    let isbn = &library.entries()[1];
+    assert_eq!(isbn.get("year"), Some("1999"));
+
+    assert_eq!(resolution.resolved.len(), 2);
+    assert_eq!(resolution.resolved[0].provider, "articles");
+    assert_eq!(resolution.resolved[0].fields, ["title", "year"]);
+    assert_eq!(
+        resolution.resolved[1].identifier,
+        Identifier::Isbn("9780201896831".to_string())
+    );
+    assert_eq!(resolution.failures.len(), 1);
+    assert_eq!(resolution.failures[0].entry, 3);
+    assert!(resolution.failures[0]
+        .message
+        .contains("service unavailable"));
+    assert_eq!(resolution.unresolved, [3, 4]);
+
+    assert_eq!(
+        *articles.calls.borrow(),
+        ["10.1000/abc", "10.1000/down", "10.1000/unknown"]
+    );
+    assert_eq!(*books.calls.borrow(), ["9780201896831"]);
+}
+
+#[test]
+fn identifiers_parse_dois_and_isbns() {
+    assert_eq!(
+        Identifier::parse("doi:10.5555/Foo"),
+        Some(Identifier::Doi("10.5555/foo".to_string()))
+    );
+    assert_eq!(
+        Identifier::parse("ISBN 0-306-40615-x"),
+        Some(Identifier::Isbn("030640615X".to_string()))
+    );
+    assert_eq!(Identifier::parse("12345"), None);
+}
+
+/// Fails the first `failures` lookups of each identifier, then answers.
+struct Flaky {
+    failures: usize,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MetadataProvider for Flaky {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    fn supports(&self, _: &Identifier) -> bool {
+        true
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push(identifier.as_str().to_string());
+        let attempts = calls
+            .iter()
+            .filter(|call| *call == identifier.as_str())
+            .count();
+        drop(calls);
+        if attempts <= self.failures {
+            return Err(Error::MetadataUnavailable {
+                message: "timed out".to_string(),
+                retry_after: None,
+            });
+        }
+        if identifier.as_str().ends_with("missing") {
+            return Ok(None);
+        }
+        let mut entry = Entry::new(EntryType::Article, "remote");
+        entry.set_literal("title", "Remote Title");
+        entry.set_literal("journal", "J");
+        Ok(Some(entry))
+    }
+}
+
+#[test]
+fn pipeline_plans_with_retries_and_caches_responses() {
+    let input = "@misc{a, doi = {10.1000/a}}\n\
+                 @article{b, doi = {10.1000/b}, journal = {Mine}}\n\
+                 @misc{c, doi = {10.1000/missing}}\n\
+                 @misc{d, doi = {10.1000/d}, title = {Done}}";
+    let mut library = Library::parse(input).unwrap();
+    let flaky = Flaky {
+        failures: 1,
+        calls: Mutex::new(Vec::new()),
+    };
+    let cache = MetadataCache::new();
+    let pipeline = EnrichmentPipeline::new(&[&flaky])
+        .concurrency(3)
+        .retries(1)
+        .backoff(Duration::from_millis(1))
+        .cache(&cache);
+
+    let plan = pipeline.plan(&library);
+    assert_eq!(library.entries()[0].get("title"), None);
+    assert_eq!(flaky.calls.lock().unwrap().len(), 6);
+    assert_eq!(cache.len(), 3);
+    assert!(plan.failures.is_empty());
+    assert_eq!(plan.unresolved, [2]);
+    assert_eq!(
+        plan.changes
+            .iter()
+            .map(|change| change.entry)
+            .collect::<Vec<_>>(),
+        [0, 1]
+    );
+    assert_eq!(plan.changes[0].entry_type, Some(EntryType::Article));
+    assert_eq!(plan.changes[1].entry_type, None);
+    assert_eq!(
+        plan.changes[1]
+            .fields
+            .iter()
+            .map(|field| field.name.as_ref())
+            .collect::<Vec<_>>(),
+        ["title"]
+    );
+    let markdown = plan.to_markdown();
+    assert!(markdown.contains("2 entries to update, 0 failed lookups, 1 unresolved."));
+    assert!(markdown.contains(
+        "## `a` (10.1000/a via flaky)\n\n- type: `article`\n- title: Remote Title\n- journal: J\n"
+    ));
+
+    let resolution = plan.apply(&mut library);
+    assert_eq!(resolution.resolved.len(), 2);
+    assert_eq!(library.entries()[0].ty, EntryType::Article);
+    assert_eq!(library.entries()[1].get("journal"), Some("Mine"));
+
+    assert!(pipeline.plan(&library).is_empty());
+    let mut fresh = Library::parse(input).unwrap();
+    let resolution = pipeline.run(&mut fresh);
+    assert_eq!(resolution.resolved.len(), 2);
+    assert_eq!(flaky.calls.lock().unwrap().len(), 6);
+}
+
+#[test]
+fn pipeline_reports_exhausted_retries_and_spaces_requests() {
+    let library =
+        Library::parse("@misc{a, doi = {10.1000/a}}\n@misc{b, doi = {10.1000/b}}").unwrap();
+    let flaky = Flaky {
+        failures: 5,
+        calls: Mutex::new(Vec::new()),
+    };
+    let started = Instant::now();
+    let plan = EnrichmentPipeline::new(&[&flaky])
+        .retries(1)
+        .backoff(Duration::ZERO)
+        .rate_limit(Duration::from_millis(20))
+        .plan(&library);
+
+    assert!(started.elapsed() >= Duration::from_millis(60));
+    assert_eq!(flaky.calls.lock().unwrap().len(), 4);
+    assert_eq!(plan.failures.len(), 2);
+    assert_eq!(plan.failures[1].entry, 1);
+    assert_eq!(plan.unresolved, [0, 1]);
+    assert!(plan.to_markdown().contains("## Failed lookups (2)"));
+}
+
+/// Answers "not found" after one error chosen by identifier.
+struct Throttled {
+    name: &'static str,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MetadataProvider for Throttled {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn supports(&self, _: &Identifier) -> bool {
+        true
+    }
+
+    fn lookup(&self, identifier: &Identifier) -> Result<Option<Entry<'static>>> {
+        let mut calls = self.calls.lock().unwrap();
+        let first = !calls.iter().any(|call| call == identifier.as_str());
+        calls.push(identifier.as_str().to_string());
+        drop(calls);
+        match identifier.as_str() {
+            "10.1000/bad" => Err(Error::MetadataLookup("status code 400".to_string())),
+            "10.1000/busy" if first => Err(Error::MetadataUnavailable {
+                message: "status code 429".to_string(),
+                retry_after: Some(Duration::from_millis(40)),
+            }),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn pipeline_retries_only_transient_errors_and_limits_each_provider() {
+    let library =
+        Library::parse("@misc{a, doi = {10.1000/bad}}\n@misc{b, doi = {10.1000/busy}}").unwrap();
+    let provider = Throttled {
+        name: "throttled",
+        calls: Mutex::new(Vec::new()),
+    };
+    let started = Instant::now();
+    let plan = EnrichmentPipeline::new(&[&provider])
+        .retries(3)
+        .backoff(Duration::ZERO)
+        .plan(&library);
+    assert!(started.elapsed() >= Duration::from_millis(40));
+    let calls = provider.calls.lock().unwrap().clone();
+    assert_eq!(
+        calls.iter().filter(|call| *call == "10.1000/bad").count(),
+        1
+    );
+    assert_eq!(
+        calls.iter().filter(|call| *call == "10.1000/busy").count(),
+        2
+    );
+    assert_eq!(plan.failures.len(), 1);
+    assert_eq!(plan.failures[0].entry, 0);
+
+    let provider = Throttled {
+        name: "throttled",
+        calls: Mutex::new(Vec::new()),
+    };
+    let plan = EnrichmentPipeline::new(&[&provider])
+        .retries(3)
+        .max_backoff(Duration::from_millis(10))
+        .plan(&Library::parse("@misc{b, doi = {10.1000/busy}}").unwrap());
+    assert_eq!(provider.calls.lock().unwrap().len(), 1);
+    assert_eq!(plan.failures.len(), 1);
+    assert!(plan.failures[0].message.contains("status code 429"));
+
+    let library = Library::parse(
+        "@misc{a, doi = {10.1000/a}}\n@misc{b, doi = {10.1000/b}}\n@misc{c, doi = {10.1000/c}}",
+    )
+    .unwrap();
+    let slow = Throttled {
+        name: "slow",
+        calls: Mutex::new(Vec::new()),
+    };
+    let fast = Throttled {
+        name: "fast",
+        calls: Mutex::new(Vec::new()),
+    };
+    let started = Instant::now();
+    let plan = EnrichmentPipeline::new(&[&slow, &fast])
+        .concurrency(1)
+        .provider_rate_limit("slow", Duration::from_millis(30))
+        .plan(&library);
+    assert!(started.elapsed() >= Duration::from_millis(60));
+    assert_eq!(plan.unresolved, [0, 1, 2]);
+    assert_eq!(fast.calls.lock().unwrap().len(), 3);
+}
+
+#[test]
+#[cfg(feature = "metadata_providers")]
+fn openalex_works_import_as_typed_entries() {
+    let json = r#"{"meta": {"count": 2}, "results": [
+        {
+            "id": "https://openalex.org/W2741809807",
+            "doi": "https://doi.org/10.7717/PEERJ.4375",
+            "title": "The state of OA",
+            "publication_year": 2018,
+            "type": "article",
+            "primary_location": {"source": {"display_name": "PeerJ", "type": "journal"}},
+            "authorships": [
+                {"author": {"display_name": "Heather Piwowar"}},
+                {"author": {"display_name": "Jason Priem"}}
+            ],
+            "biblio": {"volume": "6", "issue": null, "first_page": "e4375", "last_page": "e4375"},
+            "abstract_inverted_index": {"Despite": [0], "growing": [1], "interest": [2]}
+        },
+        {
+            "id": "https://openalex.org/W3000000001",
+            "display_name": "Fast parsing",
+            "publication_year": 2024,
+            "type": "article",
+            "primary_location": {"source": {"display_name": "Proc. PLDI", "type": "conference"}},
+            "biblio": {"first_page": "10", "last_page": "20"}
+        }
+    ]}"#;
+    let library = Library::from_openalex_json(json).unwrap();
+    let [article, paper] = library.entries() else {
+        panic!("expected two entries");
+    };
+
+    assert_eq!(article.key(), "W2741809807");
+    assert_eq!(article.ty, EntryType::Article);
+    assert_eq!(
+        article.get("author"),
+        Some("Heather Piwowar and Jason Priem")
+    );
+    assert_eq!(article.get("journal"), Some("PeerJ"));
+    assert_eq!(article.get("year"), Some("2018"));
+    assert_eq!(article.get("pages"), Some("e4375"));
+    assert_eq!(article.get("number"), None);
+    assert_eq!(article.get("abstract"), Some("Despite growing interest"));
+    assert_eq!(article.doi().as_deref(), Some("10.7717/peerj.4375"));
+    assert_eq!(article.openalex_id().as_deref(), Some("W2741809807"));
+
+    assert_eq!(paper.ty, EntryType::InProceedings);
+    assert_eq!(paper.get("title"), Some("Fast parsing"));
+    assert_eq!(paper.get("booktitle"), Some("Proc. PLDI"));
+    assert_eq!(paper.get("pages"), Some("10--20"));
+
+    assert!(matches!(
+        Library::from_openalex_json(r#"{"title": "no id"}"#),
+        Err(Error::Conversion(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "metadata_providers")]
+fn semantic_scholar_papers_import_with_external_ids() {
+    let json = r#"[
+        {
+            "paperId": "649def34f8be52c8b66281af98ae884c09aef38b",
+            "externalIds": {"DOI": "10.1093/MIND/LIX.236.433", "CorpusId": 215416146},
+            "title": "Computing Machinery and Intelligence",
+            "authors": [{"authorId": "1", "name": "A. Turing"}],
+            "year": 1950,
+            "publicationTypes": ["JournalArticle"],
+            "journal": {"name": "Mind", "volume": "LIX", "pages": "433-460"}
+        },
+        null,
+        {
+            "paperId": "204e3073870fae3d05bcbc2f6a8e263d9b72e776",
+            "externalIds": {"ArXiv": "1706.03762", "CorpusId": 13756489},
+            "title": "Attention is All you Need",
+            "venue": "Neural Information Processing Systems",
+            "year": 2017,
+            "publicationTypes": ["JournalArticle", "Conference"]
+        }
+    ]"#;
+    let library = Library::from_semantic_scholar_json(json).unwrap();
+    let [turing, attention] = library.entries() else {
+        panic!("expected two entries");
+    };
+
+    assert_eq!(turing.key(), "corpus215416146");
+    assert_eq!(turing.ty, EntryType::Article);
+    assert_eq!(turing.get("journal"), Some("Mind"));
+    assert_eq!(turing.get("pages"), Some("433--460"));
+    assert_eq!(turing.doi().as_deref(), Some("10.1093/mind/lix.236.433"));
+    assert_eq!(
+        turing.s2id().as_deref(),
+        Some("649def34f8be52c8b66281af98ae884c09aef38b")
+    );
+    assert_eq!(turing.corpus_id().as_deref(), Some("215416146"));
+
+    assert_eq!(attention.ty, EntryType::InProceedings);
+    assert_eq!(
+        attention.get("booktitle"),
+        Some("Neural Information Processing Systems")
+    );
+    assert_eq!(attention.get("eprint"), Some("1706.03762"));
+    assert_eq!(attention.get("archiveprefix"), Some("arXiv"));
+}
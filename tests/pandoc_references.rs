@@ -0,0 +1,72 @@
+use bibtex_parser::Library;
+use serde_json::{json, Value};
+
+const INPUT: &str = r#"
+@article{doe2024,
+    author = {van der Berg, Jan and Smith, Jr., John and {World Health Organization}},
+    title = {Scaling {NASA} Pipelines \& More},
+    journal = {Journal of Tests},
+    volume = 12,
+    number = 3,
+    pages = {10--20},
+    year = 2024,
+    month = mar,
+    doi = {https://doi.org/10.1000/XYZ}
+}
+@phdthesis{roe2020, author = {Roe, Rick and others}, title = {Thesis}, school = {MIT}, date = {2020-05-17}}
+@xdata{shared, publisher = {ACM}}
+@misc{web, title = {Page}, url = {https://example.org}, urldate = {2023-01-02}}
+"#;
+
+fn references() -> Value {
+    let library = Library::parse(INPUT).unwrap();
+    let metadata: Value = serde_json::from_str(&library.to_pandoc_references()).unwrap();
+    metadata["references"].clone()
+}
+
+#[test]
+fn pandoc_references_map_ids_types_names_and_dates() {
+    let references = references();
+    assert_eq!(references.as_array().unwrap().len(), 3);
+
+    let article = &references[0];
+    assert_eq!(article["id"], "doe2024");
+    assert_eq!(article["type"], "article-journal");
+    assert_eq!(
+        article["author"],
+        json!([
+            {"family": "Berg", "given": "Jan", "non-dropping-particle": "van der"},
+            {"family": "Smith", "given": "John", "suffix": "Jr."},
+            {"literal": "World Health Organization"},
+        ])
+    );
+    assert_eq!(article["title"], "Scaling NASA Pipelines & More");
+    assert_eq!(article["container-title"], "Journal of Tests");
+    assert_eq!(article["issue"], "3");
+    assert_eq!(article["page"], "10-20");
+    assert_eq!(article["DOI"], "10.1000/xyz");
+    assert_eq!(article["issued"], json!({"date-parts": [[2024, 3]]}));
+
+    let thesis = &references[1];
+    assert_eq!(
+        thesis["author"],
+        json!([{"family": "Roe", "given": "Rick"}, {"literal": "others"}])
+    );
+    assert_eq!(thesis["type"], "thesis");
+    assert_eq!(thesis["genre"], "PhD thesis");
+    assert_eq!(thesis["publisher"], "MIT");
+    assert_eq!(thesis["issued"], json!({"date-parts": [[2020, 5, 17]]}));
+
+    let web = &references[2];
+    assert_eq!(web["type"], "document");
+    assert_eq!(web["URL"], "https://example.org");
+    assert_eq!(web["accessed"], json!({"date-parts": [[2023, 1, 2]]}));
+}
+
+#[test]
+fn csl_json_is_the_bare_reference_list() {
+    let library = Library::parse(INPUT).unwrap();
+    let items: Value = serde_json::from_str(&library.to_csl_json()).unwrap();
+    assert_eq!(items, references());
+    assert_eq!(Library::new().to_csl_json(), "[]");
+}
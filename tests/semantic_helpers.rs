@@ -1,7 +1,10 @@
 use bibtex_parser::{
-    canonical_biblatex_field_alias, classify_resource_field, normalize_biblatex_field_name,
-    normalize_field_name_ascii, parse_date_parts, parse_names, DateParseError, DateParts, Library,
-    Parser, ResourceKind,
+    canonical_biblatex_field_alias, classify_resource_field, format_author_list,
+    normalize_biblatex_field_name, normalize_corpus_id, normalize_field_name_ascii,
+    normalize_openalex, normalize_s2id, parse_date_parts, parse_file_attachments, parse_name_list,
+    parse_names, DateParseError, DateParts, EtAlStyle, FieldAliases, FileAttachment, LanguageTag,
+    Library, NameStyle, Parser, PublicationYear, ResourceKind, SortOptions, ValidationLevel,
+    ValidationSeverity,
 };
 
 #[test]
@@ -34,6 +37,114 @@ fn name_helpers_preserve_raw_parsed_and_literal_forms() {
     assert_eq!(names[2].display_name(), "Donald E. Knuth, Jr.");
 }
 
+#[test]
+fn corporate_cjk_and_extended_names_are_not_mangled() {
+    let names = parse_names(
+        "{World Health Organization} and {Aristotle} and 山田 太郎 and 毛泽东 and \
+         family=Pliny, given=Gaius, nameaddon={the Elder}",
+    );
+
+    assert_eq!(names.len(), 5);
+    assert!(names[0].is_corporate);
+    assert_eq!(names[0].display_name(), "World Health Organization");
+    assert!(names[1].is_literal() && !names[1].is_corporate);
+
+    assert!(names[2].is_cjk());
+    assert_eq!(names[2].family, ["山田"]);
+    assert_eq!(names[2].given, ["太郎"]);
+    assert_eq!(names[2].display_name(), "山田太郎");
+    assert_eq!(names[3].family, ["毛泽东"]);
+    assert!(names[3].given.is_empty());
+
+    assert_eq!(names[4].family, ["Pliny"]);
+    assert_eq!(names[4].given, ["Gaius"]);
+    assert_eq!(names[4].nameaddon, "the Elder");
+    assert!(!names[4].is_corporate);
+}
+
+#[test]
+fn names_format_in_common_bibliography_styles() {
+    let names = parse_names(
+        "Jean-Paul van der Berg and Knuth, Jr., Donald Ervin and \\'Emile Zola and {ACM}",
+    );
+
+    assert_eq!(
+        names[0].format(NameStyle::FamilyInitials),
+        "van der Berg, J.-P."
+    );
+    assert_eq!(
+        names[0].format(NameStyle::InitialsFamily),
+        "J.-P. van der Berg"
+    );
+    assert_eq!(names[0].format(NameStyle::Family), "van der Berg");
+    assert_eq!(
+        names[1].format(NameStyle::FamilyGiven),
+        "Knuth, Jr., Donald Ervin"
+    );
+    assert_eq!(
+        names[1].format(NameStyle::GivenFamily),
+        "Donald Ervin Knuth, Jr."
+    );
+    assert_eq!(
+        names[1].format(NameStyle::FamilyInitials),
+        "Knuth, Jr., D. E."
+    );
+    assert_eq!(names[2].given_initials(), "\\'E.");
+    assert_eq!(names[3].format(NameStyle::InitialsFamily), "ACM");
+
+    let braced = parse_names("{Jean-Paul}-Marie Sartre and Sartre, {Jean-Paul} {Marie-Claire}");
+    assert_eq!(braced[0].given_initials(), "{Jean-Paul}.-M.");
+    assert_eq!(braced[1].given, ["Jean-Paul", "Marie-Claire"]);
+    assert_eq!(braced[1].given_initials(), "J.-P. M.-C.");
+}
+
+#[test]
+fn author_lists_truncate_with_et_al_markers() {
+    let names = parse_names("Ada Lovelace and Alan Turing and Grace Hopper");
+    assert_eq!(
+        format_author_list(&names, 3, EtAlStyle::EtAl),
+        "Lovelace, Turing, and Hopper"
+    );
+    assert_eq!(
+        format_author_list(&names[..2], 3, EtAlStyle::EtAl),
+        "Lovelace and Turing"
+    );
+    assert_eq!(
+        format_author_list(&names, 1, EtAlStyle::EtAl),
+        "Lovelace et al."
+    );
+    assert_eq!(
+        format_author_list(&names, 2, EtAlStyle::CommaEtAl),
+        "Lovelace, Turing, et al."
+    );
+    assert_eq!(
+        format_author_list(&names, 0, EtAlStyle::AndOthers),
+        "Lovelace and others"
+    );
+
+    let marked = parse_name_list("Ada Lovelace and Alan Turing and others");
+    assert!(marked.truncated);
+    assert_eq!(marked.len(), 2);
+    assert!(marked.iter().all(|name| name.last != "others"));
+    assert_eq!(
+        marked.format(5, EtAlStyle::EtAl),
+        "Lovelace and Turing et al."
+    );
+    assert_eq!(
+        format_author_list(&marked.names, 5, EtAlStyle::EtAl),
+        "Lovelace and Turing"
+    );
+    assert_eq!(parse_names("Ada Lovelace and others").len(), 1);
+    assert!(!parse_name_list("Ada Lovelace and Others Inc.").truncated);
+    assert_eq!(format_author_list(&[], 2, EtAlStyle::EtAl), "");
+
+    let nobody = parse_name_list("others");
+    assert!(nobody.truncated && nobody.is_empty());
+    for style in [EtAlStyle::EtAl, EtAlStyle::CommaEtAl, EtAlStyle::AndOthers] {
+        assert_eq!(nobody.format(3, style), "");
+    }
+}
+
 #[test]
 fn large_author_lists_parse_without_losing_order() {
     let mut input = String::new();
@@ -131,6 +242,16 @@ fn resource_classification_and_field_normalization_are_stable() {
         Some("journal")
     );
     assert_eq!(normalize_biblatex_field_name("JournalTitle"), "journal");
+    assert_eq!(normalize_biblatex_field_name("EprintClass"), "primaryclass");
+    assert_eq!(canonical_biblatex_field_alias(" date "), Some("year"));
+    assert_eq!(canonical_biblatex_field_alias("journal"), None);
+    assert_eq!(FieldAliases::biblatex().aliases_of("date").count(), 0);
+    assert_eq!(
+        FieldAliases::biblatex()
+            .aliases_of("annote")
+            .collect::<Vec<_>>(),
+        ["annotation"]
+    );
     assert_eq!(classify_resource_field("PMCID"), Some(ResourceKind::Pmcid));
 
     let library = Library::parse(
@@ -145,7 +266,10 @@ fn resource_classification_and_field_normalization_are_stable() {
             issn = "1234-567X",
             archiveprefix = "arXiv",
             eprint = "arXiv:2403.12345v2",
-            crossref = "parent"
+            crossref = "parent",
+            openalex = "https://openalex.org/w4403",
+            s2id = "https://www.semanticscholar.org/paper/Title/649DEF34F8BE52C8B66281AF98AE884C09AEF38B",
+            corpusid = "CorpusId:215416146"
         }
         "#,
     )
@@ -168,6 +292,9 @@ fn resource_classification_and_field_normalization_are_stable() {
             ResourceKind::Issn,
             ResourceKind::Arxiv,
             ResourceKind::Crossref,
+            ResourceKind::OpenAlex,
+            ResourceKind::S2Id,
+            ResourceKind::CorpusId,
         ]
     );
     assert_eq!(resources[0].normalized.as_deref(), Some("10.1000/xyz"));
@@ -175,6 +302,27 @@ fn resource_classification_and_field_normalization_are_stable() {
     assert_eq!(resources[5].normalized.as_deref(), Some("9780134671796"));
     assert_eq!(resources[6].normalized.as_deref(), Some("1234567X"));
     assert_eq!(resources[7].normalized.as_deref(), Some("2403.12345v2"));
+    assert_eq!(resources[9].normalized.as_deref(), Some("W4403"));
+    assert_eq!(library.entries()[0].openalex_id().as_deref(), Some("W4403"));
+    assert_eq!(
+        normalize_openalex("openalex:A5023888391").as_deref(),
+        Some("A5023888391")
+    );
+    assert_eq!(normalize_openalex("W44x"), None);
+    assert_eq!(
+        resources[10].normalized.as_deref(),
+        Some("649def34f8be52c8b66281af98ae884c09aef38b")
+    );
+    assert_eq!(
+        library.entries()[0].corpus_id().as_deref(),
+        Some("215416146")
+    );
+    assert_eq!(
+        library.entries()[0].s2id(),
+        resources[10].normalized.clone()
+    );
+    assert_eq!(normalize_s2id("649def34"), None);
+    assert_eq!(normalize_corpus_id(" 123 ").as_deref(), Some("123"));
 }
 
 #[test]
@@ -206,3 +354,244 @@ fn parsed_entry_helpers_match_library_helpers() {
     assert_eq!(entry.doi(), Some("10.5555/abc".to_string()));
     assert_eq!(entry.resource_fields()[0].kind, ResourceKind::Doi);
 }
+
+#[test]
+fn annotated_file_fields_parse_into_attachments() {
+    let attachments = parse_file_attachments(
+        r"Full Text PDF:papers/knuth.pdf:PDF;Notes\: draft:C\:\\Users\\me\\notes.txt:Text;:slides.pdf:application/pdf",
+    );
+
+    assert_eq!(
+        attachments,
+        [
+            FileAttachment {
+                description: "Full Text PDF".to_string(),
+                path: "papers/knuth.pdf".to_string(),
+                file_type: Some("PDF".to_string()),
+            },
+            FileAttachment {
+                description: "Notes: draft".to_string(),
+                path: r"C:\Users\me\notes.txt".to_string(),
+                file_type: Some("Text".to_string()),
+            },
+            FileAttachment {
+                description: String::new(),
+                path: "slides.pdf".to_string(),
+                file_type: Some("application/pdf".to_string()),
+            },
+        ]
+    );
+
+    let unescaped = parse_file_attachments(r":D:\papers\paper.pdf:PDF");
+    assert_eq!(unescaped[0].path, r"D:\papers\paper.pdf");
+    assert_eq!(parse_file_attachments("paper.pdf")[0].path, "paper.pdf");
+    assert_eq!(
+        parse_file_attachments(r"C:\paper.pdf")[0].path,
+        r"C:\paper.pdf"
+    );
+    let described = parse_file_attachments(r"Desc:C:\x.pdf;Desc:C:/x.pdf:application/pdf");
+    assert_eq!(
+        described,
+        [
+            FileAttachment {
+                description: "Desc".to_string(),
+                path: r"C:\x.pdf".to_string(),
+                file_type: None,
+            },
+            FileAttachment {
+                description: "Desc".to_string(),
+                path: "C:/x.pdf".to_string(),
+                file_type: Some("application/pdf".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        parse_file_attachments("C:/path.pdf:PDF")[0].description,
+        "C"
+    );
+    assert!(parse_file_attachments(" ; ").is_empty());
+
+    let library = Library::parse(r"@article{paper, file = {Preprint:preprint.pdf:PDF}}").unwrap();
+    let attachments = library.entries()[0].attachments();
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].description, "Preprint");
+}
+
+#[test]
+fn alias_aware_lookup_bridges_biblatex_and_bibtex_names() {
+    let library = Library::parse(
+        r#"
+        @article{latex, journaltitle = {Nature}, location = {London}, journal = {Raw}}
+        @thesis{tex, school = {MIT}, primaryclass = {cs.DL}}
+        "#,
+    )
+    .unwrap();
+    let latex = &library.entries()[0];
+    let tex = &library.entries()[1];
+
+    assert_eq!(latex.get_aliased("journal"), Some("Raw"));
+    assert_eq!(latex.get_aliased("JournalTitle"), Some("Nature"));
+    assert_eq!(latex.get_aliased("address"), Some("London"));
+    assert_eq!(latex.get("address"), None);
+    assert_eq!(tex.get_aliased("institution"), Some("MIT"));
+    assert_eq!(tex.get_aliased("eprintclass"), Some("cs.DL"));
+    assert_eq!(tex.get_aliased("year"), None);
+
+    let custom = FieldAliases::new().with_alias("school", "university");
+    assert_eq!(tex.get_with_aliases("university", &custom), Some("MIT"));
+    assert_eq!(tex.get_with_aliases("institution", &custom), None);
+    assert_eq!(
+        latex
+            .field_with_aliases("address", &FieldAliases::default())
+            .map(|field| field.name.as_ref()),
+        Some("location")
+    );
+}
+
+#[test]
+fn pending_and_ancient_years_sort_and_validate_cleanly() {
+    assert_eq!(
+        PublicationYear::parse("{In Press}"),
+        PublicationYear::Pending("in press".into())
+    );
+    assert_eq!(PublicationYear::parse("-350"), PublicationYear::Year(-350));
+    assert_eq!(
+        PublicationYear::parse("350 BC"),
+        PublicationYear::Year(-350)
+    );
+    assert_eq!(PublicationYear::parse("AD 800"), PublicationYear::Year(800));
+    assert_eq!(
+        PublicationYear::parse("2020-05-01"),
+        PublicationYear::Year(2020)
+    );
+    assert_eq!(PublicationYear::parse("n.d."), PublicationYear::Missing);
+    assert!(matches!(
+        PublicationYear::parse("soon"),
+        PublicationYear::Invalid(_)
+    ));
+
+    let mut library = Library::parse(
+        r#"
+        @misc{missing, title = {T}}
+        @article{press, author = {A}, title = {T}, journal = {J}, year = {in press}}
+        @book{ancient, author = {Aristotle}, title = {Politics}, publisher = {P}, year = {-350}}
+        @article{state, author = {A}, title = {T}, journal = {J}, pubstate = {forthcoming}}
+        @article{bad, author = {A}, title = {T}, journal = {J}, year = {soon}}
+        @article{recent, author = {A}, title = {T}, journal = {J}, year = 2020}
+        "#,
+    )
+    .unwrap();
+
+    for key in ["press", "ancient", "recent"] {
+        let entry = library.find_by_key(key).unwrap();
+        assert!(entry.validate(ValidationLevel::Standard).is_ok(), "{key}");
+    }
+    assert!(library
+        .find_by_key("bad")
+        .unwrap()
+        .validate(ValidationLevel::Standard)
+        .is_err());
+    let suspicious = library.report().suspicious_years;
+    assert_eq!(suspicious.len(), 1);
+    assert_eq!(suspicious[0].1.key(), "bad");
+
+    library.sort(SortOptions {
+        entries_by_year: true,
+        entries_by_key: true,
+        ..SortOptions::default()
+    });
+    let keys = library
+        .entries()
+        .iter()
+        .map(|entry| entry.key())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        ["ancient", "recent", "state", "press", "bad", "missing"]
+    );
+}
+
+#[test]
+fn language_fields_parse_to_tags_and_gate_case_changes() {
+    assert_eq!(LanguageTag::parse("ngerman").unwrap().to_string(), "de");
+    assert_eq!(LanguageTag::parse("British").unwrap().to_string(), "en-GB");
+    assert_eq!(
+        LanguageTag::parse("zh_hant_tw").unwrap().to_string(),
+        "zh-Hant-TW"
+    );
+    assert_eq!(
+        LanguageTag::parse("es-419").unwrap().region.as_deref(),
+        Some("419")
+    );
+    assert_eq!(LanguageTag::parse("klingonese"), None);
+
+    let library = Library::parse(
+        r#"
+        @article{de, author = {A}, title = {Über Bäume}, journal = {J}, year = 2020,
+                 language = {german}, langid = {ngerman}}
+        @article{list, author = {A}, title = {T}, journal = {J}, year = 2020,
+                 language = {english and french}}
+        @article{odd, author = {A}, title = {T}, journal = {J}, year = 2020, langid = {elvish}}
+        "#,
+    )
+    .unwrap();
+    let german = library.entries()[0].language().unwrap();
+    assert_eq!(german.language, "de");
+    assert!(!german.allows_case_changes());
+    assert!(library.entries()[1]
+        .language()
+        .unwrap()
+        .allows_case_changes());
+    assert_eq!(library.entries()[2].language(), None);
+
+    assert!(library.entries()[0]
+        .validate(ValidationLevel::Strict)
+        .is_ok());
+    let errors = library.entries()[2]
+        .validate(ValidationLevel::Strict)
+        .unwrap_err();
+    assert_eq!(errors[0].field.as_deref(), Some("langid"));
+}
+
+#[test]
+fn typst_profile_flags_what_the_bibliography_loader_rejects() {
+    let library = Library::parse(
+        r#"
+        @article{ok:2020, author = {A}, title = {T}, journal = {J}, year = 2020, month = jan,
+                 date = {2020-03/2021}}
+        @article{bad, author = {A}, title = {T}, year = {2020a}, date = {March 2020},
+                 publisher = nowhere}
+        @artwork{odd.key., title = {T}, keywords = {a, b}}
+        "#,
+    )
+    .unwrap();
+
+    assert!(library.entries()[0].validate_typst().is_ok());
+
+    let findings = library.validate_typst();
+    assert_eq!(findings.len(), 2);
+    let fields = |index: usize| {
+        findings[index]
+            .2
+            .iter()
+            .map(|error| (error.field.clone(), error.severity))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        fields(0),
+        [
+            (Some("year".to_string()), ValidationSeverity::Error),
+            (Some("date".to_string()), ValidationSeverity::Error),
+            (Some("publisher".to_string()), ValidationSeverity::Error),
+        ]
+    );
+    assert_eq!(
+        fields(1),
+        [
+            (None, ValidationSeverity::Warning),
+            (None, ValidationSeverity::Warning),
+            (Some("keywords".to_string()), ValidationSeverity::Info),
+        ]
+    );
+    assert!(findings[1].2[0].message.contains("@misc"));
+}
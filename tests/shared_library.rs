@@ -0,0 +1,55 @@
+use bibtex_parser::{Library, SharedLibrary};
+use std::thread;
+
+fn entry_library(source: &str) -> Library<'static> {
+    Library::parse(source).unwrap().into_owned()
+}
+
+#[test]
+fn readers_keep_their_snapshot_across_updates() {
+    let shared = SharedLibrary::new(entry_library("@misc{a, title = {A}}"));
+    let before = shared.load();
+
+    let previous = shared.store(entry_library(
+        "@misc{b, title = {B}}\n@misc{c, title = {C}}",
+    ));
+    assert!(std::sync::Arc::ptr_eq(&previous, &before));
+    assert_eq!(before.entries()[0].key(), "a");
+    assert_eq!(shared.load().entries().len(), 2);
+    assert_eq!(shared.version(), 1);
+
+    let count = shared.update(|library| {
+        library.merge(entry_library("@misc{d, title = {D}}"));
+        library.entries().len()
+    });
+    assert_eq!(count, 3);
+    assert_eq!(shared.clone().load().entries().len(), 3);
+    assert_eq!(shared.version(), 2);
+}
+
+#[test]
+fn concurrent_updates_are_not_lost() {
+    let shared = SharedLibrary::default();
+    thread::scope(|scope| {
+        for writer in 0..4 {
+            let shared = shared.clone();
+            scope.spawn(move || {
+                for index in 0..25 {
+                    let source = format!("@misc{{w{writer}i{index}, title = {{T}}}}");
+                    shared.update(|library| library.merge(entry_library(&source)));
+                }
+            });
+        }
+        for _ in 0..4 {
+            let shared = shared.clone();
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    let snapshot = shared.load();
+                    assert!(snapshot.entries().len() <= 100);
+                }
+            });
+        }
+    });
+    assert_eq!(shared.load().entries().len(), 100);
+    assert_eq!(shared.version(), 100);
+}
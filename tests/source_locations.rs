@@ -1,4 +1,8 @@
-use bibtex_parser::{Parser, SourceId, SourceMap};
+use bibtex_parser::{
+    tokenize, DiagnosticCode, ParseBudget, ParseStatus, Parser, SourceId, SourceMap, SyntaxKind,
+    SyntaxNode, SyntaxTree, TokenKind,
+};
+use std::time::Duration;
 
 #[test]
 fn parsed_document_tracks_anonymous_and_named_sources() {
@@ -132,3 +136,213 @@ fn diagnostics_carry_named_source_locations_when_available() {
         .unwrap()
         .contains("@article{bad"));
 }
+
+#[test]
+fn token_stream_classifies_every_block_with_spans() {
+    let input = "% header\n@string{j = \"J\"}\n@Article{key,\n  title = {T} # j,\n  year = 2024\n}\n@comment{note}\n@book{broken, title = }\n@misc(m, note = 2024a)";
+    let tokens = tokenize(input)
+        .map(|token| (token.kind, token.text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            (TokenKind::Comment, "% header"),
+            (TokenKind::EntryType, "@string"),
+            (TokenKind::Delimiter, "{"),
+            (TokenKind::Variable, "j"),
+            (TokenKind::Delimiter, "="),
+            (TokenKind::Literal, "\"J\""),
+            (TokenKind::Delimiter, "}"),
+            (TokenKind::EntryType, "@Article"),
+            (TokenKind::Delimiter, "{"),
+            (TokenKind::Key, "key"),
+            (TokenKind::Delimiter, ","),
+            (TokenKind::FieldName, "title"),
+            (TokenKind::Delimiter, "="),
+            (TokenKind::Literal, "{T}"),
+            (TokenKind::Delimiter, "#"),
+            (TokenKind::Variable, "j"),
+            (TokenKind::Delimiter, ","),
+            (TokenKind::FieldName, "year"),
+            (TokenKind::Delimiter, "="),
+            (TokenKind::Number, "2024"),
+            (TokenKind::Delimiter, "}"),
+            (TokenKind::EntryType, "@comment"),
+            (TokenKind::Delimiter, "{"),
+            (TokenKind::Comment, "note"),
+            (TokenKind::Delimiter, "}"),
+            (TokenKind::EntryType, "@book"),
+            (TokenKind::Delimiter, "{"),
+            (TokenKind::Key, "broken"),
+            (TokenKind::Delimiter, ","),
+            (TokenKind::FieldName, "title"),
+            (TokenKind::Delimiter, "="),
+            (TokenKind::Invalid, "}"),
+            (TokenKind::EntryType, "@misc"),
+            (TokenKind::Delimiter, "("),
+            (TokenKind::Key, "m"),
+            (TokenKind::Delimiter, ","),
+            (TokenKind::FieldName, "note"),
+            (TokenKind::Delimiter, "="),
+            (TokenKind::Literal, "2024a"),
+            (TokenKind::Delimiter, ")"),
+        ]
+    );
+
+    let key = tokenize(input)
+        .find(|token| token.kind == TokenKind::Key)
+        .unwrap();
+    assert_eq!((key.span.line, key.span.column), (3, 10));
+    assert_eq!(&input[key.span.byte_start..key.span.byte_end], "key");
+}
+
+#[test]
+fn syntax_tree_is_lossless_and_navigable() {
+    let input = "% top\n@string{j = \"J\"}\n\n@article{key,\n  title = {T} # j ,\n  year=2024\n}\n@book{bad, title = }\n";
+    let tree = SyntaxTree::parse(input);
+    let root = tree.root();
+    assert_eq!(root.text(), input);
+    let leaves = root
+        .descendants()
+        .filter(|node| node.kind().is_leaf())
+        .map(|node| node.text())
+        .collect::<String>();
+    assert_eq!(leaves, input);
+
+    let blocks = root
+        .children()
+        .filter(|node| !node.kind().is_leaf())
+        .map(|node| node.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        blocks,
+        [
+            SyntaxKind::StringDefinition,
+            SyntaxKind::Entry,
+            SyntaxKind::Entry
+        ]
+    );
+
+    let entry = root.child(SyntaxKind::Entry).unwrap();
+    let field = entry.child(SyntaxKind::Field).unwrap();
+    assert_eq!(field.text(), "title = {T} # j");
+    assert_eq!(field.parent(), Some(entry));
+    let value = field.child(SyntaxKind::Value).unwrap();
+    assert_eq!(value.text(), "{T} # j");
+    assert_eq!(
+        value.first_child().unwrap().next_sibling().unwrap().kind(),
+        SyntaxKind::Whitespace
+    );
+    let year = field
+        .next_sibling()
+        .into_iter()
+        .flat_map(|node| std::iter::successors(Some(node), SyntaxNode::next_sibling))
+        .find(|node| node.kind() == SyntaxKind::Field)
+        .unwrap();
+    assert_eq!(year.text(), "year=2024");
+    assert_eq!(year.span().line, 6);
+    assert_eq!(year.prev_sibling().unwrap().kind(), SyntaxKind::Whitespace);
+
+    let offset = input.find("2024").unwrap();
+    let leaf = tree.node_at(offset).unwrap();
+    assert_eq!(leaf.kind(), SyntaxKind::Token(TokenKind::Number));
+    assert_eq!(leaf.parent().unwrap().kind(), SyntaxKind::Value);
+
+    let broken = root
+        .children()
+        .filter(|node| node.kind() == SyntaxKind::Entry)
+        .nth(1)
+        .unwrap();
+    let field = broken.last_child().unwrap();
+    assert_eq!(field.kind(), SyntaxKind::Field);
+    assert_eq!(
+        field.last_child().unwrap().kind(),
+        SyntaxKind::Token(TokenKind::Invalid)
+    );
+}
+
+#[test]
+fn range_parse_snaps_to_blocks_and_reports_whole_input_spans() {
+    let input = "% Überblick über Quellen\n\
+@string{pub = {Société Générale}}\n\
+@book{first, title = {Événements}, publisher = pub}\n\
+\n\
+  @article{second, title = {Zweiter}, % noted\n\
+    journal = {J}}\n\
+@misc{third, title = {Drei}}\n";
+    let parser = Parser::new().preserve_raw().capture_source();
+    let full = parser.parse_document(input).unwrap();
+    let second = input.find("Zweiter").unwrap();
+
+    let ranged = parser.parse_range(input, second..second + 3).unwrap();
+    assert_eq!(ranged.entries().len(), 1);
+    let (entry, expected) = (&ranged.entries()[0], &full.entries()[1]);
+    assert_eq!(entry.key, "second");
+    assert_eq!(entry.source, expected.source);
+    assert_eq!(entry.key_source, expected.key_source);
+    for (field, expected) in entry.fields.iter().zip(&expected.fields) {
+        assert_eq!(field.name_source, expected.name_source);
+        assert_eq!(field.value_source, expected.value_source);
+        assert_eq!(field.comment_source, expected.comment_source);
+    }
+
+    let first = input.find("@book").unwrap();
+    let ranged = parser.parse_range(input, first + 10..second).unwrap();
+    let keys: Vec<&str> = ranged
+        .entries()
+        .iter()
+        .map(|entry| entry.key.as_ref())
+        .collect();
+    assert_eq!(keys, ["first", "second"]);
+    assert_eq!(ranged.entries()[0].source, full.entries()[0].source);
+    assert_eq!(
+        ranged.into_library().entries()[0].get("publisher"),
+        Some("Société Générale")
+    );
+
+    let ranged = parser
+        .parse_range(input, input.len()..input.len() + 10)
+        .unwrap();
+    assert_eq!(ranged.entries()[0].key, "third");
+}
+
+#[test]
+fn budgeted_parse_resumes_where_it_stopped() {
+    let input = "@string{j = {Journal}}\n@article{a, journal = j}\n% note\n\
+                 @article{b, journal = j}\n@article{c, journal = j}\n";
+    let parser = Parser::new().budget(ParseBudget::new().entries(1));
+
+    let first = parser.parse_document(input).unwrap();
+    assert_eq!(first.entries().len(), 1);
+    assert_eq!(first.status(), ParseStatus::Ok);
+    assert_eq!(
+        first.diagnostics()[0].code,
+        DiagnosticCode::PARSE_BUDGET_EXHAUSTED
+    );
+    let continuation = first.continuation().unwrap();
+    assert_eq!(continuation.offset(), input.find("% note").unwrap());
+
+    let second = parser.resume(input, continuation).unwrap();
+    assert_eq!(second.entries()[0].key(), "b");
+    assert_eq!(
+        second.library().entries()[0].get("journal"),
+        Some("Journal")
+    );
+    assert_eq!(second.entries()[0].source.unwrap().line, 4);
+    assert_eq!(second.comments().len(), 1);
+
+    let third = parser
+        .tolerant()
+        .resume(input, second.continuation().unwrap())
+        .unwrap();
+    assert_eq!(third.entries()[0].key(), "c");
+    assert_eq!(third.library().entries()[0].get("journal"), Some("Journal"));
+    assert!(third.continuation().is_none());
+
+    let timed = Parser::new()
+        .budget(ParseBudget::new().time(Duration::ZERO))
+        .parse_document(input)
+        .unwrap();
+    assert_eq!(timed.entries().len(), 1);
+    assert!(timed.continuation().is_some());
+}
@@ -99,3 +99,29 @@ fn failed_blocks_always_retain_raw_text() {
 
     assert_eq!(document.failed_blocks()[0].raw.as_ref(), input);
 }
+
+#[test]
+fn trailing_field_comments_attach_to_their_field() {
+    let input = "@article{paper, % imported from DBLP\n  title = {Fast}, % check casing\n  % own-line note\n  pages = {1--2} % verify\n  ,\n  year = 2026 %% final\n}";
+
+    for document in [
+        Parser::new().parse_document(input).unwrap(),
+        Parser::new().preserve_raw().parse_document(input).unwrap(),
+    ] {
+        let fields = &document.entries()[0].fields;
+        let comments = fields
+            .iter()
+            .map(|field| field.comment.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            [Some("% check casing"), Some("% verify"), Some("%% final")]
+        );
+        let source = fields[0].comment_source.unwrap();
+        assert_eq!(&input[source.byte_start..source.byte_end], "% check casing");
+        assert_eq!(fields[2].value.value.to_plain_string(), "2026");
+    }
+
+    let library = bibtex_parser::Library::parse(input).unwrap();
+    assert_eq!(library.entries()[0].fields().len(), 3);
+}
@@ -1,5 +1,6 @@
 use bibtex_parser::{
-    ParseEvent, ParseFlow, ParseStatus, ParsedEntryStatus, Parser, Value, ValueDelimiter,
+    parse_bibtex, stream, CancelToken, DiagnosticCode, Error, ParseEvent, ParseFlow, ParseStatus,
+    ParsedEntryStatus, Parser, Value, ValueDelimiter,
 };
 
 fn collect_events<'a>(parser: &Parser, input: &'a str) -> Vec<ParseEvent<'a>> {
@@ -160,3 +161,53 @@ fn named_source_streaming_attaches_source_ids_to_events() {
     assert_eq!(summary.entries, 1);
     assert!(source.is_some_and(|span| span.source.is_some()));
 }
+
+#[test]
+fn cancel_token_stops_parsing_at_the_next_block() {
+    let input = "@misc{a, title = {A}}\n@misc{b, title = {B}}\n@misc{c, title = {C}}";
+    let token = CancelToken::new();
+    let parser = Parser::new().cancel_token(token.clone());
+    let mut keys = Vec::new();
+    let summary = parser
+        .parse_events(input, |event| {
+            if let ParseEvent::Entry(entry) = event {
+                keys.push(entry.key().to_string());
+                token.cancel();
+            }
+            Ok(ParseFlow::Continue)
+        })
+        .unwrap();
+    assert_eq!(keys, ["a"]);
+    assert!(summary.stopped);
+    assert_eq!(summary.status, ParseStatus::Cancelled);
+
+    for parser in [parser.clone(), parser.tolerant()] {
+        let document = parser.parse_document(input).unwrap();
+        assert_eq!(document.status(), ParseStatus::Cancelled);
+        assert!(document.entries().is_empty());
+        let diagnostic = &document.diagnostics()[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::PARSE_CANCELLED);
+        assert_eq!(diagnostic.source.as_ref().unwrap().byte_start, 0);
+        assert!(matches!(parser.parse(input), Err(Error::Cancelled)));
+    }
+
+    let fresh = Parser::new().cancel_token(CancelToken::new());
+    assert_eq!(fresh.parse_document(input).unwrap().entries().len(), 3);
+    assert_eq!(fresh.parse(input).unwrap().entries().len(), 3);
+}
+
+#[test]
+fn pull_stream_yields_the_items_of_parse_bibtex_one_at_a_time() {
+    let input = "@string{j = {J}}\n% note\n@article{a, journal = j}\n@preamble{\"p\"}\n";
+    let items: Vec<_> = stream(input).collect::<Result<_, _>>().unwrap();
+    assert_eq!(items, parse_bibtex(input).unwrap());
+
+    let mut items = stream("@misc{a, title = {A}}\n\n@misc{b, title = {B}\n");
+    assert!(items.next().unwrap().is_ok());
+    assert_eq!(items.offset(), "@misc{a, title = {A}}".len());
+    let Some(Err(Error::ParseError { line, .. })) = items.next() else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(line, 3);
+    assert!(items.next().is_none());
+}
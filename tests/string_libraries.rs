@@ -0,0 +1,280 @@
+use bibtex_parser::{Block, Error, ExpansionPolicy, FieldOrigin, Library, Parser, Value};
+use std::collections::HashMap;
+
+const ABBREVIATIONS: &str = r#"
+@string{IEEE_J_PAMI = "IEEE Transactions on Pattern Analysis and Machine Intelligence"}
+@string{IEEE_J_TIT = "IEEE Transactions on Information Theory"}
+@misc{ignored, title = "Entries in abbreviation files are not merged"}
+"#;
+
+#[test]
+fn string_library_definitions_expand_without_being_merged() {
+    let parser = Library::with_string_library(ABBREVIATIONS).unwrap();
+    let library = parser
+        .parse(
+            r#"
+            @string{local = "Local"}
+            @article{paper, journal = IEEE_J_PAMI, note = local # " note", month = jan}
+            "#,
+        )
+        .unwrap();
+
+    assert_eq!(library.entries().len(), 1);
+    assert_eq!(
+        library.entries()[0].get("journal"),
+        Some("IEEE Transactions on Pattern Analysis and Machine Intelligence")
+    );
+    assert_eq!(library.entries()[0].get("note"), Some("Local note"));
+    assert_eq!(library.strings().len(), 1);
+    assert_eq!(library.strings()[0].name, "local");
+    assert_eq!(library.blocks().len(), 2);
+    assert!(matches!(library.blocks()[1], Block::Entry(_, None)));
+    assert!(!library.to_bibtex().unwrap().contains("IEEE_J_PAMI ="));
+
+    let second = parser
+        .parse("@article{other, journal = IEEE_J_TIT}")
+        .unwrap();
+    assert_eq!(
+        second.entries()[0].get("journal"),
+        Some("IEEE Transactions on Information Theory")
+    );
+}
+
+#[test]
+fn local_definitions_shadow_string_library_and_modes_are_respected() {
+    let parser = Parser::new()
+        .capture_source()
+        .with_string_library(ABBREVIATIONS)
+        .unwrap()
+        .with_string_library("@string{extra = \"Extra\"}")
+        .unwrap();
+    let library = parser
+        .parse(
+            "@string{IEEE_J_TIT = \"Local TIT\"}\n@article{a, journal = IEEE_J_TIT, note = extra}",
+        )
+        .unwrap();
+
+    assert_eq!(library.entries()[0].get("journal"), Some("Local TIT"));
+    assert_eq!(library.entries()[0].get("note"), Some("Extra"));
+    assert!(library.strings()[0].source.is_some());
+
+    let document = parser
+        .expand_values()
+        .parse_document("@article{a, journal = IEEE_J_PAMI}")
+        .unwrap();
+    assert!(document.strings().is_empty());
+    assert_eq!(
+        document.entries()[0].fields[0].value.expanded_text(),
+        Some("IEEE Transactions on Pattern Analysis and Machine Intelligence")
+    );
+
+    let plain = Library::parse("@article{a, journal = IEEE_J_PAMI}").unwrap();
+    assert_eq!(plain.entries()[0].get("journal"), None);
+}
+
+#[test]
+fn expansion_policy_preserves_selected_field_macros() {
+    let input = r#"
+        @string{pami = "IEEE Trans. PAMI"}
+        @article{paper, journal = pami, month = jan, note = "Vol. " # pami, series = undefined}
+    "#;
+    let policy = ExpansionPolicy::expand_all()
+        .preserve_field("journal")
+        .preserve_field("Series");
+    assert!(policy.expands_field("MONTH"));
+    assert!(!policy.expands_field("JOURNAL"));
+
+    let library = Parser::new().expansion_policy(policy).parse(input).unwrap();
+    let entry = &library.entries()[0];
+    assert_eq!(
+        entry.field("journal").unwrap().value,
+        Value::Variable("pami".into())
+    );
+    assert_eq!(entry.get("month"), Some("January"));
+    assert_eq!(entry.get("note"), Some("Vol. IEEE Trans. PAMI"));
+    assert_eq!(
+        entry.field("series").unwrap().value,
+        Value::Variable("undefined".into())
+    );
+    assert!(library.to_bibtex().unwrap().contains("journal = pami"));
+
+    let only_months = Parser::new()
+        .expansion_policy(ExpansionPolicy::preserve_all().expand_field("month"))
+        .parse(input)
+        .unwrap();
+    let entry = &only_months.entries()[0];
+    assert_eq!(entry.get("month"), Some("January"));
+    assert!(matches!(
+        entry.field("note").unwrap().value,
+        Value::Concat(_)
+    ));
+}
+
+#[test]
+fn partial_expansion_flattens_known_parts_around_unknown_variables() {
+    let input = r#"
+        @string{proc = "Proc. "}
+        @string{loop = loop}
+        @inproceedings{paper, booktitle = proc # "of " # venue # " " # 2020, month = jan # "--" # feb, note = loop}
+    "#;
+    let document = Parser::new().parse_document(input).unwrap();
+    let entry = &document.library().entries()[0];
+
+    assert_eq!(
+        entry.field("booktitle").unwrap().value,
+        Value::Concat(
+            vec![
+                Value::Literal("Proc. of ".into()),
+                Value::Variable("venue".into()),
+                Value::Literal(" 2020".into()),
+            ]
+            .into_boxed_slice()
+        )
+    );
+    assert_eq!(entry.get("month"), Some("January--February"));
+    assert_eq!(
+        entry.field("note").unwrap().value,
+        Value::Variable("loop".into())
+    );
+
+    let mut library = Library::new();
+    library.add_string("known", Value::from_plain_string("K"));
+    assert_eq!(
+        library.expand_value_partial(&Value::Concat(
+            vec![Value::Variable("known".into()), Value::Number(1)].into_boxed_slice()
+        )),
+        Value::Literal("K1".into())
+    );
+}
+
+#[test]
+fn field_origins_record_macros_and_crossref_inheritance() {
+    let input = r#"
+        @string{ieee = "IEEE"}
+        @string{trans = "Transactions"}
+        @proceedings{proc2020, publisher = ieee, title = {Proc}, crossref = {root}}
+        @inproceedings{talk, crossref = {proc2020}, journal = ieee # " " # trans, month = jan,
+                       title = {Talk}}
+    "#;
+    let mut library = Parser::new().track_field_origins().parse(input).unwrap();
+    let id = |library: &Library, key: &str| {
+        let index = library
+            .entries()
+            .iter()
+            .position(|entry| entry.key() == key);
+        library.entry_id(index.unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        library.field_origin(id(&library, "proc2020"), "Publisher"),
+        Some(&FieldOrigin::FromString("ieee".to_string()))
+    );
+    assert_eq!(
+        library.field_origin(id(&library, "talk"), "journal"),
+        Some(&FieldOrigin::FromStrings(vec![
+            "ieee".to_string(),
+            "trans".to_string()
+        ]))
+    );
+    assert_eq!(
+        library.field_origin(id(&library, "talk"), "month"),
+        Some(&FieldOrigin::FromString("jan".to_string()))
+    );
+    assert_eq!(library.field_origin(id(&library, "talk"), "title"), None);
+
+    assert_eq!(library.inherit_crossref_fields(), 1);
+    let talk = library.find_by_key("talk").unwrap();
+    assert_eq!(talk.get("publisher"), Some("IEEE"));
+    assert_eq!(talk.get("title"), Some("Talk"));
+    assert_eq!(
+        library.field_origin(id(&library, "talk"), "publisher"),
+        Some(&FieldOrigin::InheritedFrom("proc2020".to_string()))
+    );
+
+    library.regenerate_keys(|entry| (entry.key() == "talk").then(|| "talk2020".to_string()));
+    assert_eq!(
+        library.field_origin(id(&library, "talk2020"), "publisher"),
+        Some(&FieldOrigin::InheritedFrom("proc2020".to_string()))
+    );
+
+    let sorted_talk = id(&library, "talk2020");
+    library.sort(bibtex_parser::SortOptions {
+        entries_by_key: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        library.field_origin(sorted_talk, "publisher"),
+        Some(&FieldOrigin::InheritedFrom("proc2020".to_string()))
+    );
+
+    let duplicates = Parser::new()
+        .track_field_origins()
+        .parse("@string{a = \"A\"}\n@string{b = \"B\"}\n@misc{dup, note = a}\n@misc{dup, note = b}")
+        .unwrap();
+    let ids = duplicates.entry_ids();
+    assert_eq!(
+        duplicates.field_origin(ids[0], "note"),
+        Some(&FieldOrigin::FromString("a".to_string()))
+    );
+    assert_eq!(
+        duplicates.field_origin(ids[1], "note"),
+        Some(&FieldOrigin::FromString("b".to_string()))
+    );
+
+    let untracked = Library::parse(input).unwrap();
+    assert_eq!(
+        untracked.field_origin(id(&untracked, "proc2020"), "publisher"),
+        None
+    );
+}
+
+#[test]
+fn macro_resolvers_fill_in_undefined_variables_in_order() {
+    let input = r#"
+@string{local = "Local Venue"}
+@article{a, journal = local, publisher = acm, address = ny, month = jan}
+"#;
+    let table = HashMap::from([
+        ("acm".to_string(), "ACM".to_string()),
+        ("local".to_string(), "Shadowed".to_string()),
+    ]);
+    let parser = Parser::new()
+        .macro_resolver(table)
+        .macro_resolver(|name: &str| (name == "ny").then(|| "New York".to_string()));
+
+    let library = parser.parse(input).unwrap();
+    let entry = &library.entries()[0];
+    assert_eq!(entry.get("journal"), Some("Local Venue"));
+    assert_eq!(entry.get("publisher"), Some("ACM"));
+    assert_eq!(entry.get("address"), Some("New York"));
+    assert_eq!(entry.get("month"), Some("January"));
+
+    let document = parser.expand_values().parse_document(input).unwrap();
+    assert_eq!(
+        document.entries()[0].fields[1].value.expanded_text(),
+        Some("ACM")
+    );
+
+    assert!(matches!(
+        Parser::new()
+            .macro_resolver(|_: &str| None)
+            .parse("@misc{m, note = unknown}"),
+        Err(Error::UndefinedVariable(name)) if name == "unknown"
+    ));
+}
+
+#[test]
+fn library_macro_resolvers_apply_to_later_expansion() {
+    let mut library = Library::new();
+    library.add_macro_resolver(|name: &str| (name == "vldb").then(|| "VLDB".to_string()));
+
+    let value = Value::Variable("vldb".into());
+    assert_eq!(
+        library.expand_value_ref(&value).unwrap(),
+        Value::Literal("VLDB".into())
+    );
+    assert_eq!(
+        library.expand_value_partial(&value),
+        Value::Literal("VLDB".into())
+    );
+}
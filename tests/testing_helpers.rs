@@ -0,0 +1,32 @@
+#![cfg(feature = "testing")]
+
+use bibtex_parser::testing::bibtex_diff;
+use bibtex_parser::{assert_bibtex_eq, Library};
+
+#[test]
+fn semantic_comparison_ignores_formatting_and_reports_field_diffs() {
+    let library = Library::parse("@string{j = {Nature}}\n@book{b, title = {B}}").unwrap();
+    assert_bibtex_eq!(
+        library,
+        "@BOOK{b,\n  Title = \"B\"\n}\n@string{j = \"Nature\"}",
+        "round trip of {}",
+        "b"
+    );
+
+    let diff = bibtex_diff(
+        "@article{a, title = {Old}, year = 2024}\n@misc{gone,}",
+        "@inproceedings{a, title = {New}, pages = {1--2}, year = {2024}}\n@misc{new,}",
+    )
+    .unwrap();
+    assert_eq!(
+        diff,
+        "  a: type\n-   article\n+   inproceedings\n  a.title\n-   \"Old\"\n+   \"New\"\n\
+         + a.pages = \"1--2\"\n- @misc{gone}\n+ @misc{new}\n"
+    );
+
+    let panic = std::panic::catch_unwind(|| {
+        assert_bibtex_eq!("@misc{a, note = {x}}", "@misc{a, note = {y}}");
+    })
+    .unwrap_err();
+    assert!(panic.downcast_ref::<String>().unwrap().contains("a.note"));
+}
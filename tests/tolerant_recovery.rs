@@ -1,5 +1,6 @@
 use bibtex_parser::{
-    DiagnosticCode, DiagnosticTarget, ParseStatus, ParsedBlock, ParsedEntryStatus, Parser,
+    parse_fragments, DiagnosticCode, DiagnosticTarget, ParseStatus, ParsedBlock, ParsedEntryStatus,
+    Parser,
 };
 
 #[test]
@@ -94,3 +95,99 @@ fn strict_parsing_still_rejects_malformed_entries() {
         ParsedEntryStatus::Partial
     );
 }
+
+#[test]
+fn multibyte_text_at_failure_points_gets_whole_character_spans() {
+    let input = "@article{𝄞, title = {x}}\n@book{ok, title = {é}}";
+    let document = Parser::new().tolerant().parse_document(input).unwrap();
+
+    let diagnostic = &document.diagnostics()[0];
+    let span = diagnostic.source.unwrap();
+    assert_eq!(&input[span.byte_start..span.byte_end], "𝄞");
+    assert_eq!((span.column, span.end_column), (10, 11));
+
+    assert!(bibtex_parser::parse_catch(input).is_err());
+    let library = Parser::new().tolerant().parse_catch(input).unwrap();
+    assert_eq!(library.entries().len(), 1);
+
+    let panicking = Parser::new().macro_resolver(|_: &str| -> Option<String> { panic!("boom") });
+    let error = panicking.parse_catch("@misc{a, note = nyc}").unwrap_err();
+    assert!(matches!(error, bibtex_parser::Error::Panic(message) if message == "boom"));
+}
+
+#[test]
+fn truncated_and_multibyte_inputs_never_panic() {
+    let inputs = [
+        "@",
+        "@é",
+        "@stringé{a = b}",
+        "@STRİNG{a = b}",
+        "@misc{",
+        "@misc(é",
+        "@misc{a, t = {x\\",
+        "@misc{a, t = \"\\",
+        "@misc{a, t = \"é",
+        "@misc{a, t = {\\é}}",
+        "@misc{a, t = {\\}",
+        "@misc{a, t = -}",
+        "@misc{a, t = a # }",
+        "@misc{a, t = x %é\n}",
+        "@string{a = a # a}@misc{b, t = a}",
+        "@comment{",
+        "@preamble{\"",
+        "\u{feff}@misc{a,}",
+        "@misc{a, t = {x}}\r\n\r",
+        "𝄞@misc{𝄞, 𝄞 = {𝄞}} 𝄞",
+    ];
+    for input in inputs {
+        let _ = bibtex_parser::parse(input);
+        let _ = bibtex_parser::parse_bibtex(input);
+        let _ = parse_fragments(input);
+        let _ = bibtex_parser::tokenize(input).count();
+        let _ = bibtex_parser::SyntaxTree::parse(input);
+        let _ = Parser::new()
+            .tolerant()
+            .expand_values()
+            .parse_document(input);
+        let _ = Parser::new()
+            .tolerant()
+            .preserve_raw()
+            .parse_document(input);
+        let mut state = bibtex_parser::ParserState::new(Parser::new().tolerant());
+        for ch in input.chars() {
+            let _ = state.feed(ch.encode_utf8(&mut [0; 4]));
+        }
+        let _ = state.finish();
+    }
+}
+
+#[test]
+fn parse_fragments_returns_one_result_per_entry_in_clipboard_text() {
+    let input = "pages = {1--10},\n  year = 2019\n}\n\
+                 From: jane@example.org\n\
+                 Sure, here they are: @article{first, title = {First}, year = 2020}\n\
+                 @string{j = {J}}\n\
+                 Page 3 of 7\n\
+                 @inproceedings{broken, title = {Missing close,\n\
+                 @book(second, title = {Second})\n\
+                 @misc{cut, title = {Cut";
+
+    let results = parse_fragments(input);
+    let keys: Vec<_> = results
+        .iter()
+        .map(|result| result.as_ref().map(|entry| entry.key().to_string()).ok())
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            Some("first".to_string()),
+            None,
+            Some("second".to_string()),
+            None
+        ]
+    );
+    let Err(bibtex_parser::Error::ParseError { line, .. }) = &results[1] else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(*line, 8);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "tracing")]
+
+use bibtex_parser::Parser;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+type Spans = Vec<(&'static str, Vec<(String, String)>)>;
+
+#[derive(Clone, Default)]
+struct Recorder {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<Spans>>,
+}
+
+struct Fields<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .push((field.name().to_string(), format!("{value:?}")));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Vec::new();
+        span.record(&mut Fields(&mut fields));
+        self.spans
+            .lock()
+            .unwrap()
+            .push((span.metadata().name(), fields));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let index = usize::try_from(span.into_u64()).unwrap() - 1;
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut Fields(&mut spans[index].1));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .rev()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn parse_phases_emit_spans_with_counts_and_throughput() {
+    let input = "@string{j = {J}}\n@article{a, journal = j}\n@book{b, title = {B}}";
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        Parser::new().parse(input).unwrap();
+        Parser::new().tolerant().parse_document(input).unwrap();
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    let names = spans.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "parse",
+            "scan",
+            "parse",
+            "parse_document",
+            "parse",
+            "expand",
+            "index"
+        ]
+    );
+    let (_, parse) = &spans[0];
+    assert_eq!(
+        field(parse, "bytes"),
+        Some(input.len().to_string().as_str())
+    );
+    assert_eq!(field(parse, "entries"), Some("2"));
+    assert_eq!(field(parse, "tolerant"), Some("false"));
+    assert!(spans
+        .iter()
+        .all(|(_, fields)| field(fields, "mib_per_sec").is_some()));
+    assert_eq!(field(&spans[3].1, "entries"), Some("2"));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "net")]
+
+use bibtex_parser::net::FetchOptions;
+use bibtex_parser::{Error, Library, Parser};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// Serve each response once, in order, and return the base URL.
+fn serve(responses: Vec<Vec<u8>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            stream.write_all(&response).unwrap();
+        }
+    });
+    url
+}
+
+fn response(status: &str, body: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .into_bytes()
+}
+
+#[test]
+fn parse_url_downloads_and_parses_a_bibliography() {
+    let body = "@string{j = {Journal}}\n@article{a, journal = j}\n";
+    let url = serve(vec![
+        response("200 OK", body),
+        response("404 Not Found", "missing"),
+        response("200 OK", body),
+    ]);
+
+    let library = Library::parse_url(&format!("{url}/group.bib")).unwrap();
+    assert_eq!(library.entries()[0].get("journal"), Some("Journal"));
+
+    let Err(Error::Fetch(message)) = Library::parse_url(&format!("{url}/gone.bib")) else {
+        panic!("expected a fetch error");
+    };
+    assert!(message.contains("gone.bib"), "{message}");
+
+    let small = FetchOptions::new().max_bytes(16);
+    let Err(Error::Fetch(message)) = Parser::new().parse_url(&url, &small) else {
+        panic!("expected the size limit to apply");
+    };
+    assert!(
+        message.ends_with("larger than the 16-byte limit"),
+        "{message}"
+    );
+}
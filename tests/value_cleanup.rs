@@ -0,0 +1,214 @@
+use bibtex_parser::{
+    brace_acronyms, decode_html_entities, document_to_string, normalize_punctuation,
+    repair_mojibake, title_case_all_caps, ExportQuirks, Library, Parser, PunctuationStyle,
+};
+
+#[test]
+fn mojibake_is_repaired_in_literals_and_reported() {
+    assert_eq!(repair_mojibake("KÃ¶rper").as_deref(), Some("Körper"));
+    assert_eq!(
+        repair_mojibake("Itâ€™s Ã‰cole").as_deref(),
+        Some("It’s École")
+    );
+    assert_eq!(repair_mojibake("Körper"), None);
+    assert_eq!(repair_mojibake("Ã"), None);
+
+    let input = "@string{v = \"MÃ¼nchen\"}\n@article{a,\n  title = {KÃ¶rper},\n  journal = v # \" Press\"\n}";
+    let mut library = Library::parse(input).unwrap();
+    let repairs = library.repair_mojibake();
+    assert_eq!(repairs.len(), 2);
+    assert_eq!(repairs[0].field, "title");
+    assert_eq!(repairs[0].original, "KÃ¶rper");
+    assert_eq!(library.entries()[0].get("title"), Some("Körper"));
+    assert_eq!(library.entries()[0].get("journal"), Some("München Press"));
+
+    let mut document = Parser::new()
+        .preserve_raw()
+        .parse_document("@article{a,\n  title = {KÃ¶rper},\n  year = 2024\n}")
+        .unwrap();
+    let repairs = document.repair_mojibake();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(repairs[0].span.unwrap().line, 2);
+    assert_eq!(
+        document_to_string(&document).unwrap(),
+        "@article{a,\n  title = {Körper},\n  year = 2024\n}"
+    );
+}
+
+#[test]
+fn html_entities_are_decoded_to_bibtex_safe_text() {
+    assert_eq!(
+        decode_html_entities("M&uuml;ller &amp; Sons, 1&#8211;2&#x2014;3&nbsp;pp").as_deref(),
+        Some("Müller \\& Sons, 1–2—3~pp")
+    );
+    assert_eq!(decode_html_entities("AT&T &bogus; & x"), None);
+    assert_eq!(decode_html_entities("&#0;"), None);
+    assert_eq!(
+        decode_html_entities("&#123;x&#125; &#36;5 &#92;n &#35;1 50&#37; a&#x5F;b &#38;")
+            .as_deref(),
+        Some("\\{x\\} \\$5 \\textbackslash{}n \\#1 50\\% a\\_b \\&")
+    );
+
+    let mut library = Library::parse("@article{a, title = {Q&amp;A}, year = 2024}").unwrap();
+    let repairs = library.decode_html_entities();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(repairs[0].original, "Q&amp;A");
+    assert_eq!(library.entries()[0].get("title"), Some("Q\\&A"));
+
+    let mut document = Parser::new()
+        .capture_source()
+        .parse_document("@article{a, title = {&ldquo;Hi&rdquo;}}")
+        .unwrap();
+    assert_eq!(document.decode_html_entities()[0].repaired, "“Hi”");
+}
+
+#[test]
+fn punctuation_converts_between_latex_and_unicode_forms() {
+    let unicode = "\u{201C}Hi\u{201D} \u{2018}x\u{2019} 1\u{2013}2 a\u{2014}b p.\u{A0}5";
+    let latex = "``Hi'' `x' 1--2 a---b p.~5";
+    assert_eq!(
+        normalize_punctuation(unicode, PunctuationStyle::Latex).as_deref(),
+        Some(latex)
+    );
+    assert_eq!(
+        normalize_punctuation(latex, PunctuationStyle::Unicode).as_deref(),
+        Some("\u{201C}Hi\u{201D} \u{2018}x' 1\u{2013}2 a\u{2014}b p.\u{A0}5")
+    );
+    assert_eq!(
+        normalize_punctuation(r"Espa\~na $a--b$ \`e", PunctuationStyle::Unicode),
+        None
+    );
+    assert_eq!(
+        normalize_punctuation(
+            r"See \url{https://example.org/~u/a--b} and \href {http://x.org/``q''}{a--b}",
+            PunctuationStyle::Unicode
+        )
+        .as_deref(),
+        Some("See \\url{https://example.org/~u/a--b} and \\href {http://x.org/``q''}{a\u{2013}b}")
+    );
+    assert_eq!(
+        normalize_punctuation(r"\path{~/a--b/{c}}", PunctuationStyle::Unicode),
+        None
+    );
+
+    let mut library =
+        Library::parse("@article{a, title = {A\u{2013}B}, url = {https://example.org/~user/a--b}}")
+            .unwrap();
+    let repairs = library.normalize_punctuation(PunctuationStyle::Latex);
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(library.entries()[0].get("title"), Some("A--B"));
+    assert_eq!(
+        library
+            .normalize_punctuation(PunctuationStyle::Unicode)
+            .len(),
+        1
+    );
+    assert_eq!(
+        library.entries()[0].get("url"),
+        Some("https://example.org/~user/a--b")
+    );
+}
+
+#[test]
+fn export_quirk_text_helpers() {
+    assert_eq!(
+        title_case_all_caps("IEEE TRANSACTIONS ON NEURAL NETWORKS (TNN)").as_deref(),
+        Some("IEEE Transactions on Neural Networks (TNN)")
+    );
+    assert_eq!(
+        title_case_all_caps("THE WEB CONFERENCE").as_deref(),
+        Some("The Web Conference")
+    );
+    assert_eq!(title_case_all_caps("Neural Computation"), None);
+    assert_eq!(title_case_all_caps("NATURE"), None);
+    assert_eq!(
+        title_case_all_caps("JOURNAL OF PURE AND APPLIED ALGEBRA").as_deref(),
+        Some("Journal of Pure and Applied Algebra")
+    );
+    assert_eq!(
+        title_case_all_caps("PROCEEDINGS OF THE ACM ON MANAGEMENT OF DATA, PART II").as_deref(),
+        Some("Proceedings of the ACM on Management of Data, Part II")
+    );
+
+    assert_eq!(
+        brace_acronyms("BERT and GPUs on the iPhone: a COVID-19 study").as_deref(),
+        Some("{BERT} and {GPUs} on the {iPhone}: a {COVID-19} study")
+    );
+    assert_eq!(brace_acronyms("Already {NLP} and \\LaTeX"), None);
+    assert_eq!(brace_acronyms("ALL CAPS TITLE"), None);
+    assert_eq!(
+        brace_acronyms("Pre-Training of Deep Bidirectional Transformers"),
+        None
+    );
+    assert_eq!(brace_acronyms("Self-Attention with Non-Linear Gates"), None);
+    assert_eq!(
+        brace_acronyms("Fine-Tuning BERT-Large").as_deref(),
+        Some("Fine-Tuning {BERT-Large}")
+    );
+}
+
+#[test]
+fn export_quirks_fix_keys_pages_venues_and_titles_with_toggles() {
+    let input = r#"
+        @inproceedings{doe20242024deep, title = {Deep NLP}, booktitle = {PROCEEDINGS OF THE ACM CONFERENCE},
+                       pages = {1--1}, year = 2024}
+        @inproceedings{child, crossref = {doe20242024deep}, pages = {1--12}}
+    "#;
+
+    let mut library = Library::parse(input).unwrap();
+    let fixes = library.fix_export_quirks(ExportQuirks::default());
+    assert_eq!(fixes.renames.get("doe20242024deep"), Some("doe2024deep"));
+    assert_eq!(library.entries()[1].get("crossref"), Some("doe2024deep"));
+    let entry = &library.entries()[0];
+    assert_eq!(entry.get("title"), Some("Deep {NLP}"));
+    assert_eq!(
+        entry.get("booktitle"),
+        Some("Proceedings of the ACM Conference")
+    );
+    assert_eq!(entry.get("pages"), None);
+    assert_eq!(library.entries()[1].get("pages"), Some("1--12"));
+    let removed = fixes
+        .repairs
+        .iter()
+        .find(|repair| repair.field == "pages")
+        .unwrap();
+    assert_eq!(
+        (removed.original.as_str(), removed.repaired.as_str()),
+        ("1--1", "")
+    );
+
+    let mut library = Library::parse(input).unwrap();
+    let fixes = library.fix_export_quirks(ExportQuirks {
+        unbraced_acronyms: true,
+        ..ExportQuirks::none()
+    });
+    assert!(fixes.renames.is_empty());
+    assert_eq!(fixes.repairs.len(), 1);
+    assert_eq!(library.entries()[0].key(), "doe20242024deep");
+    assert_eq!(library.entries()[0].get("pages"), Some("1--1"));
+    assert!(Library::parse(input)
+        .unwrap()
+        .fix_export_quirks(ExportQuirks::none())
+        .is_empty());
+}
+
+#[test]
+fn export_quirks_leave_venue_case_alone_for_non_english_entries() {
+    let mut library = Library::parse(
+        r"
+        @article{de, journal = {ANNALEN DER PHYSIK}, langid = {ngerman}}
+        @article{en, journal = {JOURNAL OF APPLIED PHYSICS}, language = {english}}
+        ",
+    )
+    .unwrap();
+    let fixes = library.fix_export_quirks(ExportQuirks::default());
+    assert_eq!(fixes.repairs.len(), 1);
+    assert_eq!(
+        library.entries()[0].get("journal"),
+        Some("ANNALEN DER PHYSIK")
+    );
+    assert_eq!(
+        library.entries()[1].get("journal"),
+        Some("Journal of Applied Physics")
+    );
+}
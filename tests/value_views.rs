@@ -156,3 +156,80 @@ fn value_text_can_be_latex_to_unicode_normalized() {
     let value = Value::Literal(Cow::Borrowed(r#"Jos\'e"#));
     assert_eq!(value.to_unicode_plain_string(), "José");
 }
+
+#[test]
+fn expanded_views_reuse_shared_macros_and_respect_shadowing() {
+    let input = r#"
+@string{base = "Journal"}
+@string{base = "Transactions"}
+@string{venue = "IEEE " # base}
+@article{a, journal = venue}
+@article{b, journal = venue # " Letters", note = base}
+"#;
+
+    let document = Parser::new().expand_values().parse_document(input).unwrap();
+    let expanded = document
+        .entries()
+        .iter()
+        .flat_map(|entry| entry.fields.iter())
+        .map(|field| field.value.expanded_text().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        expanded,
+        vec![
+            "IEEE Transactions",
+            "IEEE Transactions Letters",
+            "Transactions"
+        ]
+    );
+}
+
+#[test]
+fn undefined_variable_policy_controls_library_values() {
+    let input = r#"
+@string{venue = "VLDB"}
+@article{paper, journal = venue, publisher = acm, note = "See " # acm}
+"#;
+    assert!(Library::parse(input).is_err());
+
+    let parse = |policy| {
+        Parser::new()
+            .undefined_variables(policy)
+            .parse(input)
+            .unwrap()
+    };
+
+    let preserved = parse(UnresolvedVariablePolicy::Preserve);
+    let entry = &preserved.entries()[0];
+    assert_eq!(entry.get("journal"), Some("VLDB"));
+    assert_eq!(
+        entry.field("publisher").unwrap().value,
+        Value::Variable(Cow::Borrowed("acm"))
+    );
+    assert!(preserved.to_bibtex().unwrap().contains("publisher = acm"));
+
+    let placeholder = parse(UnresolvedVariablePolicy::Placeholder);
+    assert_eq!(
+        placeholder.entries()[0].get("note"),
+        Some("See {undefined:acm}")
+    );
+
+    let empty = parse(UnresolvedVariablePolicy::Empty);
+    assert_eq!(empty.entries()[0].get("publisher"), Some(""));
+    assert_eq!(empty.entries()[0].get("note"), Some("See "));
+
+    assert!(Parser::new()
+        .undefined_variables(UnresolvedVariablePolicy::Error)
+        .parse("@misc{m, note = missing}")
+        .is_err());
+
+    let document = Parser::new()
+        .undefined_variables(UnresolvedVariablePolicy::Empty)
+        .expand_values()
+        .parse_document(input)
+        .unwrap();
+    assert_eq!(
+        document.entries()[0].fields[1].value.expanded_text(),
+        Some("")
+    );
+}
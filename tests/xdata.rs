@@ -0,0 +1,69 @@
+use bibtex_parser::{Error, FieldOrigin, Library};
+
+const XDATA_INPUT: &str = r#"
+@xdata{pub, publisher = "Springer", location = "Berlin"}
+@xdata{series, xdata = {pub}, series = "LNCS", publisher = "Springer-Verlag"}
+@inproceedings{paper, title = "Paper", author = "A. Author", booktitle = "Proc.", year = 2024,
+  xdata = {series}, doi = "10.1/paper"}
+@book{book, title = "Book", author = "B. Author", year = 2023, publisher = "Own",
+  xdata = {missing, pub}, url = "https://example.com"}
+"#;
+
+#[test]
+fn xdata_entries_are_kept_out_of_citable_listings() {
+    let library = Library::parse(XDATA_INPUT).unwrap();
+
+    assert_eq!(library.entries().len(), 4);
+    let citable = library
+        .citable_entries()
+        .map(|entry| entry.key())
+        .collect::<Vec<_>>();
+    assert_eq!(citable, vec!["paper", "book"]);
+    assert_eq!(library.xdata_entries().count(), 2);
+    assert!(library.report().missing_links.is_empty());
+}
+
+#[test]
+fn multi_level_xdata_chains_resolve_in_dependency_order() {
+    let mut library = Library::parse(XDATA_INPUT).unwrap();
+
+    assert_eq!(library.resolve_xdata().unwrap(), 5);
+
+    let paper_index = library
+        .entries()
+        .iter()
+        .position(|entry| entry.key() == "paper")
+        .unwrap();
+    let paper = &library.entries()[paper_index];
+    assert_eq!(paper.get("series"), Some("LNCS"));
+    assert_eq!(paper.get("publisher"), Some("Springer-Verlag"));
+    assert_eq!(paper.get("location"), Some("Berlin"));
+    assert_eq!(
+        library.field_origin(library.entry_ids()[paper_index], "location"),
+        Some(&FieldOrigin::InheritedFrom("series".to_string()))
+    );
+
+    let book = library.find_by_key("book").unwrap();
+    assert_eq!(book.get("publisher"), Some("Own"));
+    assert_eq!(book.get("location"), Some("Berlin"));
+}
+
+#[test]
+fn xdata_cycles_are_reported_and_rejected() {
+    let input = r#"
+@xdata{a, xdata = {b}, note = "A"}
+@xdata{b, xdata = {a}, note = "B"}
+@misc{m, xdata = {a}, title = "M"}
+"#;
+    let mut library = Library::parse(input).unwrap();
+
+    assert_eq!(library.xdata_cycles(), vec!["a -> b -> a"]);
+    let report = library.report();
+    assert_eq!(report.xdata_cycles, vec!["a -> b -> a"]);
+    assert!(report.to_markdown().contains("## Xdata cycles (1)"));
+    assert!(matches!(
+        library.resolve_xdata(),
+        Err(Error::CircularReference(chain)) if chain == "a -> b -> a"
+    ));
+    assert!(library.find_by_key("m").unwrap().get("note").is_none());
+}